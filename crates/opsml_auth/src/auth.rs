@@ -1,7 +1,8 @@
+use crate::permission::UserPermissions;
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use opsml_error::error::AuthError;
-use opsml_sql::schemas::schema::User;
-use password_auth::verify_password;
+use opsml_sql::schemas::schema::{RefreshTokenFamily, User};
+use password_auth::{generate_hash, verify_password};
 use rand::{distributions::Alphanumeric, Rng};
 use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -13,6 +14,9 @@ pub struct Claims {
     pub permissions: Vec<String>,
     pub group_permissions: Vec<String>,
     salt: String,
+    /// Login session a refresh token belongs to. Access tokens carry none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
 }
 
 pub struct AuthManager {
@@ -35,7 +39,9 @@ impl AuthManager {
             .collect()
     }
 
-    pub fn generate_jwt(&self, user: &User) -> String {
+    /// Access token carrying the user's effective permissions, so requests are authorized
+    /// without resolving groups again
+    pub fn generate_jwt(&self, permissions: &UserPermissions) -> String {
         let expiration = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -43,11 +49,12 @@ impl AuthManager {
             + 3600; // 1 hour expiration
 
         let claims = Claims {
-            sub: user.username.clone(),
+            sub: permissions.username.clone(),
             exp: expiration as usize,
-            permissions: user.permissions.clone(),
-            group_permissions: user.group_permissions.clone(),
+            permissions: permissions.permissions.clone(),
+            group_permissions: permissions.group_permissions.clone(),
             salt: self.generate_salt(),
+            family: None,
         };
 
         encode(
@@ -58,7 +65,16 @@ impl AuthManager {
         .unwrap()
     }
 
-    pub fn generate_refresh_token(&self, user: &User) -> String {
+    /// Id of a new refresh token family, started on every login
+    pub fn generate_refresh_family(&self) -> String {
+        rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect()
+    }
+
+    pub fn generate_refresh_token(&self, user: &User, family: &str) -> String {
         let expiration = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -71,6 +87,7 @@ impl AuthManager {
             permissions: user.permissions.clone(),
             group_permissions: user.group_permissions.clone(),
             salt: self.generate_salt(),
+            family: Some(family.to_string()),
         };
 
         encode(
@@ -117,7 +134,66 @@ impl AuthManager {
         Ok(token_data.claims)
     }
 
+    /// Refresh tokens are stored hashed, the same way as passwords
+    pub fn hash_refresh_token(&self, token: &str) -> String {
+        generate_hash(token)
+    }
+
+    /// Check a presented refresh token against the latest one issued in its family
+    pub fn validate_stored_refresh_token(
+        &self,
+        family: &RefreshTokenFamily,
+        token: &str,
+    ) -> Result<(), AuthError> {
+        verify_password(token, &family.token_hash).map_err(|_| AuthError::InvalidRefreshToken)
+    }
+
     pub fn validate_user(&self, user: &User, password: &str) -> Result<(), AuthError> {
         verify_password(password, &user.password_hash).map_err(|_| AuthError::InvalidPassword)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_token_validation() {
+        let manager = AuthManager::new("jwt_secret", "refresh_secret");
+        let user = User::new("user".to_string(), "hash".to_string(), None, None);
+        let family = manager.generate_refresh_family();
+
+        let refresh_token = manager.generate_refresh_token(&user, &family);
+        let claims = manager.validate_refresh_token(&refresh_token).unwrap();
+        assert_eq!(claims.sub, "user");
+        assert_eq!(claims.family.as_deref(), Some(family.as_str()));
+
+        // access tokens are signed with a different secret and belong to no family
+        let access_token = manager.generate_jwt(&UserPermissions::resolve(&user, &[]));
+        assert!(manager.validate_refresh_token(&access_token).is_err());
+        assert!(manager
+            .validate_jwt(&access_token)
+            .unwrap()
+            .family
+            .is_none());
+
+        let mut stored = RefreshTokenFamily::new(
+            family.clone(),
+            user.username.clone(),
+            manager.hash_refresh_token(&refresh_token),
+        );
+        assert!(manager
+            .validate_stored_refresh_token(&stored, &refresh_token)
+            .is_ok());
+
+        // a rotated-out token no longer matches
+        let rotated = manager.generate_refresh_token(&user, &family);
+        stored.token_hash = manager.hash_refresh_token(&rotated);
+        assert!(manager
+            .validate_stored_refresh_token(&stored, &refresh_token)
+            .is_err());
+        assert!(manager
+            .validate_stored_refresh_token(&stored, &rotated)
+            .is_ok());
+    }
+}
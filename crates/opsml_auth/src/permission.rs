@@ -1,3 +1,4 @@
+use opsml_sql::schemas::schema::{Group, User};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -7,16 +8,76 @@ pub struct UserPermissions {
     pub group_permissions: Vec<String>,
 }
 
+/// Match a grant against a permission. `*` in a grant matches any run of characters,
+/// so `write:*` covers every repository and `write:nlp-*` every repository starting with `nlp-`
+fn grant_matches(grant: &str, permission: &str) -> bool {
+    let mut parts = grant.split('*');
+    let first = parts.next().unwrap_or_default();
+
+    let Some(mut rest) = permission.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // no wildcard, exact match
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
 impl UserPermissions {
+    /// Effective permissions of a user: their own grants plus the grants of every group
+    /// they belong to. Group names are kept in `group_permissions`
+    pub fn resolve(user: &User, groups: &[Group]) -> Self {
+        let mut permissions = user.permissions.clone();
+        let mut group_permissions = user.group_permissions.clone();
+
+        for group in groups {
+            permissions.extend(group.permissions.iter().cloned());
+            group_permissions.push(group.name.clone());
+        }
+
+        permissions.sort();
+        permissions.dedup();
+        group_permissions.sort();
+        group_permissions.dedup();
+
+        Self {
+            username: user.username.clone(),
+            permissions,
+            group_permissions,
+        }
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.group_permissions.contains(&"admin".to_string())
+    }
+
     pub fn has_permission(&self, permission: &str) -> bool {
-        self.permissions.contains(&permission.to_string())
-            || self.group_permissions.contains(&"admin".to_string())
+        self.permissions
+            .iter()
+            .any(|grant| grant_matches(grant, permission))
+            || self.is_admin()
     }
 
     pub fn has_read_permission(&self) -> bool {
         self.has_permission("read")
     }
 
+    /// Read access to a single repository, granted by `read` or `read:<repository>`
+    pub fn has_repository_read_permission(&self, repository_id: &str) -> bool {
+        self.has_read_permission() || self.has_permission(&format!("read:{}", repository_id))
+    }
+
     pub fn has_write_permission(&self, repository_id: &str) -> bool {
         self.has_permission(&format!("write:{}", repository_id))
     }
@@ -25,3 +86,69 @@ impl UserPermissions {
         self.has_permission(&format!("delete:{}", repository_id))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn perms(permissions: &[&str]) -> UserPermissions {
+        UserPermissions {
+            username: "user".to_string(),
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            group_permissions: vec!["user".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_grant_matches() {
+        assert!(grant_matches("write:repo1", "write:repo1"));
+        assert!(!grant_matches("write:repo1", "write:repo10"));
+        assert!(grant_matches("write:*", "write:repo1"));
+        assert!(!grant_matches("write:*", "delete:repo1"));
+        assert!(grant_matches("write:nlp-*", "write:nlp-sentiment"));
+        assert!(!grant_matches("write:nlp-*", "write:cv-detection"));
+        assert!(grant_matches("*:nlp-*", "delete:nlp-sentiment"));
+        assert!(grant_matches("write:*-prod", "write:nlp-prod"));
+        assert!(!grant_matches("write:*-prod", "write:nlp-prod-backup"));
+        assert!(grant_matches("*", "read"));
+    }
+
+    #[test]
+    fn test_wildcard_permissions() {
+        let perms = perms(&["read", "write:nlp-*", "delete:nlp-scratch"]);
+
+        assert!(perms.has_read_permission());
+        assert!(perms.has_write_permission("nlp-sentiment"));
+        assert!(!perms.has_write_permission("cv-detection"));
+        assert!(perms.has_delete_permission("nlp-scratch"));
+        assert!(!perms.has_delete_permission("nlp-sentiment"));
+    }
+
+    #[test]
+    fn test_resolve_group_permissions() {
+        let user = User::new(
+            "user".to_string(),
+            "hash".to_string(),
+            Some(vec!["read".to_string()]),
+            None,
+        );
+        let groups = vec![
+            Group::new("nlp".to_string(), vec!["write:nlp-*".to_string()]),
+            Group::new(
+                "platform".to_string(),
+                vec!["read".to_string(), "delete:*".to_string()],
+            ),
+        ];
+
+        let perms = UserPermissions::resolve(&user, &groups);
+        assert_eq!(perms.permissions, vec!["delete:*", "read", "write:nlp-*"]);
+        assert_eq!(perms.group_permissions, vec!["nlp", "platform", "user"]);
+        assert!(perms.has_write_permission("nlp-sentiment"));
+        assert!(perms.has_delete_permission("cv-detection"));
+
+        // membership of a group named admin grants everything
+        let admins = vec![Group::new("admin".to_string(), vec![])];
+        let perms = UserPermissions::resolve(&user, &admins);
+        assert!(perms.has_write_permission("cv-detection"));
+    }
+}
@@ -126,6 +126,12 @@ pub enum SqlError {
 
     #[error("Failed to connect to the database - {0}")]
     ConnectionError(String),
+
+    #[error("Not found: {0}")]
+    NotFoundError(String),
+
+    #[error("Conflict: {0}")]
+    ConflictError(String),
 }
 
 #[derive(Error, Debug)]
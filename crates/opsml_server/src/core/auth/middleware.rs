@@ -9,14 +9,10 @@ use axum::{
 };
 use axum_extra::extract::cookie::CookieJar;
 use opsml_auth::permission::UserPermissions;
-use serde::Serialize;
+use opsml_error::error::SqlError;
+use opsml_sql::base::SqlClient;
 use std::sync::Arc;
-
-#[derive(Debug, Serialize)]
-pub struct ErrorResponse {
-    pub status: &'static str,
-    pub message: String,
-}
+use tracing::error;
 
 pub async fn auth_api_middleware(
     cookie_jar: CookieJar,
@@ -82,6 +78,39 @@ pub async fn auth_api_middleware(
         }
     };
 
+    // tokens of deactivated or deleted users stop working before they expire
+    let active = match state.user_status.get(&auth_middleware.username) {
+        Some(active) => active,
+        None => {
+            let active = match state.sql_client.get_user(&auth_middleware.username).await {
+                Ok(user) => user.active,
+                Err(SqlError::NotFoundError(_)) => false,
+                Err(e) => {
+                    error!("Failed to check user status: {}", e);
+                    return Err((
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        Json(AuthError {
+                            error: "Service Unavailable".to_string(),
+                            message: "Failed to check user status".to_string(),
+                        }),
+                    ));
+                }
+            };
+            state.user_status.insert(&auth_middleware.username, active);
+            active
+        }
+    };
+
+    if !active {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(AuthError {
+                error: "Unauthorized".to_string(),
+                message: "User is inactive".to_string(),
+            }),
+        ));
+    }
+
     // add the auth middleware to the request extensions
     req.extensions_mut().insert(auth_middleware);
 
@@ -1,3 +1,5 @@
 pub mod middleware;
+pub mod permission;
 pub mod route;
 pub mod schema;
+pub mod status;
@@ -0,0 +1,147 @@
+use crate::core::error::permission_denied;
+use crate::core::state::AppState;
+use axum::{http::StatusCode, Json};
+use opsml_auth::permission::UserPermissions;
+use opsml_sql::base::SqlClient;
+use opsml_types::{CardQueryArgs, CardSQLTableNames};
+use opsml_utils::utils::is_valid_uuid4;
+use tracing::error;
+
+/// Access a route needs on the repository a card or run belongs to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RepositoryAccess {
+    Read,
+    Write,
+    Delete,
+}
+
+impl RepositoryAccess {
+    pub fn as_str(&self) -> &str {
+        match self {
+            RepositoryAccess::Read => "read",
+            RepositoryAccess::Write => "write",
+            RepositoryAccess::Delete => "delete",
+        }
+    }
+
+    fn is_granted(&self, perms: &UserPermissions, repository: &str) -> bool {
+        match self {
+            RepositoryAccess::Read => perms.has_repository_read_permission(repository),
+            RepositoryAccess::Write => perms.has_write_permission(repository),
+            RepositoryAccess::Delete => perms.has_delete_permission(repository),
+        }
+    }
+}
+
+/// Reads that span repositories, such as search and registry stats, need the global read permission
+pub fn check_read_access(
+    state: &AppState,
+    perms: &UserPermissions,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if !state.config.opsml_auth || perms.has_read_permission() {
+        return Ok(());
+    }
+
+    Err(permission_denied(&perms.username, "read", None))
+}
+
+/// User and group administration is limited to members of the admin group
+pub fn check_admin(
+    state: &AppState,
+    perms: &UserPermissions,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if !state.config.opsml_auth || perms.is_admin() {
+        return Ok(());
+    }
+
+    Err(permission_denied(&perms.username, "admin", None))
+}
+
+/// Check access to a repository. Reads without a repository fall back to the global read permission
+pub fn check_repository_access(
+    state: &AppState,
+    perms: &UserPermissions,
+    access: RepositoryAccess,
+    repository: Option<&str>,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if !state.config.opsml_auth {
+        return Ok(());
+    }
+
+    let granted = match repository {
+        Some(repository) => access.is_granted(perms, repository),
+        None if access == RepositoryAccess::Read => perms.has_read_permission(),
+        None => false,
+    };
+
+    if granted {
+        return Ok(());
+    }
+
+    Err(permission_denied(
+        &perms.username,
+        access.as_str(),
+        repository,
+    ))
+}
+
+/// Check access to the repository of a stored card, including cards in the trash.
+/// Unknown or malformed uids are not found, since there is no repository to grant access on
+pub async fn check_card_access(
+    state: &AppState,
+    perms: &UserPermissions,
+    access: RepositoryAccess,
+    table: &CardSQLTableNames,
+    uid: &str,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if !state.config.opsml_auth {
+        return Ok(());
+    }
+
+    if is_valid_uuid4(uid).is_err() {
+        return Err(card_not_found(uid));
+    }
+
+    let query_args = CardQueryArgs {
+        uid: Some(uid.to_string()),
+        include_deleted: true,
+        ..Default::default()
+    };
+
+    let card = state
+        .sql_client
+        .query_cards(table, &query_args)
+        .await
+        .map_err(|e| {
+            error!("Failed to get card repository: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?
+        .to_values()
+        .into_iter()
+        .next();
+
+    match card.as_ref().and_then(|card| card["repository"].as_str()) {
+        Some(repository) => check_repository_access(state, perms, access, Some(repository)),
+        None => Err(card_not_found(uid)),
+    }
+}
+
+fn card_not_found(uid: &str) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({ "error": format!("Card {} not found", uid) })),
+    )
+}
+
+/// Check access to the repository of the run card behind a run uid
+pub async fn check_run_access(
+    state: &AppState,
+    perms: &UserPermissions,
+    access: RepositoryAccess,
+    run_uid: &str,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    check_card_access(state, perms, access, &CardSQLTableNames::Run, run_uid).await
+}
@@ -2,12 +2,41 @@ use crate::core::state::AppState;
 use anyhow::{Context, Result};
 /// Route for debugging information
 use axum::extract::State;
-use axum::{http::header, http::header::HeaderMap, http::StatusCode, routing::get, Json, Router};
+use axum::{
+    http::header,
+    http::header::HeaderMap,
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use opsml_auth::permission::UserPermissions;
+use opsml_error::error::SqlError;
 use opsml_sql::base::SqlClient;
-use opsml_types::JwtToken;
+use opsml_sql::schemas::schema::{RefreshTokenFamily, User};
+use opsml_types::{JwtToken, LogoutResponse};
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::Arc;
-use tracing::error;
+use tracing::{error, info, warn};
+
+/// Effective permissions of a user: their own grants plus those of their groups
+pub async fn get_user_permissions(
+    state: &AppState,
+    user: &User,
+) -> Result<UserPermissions, (StatusCode, Json<serde_json::Value>)> {
+    let groups = state
+        .sql_client
+        .get_user_groups(&user.username)
+        .await
+        .map_err(|e| {
+            error!("Failed to get user groups: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?;
+
+    Ok(UserPermissions::resolve(user, &groups))
+}
 
 /// Route for the login endpoint when using the API
 ///
@@ -38,15 +67,25 @@ pub async fn api_login_handler(
         .expect("Failed to convert Password to string")
         .to_string();
 
-    // get user from database
-    let mut user = state.sql_client.get_user(&username).await.map_err(|e| {
+    // get user from database. Unknown users get the same response as a wrong password
+    let user = state.sql_client.get_user(&username).await.map_err(|e| {
         error!("Failed to get user from database: {}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({})),
-        )
+        match e {
+            SqlError::NotFoundError(_) => (StatusCode::UNAUTHORIZED, Json(serde_json::json!({}))),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            ),
+        }
     })?;
 
+    if !user.active {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({ "error": "User is inactive" })),
+        ));
+    }
+
     // check if password is correct
     state
         .auth_manager
@@ -56,26 +95,161 @@ pub async fn api_login_handler(
             (StatusCode::UNAUTHORIZED, Json(serde_json::json!({})))
         })?;
 
-    // generate JWT token
-    let jwt_token = state.auth_manager.generate_jwt(&user);
-    let refresh_token = state.auth_manager.generate_refresh_token(&user);
+    let jwt_token = issue_tokens(&state, &user, None).await?;
+
+    Ok(Json(jwt_token))
+}
 
-    user.refresh_token = Some(refresh_token);
+/// Extract the bearer token from the authorization header
+fn get_bearer_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|auth_header| auth_header.to_str().ok())
+        .and_then(|auth_value| {
+            auth_value
+                .strip_prefix("Bearer ")
+                .map(|token| token.to_owned())
+        })
+}
 
-    // set refresh token in db
-    state.sql_client.update_user(&user).await.map_err(|e| {
-        error!("Failed to set refresh token in database: {}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({})),
-        )
+fn unauthorized(message: &str) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(serde_json::json!({ "error": message })),
+    )
+}
+
+fn refresh_token_error(e: SqlError) -> (StatusCode, Json<serde_json::Value>) {
+    error!("Failed to access refresh tokens in database: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({})),
+    )
+}
+
+/// Revoke a single refresh token family, leaving the user's other sessions alone
+async fn revoke_refresh_family(
+    state: &AppState,
+    family: &str,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    state
+        .sql_client
+        .delete_refresh_token(family)
+        .await
+        .map_err(refresh_token_error)
+}
+
+/// Issue a new access token and refresh token for the user. A login starts a new refresh
+/// token family. On refresh, `rotated_from` is the stored family the presented token was
+/// verified against, and the new token only replaces it if no concurrent refresh has
+/// rotated it first
+async fn issue_tokens(
+    state: &AppState,
+    user: &User,
+    rotated_from: Option<&RefreshTokenFamily>,
+) -> Result<JwtToken, (StatusCode, Json<serde_json::Value>)> {
+    // group changes apply from the next login or refresh
+    let permissions = get_user_permissions(state, user).await?;
+    let jwt_token = state.auth_manager.generate_jwt(&permissions);
+
+    let refresh_token = match rotated_from {
+        Some(stored) => {
+            let refresh_token = state
+                .auth_manager
+                .generate_refresh_token(user, &stored.family);
+            let hashed_token = state.auth_manager.hash_refresh_token(&refresh_token);
+
+            let rotated = state
+                .sql_client
+                .rotate_refresh_token(&stored.family, &stored.token_hash, &hashed_token)
+                .await
+                .map_err(refresh_token_error)?;
+
+            // the same token was used twice at once, which is reuse like any other
+            if !rotated {
+                warn!(
+                    "Concurrent refresh token reuse detected for user {}, revoking token family",
+                    user.username
+                );
+                revoke_refresh_family(state, &stored.family).await?;
+
+                return Err(unauthorized("Refresh token has been revoked"));
+            }
+
+            refresh_token
+        }
+        None => {
+            let family = state.auth_manager.generate_refresh_family();
+            let refresh_token = state.auth_manager.generate_refresh_token(user, &family);
+            let hashed_token = state.auth_manager.hash_refresh_token(&refresh_token);
+
+            state
+                .sql_client
+                .insert_refresh_token(&RefreshTokenFamily::new(
+                    family,
+                    user.username.clone(),
+                    hashed_token,
+                ))
+                .await
+                .map_err(refresh_token_error)?;
+
+            refresh_token
+        }
+    };
+
+    Ok(JwtToken {
+        token: jwt_token,
+        refresh_token: Some(refresh_token),
+    })
+}
+
+/// Resolve the user a refresh token was issued to and the stored family it belongs to.
+/// The token must be signed with the refresh secret, unexpired, and its family not revoked
+async fn get_refresh_token_session(
+    state: &AppState,
+    refresh_token: &str,
+) -> Result<(User, RefreshTokenFamily), (StatusCode, Json<serde_json::Value>)> {
+    let claims = state
+        .auth_manager
+        .validate_refresh_token(refresh_token)
+        .map_err(|e| {
+            error!("Failed to validate refresh token: {}", e);
+            unauthorized("Invalid refresh token")
+        })?;
+
+    let family = claims
+        .family
+        .ok_or_else(|| unauthorized("Invalid refresh token"))?;
+
+    let user = state.sql_client.get_user(&claims.sub).await.map_err(|e| {
+        error!("Failed to get user from database: {}", e);
+        match e {
+            SqlError::NotFoundError(_) => unauthorized("Invalid refresh token"),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            ),
+        }
     })?;
 
-    Ok(Json(JwtToken { token: jwt_token }))
+    let stored = state
+        .sql_client
+        .get_refresh_token(&family)
+        .await
+        .map_err(refresh_token_error)?
+        .filter(|stored| stored.username == user.username)
+        .ok_or_else(|| unauthorized("Refresh token has been revoked"))?;
+
+    Ok((user, stored))
 }
 
 /// Route for the refresh token endpoint when using the API
 ///
+/// The refresh token is passed as the bearer token. Every refresh token can be used once:
+/// a successful refresh rotates it, and presenting a token that was already rotated out
+/// revokes its family so a stolen token cannot keep that session alive. Other sessions of
+/// the same user are not affected
+///
 /// # Parameters
 ///
 /// - `state` - The application state
@@ -83,63 +257,74 @@ pub async fn api_login_handler(
 ///
 /// # Returns
 ///
-/// Returns a `Result` containing either the JWT token or an error
+/// Returns a `Result` containing either the new JWT and refresh tokens or an error
 pub async fn api_refresh_token_handler(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
 ) -> Result<Json<JwtToken>, (StatusCode, Json<serde_json::Value>)> {
-    let bearer_token = headers
-        .get(header::AUTHORIZATION)
-        .and_then(|auth_header| auth_header.to_str().ok())
-        .and_then(|auth_value| {
-            auth_value
-                .strip_prefix("Bearer ")
-                .map(|token| token.to_owned())
-        });
-
-    if let Some(bearer_token) = bearer_token {
-        // validate the refresh token
-        let claims = state
-            .auth_manager
-            .decode_jwt_without_validation(&bearer_token)
-            .map_err(|e| {
-                error!("Failed to decode JWT token: {}", e);
-                (StatusCode::UNAUTHORIZED, Json(serde_json::json!({})))
-            })?;
-
-        // get user from database
-        let mut user = state.sql_client.get_user(&claims.sub).await.map_err(|e| {
-            error!("Failed to get user from database: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({})),
-            )
-        })?;
+    let refresh_token =
+        get_bearer_token(&headers).ok_or_else(|| unauthorized("No refresh token found"))?;
+
+    let (user, stored) = get_refresh_token_session(&state, &refresh_token).await?;
 
-        // generate JWT token
-        let jwt_token = state.auth_manager.generate_jwt(&user);
+    if !user.active {
+        return Err(unauthorized("User is inactive"));
+    }
 
-        // generate refresh token
-        let refresh_token = state.auth_manager.generate_refresh_token(&user);
+    if state
+        .auth_manager
+        .validate_stored_refresh_token(&stored, &refresh_token)
+        .is_err()
+    {
+        // a validly signed token that is not the latest of its family was already used
+        warn!(
+            "Refresh token reuse detected for user {}, revoking token family",
+            user.username
+        );
+        revoke_refresh_family(&state, &stored.family).await?;
 
-        user.refresh_token = Some(refresh_token);
+        return Err(unauthorized("Refresh token has been revoked"));
+    }
 
-        // set refresh token in db
-        state.sql_client.update_user(&user).await.map_err(|e| {
-            error!("Failed to set refresh token in database: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({})),
-            )
+    let jwt_token = issue_tokens(&state, &user, Some(&stored)).await?;
+
+    Ok(Json(jwt_token))
+}
+
+/// Route for the logout endpoint when using the API
+///
+/// Revokes the refresh token family of the bearer refresh token, which must be the latest
+/// one issued in it. Access tokens already issued stay valid until they expire
+///
+/// # Parameters
+///
+/// - `state` - The application state
+/// - `headers` - The headers from the request
+///
+/// # Returns
+///
+/// Returns a `Result` containing either the logout response or an error
+pub async fn api_logout_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<LogoutResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let refresh_token =
+        get_bearer_token(&headers).ok_or_else(|| unauthorized("No refresh token found"))?;
+
+    let (user, stored) = get_refresh_token_session(&state, &refresh_token).await?;
+
+    state
+        .auth_manager
+        .validate_stored_refresh_token(&stored, &refresh_token)
+        .map_err(|e| {
+            error!("Failed to validate stored refresh token: {}", e);
+            unauthorized("Invalid refresh token")
         })?;
 
-        Ok(Json(JwtToken { token: jwt_token }))
-    } else {
-        Err((
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({ "error": "No refresh token found" })),
-        ))
-    }
+    revoke_refresh_family(&state, &stored.family).await?;
+    info!("User {} logged out", user.username);
+
+    Ok(Json(LogoutResponse { logged_out: true }))
 }
 
 pub async fn get_auth_router(prefix: &str) -> Result<Router<Arc<AppState>>> {
@@ -153,6 +338,10 @@ pub async fn get_auth_router(prefix: &str) -> Result<Router<Arc<AppState>>> {
                 &format!("{}/auth/api/refresh", prefix),
                 get(api_refresh_token_handler),
             )
+            .route(
+                &format!("{}/auth/api/logout", prefix),
+                post(api_logout_handler),
+            )
     }));
 
     match result {
@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// How long a looked up active flag is trusted before the user is read again
+const USER_STATUS_TTL: Duration = Duration::from_secs(60);
+
+/// Active flags of recently seen users, so the auth middleware does not read the user
+/// on every request. The user routes invalidate entries when they change a user, and
+/// other server instances pick the change up once the entry expires
+#[derive(Default)]
+pub struct UserStatusCache {
+    entries: RwLock<HashMap<String, (bool, Instant)>>,
+}
+
+impl UserStatusCache {
+    pub fn get(&self, username: &str) -> Option<bool> {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+
+        entries
+            .get(username)
+            .filter(|(_, checked_at)| checked_at.elapsed() < USER_STATUS_TTL)
+            .map(|(active, _)| *active)
+    }
+
+    pub fn insert(&self, username: &str, active: bool) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+
+        entries.retain(|_, (_, checked_at)| checked_at.elapsed() < USER_STATUS_TTL);
+        entries.insert(username.to_string(), (active, Instant::now()));
+    }
+
+    pub fn invalidate(&self, username: &str) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+
+        entries.remove(username);
+    }
+}
@@ -1,2 +1,3 @@
+pub mod purge;
 pub mod route;
 pub mod schema;
@@ -0,0 +1,65 @@
+use crate::core::state::AppState;
+use anyhow::Result;
+use opsml_sql::base::{SqlClient, CARD_REGISTRIES};
+use opsml_utils::utils::get_utc_datetime;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// How often the trash is checked for cards past their retention
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Permanently remove cards that have been in the trash longer than the configured
+/// retention, along with their storage prefix
+///
+/// # Returns
+///
+/// * `usize` - The number of purged cards
+pub async fn purge_trash(state: &AppState) -> Result<usize> {
+    let cutoff =
+        get_utc_datetime() - chrono::Duration::days(state.config.opsml_trash_retention_days);
+    let mut purged = 0;
+
+    for (_, table) in CARD_REGISTRIES.iter() {
+        let cards = state
+            .sql_client
+            .get_deleted_cards(table, Some(cutoff))
+            .await?;
+
+        for card in cards {
+            // artifacts are stored under {table}/{repository}/{name}/v{version}
+            let uri = format!(
+                "{}/{}/{}/v{}",
+                table, card.repository, card.name, card.version
+            );
+            let rpath = Path::new(&uri);
+
+            if state.storage_client.exists(rpath).await? {
+                state.storage_client.rm(rpath, true).await?;
+            }
+
+            state.sql_client.purge_card(table, &card.uid).await?;
+            purged += 1;
+        }
+    }
+
+    Ok(purged)
+}
+
+/// Spawn a background task that purges expired cards from the trash
+pub fn spawn_trash_purge(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            match purge_trash(&state).await {
+                Ok(0) => {}
+                Ok(purged) => info!("Purged {} cards from the trash", purged),
+                Err(e) => error!("Failed to purge trash: {}", e),
+            }
+        }
+    });
+}
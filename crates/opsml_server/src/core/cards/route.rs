@@ -1,27 +1,44 @@
-use crate::core::cards::schema::{QueryPageResponse, RegistryStatsResponse};
+use crate::core::auth::permission::{
+    check_card_access, check_read_access, check_repository_access, RepositoryAccess,
+};
+use crate::core::cards::schema::{
+    CardAliasHistoryResponse, CardAliasResponse, CardAliasesResponse, CardHistoryResponse,
+    CardSearchResponse, DeletedCardsResponse, LineageResponse, ListCardResponse, QueryPageResponse,
+    RegistryStatsResponse,
+};
 use crate::core::state::AppState;
 use anyhow::{Context, Result};
 use axum::{
-    extract::{Query, State},
+    extract::{Query, RawQuery, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     routing::{delete, get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
-use opsml_sql::base::SqlClient;
+use chrono::Utc;
+use opsml_auth::permission::UserPermissions;
+use opsml_error::error::SqlError;
+use opsml_sql::base::{SqlClient, SqlTransaction};
+use opsml_sql::enums::client::SqlTransactionEnum;
 use opsml_sql::schemas::schema::{
-    AuditCardRecord, Card, CardResults, DataCardRecord, ModelCardRecord, PipelineCardRecord,
-    ProjectCardRecord, RunCardRecord,
+    AuditCardRecord, Card, CardAlias, CardHistoryRecord, CardResults, DataCardRecord,
+    ModelCardRecord, PipelineCardRecord, ProjectCardRecord, RunCardRecord, SummaryCursor,
 };
 use opsml_types::{
-    CardQueryArgs, CardSQLTableNames, CardVersionRequest, CardVersionResponse, ClientCard,
-    CreateCardRequest, CreateCardResponse, ListCardRequest, QueryPageRequest, RegistryStatsRequest,
-    RepositoryRequest, RepositoryResponse, UidRequest, UidResponse, UpdateCardRequest,
-    UpdateCardResponse,
+    CardAliasRequest, CardAliasesRequest, CardHistoryRequest, CardQueryArgs, CardSQLTableNames,
+    CardSearchRequest, CardStatus, CardStatusRequest, CardStatusResponse, CardVersionRequest,
+    CardVersionResponse, CardYankRequest, CardYankResponse, ClientCard, CreateCardRequest,
+    CreateCardResponse, DeletedCardsRequest, LineageDirection, LineageRequest, ListCardRequest,
+    QueryPageRequest, RegistryStatsRequest, RepositoryRequest, RepositoryResponse,
+    RepositoryVersionScheme, ResolveCardAliasRequest, UidRequest, UidResponse, UpdateCardRequest,
+    UpdateCardResponse, VersionScheme, VersionSchemeRequest, MAX_LINEAGE_DEPTH, REGISTRY_PAGE_SIZE,
 };
+use opsml_utils::calver::{parse_card_version, CalVer};
 use opsml_utils::semver::{VersionArgs, VersionValidator};
+use opsml_utils::utils::is_valid_uuid4;
 use semver::Version;
 use sqlx::types::Json as SqlxJson;
+use std::collections::HashSet;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::Arc;
 use tracing::error;
@@ -29,9 +46,11 @@ use tracing::error;
 /// Route for checking if a card UID exists
 pub async fn check_card_uid(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     params: Query<UidRequest>,
 ) -> Result<Json<UidResponse>, (StatusCode, Json<serde_json::Value>)> {
     let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+
     let exists = state
         .sql_client
         .check_uid_exists(&params.uid, &table)
@@ -44,15 +63,23 @@ pub async fn check_card_uid(
             )
         })?;
 
+    // free uids belong to no repository, so only existing cards need access
+    if exists {
+        check_card_access(&state, &perms, RepositoryAccess::Read, &table, &params.uid).await?;
+    }
+
     Ok(Json(UidResponse { exists }))
 }
 
 /// Get card respositories
 pub async fn get_card_repositories(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     params: Query<RepositoryRequest>,
 ) -> Result<Json<RepositoryResponse>, (StatusCode, Json<serde_json::Value>)> {
     let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_read_access(&state, &perms)?;
+
     let repos = state
         .sql_client
         .get_unique_repository_names(&table)
@@ -73,9 +100,12 @@ pub async fn get_card_repositories(
 /// query stats page
 pub async fn get_registry_stats(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     params: Query<RegistryStatsRequest>,
 ) -> Result<Json<RegistryStatsResponse>, (StatusCode, Json<serde_json::Value>)> {
     let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_read_access(&state, &perms)?;
+
     let stats = state
         .sql_client
         .query_stats(&table, params.search_term.as_deref())
@@ -94,9 +124,25 @@ pub async fn get_registry_stats(
 // query page
 pub async fn get_page(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     params: Query<QueryPageRequest>,
 ) -> Result<Json<QueryPageResponse>, (StatusCode, Json<serde_json::Value>)> {
     let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Read,
+        params.repository.as_deref(),
+    )?;
+
+    // cursors are keyed on updated_at, so they can't continue a page ranked by another column
+    if params.cursor.is_some() && params.sort_by.is_some() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "sort_by cannot be combined with a cursor" })),
+        ));
+    }
+
     let sort_by = &params.sort_by.clone().unwrap_or("updated_at".to_string());
     let page = params.page.unwrap_or(0);
     let summaries = state
@@ -104,8 +150,10 @@ pub async fn get_page(
         .query_page(
             sort_by,
             page,
+            params.cursor.as_deref(),
             params.search_term.as_deref(),
             params.repository.as_deref(),
+            params.status.as_ref(),
             &table,
         )
         .await
@@ -117,14 +165,92 @@ pub async fn get_page(
             )
         })?;
 
-    Ok(Json(QueryPageResponse { summaries }))
+    let next_cursor = match params.cursor {
+        Some(_) => SummaryCursor::next_cursor(&summaries, REGISTRY_PAGE_SIZE as usize),
+        None => None,
+    };
+
+    Ok(Json(QueryPageResponse {
+        summaries,
+        next_cursor,
+    }))
+}
+
+/// Versioning scheme for a request. An explicit scheme wins over the repository setting
+async fn resolve_version_scheme(
+    state: &AppState,
+    repository: Option<&str>,
+    requested: Option<&VersionScheme>,
+) -> Result<VersionScheme, (StatusCode, Json<serde_json::Value>)> {
+    match (requested, repository) {
+        (Some(version_scheme), _) => Ok(version_scheme.clone()),
+        (None, Some(repository)) => state
+            .sql_client
+            .get_version_scheme(repository)
+            .await
+            .map_err(|e| {
+                error!("Failed to get version scheme: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({})),
+                )
+            }),
+        (None, None) => Ok(VersionScheme::default()),
+    }
+}
+
+/// Next calendar version of a card. Counts up from the latest version registered today
+async fn get_next_calver(
+    state: &AppState,
+    table: &CardSQLTableNames,
+    params: &CardVersionRequest,
+) -> Result<String, (StatusCode, Json<serde_json::Value>)> {
+    let versions = state
+        .sql_client
+        .get_versions(table, &params.name, &params.repository, None)
+        .await
+        .map_err(|e| {
+            error!("Failed to get versions: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?;
+
+    let latest = versions
+        .iter()
+        .filter_map(|version| Version::parse(version).ok())
+        .filter_map(|version| CalVer::from_semver(&version))
+        .max();
+
+    Ok(CalVer::next(latest.as_ref(), Utc::now().date_naive()).to_string())
 }
 
 pub async fn get_next_version(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     params: Query<CardVersionRequest>,
 ) -> Result<Json<CardVersionResponse>, (StatusCode, Json<serde_json::Value>)> {
     let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Read,
+        Some(&params.repository),
+    )?;
+
+    let version_scheme = resolve_version_scheme(
+        &state,
+        Some(&params.repository),
+        params.version_scheme.as_ref(),
+    )
+    .await?;
+
+    if version_scheme == VersionScheme::CalVer {
+        let version = get_next_calver(&state, &table, &params).await?;
+        return Ok(Json(CardVersionResponse { version }));
+    }
+
     let version_type = params.version_type.clone();
     let pre_tag = params.pre_tag.clone();
     let build_tag = params.build_tag.clone();
@@ -173,20 +299,67 @@ pub async fn get_next_version(
     }))
 }
 
+/// Route for listing cards
+///
+/// Parsed with serde_qs (instead of axum's Query) so that nested `tags` and
+/// `tag_filters` can be passed in the query string
 pub async fn list_cards(
     State(state): State<Arc<AppState>>,
-    params: Query<ListCardRequest>,
-) -> Result<Json<CardResults>, (StatusCode, Json<serde_json::Value>)> {
+    Extension(perms): Extension<UserPermissions>,
+    RawQuery(query): RawQuery,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    let params: ListCardRequest = serde_qs::Config::new(5, false)
+        .deserialize_str(query.as_deref().unwrap_or_default())
+        .map_err(|e| {
+            error!("Failed to parse list card request: {}", e);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": format!("Invalid query: {}", e) })),
+            )
+        })?;
+
     let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Read,
+        params.repository.as_deref(),
+    )?;
+
+    // the repository scheme only matters when filtering by version
+    let version_scheme = match &params.version {
+        Some(_) => Some(
+            resolve_version_scheme(
+                &state,
+                params.repository.as_deref(),
+                params.version_scheme.as_ref(),
+            )
+            .await?,
+        ),
+        None => None,
+    };
+
+    // cursor paging always walks (created_at, uid) order
+    let paginate = params.paginate || params.cursor.is_some();
     let card_args = CardQueryArgs {
-        name: params.name.clone(),
-        repository: params.repository.clone(),
-        version: params.version.clone(),
-        uid: params.uid.clone(),
-        max_date: params.max_date.clone(),
-        tags: params.tags.clone(),
+        name: params.name,
+        repository: params.repository,
+        version: params.version,
+        uid: params.uid,
+        max_date: params.max_date,
+        tags: params.tags,
+        tag_filters: params.tag_filters,
         limit: params.limit,
-        sort_by_timestamp: params.sort_by_timestamp,
+        sort_by_timestamp: if paginate {
+            Some(true)
+        } else {
+            params.sort_by_timestamp
+        },
+        cursor: params.cursor,
+        include_deleted: params.include_deleted,
+        alias: params.alias,
+        status: params.status,
+        version_scheme,
     };
 
     let cards = state
@@ -201,22 +374,272 @@ pub async fn list_cards(
             )
         })?;
 
-    Ok(Json(cards))
+    if !paginate {
+        return Ok(Json(cards).into_response());
+    }
+
+    let next_cursor = cards.next_cursor(&card_args);
+
+    Ok(Json(ListCardResponse { cards, next_cursor }).into_response())
+}
+
+/// Full-text search across all registries
+pub async fn search_cards(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    params: Query<CardSearchRequest>,
+) -> Result<Json<CardSearchResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_read_access(&state, &perms)?;
+
+    let results = state
+        .sql_client
+        .search_cards(&params.search_term, params.limit.unwrap_or(50))
+        .await
+        .map_err(|e| {
+            error!("Failed to search cards: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?;
+
+    Ok(Json(CardSearchResponse { results }))
+}
+
+/// Lineage graph of cards upstream and/or downstream of a card. Cards in repositories the
+/// caller cannot read are left out, along with their edges
+pub async fn get_card_lineage(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    params: Query<LineageRequest>,
+) -> Result<Json<LineageResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_card_access(&state, &perms, RepositoryAccess::Read, &table, &params.uid).await?;
+
+    let depth = params.depth.unwrap_or(3).clamp(1, MAX_LINEAGE_DEPTH);
+    let internal_error = |e: SqlError| {
+        error!("Failed to get card lineage: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    };
+
+    let mut edges = Vec::new();
+    if params.direction != LineageDirection::Downstream {
+        edges.extend(
+            state
+                .sql_client
+                .get_upstream_lineage(&params.uid, depth)
+                .await
+                .map_err(internal_error)?,
+        );
+    }
+    if params.direction != LineageDirection::Upstream {
+        edges.extend(
+            state
+                .sql_client
+                .get_downstream_lineage(&params.uid, depth)
+                .await
+                .map_err(internal_error)?,
+        );
+    }
+
+    let mut node_keys: Vec<(String, String)> = edges
+        .iter()
+        .flat_map(|edge| {
+            [
+                (edge.upstream_type.clone(), edge.upstream_uid.clone()),
+                (edge.downstream_type.clone(), edge.downstream_uid.clone()),
+            ]
+        })
+        .collect();
+    node_keys.sort();
+    node_keys.dedup();
+
+    let mut nodes = state
+        .sql_client
+        .get_lineage_nodes(&node_keys)
+        .await
+        .map_err(internal_error)?;
+
+    if state.config.opsml_auth {
+        let hidden: HashSet<String> = nodes
+            .iter()
+            .filter(|node| !perms.has_repository_read_permission(&node.repository))
+            .map(|node| node.uid.clone())
+            .collect();
+
+        nodes.retain(|node| !hidden.contains(&node.uid));
+        edges.retain(|edge| {
+            !hidden.contains(&edge.upstream_uid) && !hidden.contains(&edge.downstream_uid)
+        });
+    }
+
+    Ok(Json(LineageResponse { nodes, edges }))
+}
+
+/// Start the transaction a card change and its history entry are written in
+async fn begin_transaction(
+    state: &AppState,
+) -> Result<SqlTransactionEnum, (StatusCode, Json<serde_json::Value>)> {
+    state.sql_client.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })
+}
+
+async fn commit_transaction(
+    tx: SqlTransactionEnum,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    tx.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })
+}
+
+/// Current state of a card as json, used as one side of a history diff
+async fn get_card_state(
+    tx: &mut SqlTransactionEnum,
+    table: &CardSQLTableNames,
+    uid: &str,
+) -> Result<Option<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let query_args = CardQueryArgs {
+        uid: Some(uid.to_string()),
+        include_deleted: true,
+        ..Default::default()
+    };
+
+    let cards = tx.query_cards(table, &query_args).await.map_err(|e| {
+        error!("Failed to get card state: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })?;
+
+    Ok(cards.to_values().into_iter().next())
+}
+
+/// Append a change to the card history
+async fn record_card_history(
+    tx: &mut SqlTransactionEnum,
+    table: &CardSQLTableNames,
+    uid: &str,
+    action: &str,
+    perms: &UserPermissions,
+    before: Option<&serde_json::Value>,
+    after: Option<&serde_json::Value>,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let record = CardHistoryRecord::new(table, uid, action, &perms.username, before, after);
+
+    tx.insert_card_history(&record).await.map_err(|e| {
+        error!("Failed to record card history: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })
+}
+
+/// A run's parent must be a registered run that is not the run itself or one of its children
+async fn validate_parent_run(
+    state: &AppState,
+    uid: &str,
+    parent_uid: Option<&str>,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let Some(parent_uid) = parent_uid else {
+        return Ok(());
+    };
+
+    let bad_request = |message: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": message })),
+        )
+    };
+
+    // query_cards rejects malformed uids, which would otherwise surface as a 500
+    if is_valid_uuid4(parent_uid).is_err() {
+        return Err(bad_request(format!("Parent run {} not found", parent_uid)));
+    }
+
+    // walk up from the new parent. Reaching the run itself would close a cycle
+    let mut visited = HashSet::new();
+    let mut ancestor = Some(parent_uid.to_string());
+
+    while let Some(current) = ancestor {
+        if current == uid {
+            return Err(bad_request(format!(
+                "Run {} cannot be nested under itself or one of its children",
+                uid
+            )));
+        }
+
+        if !visited.insert(current.clone()) {
+            break;
+        }
+
+        let args = CardQueryArgs {
+            uid: Some(current.clone()),
+            ..Default::default()
+        };
+        let runs = state
+            .sql_client
+            .query_cards(&CardSQLTableNames::Run, &args)
+            .await
+            .map_err(|e| {
+                error!("Failed to get parent run: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({})),
+                )
+            })?;
+
+        ancestor = match runs {
+            CardResults::Run(runs) => match runs.into_iter().next() {
+                Some(run) => run.parent_uid,
+                None if current == parent_uid => {
+                    return Err(bad_request(format!("Parent run {} not found", parent_uid)));
+                }
+                None => None,
+            },
+            _ => None,
+        };
+    }
+
+    Ok(())
 }
 
 pub async fn create_card(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     Json(card_request): Json<CreateCardRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     let table = CardSQLTableNames::from_registry_type(&card_request.registry_type);
 
+    // calendar versions are stored in semver form and keep their label as `version`
+    let version_label = card_request.card.version().to_string();
+    let version = parse_card_version(&version_label).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+    })?;
+
     // match on registry type
-    let card = match card_request.card {
+    let mut card = match card_request.card {
         ClientCard::Data(client_card) => {
             let server_card = DataCardRecord::new(
                 client_card.name,
                 client_card.repository,
-                client_card.version.parse().unwrap(),
+                version,
                 client_card.contact,
                 client_card.tags,
                 client_card.data_type,
@@ -231,7 +654,7 @@ pub async fn create_card(
             let server_card = ModelCardRecord::new(
                 client_card.name,
                 client_card.repository,
-                client_card.version.parse().unwrap(),
+                version,
                 client_card.contact,
                 client_card.tags,
                 client_card.datacard_uid,
@@ -250,7 +673,7 @@ pub async fn create_card(
             let server_card = ProjectCardRecord::new(
                 client_card.name,
                 client_card.repository,
-                client_card.version.parse().unwrap(),
+                version,
                 client_card.project_id,
             );
             Card::Project(server_card)
@@ -260,7 +683,7 @@ pub async fn create_card(
             let server_card = RunCardRecord::new(
                 client_card.name,
                 client_card.repository,
-                client_card.version.parse().unwrap(),
+                version,
                 client_card.contact,
                 client_card.tags,
                 client_card.datacard_uids,
@@ -269,6 +692,7 @@ pub async fn create_card(
                 client_card.project,
                 client_card.artifact_uris,
                 client_card.compute_environment,
+                client_card.parent_uid,
             );
             Card::Run(server_card)
         }
@@ -277,7 +701,7 @@ pub async fn create_card(
             let server_card = PipelineCardRecord::new(
                 client_card.name,
                 client_card.repository,
-                client_card.version.parse().unwrap(),
+                version,
                 client_card.contact,
                 client_card.tags,
                 client_card.pipeline_code_uri,
@@ -292,7 +716,7 @@ pub async fn create_card(
             let server_card = AuditCardRecord::new(
                 client_card.name,
                 client_card.repository,
-                client_card.version.parse().unwrap(),
+                version,
                 client_card.contact,
                 client_card.tags,
                 client_card.approved,
@@ -304,17 +728,59 @@ pub async fn create_card(
         }
     };
 
-    state
-        .sql_client
-        .insert_card(&table, &card)
-        .await
-        .map_err(|e| {
-            error!("Failed to insert card: {}", e);
-            (
+    card.set_version(&version_label);
+
+    if let Some(status) = &card_request.status {
+        card.set_status(status);
+    }
+
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Write,
+        Some(&card.repository()),
+    )?;
+
+    if let Card::Run(run) = &card {
+        validate_parent_run(&state, &run.uid, run.parent_uid.as_deref()).await?;
+    }
+
+    let history = CardHistoryRecord::new(
+        &table,
+        &card.uid(),
+        "insert",
+        &perms.username,
+        None,
+        Some(&card.to_value()),
+    );
+
+    // the card, its lineage and its history entry are written together or not at all
+    let mut tx = begin_transaction(&state).await?;
+
+    tx.insert_card(&table, &card).await.map_err(|e| {
+        error!("Failed to insert card: {}", e);
+        match e {
+            // another registration took the version first. Clients request a new version
+            SqlError::ConflictError(_) => (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            ),
+            _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(serde_json::json!({})),
-            )
-        })?;
+            ),
+        }
+    })?;
+
+    tx.insert_card_history(&history).await.map_err(|e| {
+        error!("Failed to record card history: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })?;
+
+    commit_transaction(tx).await?;
 
     Ok(Json(CreateCardResponse {
         registered: true,
@@ -325,15 +791,17 @@ pub async fn create_card(
 /// update card
 pub async fn update_card(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     Json(card_request): Json<UpdateCardRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     let table = CardSQLTableNames::from_registry_type(&card_request.registry_type);
 
     // Note: We can use unwrap() here because a card being updated has already been created and thus has defaults.
+    // Status is not written by updates, it only changes through the status route
     // match on registry type (all fields should be supplied)
     let card = match card_request.card {
         ClientCard::Data(client_card) => {
-            let version = Version::parse(&client_card.version).map_err(|e| {
+            let version = parse_card_version(&client_card.version).map_err(|e| {
                 error!("Failed to parse version: {}", e);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -344,6 +812,8 @@ pub async fn update_card(
             let server_card = DataCardRecord {
                 uid: client_card.uid.unwrap(),
                 created_at: client_card.created_at,
+                status: CardStatus::default().to_string(),
+                yanked: false,
                 app_env: client_card.app_env.unwrap(),
                 name: client_card.name,
                 repository: client_card.repository,
@@ -365,7 +835,7 @@ pub async fn update_card(
         }
 
         ClientCard::Model(client_card) => {
-            let version = Version::parse(&client_card.version).map_err(|e| {
+            let version = parse_card_version(&client_card.version).map_err(|e| {
                 error!("Failed to parse version: {}", e);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -376,6 +846,8 @@ pub async fn update_card(
             let server_card = ModelCardRecord {
                 uid: client_card.uid.unwrap(),
                 created_at: client_card.created_at,
+                status: CardStatus::default().to_string(),
+                yanked: false,
                 app_env: client_card.app_env.unwrap(),
                 name: client_card.name,
                 repository: client_card.repository,
@@ -400,7 +872,7 @@ pub async fn update_card(
         }
 
         ClientCard::Project(client_card) => {
-            let version = Version::parse(&client_card.version).map_err(|e| {
+            let version = parse_card_version(&client_card.version).map_err(|e| {
                 error!("Failed to parse version: {}", e);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -411,6 +883,8 @@ pub async fn update_card(
             let server_card = ProjectCardRecord {
                 uid: client_card.uid.unwrap(),
                 created_at: client_card.created_at,
+                status: CardStatus::default().to_string(),
+                yanked: false,
                 name: client_card.name,
                 repository: client_card.repository,
                 major: version.major as i32,
@@ -425,7 +899,7 @@ pub async fn update_card(
         }
 
         ClientCard::Run(client_card) => {
-            let version = Version::parse(&client_card.version).map_err(|e| {
+            let version = parse_card_version(&client_card.version).map_err(|e| {
                 error!("Failed to parse version: {}", e);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -436,6 +910,8 @@ pub async fn update_card(
             let server_card = RunCardRecord {
                 uid: client_card.uid.unwrap(),
                 created_at: client_card.created_at,
+                status: CardStatus::default().to_string(),
+                yanked: false,
                 app_env: client_card.app_env.unwrap(),
                 name: client_card.name,
                 repository: client_card.repository,
@@ -453,12 +929,13 @@ pub async fn update_card(
                 project: client_card.project,
                 artifact_uris: SqlxJson(client_card.artifact_uris.unwrap()),
                 compute_environment: SqlxJson(client_card.compute_environment.unwrap()),
+                parent_uid: client_card.parent_uid,
             };
             Card::Run(server_card)
         }
 
         ClientCard::Pipeline(client_card) => {
-            let version = Version::parse(&client_card.version).map_err(|e| {
+            let version = parse_card_version(&client_card.version).map_err(|e| {
                 error!("Failed to parse version: {}", e);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -469,6 +946,8 @@ pub async fn update_card(
             let server_card = PipelineCardRecord {
                 uid: client_card.uid.unwrap(),
                 created_at: client_card.created_at,
+                status: CardStatus::default().to_string(),
+                yanked: false,
                 app_env: client_card.app_env.unwrap(),
                 name: client_card.name,
                 repository: client_card.repository,
@@ -489,7 +968,7 @@ pub async fn update_card(
         }
 
         ClientCard::Audit(client_card) => {
-            let version = Version::parse(&client_card.version).map_err(|e| {
+            let version = parse_card_version(&client_card.version).map_err(|e| {
                 error!("Failed to parse version: {}", e);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -500,6 +979,8 @@ pub async fn update_card(
             let server_card = AuditCardRecord {
                 uid: client_card.uid.unwrap(),
                 created_at: client_card.created_at,
+                status: CardStatus::default().to_string(),
+                yanked: false,
                 app_env: client_card.app_env.unwrap(),
                 name: client_card.name,
                 repository: client_card.repository,
@@ -520,39 +1001,626 @@ pub async fn update_card(
         }
     };
 
+    // moving a card needs write access to both its current and its new repository
+    check_card_access(&state, &perms, RepositoryAccess::Write, &table, &card.uid()).await?;
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Write,
+        Some(&card.repository()),
+    )?;
+
+    if let Card::Run(run) = &card {
+        validate_parent_run(&state, &run.uid, run.parent_uid.as_deref()).await?;
+    }
+
+    // the change and its history entry are written together or not at all
+    let mut tx = begin_transaction(&state).await?;
+    let before = get_card_state(&mut tx, &table, &card.uid()).await?;
+
+    tx.update_card(&table, &card).await.map_err(|e| {
+        error!("Failed to update card: {}", e);
+        match e {
+            SqlError::ConflictError(_) => (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            ),
+        }
+    })?;
+
+    // status, yanked and created_at are not written by an update, so read back what was stored
+    let after = get_card_state(&mut tx, &table, &card.uid()).await?;
+    record_card_history(
+        &mut tx,
+        &table,
+        &card.uid(),
+        "update",
+        &perms,
+        before.as_ref(),
+        after.as_ref(),
+    )
+    .await?;
+    commit_transaction(tx).await?;
+
+    Ok(Json(UpdateCardResponse { updated: true }))
+}
+
+pub async fn delete_card(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    params: Query<UidRequest>,
+) -> Result<Json<UidResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_card_access(
+        &state,
+        &perms,
+        RepositoryAccess::Delete,
+        &table,
+        &params.uid,
+    )
+    .await?;
+
+    let mut tx = begin_transaction(&state).await?;
+    let before = get_card_state(&mut tx, &table, &params.uid).await?;
+
+    tx.delete_card(&table, &params.uid, &perms.username)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete card: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?;
+
+    record_card_history(
+        &mut tx,
+        &table,
+        &params.uid,
+        "delete",
+        &perms,
+        before.as_ref(),
+        None,
+    )
+    .await?;
+    commit_transaction(tx).await?;
+
+    Ok(Json(UidResponse { exists: false }))
+}
+
+/// Restore a card from the trash
+pub async fn restore_card(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(params): Json<UidRequest>,
+) -> Result<Json<UidResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_card_access(
+        &state,
+        &perms,
+        RepositoryAccess::Delete,
+        &table,
+        &params.uid,
+    )
+    .await?;
+
+    let mut tx = begin_transaction(&state).await?;
+    tx.restore_card(&table, &params.uid).await.map_err(|e| {
+        error!("Failed to restore card: {}", e);
+        match e {
+            SqlError::NotFoundError(_) => (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            ),
+            SqlError::ConflictError(_) => (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            ),
+        }
+    })?;
+
+    let after = get_card_state(&mut tx, &table, &params.uid).await?;
+    record_card_history(
+        &mut tx,
+        &table,
+        &params.uid,
+        "restore",
+        &perms,
+        None,
+        after.as_ref(),
+    )
+    .await?;
+    commit_transaction(tx).await?;
+
+    Ok(Json(UidResponse { exists: true }))
+}
+
+/// Move a card to a new lifecycle status
+pub async fn update_card_status(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(params): Json<CardStatusRequest>,
+) -> Result<Json<CardStatusResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_card_access(&state, &perms, RepositoryAccess::Write, &table, &params.uid).await?;
+
+    let mut tx = begin_transaction(&state).await?;
+    let before = get_card_state(&mut tx, &table, &params.uid).await?;
+
+    let previous_status = tx
+        .update_card_status(&table, &params.uid, &params.status)
+        .await
+        .map_err(|e| {
+            error!("Failed to update card status: {}", e);
+            match e {
+                SqlError::NotFoundError(_) => (
+                    StatusCode::NOT_FOUND,
+                    Json(serde_json::json!({ "error": e.to_string() })),
+                ),
+                SqlError::ConflictError(_) => (
+                    StatusCode::CONFLICT,
+                    Json(serde_json::json!({ "error": e.to_string() })),
+                ),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({})),
+                ),
+            }
+        })?;
+
+    let after = get_card_state(&mut tx, &table, &params.uid).await?;
+    record_card_history(
+        &mut tx,
+        &table,
+        &params.uid,
+        "status",
+        &perms,
+        before.as_ref(),
+        after.as_ref(),
+    )
+    .await?;
+    commit_transaction(tx).await?;
+
+    Ok(Json(CardStatusResponse {
+        uid: params.uid,
+        previous_status,
+        status: params.status,
+    }))
+}
+
+/// Yank a card version so that version ranges skip it
+pub async fn yank_card(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(params): Json<CardYankRequest>,
+) -> Result<Json<CardYankResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_card_access(&state, &perms, RepositoryAccess::Write, &table, &params.uid).await?;
+
+    let mut tx = begin_transaction(&state).await?;
+    let before = get_card_state(&mut tx, &table, &params.uid).await?;
+
+    tx.yank_card(&table, &params.uid, params.yanked)
+        .await
+        .map_err(|e| {
+            error!("Failed to yank card: {}", e);
+            match e {
+                SqlError::NotFoundError(_) => (
+                    StatusCode::NOT_FOUND,
+                    Json(serde_json::json!({ "error": e.to_string() })),
+                ),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({})),
+                ),
+            }
+        })?;
+
+    let action = if params.yanked { "yank" } else { "unyank" };
+    let after = get_card_state(&mut tx, &table, &params.uid).await?;
+    record_card_history(
+        &mut tx,
+        &table,
+        &params.uid,
+        action,
+        &perms,
+        before.as_ref(),
+        after.as_ref(),
+    )
+    .await?;
+    commit_transaction(tx).await?;
+
+    Ok(Json(CardYankResponse {
+        uid: params.uid,
+        yanked: params.yanked,
+    }))
+}
+
+/// Get the versioning scheme of a repository
+pub async fn get_version_scheme(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    params: Query<VersionSchemeRequest>,
+) -> Result<Json<RepositoryVersionScheme>, (StatusCode, Json<serde_json::Value>)> {
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Read,
+        Some(&params.repository),
+    )?;
+
+    let version_scheme = resolve_version_scheme(&state, Some(&params.repository), None).await?;
+
+    Ok(Json(RepositoryVersionScheme {
+        repository: params.repository.clone(),
+        version_scheme,
+    }))
+}
+
+/// Set the versioning scheme of a repository
+pub async fn set_version_scheme(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(params): Json<RepositoryVersionScheme>,
+) -> Result<Json<RepositoryVersionScheme>, (StatusCode, Json<serde_json::Value>)> {
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Write,
+        Some(&params.repository),
+    )?;
+
     state
         .sql_client
-        .update_card(&table, &card)
+        .set_version_scheme(&params.repository, &params.version_scheme)
         .await
         .map_err(|e| {
-            error!("Failed to update card: {}", e);
+            error!("Failed to set version scheme: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(serde_json::json!({})),
             )
         })?;
 
-    Ok(Json(UpdateCardResponse { updated: true }))
+    Ok(Json(params))
 }
 
-pub async fn delete_card(
+/// List cards in the trash
+pub async fn get_deleted_cards(
     State(state): State<Arc<AppState>>,
-    params: Query<UidRequest>,
-) -> Result<Json<UidResponse>, (StatusCode, Json<serde_json::Value>)> {
+    Extension(perms): Extension<UserPermissions>,
+    params: Query<DeletedCardsRequest>,
+) -> Result<Json<DeletedCardsResponse>, (StatusCode, Json<serde_json::Value>)> {
     let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    if params.repository.is_some() {
+        check_repository_access(
+            &state,
+            &perms,
+            RepositoryAccess::Read,
+            params.repository.as_deref(),
+        )?;
+    }
+
+    let mut cards = state
+        .sql_client
+        .get_deleted_cards(&table, None)
+        .await
+        .map_err(|e| {
+            error!("Failed to get deleted cards: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?;
+
+    match &params.repository {
+        Some(repository) => cards.retain(|card| &card.repository == repository),
+        None if state.config.opsml_auth => {
+            cards.retain(|card| perms.has_repository_read_permission(&card.repository))
+        }
+        None => {}
+    }
+
+    Ok(Json(DeletedCardsResponse { cards }))
+}
+
+/// Change history of a card, oldest first
+pub async fn get_card_history(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    params: Query<CardHistoryRequest>,
+) -> Result<Json<CardHistoryResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_card_access(&state, &perms, RepositoryAccess::Read, &table, &params.uid).await?;
+
+    let history = state
+        .sql_client
+        .get_card_history(&params.uid)
+        .await
+        .map_err(|e| {
+            error!("Failed to get card history: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?;
+
+    Ok(Json(CardHistoryResponse { history }))
+}
+
+/// Check the alias is well formed and its target is an active card of the same (repository, name)
+async fn validate_alias_target(
+    state: &AppState,
+    table: &CardSQLTableNames,
+    params: &CardAliasRequest,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    params.validate_alias().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": e })),
+        )
+    })?;
+
+    let query_args = CardQueryArgs {
+        uid: Some(params.uid.clone()),
+        ..Default::default()
+    };
+
+    let card = state
+        .sql_client
+        .query_cards(table, &query_args)
+        .await
+        .map_err(|e| {
+            error!("Failed to get alias target: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?
+        .to_values()
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": format!("Card {} not found", params.uid) })),
+            )
+        })?;
+
+    if card["repository"] != params.repository || card["name"] != params.name {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!(
+                    "Card {} does not belong to {}/{}",
+                    params.uid, params.repository, params.name
+                )
+            })),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn get_current_alias(
+    state: &AppState,
+    table: &CardSQLTableNames,
+    repository: &str,
+    name: &str,
+    alias: &str,
+) -> Result<Option<CardAlias>, (StatusCode, Json<serde_json::Value>)> {
     state
         .sql_client
-        .delete_card(&table, &params.uid)
+        .get_card_alias(table, repository, name, alias)
         .await
         .map_err(|e| {
-            error!("Failed to delete card: {}", e);
+            error!("Failed to get card alias: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })
+}
+
+async fn point_alias(
+    state: &AppState,
+    table: &CardSQLTableNames,
+    params: &CardAliasRequest,
+    perms: &UserPermissions,
+) -> Result<Json<CardAliasResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let alias = CardAlias::new(
+        table,
+        &params.repository,
+        &params.name,
+        &params.alias,
+        &params.uid,
+        &perms.username,
+    );
+
+    let previous_uid = state.sql_client.set_card_alias(&alias).await.map_err(|e| {
+        error!("Failed to set card alias: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })?;
+
+    Ok(Json(CardAliasResponse {
+        alias,
+        previous_uid,
+    }))
+}
+
+/// Create an alias. Fails with a conflict if the alias already points at another card
+pub async fn set_card_alias(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(params): Json<CardAliasRequest>,
+) -> Result<Json<CardAliasResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Write,
+        Some(&params.repository),
+    )?;
+
+    validate_alias_target(&state, &table, &params).await?;
+
+    let current = get_current_alias(
+        &state,
+        &table,
+        &params.repository,
+        &params.name,
+        &params.alias,
+    )
+    .await?;
+
+    if let Some(current) = current.filter(|current| current.uid != params.uid) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": format!(
+                    "Alias {} already points to {}. Move it instead",
+                    params.alias, current.uid
+                )
+            })),
+        ));
+    }
+
+    point_alias(&state, &table, &params, &perms).await
+}
+
+/// Move an existing alias to another card of the same (repository, name)
+pub async fn move_card_alias(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(params): Json<CardAliasRequest>,
+) -> Result<Json<CardAliasResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Write,
+        Some(&params.repository),
+    )?;
+
+    validate_alias_target(&state, &table, &params).await?;
+
+    let current = get_current_alias(
+        &state,
+        &table,
+        &params.repository,
+        &params.name,
+        &params.alias,
+    )
+    .await?;
+
+    if current.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Alias {} not found", params.alias) })),
+        ));
+    }
+
+    point_alias(&state, &table, &params, &perms).await
+}
+
+/// Resolve an alias to the card uid it points to
+pub async fn resolve_card_alias(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    params: Query<ResolveCardAliasRequest>,
+) -> Result<Json<CardAlias>, (StatusCode, Json<serde_json::Value>)> {
+    let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Read,
+        Some(&params.repository),
+    )?;
+
+    get_current_alias(
+        &state,
+        &table,
+        &params.repository,
+        &params.name,
+        &params.alias,
+    )
+    .await?
+    .map(Json)
+    .ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Alias {} not found", params.alias) })),
+        )
+    })
+}
+
+/// List the aliases of a (repository, name)
+pub async fn list_card_aliases(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    params: Query<CardAliasesRequest>,
+) -> Result<Json<CardAliasesResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Read,
+        Some(&params.repository),
+    )?;
+
+    let aliases = state
+        .sql_client
+        .get_card_aliases(&table, &params.repository, &params.name)
+        .await
+        .map_err(|e| {
+            error!("Failed to list card aliases: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(serde_json::json!({})),
             )
         })?;
 
-    Ok(Json(UidResponse { exists: false }))
+    Ok(Json(CardAliasesResponse { aliases }))
+}
+
+/// Alias moves of a (repository, name), oldest first
+pub async fn get_card_alias_history(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    params: Query<CardAliasesRequest>,
+) -> Result<Json<CardAliasHistoryResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let table = CardSQLTableNames::from_registry_type(&params.registry_type);
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Read,
+        Some(&params.repository),
+    )?;
+
+    let history = state
+        .sql_client
+        .get_card_alias_history(&table, &params.repository, &params.name)
+        .await
+        .map_err(|e| {
+            error!("Failed to get card alias history: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?;
+
+    Ok(Json(CardAliasHistoryResponse { history }))
 }
 
 pub async fn get_card_router(prefix: &str) -> Result<Router<Arc<AppState>>> {
@@ -570,9 +1638,34 @@ pub async fn get_card_router(prefix: &str) -> Result<Router<Arc<AppState>>> {
             .route(&format!("{}/card/registry/page", prefix), get(get_page))
             .route(&format!("{}/card/version", prefix), get(get_next_version))
             .route(&format!("{}/card/list", prefix), get(list_cards))
+            .route(&format!("{}/card/search", prefix), get(search_cards))
+            .route(&format!("{}/card/lineage", prefix), get(get_card_lineage))
             .route(&format!("{}/card/create", prefix), post(create_card))
             .route(&format!("{}/card/update", prefix), post(update_card))
             .route(&format!("{}/card/delete", prefix), delete(delete_card))
+            .route(&format!("{}/card/restore", prefix), post(restore_card))
+            .route(&format!("{}/card/status", prefix), post(update_card_status))
+            .route(&format!("{}/card/yank", prefix), post(yank_card))
+            .route(
+                &format!("{}/card/repository/scheme", prefix),
+                get(get_version_scheme).post(set_version_scheme),
+            )
+            .route(&format!("{}/card/trash", prefix), get(get_deleted_cards))
+            .route(&format!("{}/card/history", prefix), get(get_card_history))
+            .route(&format!("{}/card/alias", prefix), get(resolve_card_alias))
+            .route(&format!("{}/card/alias/set", prefix), post(set_card_alias))
+            .route(
+                &format!("{}/card/alias/move", prefix),
+                post(move_card_alias),
+            )
+            .route(
+                &format!("{}/card/alias/list", prefix),
+                get(list_card_aliases),
+            )
+            .route(
+                &format!("{}/card/alias/history", prefix),
+                get(get_card_alias_history),
+            )
     }));
 
     match result {
@@ -1,12 +1,60 @@
-use opsml_sql::schemas::schema::{CardSummary, QueryStats};
+use opsml_sql::schemas::schema::{
+    CardAlias, CardAliasHistoryRecord, CardHistoryRecord, CardResults, CardSearchResult,
+    CardSummary, DeletedCard, LineageEdge, LineageNode, QueryStats,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QueryPageResponse {
     pub summaries: Vec<CardSummary>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListCardResponse {
+    pub cards: CardResults,
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RegistryStatsResponse {
     pub stats: QueryStats,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CardSearchResponse {
+    pub results: Vec<CardSearchResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LineageResponse {
+    pub nodes: Vec<LineageNode>,
+    pub edges: Vec<LineageEdge>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeletedCardsResponse {
+    pub cards: Vec<DeletedCard>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CardHistoryResponse {
+    pub history: Vec<CardHistoryRecord>,
+}
+
+/// Alias after a set or move. `previous_uid` is the card the alias pointed to before
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CardAliasResponse {
+    pub alias: CardAlias,
+    pub previous_uid: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CardAliasesResponse {
+    pub aliases: Vec<CardAlias>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CardAliasHistoryResponse {
+    pub history: Vec<CardAliasHistoryRecord>,
+}
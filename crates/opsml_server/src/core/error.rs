@@ -10,3 +10,23 @@ pub fn internal_server_error<E: std::fmt::Display>(
         Json(json!({ "error": error.to_string() })),
     )
 }
+
+/// Uniform 403 body for requests outside a user's repository permissions
+pub fn permission_denied(
+    username: &str,
+    permission: &str,
+    repository: Option<&str>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let message = match repository {
+        Some(repository) => format!(
+            "User {} does not have {} permission on repository {}",
+            username, permission, repository
+        ),
+        None => format!("User {} does not have {} permission", username, permission),
+    };
+
+    (
+        StatusCode::FORBIDDEN,
+        Json(json!({ "error": "Permission denied", "message": message })),
+    )
+}
@@ -1,4 +1,4 @@
-use crate::core::error::internal_server_error;
+use crate::core::error::{internal_server_error, permission_denied};
 use crate::core::files::schema::{DeleteFileQuery, ListFileQuery, MultiPartQuery, PresignedQuery};
 use crate::core::state::AppState;
 use axum::extract::DefaultBodyLimit;
@@ -56,9 +56,10 @@ pub async fn create_multipart_upload(
 
         // check if user has permission to write to the repo
         if !perms.has_write_permission(repository_id.to_str().unwrap()) {
-            return Err((
-                StatusCode::FORBIDDEN,
-                Json(json!({ "error": "Permission denied" })),
+            return Err(permission_denied(
+                &perms.username,
+                "write",
+                repository_id.to_str(),
             ));
         }
     }
@@ -103,10 +104,7 @@ pub async fn generate_presigned_url(
     if state.config.opsml_auth {
         // check if user has permission to write to the repo
         if !perms.has_read_permission() {
-            return Err((
-                StatusCode::FORBIDDEN,
-                Json(json!({ "error": "Permission denied" })),
-            ));
+            return Err(permission_denied(&perms.username, "read", None));
         }
     }
 
@@ -258,9 +256,10 @@ pub async fn delete_file(
         })?;
 
         if !perms.has_delete_permission(repository_id.to_str().unwrap()) {
-            return Err((
-                StatusCode::FORBIDDEN,
-                Json(json!({ "error": "Permission denied" })),
+            return Err(permission_denied(
+                &perms.username,
+                "delete",
+                repository_id.to_str(),
             ));
         }
     }
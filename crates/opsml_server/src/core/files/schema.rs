@@ -13,9 +13,6 @@ pub struct PresignedQuery {
     pub for_multi_part: Option<bool>,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct UploadPartArgParser {}
-
 #[derive(Serialize, Deserialize)]
 pub struct ListFileQuery {
     pub path: String,
@@ -26,8 +23,3 @@ pub struct DeleteFileQuery {
     pub path: String,
     pub recursive: bool,
 }
-
-#[derive(Serialize, Deserialize)]
-pub struct DownloadFileQuery {
-    pub path: String,
-}
@@ -9,3 +9,4 @@ pub mod run;
 pub mod settings;
 pub mod setup;
 pub mod state;
+pub mod users;
@@ -1 +1,3 @@
+pub mod monitor;
 pub mod route;
+pub mod schema;
@@ -0,0 +1,39 @@
+use crate::core::state::AppState;
+use anyhow::Result;
+use opsml_sql::base::SqlClient;
+use opsml_utils::utils::get_utc_datetime;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, warn};
+
+/// How often running runs are checked for missed heartbeats
+const HEARTBEAT_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Mark running runs as crashed when their last heartbeat is older than the configured timeout
+///
+/// # Returns
+///
+/// * `u64` - The number of runs marked as crashed
+pub async fn mark_crashed_runs(state: &AppState) -> Result<u64> {
+    let cutoff = get_utc_datetime()
+        - chrono::Duration::seconds(state.config.opsml_run_heartbeat_timeout_secs);
+
+    Ok(state.sql_client.mark_crashed_runs(cutoff).await?)
+}
+
+/// Spawn a background task that marks runs with missed heartbeats as crashed
+pub fn spawn_heartbeat_monitor(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(HEARTBEAT_CHECK_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            match mark_crashed_runs(&state).await {
+                Ok(0) => {}
+                Ok(crashed) => warn!("Marked {} runs as crashed after missed heartbeats", crashed),
+                Err(e) => error!("Failed to check run heartbeats: {}", e),
+            }
+        }
+    });
+}
@@ -1,30 +1,72 @@
 /// Route for checking if a card UID exists
+use crate::core::auth::permission::{check_repository_access, check_run_access, RepositoryAccess};
+use crate::core::run::schema::{
+    ChildMetricsResponse, ChildRunsResponse, RunQueryResponse, RunStatusResponse,
+};
 use crate::core::state::AppState;
 use anyhow::{Context, Result};
 use axum::{
     extract::{Query, State},
     http::StatusCode,
     routing::{get, post, put},
-    Json, Router,
+    Extension, Json, Router,
 };
-use opsml_sql::base::SqlClient;
+use opsml_auth::permission::UserPermissions;
+use opsml_error::error::SqlError;
+use opsml_sql::base::{SqlClient, SqlTransaction};
+use opsml_sql::enums::client::SqlTransactionEnum;
 use opsml_sql::schemas::schema::{
-    CardResults, HardwareMetricsRecord, MetricRecord, ParameterRecord,
+    CardResults, HardwareMetricsRecord, MetricRecord, ParameterRecord, RunStatusRecord,
 };
 use opsml_types::*;
 
 use opsml_utils::utils::get_utc_datetime;
 use sqlx::types::Json as SqlxJson;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::path::Path;
 use std::sync::Arc;
 use tempfile::TempDir;
 use tracing::error;
 
+/// Start a transaction for writes against a run. The run card is checked inside the
+/// transaction so records are never written for a run that does not exist
+async fn begin_run_write(
+    state: &AppState,
+    run_uid: &str,
+) -> Result<SqlTransactionEnum, (StatusCode, Json<serde_json::Value>)> {
+    let internal_error = |e: SqlError| {
+        error!("Failed to start run write: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    };
+
+    let mut tx = state.sql_client.begin().await.map_err(internal_error)?;
+
+    let exists = tx
+        .check_uid_exists(run_uid, &CardSQLTableNames::Run)
+        .await
+        .map_err(internal_error)?;
+
+    if !exists {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Run {} not found", run_uid) })),
+        ));
+    }
+
+    Ok(tx)
+}
+
 pub async fn insert_metrics(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     Json(req): Json<MetricRequest>,
 ) -> Result<Json<MetricResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Write, &req.run_uid).await?;
+
     let records = req
         .metrics
         .iter()
@@ -39,29 +81,38 @@ pub async fn insert_metrics(
         })
         .collect::<Vec<_>>();
 
-    state
-        .sql_client
-        .insert_run_metrics(&records)
-        .await
-        .map_err(|e| {
-            error!("Failed to insert metric: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({})),
-            )
-        })?;
+    let mut tx = begin_run_write(&state, &req.run_uid).await?;
+
+    tx.insert_run_metrics(&records).await.map_err(|e| {
+        error!("Failed to insert metric: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })?;
+
+    tx.commit().await.map_err(|e| {
+        error!("Failed to commit metric: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })?;
 
     Ok(Json(MetricResponse { success: true }))
 }
 
 pub async fn get_metrics(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     Json(req): Json<GetMetricRequest>,
 ) -> Result<Json<Vec<Metric>>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Read, &req.run_uid).await?;
+
     // something is going on with how serde_qs is parsing the query when using names as a list
     let metrics = state
         .sql_client
-        .get_run_metric(&req.run_uid, &req.names)
+        .query_run_metrics(&req)
         .await
         .map_err(|e| {
             error!("Failed to get metrics: {}", e);
@@ -86,10 +137,49 @@ pub async fn get_metrics(
     Ok(Json(metrics))
 }
 
+/// Min, max, mean and last value of each run metric within the requested step range
+pub async fn get_metric_summary(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(req): Json<GetMetricRequest>,
+) -> Result<Json<Vec<MetricSummary>>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Read, &req.run_uid).await?;
+
+    let summaries = state
+        .sql_client
+        .get_run_metric_summary(&req)
+        .await
+        .map_err(|e| {
+            error!("Failed to get metric summary: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?;
+
+    let summaries = summaries
+        .into_iter()
+        .map(|s| MetricSummary {
+            name: s.name,
+            count: s.count,
+            min: s.min_value,
+            max: s.max_value,
+            mean: s.mean_value,
+            last: s.last_value,
+            last_step: s.last_step,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(summaries))
+}
+
 pub async fn get_metric_names(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     Query(req): Query<GetMetricNamesRequest>,
 ) -> Result<Json<Vec<String>>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Read, &req.run_uid).await?;
+
     let names = state
         .sql_client
         .get_run_metric_names(&req.run_uid)
@@ -107,33 +197,45 @@ pub async fn get_metric_names(
 
 pub async fn insert_parameters(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     Json(req): Json<ParameterRequest>,
 ) -> Result<Json<ParameterResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Write, &req.run_uid).await?;
+
     let records = req
         .parameters
         .iter()
         .map(|p| ParameterRecord::new(req.run_uid.clone(), p.name.clone(), p.value.clone()))
         .collect::<Vec<_>>();
 
-    state
-        .sql_client
-        .insert_run_parameters(&records)
-        .await
-        .map_err(|e| {
-            error!("Failed to insert parameter: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({})),
-            )
-        })?;
+    let mut tx = begin_run_write(&state, &req.run_uid).await?;
+
+    tx.insert_run_parameters(&records).await.map_err(|e| {
+        error!("Failed to insert parameter: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })?;
+
+    tx.commit().await.map_err(|e| {
+        error!("Failed to commit parameter: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })?;
 
     Ok(Json(ParameterResponse { success: true }))
 }
 
 pub async fn get_parameter(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     Json(req): Json<GetParameterRequest>,
 ) -> Result<Json<Vec<Parameter>>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Read, &req.run_uid).await?;
+
     let params = state
         .sql_client
         .get_run_parameter(&req.run_uid, &req.names)
@@ -159,10 +261,228 @@ pub async fn get_parameter(
     Ok(Json(params))
 }
 
+/// Align the metric series of several runs on the union of their steps. Points logged
+/// without a step are placed by their position in the run's series
+fn align_metrics(records: Vec<MetricRecord>, run_uids: &[String]) -> Vec<AlignedMetric> {
+    // name -> run_uid -> step -> value
+    let mut series: BTreeMap<String, HashMap<String, BTreeMap<i32, f64>>> = BTreeMap::new();
+    for record in records {
+        let run_series = series
+            .entry(record.name)
+            .or_default()
+            .entry(record.run_uid)
+            .or_default();
+        let step = record.step.unwrap_or(run_series.len() as i32);
+        run_series.insert(step, record.value);
+    }
+
+    series
+        .into_iter()
+        .map(|(name, runs)| {
+            let steps: Vec<i32> = runs
+                .values()
+                .flat_map(|run_series| run_series.keys().copied())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+
+            let values = run_uids
+                .iter()
+                .map(|uid| {
+                    let run_series = runs.get(uid);
+                    let aligned = steps
+                        .iter()
+                        .map(|step| run_series.and_then(|s| s.get(step).copied()))
+                        .collect();
+                    (uid.clone(), aligned)
+                })
+                .collect();
+
+            AlignedMetric {
+                name,
+                steps,
+                values,
+            }
+        })
+        .collect()
+}
+
+/// Build the parameter matrix of several runs, one row per parameter name
+fn compare_parameters(
+    records: Vec<ParameterRecord>,
+    run_uids: &[String],
+) -> Vec<ParameterComparison> {
+    let mut matrix: BTreeMap<String, HashMap<String, String>> = BTreeMap::new();
+    for record in records {
+        matrix
+            .entry(record.name)
+            .or_default()
+            .insert(record.run_uid, record.value);
+    }
+
+    matrix
+        .into_iter()
+        .map(|(name, run_values)| ParameterComparison {
+            name,
+            values: run_uids
+                .iter()
+                .map(|uid| (uid.clone(), run_values.get(uid).cloned()))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Compare metrics and parameters across runs
+pub async fn compare_runs(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(req): Json<CompareRunsRequest>,
+) -> Result<Json<CompareRunsResponse>, (StatusCode, Json<serde_json::Value>)> {
+    for run_uid in &req.run_uids {
+        check_run_access(&state, &perms, RepositoryAccess::Read, run_uid).await?;
+    }
+
+    let internal_error = |e: SqlError| {
+        error!("Failed to compare runs: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    };
+
+    let metrics = state
+        .sql_client
+        .get_runs_metrics(&req.run_uids, &req.metric_names)
+        .await
+        .map_err(internal_error)?;
+
+    let parameters = state
+        .sql_client
+        .get_runs_parameters(&req.run_uids, &req.parameter_names)
+        .await
+        .map_err(internal_error)?;
+
+    let mut metrics = align_metrics(metrics, &req.run_uids);
+    let mut parameters = compare_parameters(parameters, &req.run_uids);
+
+    if req.differs_only {
+        metrics.retain(|metric| metric.differs());
+        parameters.retain(|parameter| parameter.differs());
+    }
+
+    Ok(Json(CompareRunsResponse {
+        metrics,
+        parameters,
+    }))
+}
+
+/// Search runs by parameter values and metric thresholds
+pub async fn search_runs(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(req): Json<RunQueryRequest>,
+) -> Result<Json<RunQueryResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Read,
+        req.repository.as_deref(),
+    )?;
+
+    let runs = state.sql_client.query_runs(&req).await.map_err(|e| {
+        error!("Failed to search runs: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })?;
+
+    Ok(Json(RunQueryResponse { runs }))
+}
+
+/// Repository of a project: the requested one, or the repository of its project card when
+/// only one repository has a project of that name
+async fn resolve_project_repository(
+    state: &AppState,
+    req: &RunLeaderboardRequest,
+) -> Result<Option<String>, (StatusCode, Json<serde_json::Value>)> {
+    if req.repository.is_some() {
+        return Ok(req.repository.clone());
+    }
+
+    let query_args = CardQueryArgs {
+        name: Some(req.project.clone()),
+        ..Default::default()
+    };
+
+    let repositories: BTreeSet<String> = state
+        .sql_client
+        .query_cards(&CardSQLTableNames::Project, &query_args)
+        .await
+        .map_err(|e| {
+            error!("Failed to get project repository: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?
+        .to_values()
+        .iter()
+        .filter_map(|card| card["repository"].as_str().map(str::to_string))
+        .collect();
+
+    if repositories.len() == 1 {
+        Ok(repositories.into_iter().next())
+    } else {
+        Ok(None)
+    }
+}
+
+/// Runs of a project ranked by a metric. Projects that cannot be tied to one repository
+/// need the global read permission
+pub async fn get_leaderboard(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Query(req): Query<RunLeaderboardRequest>,
+) -> Result<Json<RunQueryResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let repository = resolve_project_repository(&state, &req).await?;
+    check_repository_access(
+        &state,
+        &perms,
+        RepositoryAccess::Read,
+        repository.as_deref(),
+    )?;
+
+    let args = RunQueryRequest {
+        project: Some(req.project),
+        repository,
+        sort_by: Some(RunMetricSort {
+            name: req.metric,
+            aggregation: req.aggregation,
+            descending: req.descending,
+        }),
+        limit: Some(req.limit.unwrap_or(10)),
+        ..Default::default()
+    };
+
+    let runs = state.sql_client.query_runs(&args).await.map_err(|e| {
+        error!("Failed to get leaderboard: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })?;
+
+    Ok(Json(RunQueryResponse { runs }))
+}
+
 pub async fn insert_hardware_metrics(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     Json(req): Json<HardwareMetricRequest>,
 ) -> Result<Json<HardwareMetricResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Write, &req.run_uid).await?;
+
     let created_at = get_utc_datetime();
 
     let records = req
@@ -194,25 +514,34 @@ pub async fn insert_hardware_metrics(
         })
         .collect::<Vec<_>>();
 
-    state
-        .sql_client
-        .insert_hardware_metrics(&records)
-        .await
-        .map_err(|e| {
-            error!("Failed to insert hardware metrics: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({})),
-            )
-        })?;
+    let mut tx = begin_run_write(&state, &req.run_uid).await?;
+
+    tx.insert_hardware_metrics(&records).await.map_err(|e| {
+        error!("Failed to insert hardware metrics: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })?;
+
+    tx.commit().await.map_err(|e| {
+        error!("Failed to commit hardware metrics: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        )
+    })?;
 
     Ok(Json(HardwareMetricResponse { success: true }))
 }
 
 pub async fn get_hardware_metrics(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     Query(req): Query<GetHardwareMetricRequest>,
 ) -> Result<Json<Vec<HardwareMetrics>>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Read, &req.run_uid).await?;
+
     let metrics = state
         .sql_client
         .get_hardware_metric(&req.run_uid)
@@ -270,8 +599,11 @@ pub async fn get_hardware_metrics(
 
 pub async fn get_run_graphs(
     State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
     Query(req): Query<GetRunGraphsRequest>,
 ) -> Result<Json<Vec<RunGraph>>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Read, &req.run_uid).await?;
+
     // get the run card
     let args = CardQueryArgs {
         uid: Some(req.run_uid.clone()),
@@ -392,6 +724,216 @@ pub async fn get_run_graphs(
     // load all run graphs. Can be either SingleRunGraph or MultiRunGraph
 }
 
+/// 404 unless the parent run is registered
+async fn check_parent_run(
+    state: &AppState,
+    parent_uid: &str,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let exists = state
+        .sql_client
+        .check_uid_exists(parent_uid, &CardSQLTableNames::Run)
+        .await
+        .map_err(|e| {
+            error!("Failed to check parent run: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?;
+
+    if !exists {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Run {} not found", parent_uid) })),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn get_child_runs(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Query(req): Query<ChildRunsRequest>,
+) -> Result<Json<ChildRunsResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Read, &req.parent_uid).await?;
+
+    check_parent_run(&state, &req.parent_uid).await?;
+
+    let runs = state
+        .sql_client
+        .get_child_runs(&req.parent_uid)
+        .await
+        .map_err(|e| {
+            error!("Failed to get child runs: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?;
+
+    Ok(Json(ChildRunsResponse { runs }))
+}
+
+/// Best and mean of each metric across the child runs of a parent
+pub async fn get_child_metrics(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Query(req): Query<ChildRunsRequest>,
+) -> Result<Json<ChildMetricsResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Read, &req.parent_uid).await?;
+
+    check_parent_run(&state, &req.parent_uid).await?;
+
+    let metrics = state
+        .sql_client
+        .get_child_metric_summary(&req.parent_uid)
+        .await
+        .map_err(|e| {
+            error!("Failed to get child run metrics: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })?;
+
+    Ok(Json(ChildMetricsResponse {
+        parent_uid: req.parent_uid,
+        metrics,
+    }))
+}
+
+/// Map run lifecycle errors: unknown runs are 404 and disallowed transitions are 409
+fn run_status_error(e: SqlError) -> (StatusCode, Json<serde_json::Value>) {
+    error!("Failed to update run status: {}", e);
+    match e {
+        SqlError::NotFoundError(_) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        ),
+        SqlError::ConflictError(_) => (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        ),
+    }
+}
+
+fn run_status_response(
+    record: RunStatusRecord,
+) -> Result<Json<RunStatusResponse>, (StatusCode, Json<serde_json::Value>)> {
+    RunStatusResponse::from_record(record)
+        .map(Json)
+        .ok_or_else(|| {
+            error!("Run has an unknown status");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({})),
+            )
+        })
+}
+
+/// Mark a run as running and start expecting heartbeats
+pub async fn start_run(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(req): Json<RunStatusRequest>,
+) -> Result<Json<RunStatusResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Write, &req.run_uid).await?;
+
+    let exists = state
+        .sql_client
+        .check_uid_exists(&req.run_uid, &CardSQLTableNames::Run)
+        .await
+        .map_err(run_status_error)?;
+
+    if !exists {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Run {} not found", req.run_uid) })),
+        ));
+    }
+
+    let record = state
+        .sql_client
+        .start_run(&req.run_uid)
+        .await
+        .map_err(run_status_error)?;
+
+    run_status_response(record)
+}
+
+/// Record that a run is still alive. A run marked as crashed is running again
+pub async fn run_heartbeat(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(req): Json<RunStatusRequest>,
+) -> Result<Json<RunStatusResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Write, &req.run_uid).await?;
+
+    let record = state
+        .sql_client
+        .update_run_status(&req.run_uid, &RunStatus::Running)
+        .await
+        .map_err(run_status_error)?;
+
+    run_status_response(record)
+}
+
+/// End a run as finished or failed
+pub async fn finish_run(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(req): Json<FinishRunRequest>,
+) -> Result<Json<RunStatusResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Write, &req.run_uid).await?;
+
+    // crashed is only set by the heartbeat monitor
+    if !matches!(req.status, RunStatus::Finished | RunStatus::Failed) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("Runs finish as finished or failed, not {}", req.status)
+            })),
+        ));
+    }
+
+    let record = state
+        .sql_client
+        .update_run_status(&req.run_uid, &req.status)
+        .await
+        .map_err(run_status_error)?;
+
+    run_status_response(record)
+}
+
+pub async fn get_run_status(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Query(req): Query<RunStatusRequest>,
+) -> Result<Json<RunStatusResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_run_access(&state, &perms, RepositoryAccess::Read, &req.run_uid).await?;
+
+    let record = state
+        .sql_client
+        .get_run_status(&req.run_uid)
+        .await
+        .map_err(run_status_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": format!("Run {} has not started", req.run_uid)
+                })),
+            )
+        })?;
+
+    run_status_response(record)
+}
+
 pub async fn get_run_router(prefix: &str) -> Result<Router<Arc<AppState>>> {
     let result = catch_unwind(AssertUnwindSafe(|| {
         Router::new()
@@ -401,6 +943,10 @@ pub async fn get_run_router(prefix: &str) -> Result<Router<Arc<AppState>>> {
                 &format!("{}/run/metrics/names", prefix),
                 get(get_metric_names),
             )
+            .route(
+                &format!("{}/run/metrics/summary", prefix),
+                post(get_metric_summary),
+            )
             .route(
                 &format!("{}/run/parameters", prefix),
                 put(insert_parameters),
@@ -415,6 +961,18 @@ pub async fn get_run_router(prefix: &str) -> Result<Router<Arc<AppState>>> {
                 get(get_hardware_metrics),
             )
             .route(&format!("{}/run/graphs", prefix), get(get_run_graphs))
+            .route(&format!("{}/run/compare", prefix), post(compare_runs))
+            .route(&format!("{}/run/search", prefix), post(search_runs))
+            .route(&format!("{}/run/leaderboard", prefix), get(get_leaderboard))
+            .route(&format!("{}/run/children", prefix), get(get_child_runs))
+            .route(
+                &format!("{}/run/children/metrics", prefix),
+                get(get_child_metrics),
+            )
+            .route(&format!("{}/run/start", prefix), post(start_run))
+            .route(&format!("{}/run/heartbeat", prefix), post(run_heartbeat))
+            .route(&format!("{}/run/finish", prefix), post(finish_run))
+            .route(&format!("{}/run/status", prefix), get(get_run_status))
     }));
 
     match result {
@@ -0,0 +1,46 @@
+use chrono::NaiveDateTime;
+use opsml_sql::schemas::schema::{
+    ChildMetricSummaryRecord, RunCardRecord, RunQueryResult, RunStatusRecord,
+};
+use opsml_types::RunStatus;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunQueryResponse {
+    pub runs: Vec<RunQueryResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChildRunsResponse {
+    pub runs: Vec<RunCardRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChildMetricsResponse {
+    pub parent_uid: String,
+    pub metrics: Vec<ChildMetricSummaryRecord>,
+}
+
+/// Status and timing of a run. `duration_seconds` runs to now while the run is running
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunStatusResponse {
+    pub run_uid: String,
+    pub status: RunStatus,
+    pub started_at: NaiveDateTime,
+    pub ended_at: Option<NaiveDateTime>,
+    pub heartbeat_at: NaiveDateTime,
+    pub duration_seconds: i64,
+}
+
+impl RunStatusResponse {
+    pub fn from_record(record: RunStatusRecord) -> Option<Self> {
+        Some(RunStatusResponse {
+            status: RunStatus::from_string(&record.status)?,
+            duration_seconds: record.duration_seconds(),
+            run_uid: record.run_uid,
+            started_at: record.started_at,
+            ended_at: record.ended_at,
+            heartbeat_at: record.heartbeat_at,
+        })
+    }
+}
@@ -1,10 +1,112 @@
 use anyhow::{Context, Result as AnyhowResult};
+use opsml_error::error::SqlError;
 use opsml_logging::logging::setup_logging;
 use opsml_settings::config::OpsmlConfig;
+use opsml_sql::base::SqlClient;
 use opsml_sql::enums::client::{get_sql_client, SqlClientEnum};
+use opsml_sql::schemas::schema::User;
 use opsml_storage::storage::enums::client::{get_storage_system, StorageClientEnum};
 use opsml_utils::color::LogColors;
-use tracing::{debug, info};
+use password_auth::generate_hash;
+use rand::Rng;
+use tracing::{debug, info, warn};
+
+const DEFAULT_ADMIN_USERNAME: &str = "admin";
+const GENERATED_PASSWORD_LENGTH: usize = 24;
+
+fn generate_password() -> String {
+    let mut rng = rand::thread_rng();
+    (0..GENERATED_PASSWORD_LENGTH)
+        .map(|_| rng.sample(rand::distributions::Alphanumeric) as char)
+        .collect()
+}
+
+/// Create the first admin user when auth is enabled and the users table is empty.
+///
+/// The user is created from `OPSML_USERNAME`/`OPSML_PASSWORD`. When no password is configured
+/// a one-time password is generated and logged once; it is never stored in plain text.
+/// Running against a database that already has users is a no-op.
+///
+/// # Arguments
+///
+/// * `config` - The server config
+/// * `sql` - The sql client
+///
+/// # Returns
+///
+/// * `Result<()>` - Result of the bootstrap
+pub async fn initialize_default_user(
+    config: &OpsmlConfig,
+    sql: &SqlClientEnum,
+) -> AnyhowResult<()> {
+    if !config.opsml_auth {
+        debug!("Auth is disabled, skipping admin bootstrap");
+        return Ok(());
+    }
+
+    let users = sql.get_users().await?;
+    if !users.is_empty() {
+        info!(
+            "✅ Found {} existing user(s), skipping admin bootstrap",
+            users.len()
+        );
+        return Ok(());
+    }
+
+    let username = config
+        .opsml_username
+        .clone()
+        .filter(|username| !username.is_empty())
+        .unwrap_or_else(|| DEFAULT_ADMIN_USERNAME.to_string());
+
+    let (password, generated) = match config
+        .opsml_password
+        .clone()
+        .filter(|password| !password.is_empty())
+    {
+        Some(password) => (password, false),
+        None => (generate_password(), true),
+    };
+
+    let user = User::new(
+        username.clone(),
+        generate_hash(&password),
+        Some(vec![
+            "read".to_string(),
+            "write:*".to_string(),
+            "delete:*".to_string(),
+        ]),
+        Some(vec!["admin".to_string()]),
+    );
+
+    match sql.insert_user(&user).await {
+        Ok(_) => {}
+        // another server instance bootstrapped the same database first
+        Err(SqlError::ConflictError(_)) => {
+            info!(
+                "✅ Admin user {} already exists, skipping admin bootstrap",
+                username
+            );
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    if generated {
+        warn!(
+            "🔑 Created admin user {} with generated one-time password: {} \
+            (set OPSML_PASSWORD to choose one, and change it via /opsml/auth/users/password)",
+            username, password
+        );
+    } else {
+        info!(
+            "✅ Created admin user {} from OPSML_USERNAME/OPSML_PASSWORD",
+            username
+        );
+    }
+
+    Ok(())
+}
 
 pub async fn setup_components() -> AnyhowResult<(OpsmlConfig, StorageClientEnum, SqlClientEnum)> {
     // setup config
@@ -33,5 +135,9 @@ pub async fn setup_components() -> AnyhowResult<(OpsmlConfig, StorageClientEnum,
 
     info!("✅ Sql client: {}", sql.name());
 
+    initialize_default_user(&config, &sql)
+        .await
+        .context(LogColors::purple("❌ Failed to bootstrap admin user"))?;
+
     Ok((config, storage, sql))
 }
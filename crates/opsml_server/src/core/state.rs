@@ -1,3 +1,4 @@
+use crate::core::auth::status::UserStatusCache;
 use opsml_auth::auth::AuthManager;
 use opsml_settings::config::OpsmlConfig;
 use opsml_sql::enums::client::SqlClientEnum;
@@ -9,4 +10,5 @@ pub struct AppState {
     pub sql_client: Arc<SqlClientEnum>,
     pub auth_manager: Arc<AuthManager>,
     pub config: Arc<OpsmlConfig>,
+    pub user_status: Arc<UserStatusCache>,
 }
@@ -0,0 +1,460 @@
+use crate::core::auth::permission::check_admin;
+use crate::core::state::AppState;
+use anyhow::{Context, Result};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::{delete, get, post, put},
+    Extension, Json, Router,
+};
+use opsml_auth::permission::UserPermissions;
+use opsml_error::error::SqlError;
+use opsml_sql::base::{SqlClient, SqlTransaction};
+use opsml_sql::schemas::schema::{Group, User};
+use opsml_types::{
+    ChangePasswordRequest, CreateGroupRequest, CreateUserRequest, GroupMemberRequest, GroupRequest,
+    GroupResponse, GroupsResponse, UpdateGroupRequest, UpdateUserRequest, UserRequest,
+    UserResponse, UsersResponse,
+};
+use password_auth::generate_hash;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+use tracing::{error, info};
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+fn bad_request(message: String) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": message })),
+    )
+}
+
+/// Map sql errors of the user and group routes. Missing rows and name clashes are
+/// reported to the caller, everything else is logged
+fn user_error(context: &str, e: SqlError) -> (StatusCode, Json<serde_json::Value>) {
+    error!("{}: {}", context, e);
+    match e {
+        SqlError::NotFoundError(_) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        ),
+        SqlError::ConflictError(_) => (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({})),
+        ),
+    }
+}
+
+fn validate_password(password: &str) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(bad_request(format!(
+            "Password must be at least {} characters",
+            MIN_PASSWORD_LENGTH
+        )));
+    }
+
+    Ok(())
+}
+
+fn user_response(user: &User) -> UserResponse {
+    UserResponse {
+        username: user.username.clone(),
+        active: user.active,
+        permissions: user.permissions.clone(),
+        group_permissions: user.group_permissions.clone(),
+    }
+}
+
+async fn get_existing_user(
+    state: &AppState,
+    username: &str,
+) -> Result<User, (StatusCode, Json<serde_json::Value>)> {
+    state
+        .sql_client
+        .get_user(username)
+        .await
+        .map_err(|e| user_error("Failed to get user", e))
+}
+
+async fn get_existing_group(
+    state: &AppState,
+    name: &str,
+) -> Result<Group, (StatusCode, Json<serde_json::Value>)> {
+    state
+        .sql_client
+        .get_group(name)
+        .await
+        .map_err(|e| user_error("Failed to get group", e))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": format!("Group {} not found", name) })),
+            )
+        })
+}
+
+async fn group_response(
+    state: &AppState,
+    group: Group,
+) -> Result<GroupResponse, (StatusCode, Json<serde_json::Value>)> {
+    let members = state
+        .sql_client
+        .get_group_members(&group.name)
+        .await
+        .map_err(|e| user_error("Failed to get group members", e))?;
+
+    Ok(GroupResponse {
+        name: group.name,
+        permissions: group.permissions,
+        members,
+    })
+}
+
+/// List all users
+pub async fn list_users(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+) -> Result<Json<UsersResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_admin(&state, &perms)?;
+
+    let users = state
+        .sql_client
+        .get_users()
+        .await
+        .map_err(|e| user_error("Failed to get users", e))?;
+
+    Ok(Json(UsersResponse {
+        users: users.iter().map(user_response).collect(),
+    }))
+}
+
+/// Create a user with a hashed password
+pub async fn create_user(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(req): Json<CreateUserRequest>,
+) -> Result<Json<UserResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_admin(&state, &perms)?;
+
+    if req.username.trim().is_empty() {
+        return Err(bad_request("Username cannot be empty".to_string()));
+    }
+    validate_password(&req.password)?;
+
+    let user = User::new(
+        req.username,
+        generate_hash(&req.password),
+        req.permissions,
+        req.group_permissions,
+    );
+
+    state
+        .sql_client
+        .insert_user(&user)
+        .await
+        .map_err(|e| user_error("Failed to create user", e))?;
+    state.user_status.invalidate(&user.username);
+
+    info!("User {} created by {}", user.username, perms.username);
+
+    Ok(Json(user_response(&user)))
+}
+
+/// Update a user's password, grants or active flag. Deactivating a user revokes their
+/// refresh token, and the auth middleware rejects their outstanding access tokens
+pub async fn update_user(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(req): Json<UpdateUserRequest>,
+) -> Result<Json<UserResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_admin(&state, &perms)?;
+
+    if req.active == Some(false) && req.username == perms.username {
+        return Err(bad_request("You cannot deactivate yourself".to_string()));
+    }
+
+    let mut user = get_existing_user(&state, &req.username).await?;
+
+    if let Some(password) = &req.password {
+        validate_password(password)?;
+        user.password_hash = generate_hash(password);
+    }
+    if let Some(permissions) = req.permissions {
+        user.permissions = permissions;
+    }
+    if let Some(group_permissions) = req.group_permissions {
+        user.group_permissions = group_permissions;
+    }
+    if let Some(active) = req.active {
+        user.active = active;
+    }
+
+    state
+        .sql_client
+        .update_user(&user)
+        .await
+        .map_err(|e| user_error("Failed to update user", e))?;
+    if !user.active {
+        state
+            .sql_client
+            .delete_user_refresh_tokens(&user.username)
+            .await
+            .map_err(|e| user_error("Failed to revoke refresh tokens", e))?;
+    }
+    state.user_status.invalidate(&user.username);
+
+    info!("User {} updated by {}", user.username, perms.username);
+
+    Ok(Json(user_response(&user)))
+}
+
+/// Delete a user, their group memberships and their refresh tokens
+pub async fn delete_user(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Query(req): Query<UserRequest>,
+) -> Result<Json<UserResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_admin(&state, &perms)?;
+
+    if req.username == perms.username {
+        return Err(bad_request("You cannot delete yourself".to_string()));
+    }
+
+    let user = get_existing_user(&state, &req.username).await?;
+
+    state
+        .sql_client
+        .delete_user(&user.username)
+        .await
+        .map_err(|e| user_error("Failed to delete user", e))?;
+    state.user_status.invalidate(&user.username);
+
+    info!("User {} deleted by {}", user.username, perms.username);
+
+    Ok(Json(user_response(&user)))
+}
+
+/// Change the password of the calling user. Other sessions must log in again once
+/// their access token expires
+pub async fn change_password(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(req): Json<ChangePasswordRequest>,
+) -> Result<Json<UserResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let mut user = get_existing_user(&state, &perms.username).await?;
+
+    state
+        .auth_manager
+        .validate_user(&user, &req.current_password)
+        .map_err(|_| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({ "error": "Current password is incorrect" })),
+            )
+        })?;
+    validate_password(&req.new_password)?;
+
+    user.password_hash = generate_hash(&req.new_password);
+
+    state
+        .sql_client
+        .update_user(&user)
+        .await
+        .map_err(|e| user_error("Failed to change password", e))?;
+    state
+        .sql_client
+        .delete_user_refresh_tokens(&user.username)
+        .await
+        .map_err(|e| user_error("Failed to revoke refresh tokens", e))?;
+
+    info!("User {} changed their password", user.username);
+
+    Ok(Json(user_response(&user)))
+}
+
+/// List all groups with their members
+pub async fn list_groups(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+) -> Result<Json<GroupsResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_admin(&state, &perms)?;
+
+    let groups = state
+        .sql_client
+        .get_groups()
+        .await
+        .map_err(|e| user_error("Failed to get groups", e))?;
+
+    let mut responses = Vec::with_capacity(groups.len());
+    for group in groups {
+        responses.push(group_response(&state, group).await?);
+    }
+
+    Ok(Json(GroupsResponse { groups: responses }))
+}
+
+/// Create a group. Members must be existing users
+pub async fn create_group(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(req): Json<CreateGroupRequest>,
+) -> Result<Json<GroupResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_admin(&state, &perms)?;
+
+    if req.name.trim().is_empty() {
+        return Err(bad_request("Group name cannot be empty".to_string()));
+    }
+    for username in &req.members {
+        get_existing_user(&state, username).await?;
+    }
+
+    // the group and its memberships are written together or not at all
+    let group = Group::new(req.name, req.permissions);
+    let mut tx = state
+        .sql_client
+        .begin()
+        .await
+        .map_err(|e| user_error("Failed to start transaction", e))?;
+
+    tx.insert_group(&group)
+        .await
+        .map_err(|e| user_error("Failed to create group", e))?;
+
+    for username in &req.members {
+        tx.add_group_member(&group.name, username)
+            .await
+            .map_err(|e| user_error("Failed to add group member", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| user_error("Failed to create group", e))?;
+
+    info!("Group {} created by {}", group.name, perms.username);
+
+    Ok(Json(group_response(&state, group).await?))
+}
+
+/// Replace the permission grants of a group
+pub async fn update_group(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(req): Json<UpdateGroupRequest>,
+) -> Result<Json<GroupResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_admin(&state, &perms)?;
+
+    let mut group = get_existing_group(&state, &req.name).await?;
+    group.permissions = req.permissions;
+
+    state
+        .sql_client
+        .update_group(&group)
+        .await
+        .map_err(|e| user_error("Failed to update group", e))?;
+
+    info!("Group {} updated by {}", group.name, perms.username);
+
+    Ok(Json(group_response(&state, group).await?))
+}
+
+/// Delete a group and its memberships
+pub async fn delete_group(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Query(req): Query<GroupRequest>,
+) -> Result<Json<GroupResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_admin(&state, &perms)?;
+
+    let group = get_existing_group(&state, &req.name).await?;
+    let response = group_response(&state, group).await?;
+
+    state
+        .sql_client
+        .delete_group(&response.name)
+        .await
+        .map_err(|e| user_error("Failed to delete group", e))?;
+
+    info!("Group {} deleted by {}", response.name, perms.username);
+
+    Ok(Json(response))
+}
+
+/// Add an existing user to a group
+pub async fn add_group_member(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Json(req): Json<GroupMemberRequest>,
+) -> Result<Json<GroupResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_admin(&state, &perms)?;
+
+    let group = get_existing_group(&state, &req.name).await?;
+    get_existing_user(&state, &req.username).await?;
+
+    state
+        .sql_client
+        .add_group_member(&group.name, &req.username)
+        .await
+        .map_err(|e| user_error("Failed to add group member", e))?;
+
+    Ok(Json(group_response(&state, group).await?))
+}
+
+/// Remove a user from a group
+pub async fn remove_group_member(
+    State(state): State<Arc<AppState>>,
+    Extension(perms): Extension<UserPermissions>,
+    Query(req): Query<GroupMemberRequest>,
+) -> Result<Json<GroupResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_admin(&state, &perms)?;
+
+    let group = get_existing_group(&state, &req.name).await?;
+
+    state
+        .sql_client
+        .remove_group_member(&group.name, &req.username)
+        .await
+        .map_err(|e| user_error("Failed to remove group member", e))?;
+
+    Ok(Json(group_response(&state, group).await?))
+}
+
+pub async fn get_user_router(prefix: &str) -> Result<Router<Arc<AppState>>> {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        Router::new()
+            .route(&format!("{}/auth/users", prefix), get(list_users))
+            .route(&format!("{}/auth/users", prefix), post(create_user))
+            .route(&format!("{}/auth/users", prefix), put(update_user))
+            .route(&format!("{}/auth/users", prefix), delete(delete_user))
+            .route(
+                &format!("{}/auth/users/password", prefix),
+                put(change_password),
+            )
+            .route(&format!("{}/auth/groups", prefix), get(list_groups))
+            .route(&format!("{}/auth/groups", prefix), post(create_group))
+            .route(&format!("{}/auth/groups", prefix), put(update_group))
+            .route(&format!("{}/auth/groups", prefix), delete(delete_group))
+            .route(
+                &format!("{}/auth/groups/members", prefix),
+                post(add_group_member),
+            )
+            .route(
+                &format!("{}/auth/groups/members", prefix),
+                delete(remove_group_member),
+            )
+    }));
+
+    match result {
+        Ok(router) => Ok(router),
+        Err(_) => {
+            error!("Failed to create user router");
+            // panic
+            Err(anyhow::anyhow!("Failed to create user router"))
+                .context("Panic occurred while creating the router")
+        }
+    }
+}
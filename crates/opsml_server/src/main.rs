@@ -1,4 +1,7 @@
+use crate::core::auth::status::UserStatusCache;
+use crate::core::cards::purge::spawn_trash_purge;
 use crate::core::router::create_router;
+use crate::core::run::monitor::spawn_heartbeat_monitor;
 use crate::core::setup::setup_components;
 use crate::core::state::AppState;
 use anyhow::Ok;
@@ -25,10 +28,17 @@ async fn create_app() -> Result<Router> {
             &config.opsml_refresh_secret,
         )),
         config: Arc::new(config),
+        user_status: Arc::new(UserStatusCache::default()),
     });
 
     info!("✅ Application state created");
 
+    // purge cards that have outlived their trash retention
+    spawn_trash_purge(app_state.clone());
+
+    // mark runs that stopped sending heartbeats as crashed
+    spawn_heartbeat_monitor(app_state.clone());
+
     // create the router
     let app = create_router(app_state).await?;
 
@@ -76,18 +86,25 @@ async fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::cards::schema::{QueryPageResponse, RegistryStatsResponse};
+    use crate::core::cards::schema::{
+        CardAliasHistoryResponse, CardAliasResponse, CardHistoryResponse, CardSearchResponse,
+        DeletedCardsResponse, LineageResponse, ListCardResponse, QueryPageResponse,
+        RegistryStatsResponse,
+    };
+    use crate::core::run::schema::RunQueryResponse;
+    use crate::core::setup::initialize_default_user;
     use axum::response::Response;
     use axum::{
         body::Body,
         http::{header, Request, StatusCode},
     };
     use http_body_util::BodyExt; // for `collect`
-    use opsml_settings::config::OpsmlDatabaseSettings;
+    use opsml_settings::config::{OpsmlConfig, OpsmlDatabaseSettings};
     use opsml_sql::base::SqlClient;
     use opsml_sql::enums::client::SqlClientEnum;
-    use opsml_sql::schemas::schema::CardResults;
+    use opsml_sql::schemas::schema::{CardResults, Group, User};
     use opsml_types::*;
+    use opsml_utils::calver::CalVer;
     use std::collections::HashMap;
     use std::path::PathBuf;
     use std::{env, vec};
@@ -120,14 +137,18 @@ mod tests {
         )
     }
 
-    async fn setup() {
+    async fn get_sql_client() -> SqlClientEnum {
         let config = OpsmlDatabaseSettings {
             connection_uri: get_connection_uri(),
             max_connections: 1,
             sql_type: SqlType::Sqlite,
         };
 
-        let client = SqlClientEnum::new(&config).await.unwrap();
+        SqlClientEnum::new(&config).await.unwrap()
+    }
+
+    async fn setup() {
+        let client = get_sql_client().await;
 
         // Run the SQL script to populate the database
         let script = std::fs::read_to_string("tests/populate_db.sql").unwrap();
@@ -142,8 +163,10 @@ mod tests {
 
     impl TestHelper {
         pub async fn new() -> Self {
-            // set OPSML_AUTH to true
+            // set OPSML_AUTH to true and bootstrap the admin user
             env::set_var("OPSML_AUTH", "true");
+            env::set_var("OPSML_USERNAME", "admin");
+            env::set_var("OPSML_PASSWORD", "test_password");
 
             cleanup();
 
@@ -171,13 +194,17 @@ mod tests {
         }
 
         pub async fn login(app: &Router) -> JwtToken {
+            TestHelper::login_as(app, "admin", "test_password").await
+        }
+
+        pub async fn login_as(app: &Router, username: &str, password: &str) -> JwtToken {
             let response = app
                 .clone()
                 .oneshot(
                     Request::builder()
                         .uri("/opsml/auth/api/login")
-                        .header("Username", "admin")
-                        .header("Password", "test_password")
+                        .header("Username", username)
+                        .header("Password", password)
                         .body(Body::empty())
                         .unwrap(),
                 )
@@ -213,6 +240,35 @@ mod tests {
             }
         }
 
+        /// Add a user that shares the admin password but only has the given permissions
+        pub async fn create_user(&self, username: &str, permissions: &[&str]) -> JwtToken {
+            let client = get_sql_client().await;
+
+            let admin = client.get_user("admin").await.unwrap();
+            let user = User::new(
+                username.to_string(),
+                admin.password_hash,
+                Some(permissions.iter().map(|p| p.to_string()).collect()),
+                None,
+            );
+            client.insert_user(&user).await.unwrap();
+
+            TestHelper::login_as(&self.app, username, "test_password").await
+        }
+
+        pub async fn send_as(
+            &self,
+            mut request: Request<Body>,
+            token: &JwtToken,
+        ) -> Response<Body> {
+            request.headers_mut().insert(
+                header::AUTHORIZATION,
+                format!("Bearer {}", token.token).parse().unwrap(),
+            );
+
+            self.app.clone().oneshot(request).await.unwrap()
+        }
+
         pub fn cleanup(&self) {
             cleanup();
         }
@@ -244,10 +300,14 @@ mod tests {
         // refresh token
         let request = Request::builder()
             .uri("/opsml/auth/api/refresh")
+            .header(
+                header::AUTHORIZATION,
+                format!("Bearer {}", helper.token.refresh_token.as_ref().unwrap()),
+            )
             .body(Body::empty())
             .unwrap();
 
-        let response = helper.send_oneshot(request, true).await;
+        let response = helper.send_oneshot(request, false).await;
         assert_eq!(response.status(), StatusCode::OK);
 
         let body = response.into_body().collect().await.unwrap().to_bytes();
@@ -260,6 +320,103 @@ mod tests {
         helper.cleanup();
     }
 
+    #[tokio::test]
+    async fn test_opsml_server_refresh_token_rotation() {
+        let helper = TestHelper::new().await;
+
+        let bearer_request = |uri: &str, method: &str, token: &str| {
+            Request::builder()
+                .uri(uri)
+                .method(method)
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let refresh = |token: &str| bearer_request("/opsml/auth/api/refresh", "GET", token);
+        let logout = |token: &str| bearer_request("/opsml/auth/api/logout", "POST", token);
+
+        // access tokens cannot be used to refresh
+        let response = helper
+            .send_oneshot(refresh(&helper.token.token), false)
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let refresh_ok = |response: Response| async move {
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let token: JwtToken = serde_json::from_slice(&body).unwrap();
+            token.refresh_token.unwrap()
+        };
+
+        // a second login starts its own refresh token family
+        let other = TestHelper::login(&helper.app).await.refresh_token.unwrap();
+
+        // every refresh rotates the refresh token
+        let first = helper.token.refresh_token.clone().unwrap();
+        let second = refresh_ok(helper.send_oneshot(refresh(&first), false).await).await;
+        assert_ne!(first, second);
+
+        // the stored token is hashed
+        let family = AuthManager::new("", "")
+            .decode_jwt_without_validation(&second)
+            .unwrap()
+            .family
+            .unwrap();
+        let client = get_sql_client().await;
+        let stored = client.get_refresh_token(&family).await.unwrap().unwrap();
+        assert_eq!(stored.username, "admin");
+        assert_ne!(stored.token_hash, second);
+
+        // reusing a rotated-out token revokes its whole family
+        let response = helper.send_oneshot(refresh(&first), false).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = helper.send_oneshot(refresh(&second), false).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // but not the other session
+        let other = refresh_ok(helper.send_oneshot(refresh(&other), false).await).await;
+
+        // the same token used twice at once rotates at most once
+        let token = TestHelper::login(&helper.app).await;
+        let refresh_token = token.refresh_token.unwrap();
+        let (first, second) = tokio::join!(
+            helper.send_oneshot(refresh(&refresh_token), false),
+            helper.send_oneshot(refresh(&refresh_token), false),
+        );
+        let mut statuses = vec![first.status(), second.status()];
+        statuses.sort();
+        assert_eq!(statuses, vec![StatusCode::OK, StatusCode::UNAUTHORIZED]);
+
+        // logging out needs the latest token of the family
+        let token = TestHelper::login(&helper.app).await;
+        let rotated_out = token.refresh_token.unwrap();
+        let refresh_token =
+            refresh_ok(helper.send_oneshot(refresh(&rotated_out), false).await).await;
+
+        let response = helper.send_oneshot(logout(&rotated_out), false).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let refresh_token =
+            refresh_ok(helper.send_oneshot(refresh(&refresh_token), false).await).await;
+
+        // logging out revokes the refresh token
+        let response = helper.send_oneshot(logout(&refresh_token), false).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let logout_response: LogoutResponse = serde_json::from_slice(&body).unwrap();
+        assert!(logout_response.logged_out);
+
+        let response = helper.send_oneshot(refresh(&refresh_token), false).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = helper.send_oneshot(logout("invalid_token"), false).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // logging out of one session keeps the others
+        refresh_ok(helper.send_oneshot(refresh(&other), false).await).await;
+
+        helper.cleanup();
+    }
+
     #[tokio::test]
     async fn test_opsml_server_card_uid() {
         let helper = TestHelper::new().await;
@@ -335,208 +492,2050 @@ mod tests {
             .body(Body::empty())
             .unwrap();
 
-        let response = helper.send_oneshot(request, true).await;
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let repository_response: RepositoryResponse = serde_json::from_slice(&body).unwrap();
+
+        // assert 10
+        assert_eq!(repository_response.repositories.len(), 10);
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_card_stats_and_query() {
+        let helper = TestHelper::new().await;
+
+        /////////////////////// Test registry stats ///////////////////////
+
+        let params = RegistryStatsRequest {
+            registry_type: RegistryType::Model,
+            search_term: None,
+        };
+
+        let query_string = serde_qs::to_string(&params).unwrap();
+        let request = Request::builder()
+            .uri(format!("/opsml/card/registry/stats?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let stats_response: RegistryStatsResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(stats_response.stats.nbr_names, 10);
+
+        let params = RegistryStatsRequest {
+            registry_type: RegistryType::Model,
+            search_term: Some("Model1".to_string()),
+        };
+
+        let query_string = serde_qs::to_string(&params).unwrap();
+        let request = Request::builder()
+            .uri(format!("/opsml/card/registry/stats?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let stats_response: RegistryStatsResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(stats_response.stats.nbr_names, 2);
+
+        /////////////////////// Test query page ///////////////////////
+
+        let args = QueryPageRequest {
+            registry_type: RegistryType::Model,
+            sort_by: None,
+            repository: None,
+            search_term: None,
+            page: None,
+            cursor: None,
+            status: None,
+        };
+
+        let query_string = serde_qs::to_string(&args).unwrap();
+
+        let request = Request::builder()
+            .uri(format!("/opsml/card/registry/page?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let page_response: QueryPageResponse = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(page_response.summaries.len(), 10);
+        assert!(page_response.next_cursor.is_none());
+
+        // cursors are keyed on updated_at and can't continue a differently sorted page
+        let args = QueryPageRequest {
+            registry_type: RegistryType::Model,
+            sort_by: Some("name".to_string()),
+            repository: None,
+            search_term: None,
+            page: None,
+            cursor: Some(String::new()),
+            status: None,
+        };
+
+        let query_string = serde_qs::to_string(&args).unwrap();
+
+        let request = Request::builder()
+            .uri(format!("/opsml/card/registry/page?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let args = QueryPageRequest {
+            registry_type: RegistryType::Model,
+            sort_by: None,
+            repository: None,
+            search_term: Some("Model2".to_string()),
+            page: None,
+            cursor: None,
+            status: None,
+        };
+
+        let query_string = serde_qs::to_string(&args).unwrap();
+
+        let request = Request::builder()
+            .uri(format!("/opsml/card/registry/page?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let page_response: QueryPageResponse = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(page_response.summaries.len(), 1);
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_card_versions() {
+        let helper = TestHelper::new().await;
+
+        /////////////////////////// Card Versions/////////////////////
+        let args = CardVersionRequest {
+            registry_type: RegistryType::Data,
+            name: "Data1".to_string(),
+            repository: "repo1".to_string(),
+            version: None,
+            version_type: VersionType::Minor,
+            pre_tag: None,
+            build_tag: None,
+            version_scheme: None,
+        };
+
+        let query_string = serde_qs::to_string(&args).unwrap();
+
+        let request = Request::builder()
+            .uri(format!("/opsml/card/version?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let version_response: CardVersionResponse = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(version_response.version, "3.1.0");
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_list_cards() {
+        let helper = TestHelper::new().await;
+
+        let args = ListCardRequest {
+            uid: None,
+            name: None,
+            repository: None,
+            version: None,
+            max_date: None,
+            tags: None,
+            tag_filters: None,
+            limit: None,
+            sort_by_timestamp: None,
+            cursor: None,
+            paginate: false,
+            include_deleted: false,
+            alias: None,
+            status: None,
+            version_scheme: None,
+            registry_type: RegistryType::Data,
+        };
+
+        let query_string = serde_qs::to_string(&args).unwrap();
+
+        let request = Request::builder()
+            .uri(format!("/opsml/card/list?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let card_results: CardResults = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(card_results.len(), 10);
+
+        let args = ListCardRequest {
+            uid: None,
+            name: None,
+            repository: Some("repo1".to_string()),
+            version: None,
+            max_date: None,
+            tags: None,
+            tag_filters: None,
+            limit: None,
+            sort_by_timestamp: None,
+            cursor: None,
+            paginate: false,
+            include_deleted: false,
+            alias: None,
+            status: None,
+            version_scheme: None,
+            registry_type: RegistryType::Model,
+        };
+
+        let query_string = serde_qs::to_string(&args).unwrap();
+
+        let request = Request::builder()
+            .uri(format!("/opsml/card/list?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let card_results: CardResults = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(card_results.len(), 1);
+
+        // tag filters
+        let args = ListCardRequest {
+            tag_filters: Some(vec![
+                TagFilter::new("key1", TagOperator::Exists, vec![]),
+                TagFilter::new(
+                    "key2",
+                    TagOperator::In,
+                    vec!["value2".to_string(), "value3".to_string()],
+                ),
+            ]),
+            registry_type: RegistryType::Data,
+            ..ListCardRequest::default()
+        };
+
+        let query_string = serde_qs::to_string(&args).unwrap();
+
+        let request = Request::builder()
+            .uri(format!("/opsml/card/list?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let card_results: CardResults = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(card_results.len(), 1);
+
+        // cursor pagination
+        let mut args = ListCardRequest {
+            limit: Some(4),
+            paginate: true,
+            registry_type: RegistryType::Data,
+            ..ListCardRequest::default()
+        };
+        let mut nbr_cards = 0;
+
+        loop {
+            let query_string = serde_qs::to_string(&args).unwrap();
+
+            let request = Request::builder()
+                .uri(format!("/opsml/card/list?{}", query_string))
+                .method("GET")
+                .body(Body::empty())
+                .unwrap();
+
+            let response = helper.send_oneshot(request, true).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let list_response: ListCardResponse = serde_json::from_slice(&body).unwrap();
+            nbr_cards += list_response.cards.len();
+
+            match list_response.next_cursor {
+                Some(cursor) => args.cursor = Some(cursor),
+                None => break,
+            }
+        }
+
+        assert_eq!(nbr_cards, 10);
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_search_cards() {
+        let helper = TestHelper::new().await;
+
+        let params = CardSearchRequest {
+            search_term: "contact1".to_string(),
+            limit: None,
+        };
+
+        let query_string = serde_qs::to_string(&params).unwrap();
+
+        let request = Request::builder()
+            .uri(format!("/opsml/card/search?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let search_response: CardSearchResponse = serde_json::from_slice(&body).unwrap();
+
+        // results span registries
+        assert!(search_response
+            .results
+            .iter()
+            .any(|result| result.registry_type == "data"));
+        assert!(search_response
+            .results
+            .iter()
+            .any(|result| result.registry_type == "model"));
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_card_lineage() {
+        let helper = TestHelper::new().await;
+        let data_uid = "550e8400-e29b-41d4-a716-446655440000".to_string();
+
+        // model trained on an existing datacard
+        let card_request = CreateCardRequest {
+            card: ClientCard::Model(ModelCardClientRecord {
+                name: "ModelCard".to_string(),
+                repository: "repo1".to_string(),
+                version: "1.0.0".to_string(),
+                contact: "test".to_string(),
+                datacard_uid: Some(data_uid.clone()),
+                ..ModelCardClientRecord::default()
+            }),
+            registry_type: RegistryType::Model,
+            status: None,
+        };
+
+        let body = serde_json::to_string(&card_request).unwrap();
+
+        let request = Request::builder()
+            .uri("/opsml/card/create")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let create_response: CreateCardResponse = serde_json::from_slice(&body).unwrap();
+
+        let params = LineageRequest {
+            uid: data_uid.clone(),
+            registry_type: RegistryType::Data,
+            direction: LineageDirection::Downstream,
+            depth: Some(2),
+        };
+
+        let query_string = serde_qs::to_string(&params).unwrap();
+
+        let request = Request::builder()
+            .uri(format!("/opsml/card/lineage?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let lineage: LineageResponse = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(lineage.edges.len(), 1);
+        assert_eq!(lineage.edges[0].downstream_uid, create_response.uid);
+        assert_eq!(lineage.nodes.len(), 2);
+        assert!(lineage
+            .nodes
+            .iter()
+            .any(|node| node.uid == data_uid && node.registry_type == "data"));
+
+        // oversized depths are capped rather than walked
+        let params = LineageRequest {
+            uid: data_uid.clone(),
+            registry_type: RegistryType::Data,
+            direction: LineageDirection::Both,
+            depth: Some(i32::MAX),
+        };
+
+        let query_string = serde_qs::to_string(&params).unwrap();
+
+        let request = Request::builder()
+            .uri(format!("/opsml/card/lineage?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let lineage: LineageResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(lineage.edges.len(), 1);
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_compare_runs() {
+        let helper = TestHelper::new().await;
+        let run_uids = vec![
+            "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            "550e8400-e29b-41d4-a716-446655440001".to_string(),
+        ];
+
+        for (i, run_uid) in run_uids.iter().enumerate() {
+            // the second run logs one extra step
+            let metrics = MetricRequest {
+                run_uid: run_uid.clone(),
+                metrics: (0..=i as i32 + 1)
+                    .map(|step| Metric {
+                        name: "loss".to_string(),
+                        value: step as f64,
+                        step: Some(step),
+                        ..Default::default()
+                    })
+                    .collect(),
+            };
+
+            let request = Request::builder()
+                .uri("/opsml/run/metrics")
+                .method("PUT")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&metrics).unwrap()))
+                .unwrap();
+
+            let response = helper.send_oneshot(request, true).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let parameters = ParameterRequest {
+                run_uid: run_uid.clone(),
+                parameters: vec![
+                    Parameter {
+                        name: "lr".to_string(),
+                        value: format!("0.{}", i + 1),
+                        ..Default::default()
+                    },
+                    Parameter {
+                        name: "epochs".to_string(),
+                        value: "10".to_string(),
+                        ..Default::default()
+                    },
+                ],
+            };
+
+            let request = Request::builder()
+                .uri("/opsml/run/parameters")
+                .method("PUT")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&parameters).unwrap()))
+                .unwrap();
+
+            let response = helper.send_oneshot(request, true).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let compare = CompareRunsRequest {
+            run_uids: run_uids.clone(),
+            ..Default::default()
+        };
+
+        let request = Request::builder()
+            .uri("/opsml/run/compare")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&compare).unwrap()))
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let comparison: CompareRunsResponse = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(comparison.metrics.len(), 1);
+        let loss = &comparison.metrics[0];
+        assert_eq!(loss.steps, vec![0, 1, 2]);
+        assert_eq!(loss.values[&run_uids[0]], vec![Some(0.0), Some(1.0), None]);
+        assert_eq!(
+            loss.values[&run_uids[1]],
+            vec![Some(0.0), Some(1.0), Some(2.0)]
+        );
+        assert_eq!(comparison.parameters.len(), 2);
+
+        // only the learning rate differs between the runs
+        let compare = CompareRunsRequest {
+            run_uids: run_uids.clone(),
+            differs_only: true,
+            ..Default::default()
+        };
+
+        let request = Request::builder()
+            .uri("/opsml/run/compare")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&compare).unwrap()))
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let comparison: CompareRunsResponse = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(comparison.parameters.len(), 1);
+        assert_eq!(comparison.parameters[0].name, "lr");
+        assert_eq!(
+            comparison.parameters[0].values[&run_uids[1]],
+            Some("0.2".to_string())
+        );
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_search_runs() {
+        let helper = TestHelper::new().await;
+        let run_uids = [
+            "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            "550e8400-e29b-41d4-a716-446655440001".to_string(),
+        ];
+
+        for (run_uid, (lr, auc)) in run_uids.iter().zip([("0.001", 0.9), ("0.01", 0.8)]) {
+            let metrics = MetricRequest {
+                run_uid: run_uid.clone(),
+                metrics: vec![Metric {
+                    name: "auc".to_string(),
+                    value: auc,
+                    step: Some(0),
+                    ..Default::default()
+                }],
+            };
+
+            let request = Request::builder()
+                .uri("/opsml/run/metrics")
+                .method("PUT")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&metrics).unwrap()))
+                .unwrap();
+
+            let response = helper.send_oneshot(request, true).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let parameters = ParameterRequest {
+                run_uid: run_uid.clone(),
+                parameters: vec![Parameter {
+                    name: "lr".to_string(),
+                    value: lr.to_string(),
+                    ..Default::default()
+                }],
+            };
+
+            let request = Request::builder()
+                .uri("/opsml/run/parameters")
+                .method("PUT")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&parameters).unwrap()))
+                .unwrap();
+
+            let response = helper.send_oneshot(request, true).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let search = RunQueryRequest {
+            parameters: vec![RunParameterFilter {
+                name: "lr".to_string(),
+                operator: ComparisonOperator::Lt,
+                value: "0.005".to_string(),
+            }],
+            metrics: vec![RunMetricFilter {
+                name: "auc".to_string(),
+                operator: ComparisonOperator::Gte,
+                value: 0.5,
+                aggregation: MetricAggregation::Last,
+            }],
+            ..Default::default()
+        };
+
+        let request = Request::builder()
+            .uri("/opsml/run/search")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&search).unwrap()))
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let results: RunQueryResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(results.runs.len(), 1);
+        assert_eq!(results.runs[0].uid, run_uids[0]);
+
+        // leaderboard
+        let leaderboard = RunLeaderboardRequest {
+            project: "project2".to_string(),
+            repository: None,
+            metric: "auc".to_string(),
+            aggregation: MetricAggregation::Max,
+            descending: true,
+            limit: None,
+        };
+
+        let query_string = serde_qs::to_string(&leaderboard).unwrap();
+
+        let request = Request::builder()
+            .uri(format!("/opsml/run/leaderboard?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let results: RunQueryResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(results.runs.len(), 1);
+        assert_eq!(results.runs[0].uid, run_uids[1]);
+        assert_eq!(results.runs[0].sort_value, Some(0.8));
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_card_aliases() {
+        let helper = TestHelper::new().await;
+
+        let mut uids = Vec::new();
+        for version in ["1.0.0", "1.1.0"] {
+            let card_request = CreateCardRequest {
+                card: ClientCard::Model(ModelCardClientRecord {
+                    name: "detector".to_string(),
+                    repository: "fraud".to_string(),
+                    version: version.to_string(),
+                    contact: "test".to_string(),
+                    ..ModelCardClientRecord::default()
+                }),
+                registry_type: RegistryType::Model,
+                status: None,
+            };
+
+            let request = Request::builder()
+                .uri("/opsml/card/create")
+                .method("POST")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&card_request).unwrap()))
+                .unwrap();
+
+            let response = helper.send_oneshot(request, true).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let create_response: CreateCardResponse = serde_json::from_slice(&body).unwrap();
+            uids.push(create_response.uid);
+        }
+
+        let alias_request = |route: &str, uid: &str| {
+            let params = CardAliasRequest {
+                registry_type: RegistryType::Model,
+                repository: "fraud".to_string(),
+                name: "detector".to_string(),
+                alias: "production".to_string(),
+                uid: uid.to_string(),
+            };
+
+            Request::builder()
+                .uri(format!("/opsml/card/alias/{}", route))
+                .method("POST")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&params).unwrap()))
+                .unwrap()
+        };
+
+        // moving an alias that does not exist yet
+        let response = helper
+            .send_oneshot(alias_request("move", &uids[1]), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = helper
+            .send_oneshot(alias_request("set", &uids[0]), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let alias: CardAliasResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(alias.alias.uid, uids[0]);
+        assert_eq!(alias.previous_uid, None);
+
+        // set does not silently move an alias
+        let response = helper
+            .send_oneshot(alias_request("set", &uids[1]), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        // cards of another (repository, name) cannot be aliased
+        let response = helper
+            .send_oneshot(
+                alias_request("move", "550e8400-e29b-41d4-a716-446655440000"),
+                true,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = helper
+            .send_oneshot(alias_request("move", &uids[1]), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let alias: CardAliasResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(alias.previous_uid, Some(uids[0].clone()));
+
+        // deployment tooling asks for the production model by alias
+        let args = ListCardRequest {
+            repository: Some("fraud".to_string()),
+            name: Some("detector".to_string()),
+            alias: Some("production".to_string()),
+            registry_type: RegistryType::Model,
+            ..ListCardRequest::default()
+        };
+        let query_string = serde_qs::to_string(&args).unwrap();
+        let request = Request::builder()
+            .uri(format!("/opsml/card/list?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let cards: CardResults = serde_json::from_slice(&body).unwrap();
+        match cards {
+            CardResults::Model(cards) => {
+                assert_eq!(cards.len(), 1);
+                assert_eq!(cards[0].uid, uids[1]);
+            }
+            _ => panic!("expected model cards"),
+        }
+
+        let params = ResolveCardAliasRequest {
+            registry_type: RegistryType::Model,
+            repository: "fraud".to_string(),
+            name: "detector".to_string(),
+            alias: "champion".to_string(),
+        };
+        let request = Request::builder()
+            .uri(format!(
+                "/opsml/card/alias?{}",
+                serde_qs::to_string(&params).unwrap()
+            ))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let params = CardAliasesRequest {
+            registry_type: RegistryType::Model,
+            repository: "fraud".to_string(),
+            name: "detector".to_string(),
+        };
+        let request = Request::builder()
+            .uri(format!(
+                "/opsml/card/alias/history?{}",
+                serde_qs::to_string(&params).unwrap()
+            ))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let history: CardAliasHistoryResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(history.history.len(), 2);
+        assert_eq!(history.history[1].previous_uid, Some(uids[0].clone()));
+        assert_eq!(history.history[1].updated_by, "admin");
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_card_status() {
+        let helper = TestHelper::new().await;
+
+        let mut uids = Vec::new();
+        for (version, status) in [("1.0.0", None), ("1.1.0", Some(CardStatus::Draft))] {
+            let card_request = CreateCardRequest {
+                card: ClientCard::Model(ModelCardClientRecord {
+                    name: "detector".to_string(),
+                    repository: "fraud".to_string(),
+                    version: version.to_string(),
+                    contact: "test".to_string(),
+                    ..ModelCardClientRecord::default()
+                }),
+                registry_type: RegistryType::Model,
+                status,
+            };
+
+            let request = Request::builder()
+                .uri("/opsml/card/create")
+                .method("POST")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&card_request).unwrap()))
+                .unwrap();
+
+            let response = helper.send_oneshot(request, true).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let create_response: CreateCardResponse = serde_json::from_slice(&body).unwrap();
+            uids.push(create_response.uid);
+        }
+
+        let status_request = |uid: &str, status: CardStatus| {
+            let params = CardStatusRequest {
+                registry_type: RegistryType::Model,
+                uid: uid.to_string(),
+                status,
+            };
+
+            Request::builder()
+                .uri("/opsml/card/status")
+                .method("POST")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&params).unwrap()))
+                .unwrap()
+        };
+
+        let response = helper
+            .send_oneshot(status_request(&uids[0], CardStatus::Archived), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let status: CardStatusResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(status.previous_status, CardStatus::Active);
+        assert_eq!(status.status, CardStatus::Archived);
+
+        // archived is terminal
+        let response = helper
+            .send_oneshot(status_request(&uids[0], CardStatus::Active), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response = helper
+            .send_oneshot(
+                status_request("00000000-0000-4000-8000-000000000000", CardStatus::Active),
+                true,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let list_cards = |status: Option<Vec<CardStatus>>| {
+            let args = ListCardRequest {
+                repository: Some("fraud".to_string()),
+                name: Some("detector".to_string()),
+                registry_type: RegistryType::Model,
+                status,
+                ..ListCardRequest::default()
+            };
+            let query_string = serde_qs::to_string(&args).unwrap();
+            Request::builder()
+                .uri(format!("/opsml/card/list?{}", query_string))
+                .method("GET")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // archived cards are hidden unless asked for
+        let response = helper.send_oneshot(list_cards(None), true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let cards: CardResults = serde_json::from_slice(&body).unwrap();
+        match cards {
+            CardResults::Model(cards) => {
+                assert_eq!(cards.len(), 1);
+                assert_eq!(cards[0].uid, uids[1]);
+                assert_eq!(cards[0].status, "draft");
+            }
+            _ => panic!("expected model cards"),
+        }
+
+        let response = helper
+            .send_oneshot(list_cards(Some(vec![CardStatus::Archived])), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let cards: CardResults = serde_json::from_slice(&body).unwrap();
+        match cards {
+            CardResults::Model(cards) => {
+                assert_eq!(cards.len(), 1);
+                assert_eq!(cards[0].uid, uids[0]);
+            }
+            _ => panic!("expected model cards"),
+        }
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_card_yank() {
+        let helper = TestHelper::new().await;
+
+        let mut uids = Vec::new();
+        for version in ["2.1.0", "2.1.1"] {
+            let card_request = CreateCardRequest {
+                card: ClientCard::Data(DataCardClientRecord {
+                    name: "daily".to_string(),
+                    repository: "snapshots".to_string(),
+                    version: version.to_string(),
+                    contact: "test".to_string(),
+                    ..DataCardClientRecord::default()
+                }),
+                registry_type: RegistryType::Data,
+                status: None,
+            };
+
+            let request = Request::builder()
+                .uri("/opsml/card/create")
+                .method("POST")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&card_request).unwrap()))
+                .unwrap();
+
+            let response = helper.send_oneshot(request, true).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let create_response: CreateCardResponse = serde_json::from_slice(&body).unwrap();
+            uids.push(create_response.uid);
+        }
+
+        let params = CardYankRequest {
+            registry_type: RegistryType::Data,
+            uid: uids[1].clone(),
+            yanked: true,
+        };
+        let request = Request::builder()
+            .uri("/opsml/card/yank")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&params).unwrap()))
+            .unwrap();
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let list_cards = |version: &str| {
+            let args = ListCardRequest {
+                repository: Some("snapshots".to_string()),
+                name: Some("daily".to_string()),
+                version: Some(version.to_string()),
+                registry_type: RegistryType::Data,
+                ..ListCardRequest::default()
+            };
+            let query_string = serde_qs::to_string(&args).unwrap();
+            Request::builder()
+                .uri(format!("/opsml/card/list?{}", query_string))
+                .method("GET")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // pipelines pinned to a range no longer pick up the yanked snapshot
+        let response = helper.send_oneshot(list_cards("^2.1.0"), true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let cards: CardResults = serde_json::from_slice(&body).unwrap();
+        match cards {
+            CardResults::Data(cards) => {
+                assert_eq!(cards.len(), 1);
+                assert_eq!(cards[0].uid, uids[0]);
+            }
+            _ => panic!("expected data cards"),
+        }
+
+        let response = helper.send_oneshot(list_cards("2.1.1"), true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let cards: CardResults = serde_json::from_slice(&body).unwrap();
+        match cards {
+            CardResults::Data(cards) => {
+                assert_eq!(cards.len(), 1);
+                assert!(cards[0].yanked);
+            }
+            _ => panic!("expected data cards"),
+        }
+
+        // the yanked version is still taken, so the next version is bumped past it
+        let args = CardVersionRequest {
+            registry_type: RegistryType::Data,
+            name: "daily".to_string(),
+            repository: "snapshots".to_string(),
+            version: Some("^2.1.0".to_string()),
+            version_type: VersionType::Patch,
+            pre_tag: None,
+            build_tag: None,
+            version_scheme: None,
+        };
+        let request = Request::builder()
+            .uri(format!(
+                "/opsml/card/version?{}",
+                serde_qs::to_string(&args).unwrap()
+            ))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let version_response: CardVersionResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(version_response.version, "2.1.2");
+
+        let card_request = CreateCardRequest {
+            card: ClientCard::Data(DataCardClientRecord {
+                name: "daily".to_string(),
+                repository: "snapshots".to_string(),
+                version: version_response.version,
+                contact: "test".to_string(),
+                ..DataCardClientRecord::default()
+            }),
+            registry_type: RegistryType::Data,
+            status: None,
+        };
+        let request = Request::builder()
+            .uri("/opsml/card/create")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&card_request).unwrap()))
+            .unwrap();
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_calver() {
+        let helper = TestHelper::new().await;
+
+        let scheme = RepositoryVersionScheme {
+            repository: "nightly".to_string(),
+            version_scheme: VersionScheme::CalVer,
+        };
+        let request = Request::builder()
+            .uri("/opsml/card/repository/scheme")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&scheme).unwrap()))
+            .unwrap();
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = Request::builder()
+            .uri("/opsml/card/repository/scheme?repository=nightly")
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let scheme: RepositoryVersionScheme = serde_json::from_slice(&body).unwrap();
+        assert_eq!(scheme.version_scheme, VersionScheme::CalVer);
+
+        let next_version = || {
+            let args = CardVersionRequest {
+                registry_type: RegistryType::Data,
+                name: "snapshot".to_string(),
+                repository: "nightly".to_string(),
+                version: None,
+                version_type: VersionType::Patch,
+                pre_tag: None,
+                build_tag: None,
+                version_scheme: None,
+            };
+            let query_string = serde_qs::to_string(&args).unwrap();
+            Request::builder()
+                .uri(format!("/opsml/card/version?{}", query_string))
+                .method("GET")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // each registration today gets the next build number of the day
+        let mut versions = Vec::new();
+        for _ in 0..2 {
+            let response = helper.send_oneshot(next_version(), true).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let version = serde_json::from_slice::<CardVersionResponse>(&body)
+                .unwrap()
+                .version;
+
+            let card_request = CreateCardRequest {
+                card: ClientCard::Data(DataCardClientRecord {
+                    name: "snapshot".to_string(),
+                    repository: "nightly".to_string(),
+                    version: version.clone(),
+                    contact: "test".to_string(),
+                    ..DataCardClientRecord::default()
+                }),
+                registry_type: RegistryType::Data,
+                status: None,
+            };
+            let request = Request::builder()
+                .uri("/opsml/card/create")
+                .method("POST")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&card_request).unwrap()))
+                .unwrap();
+            let response = helper.send_oneshot(request, true).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            versions.push(CalVer::parse(&version).unwrap());
+        }
+        assert_eq!(versions[0].build, 1);
+        assert_eq!(
+            versions[1],
+            CalVer::next(Some(&versions[0]), versions[0].date)
+        );
+
+        // version filters follow the repository scheme
+        let args = ListCardRequest {
+            repository: Some("nightly".to_string()),
+            name: Some("snapshot".to_string()),
+            version: Some(versions[1].to_string()),
+            registry_type: RegistryType::Data,
+            ..ListCardRequest::default()
+        };
+        let query_string = serde_qs::to_string(&args).unwrap();
+        let request = Request::builder()
+            .uri(format!("/opsml/card/list?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let cards: CardResults = serde_json::from_slice(&body).unwrap();
+        match cards {
+            CardResults::Data(cards) => {
+                assert_eq!(cards.len(), 1);
+                assert_eq!(cards[0].version, versions[1].to_string());
+            }
+            _ => panic!("expected data cards"),
+        }
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_card_version_conflict() {
+        let helper = TestHelper::new().await;
+
+        let card_request = CreateCardRequest {
+            card: ClientCard::Model(ModelCardClientRecord {
+                name: "classifier".to_string(),
+                repository: "ci".to_string(),
+                version: "1.3.0".to_string(),
+                contact: "test".to_string(),
+                ..ModelCardClientRecord::default()
+            }),
+            registry_type: RegistryType::Model,
+            status: None,
+        };
+        let create_card = || {
+            Request::builder()
+                .uri("/opsml/card/create")
+                .method("POST")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&card_request).unwrap()))
+                .unwrap()
+        };
+
+        let response = helper.send_oneshot(create_card(), true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // a second job that allocated the same version is told to retry
+        let response = helper.send_oneshot(create_card(), true).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(body["error"].as_str().unwrap().contains("1.3.0"));
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_run_metrics_unknown_run() {
+        let helper = TestHelper::new().await;
+        let run_uid = "00000000-0000-0000-0000-000000000000".to_string();
+
+        let request = MetricRequest {
+            run_uid: run_uid.clone(),
+            metrics: vec![Metric {
+                name: "metric1".to_string(),
+                value: 1.0,
+                ..Default::default()
+            }],
+        };
+
+        let request = Request::builder()
+            .uri("/opsml/run/metrics")
+            .method("PUT")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&request).unwrap()))
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // the missing run has no metrics to read back either
+        let request = Request::builder()
+            .uri("/opsml/run/metrics")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(&GetMetricRequest::new(run_uid, None)).unwrap(),
+            ))
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_run_lifecycle() {
+        let helper = TestHelper::new().await;
+        let run_uid = "550e8400-e29b-41d4-a716-446655440000".to_string();
+
+        let post = |uri: &str, body: String| {
+            Request::builder()
+                .uri(uri)
+                .method("POST")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let run_request = |run_uid: &str| {
+            serde_json::to_string(&RunStatusRequest {
+                run_uid: run_uid.to_string(),
+            })
+            .unwrap()
+        };
+
+        // only registered runs can start
+        let response = helper
+            .send_oneshot(post("/opsml/run/start", run_request("missing")), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = helper
+            .send_oneshot(post("/opsml/run/start", run_request(&run_uid)), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let started: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(started["status"], "running");
+
+        let response = helper
+            .send_oneshot(post("/opsml/run/start", run_request(&run_uid)), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response = helper
+            .send_oneshot(post("/opsml/run/heartbeat", run_request(&run_uid)), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // crashed is reserved for the heartbeat monitor
+        let finish = |status: RunStatus| {
+            serde_json::to_string(&FinishRunRequest {
+                run_uid: run_uid.clone(),
+                status,
+            })
+            .unwrap()
+        };
+        let response = helper
+            .send_oneshot(post("/opsml/run/finish", finish(RunStatus::Crashed)), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = helper
+            .send_oneshot(post("/opsml/run/finish", finish(RunStatus::Failed)), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = Request::builder()
+            .uri(format!("/opsml/run/status?run_uid={}", run_uid))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let status: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(status["status"], "failed");
+        assert!(!status["ended_at"].is_null());
+
+        // ended runs no longer take heartbeats
+        let response = helper
+            .send_oneshot(post("/opsml/run/heartbeat", run_request(&run_uid)), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_child_runs() {
+        let helper = TestHelper::new().await;
+        let parent_uid = "550e8400-e29b-41d4-a716-446655440000".to_string();
+
+        let create = |name: &str, parent_uid: &str| {
+            let card_request = CreateCardRequest {
+                card: ClientCard::Run(RunCardClientRecord {
+                    name: name.to_string(),
+                    repository: "repo1".to_string(),
+                    version: "1.0.0".to_string(),
+                    contact: "test".to_string(),
+                    parent_uid: Some(parent_uid.to_string()),
+                    ..RunCardClientRecord::default()
+                }),
+                registry_type: RegistryType::Run,
+                status: None,
+            };
+            Request::builder()
+                .uri("/opsml/card/create")
+                .method("POST")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&card_request).unwrap()))
+                .unwrap()
+        };
+
+        // parents must already be registered
+        let response = helper
+            .send_oneshot(create("orphan", "missing-parent"), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let mut child_uids = Vec::new();
+        for (name, loss) in [("trial-1", 0.4), ("trial-2", 0.6)] {
+            let response = helper.send_oneshot(create(name, &parent_uid), true).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let created: CreateCardResponse = serde_json::from_slice(&body).unwrap();
+
+            let metrics = MetricRequest {
+                run_uid: created.uid.clone(),
+                metrics: vec![Metric {
+                    name: "loss".to_string(),
+                    value: loss,
+                    ..Default::default()
+                }],
+            };
+            let request = Request::builder()
+                .uri("/opsml/run/metrics")
+                .method("PUT")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&metrics).unwrap()))
+                .unwrap();
+            let response = helper.send_oneshot(request, true).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            child_uids.push(created.uid);
+        }
+
+        let request = Request::builder()
+            .uri(format!("/opsml/run/children?parent_uid={}", parent_uid))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let children: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let runs = children["runs"].as_array().unwrap();
+        assert_eq!(runs.len(), 2);
+        assert!(runs
+            .iter()
+            .all(|run| run["parent_uid"] == parent_uid.as_str()));
+
+        let request = Request::builder()
+            .uri(format!(
+                "/opsml/run/children/metrics?parent_uid={}",
+                parent_uid
+            ))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let summary: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let loss = &summary["metrics"][0];
+        assert_eq!(loss["name"], "loss");
+        assert_eq!(loss["run_count"], 2);
+        assert_eq!(loss["min_run_uid"], child_uids[0].as_str());
+        assert_eq!(loss["max_run_uid"], child_uids[1].as_str());
+
+        // unknown parents are not found
+        let request = Request::builder()
+            .uri("/opsml/run/children?parent_uid=missing")
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_repository_permissions() {
+        let helper = TestHelper::new().await;
+        let token = helper.create_user("intern", &["read", "write:repo2"]).await;
+
+        // seeded run in repo1
+        let run_uid = "550e8400-e29b-41d4-a716-446655440000";
+
+        let create = |repository: &str| {
+            let card_request = CreateCardRequest {
+                card: ClientCard::Run(RunCardClientRecord {
+                    name: "intern-run".to_string(),
+                    repository: repository.to_string(),
+                    version: "1.0.0".to_string(),
+                    contact: "intern".to_string(),
+                    ..RunCardClientRecord::default()
+                }),
+                registry_type: RegistryType::Run,
+                status: None,
+            };
+            Request::builder()
+                .uri("/opsml/card/create")
+                .method("POST")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&card_request).unwrap()))
+                .unwrap()
+        };
+        let delete_query = |uid: String| {
+            serde_qs::to_string(&UidRequest {
+                uid,
+                registry_type: RegistryType::Run,
+            })
+            .unwrap()
+        };
+        let log_metric = |run_uid: &str| {
+            let request = MetricRequest {
+                run_uid: run_uid.to_string(),
+                metrics: vec![Metric {
+                    name: "loss".to_string(),
+                    value: 1.0,
+                    ..Default::default()
+                }],
+            };
+            Request::builder()
+                .uri("/opsml/run/metrics")
+                .method("PUT")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&request).unwrap()))
+                .unwrap()
+        };
+
+        // writes outside the granted repository are denied with a uniform body
+        let response = helper.send_as(create("repo1"), &token).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let denied: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(denied["error"], "Permission denied");
+        assert_eq!(
+            denied["message"],
+            "User intern does not have write permission on repository repo1"
+        );
+
+        let response = helper.send_as(log_metric(run_uid), &token).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // the repository of an existing card comes from its record
+        let request = Request::builder()
+            .uri(format!(
+                "/opsml/card/delete?{}",
+                delete_query(run_uid.to_string())
+            ))
+            .method("DELETE")
+            .body(Body::empty())
+            .unwrap();
+        let response = helper.send_as(request, &token).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let denied: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            denied["message"],
+            "User intern does not have delete permission on repository repo1"
+        );
+
+        // global read still covers other repositories
+        let request = Request::builder()
+            .uri(format!("/opsml/run/metrics/names?run_uid={}", run_uid))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+        let response = helper.send_as(request, &token).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // the granted repository accepts writes
+        let response = helper.send_as(create("repo2"), &token).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let created: CreateCardResponse = serde_json::from_slice(&body).unwrap();
+
+        let response = helper.send_as(log_metric(&created.uid), &token).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // write does not imply delete
+        let request = Request::builder()
+            .uri(format!(
+                "/opsml/card/delete?{}",
+                delete_query(created.uid.clone())
+            ))
+            .method("DELETE")
+            .body(Body::empty())
+            .unwrap();
+        let response = helper.send_as(request, &token).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // unknown and malformed uids have no repository to grant access on
+        let request = Request::builder()
+            .uri(format!(
+                "/opsml/card/delete?{}",
+                delete_query("00000000-0000-4000-8000-000000000000".to_string())
+            ))
+            .method("DELETE")
+            .body(Body::empty())
+            .unwrap();
+        let response = helper.send_as(request, &token).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = helper.send_as(log_metric("not-a-uid"), &token).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let request = Request::builder()
+            .uri("/opsml/run/metrics")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(&GetMetricRequest::new("not-a-uid".to_string(), None))
+                    .unwrap(),
+            ))
+            .unwrap();
+        let response = helper.send_as(request, &token).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_repository_read_permissions() {
+        let helper = TestHelper::new().await;
+        let token = helper.create_user("reader", &["read:repo2"]).await;
+
+        // seeded data card and runs in repo1 and repo2
+        let data_uid = "550e8400-e29b-41d4-a716-446655440000";
+        let run_uids = [
+            "550e8400-e29b-41d4-a716-446655440000",
+            "550e8400-e29b-41d4-a716-446655440001",
+        ];
+
+        let create = |card: ClientCard, registry_type: RegistryType| {
+            let card_request = CreateCardRequest {
+                card,
+                registry_type,
+                status: None,
+            };
+            Request::builder()
+                .uri("/opsml/card/create")
+                .method("POST")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&card_request).unwrap()))
+                .unwrap()
+        };
+        let get = |uri: String| {
+            Request::builder()
+                .uri(uri)
+                .method("GET")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // model in repo2 trained on the repo1 data card
+        let model = ClientCard::Model(ModelCardClientRecord {
+            name: "reader-model".to_string(),
+            repository: "repo2".to_string(),
+            version: "1.0.0".to_string(),
+            contact: "test".to_string(),
+            datacard_uid: Some(data_uid.to_string()),
+            ..ModelCardClientRecord::default()
+        });
+        let response = helper
+            .send_oneshot(create(model, RegistryType::Model), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let model_uid = serde_json::from_slice::<CreateCardResponse>(&body)
+            .unwrap()
+            .uid;
+
+        // history is read from the repository of the card
+        let history = |uid: &str, registry_type: RegistryType| {
+            let args = CardHistoryRequest {
+                uid: uid.to_string(),
+                registry_type,
+            };
+            get(format!(
+                "/opsml/card/history?{}",
+                serde_qs::to_string(&args).unwrap()
+            ))
+        };
+        let response = helper
+            .send_as(history(&model_uid, RegistryType::Model), &token)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let entries = serde_json::from_slice::<CardHistoryResponse>(&body)
+            .unwrap()
+            .history;
+        assert_eq!(entries.len(), 1);
+
+        let response = helper
+            .send_as(history(run_uids[0], RegistryType::Run), &token)
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // lineage leaves out cards in repositories the reader cannot see
+        let lineage = |uid: &str, registry_type: RegistryType| {
+            let args = LineageRequest {
+                uid: uid.to_string(),
+                registry_type,
+                direction: LineageDirection::Both,
+                depth: None,
+            };
+            get(format!(
+                "/opsml/card/lineage?{}",
+                serde_qs::to_string(&args).unwrap()
+            ))
+        };
+        let response = helper
+            .send_as(lineage(&model_uid, RegistryType::Model), &token)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let graph: LineageResponse = serde_json::from_slice(&body).unwrap();
+        assert!(graph.edges.is_empty());
+        assert!(graph.nodes.iter().all(|node| node.repository == "repo2"));
+
+        let response = helper
+            .send_oneshot(lineage(&model_uid, RegistryType::Model), true)
+            .await;
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let graph: LineageResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(graph.edges.len(), 1);
+
+        let response = helper
+            .send_as(lineage(data_uid, RegistryType::Data), &token)
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // leaderboards need the repository of the project
+        let leaderboard = |repository: Option<&str>| {
+            let args = RunLeaderboardRequest {
+                project: "project2".to_string(),
+                repository: repository.map(str::to_string),
+                metric: "auc".to_string(),
+                aggregation: MetricAggregation::Max,
+                descending: true,
+                limit: None,
+            };
+            get(format!(
+                "/opsml/run/leaderboard?{}",
+                serde_qs::to_string(&args).unwrap()
+            ))
+        };
+        let response = helper.send_as(leaderboard(None), &token).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = helper.send_as(leaderboard(Some("repo1")), &token).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = helper.send_as(leaderboard(Some("repo2")), &token).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let results: RunQueryResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(results.runs.len(), 1);
+        assert_eq!(results.runs[0].uid, run_uids[1]);
+
+        // a project card ties the project to its repository
+        let project = ClientCard::Project(ProjectCardClientRecord {
+            name: "project2".to_string(),
+            repository: "repo2".to_string(),
+            version: "1.0.0".to_string(),
+            ..ProjectCardClientRecord::default()
+        });
+        let response = helper
+            .send_oneshot(create(project, RegistryType::Project), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = helper.send_as(leaderboard(None), &token).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // the trash only lists cards of readable repositories
+        for run_uid in run_uids {
+            let args = UidRequest {
+                uid: run_uid.to_string(),
+                registry_type: RegistryType::Run,
+            };
+            let request = Request::builder()
+                .uri(format!(
+                    "/opsml/card/delete?{}",
+                    serde_qs::to_string(&args).unwrap()
+                ))
+                .method("DELETE")
+                .body(Body::empty())
+                .unwrap();
+            let response = helper.send_oneshot(request, true).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let trash = |repository: Option<&str>| {
+            let args = DeletedCardsRequest {
+                registry_type: RegistryType::Run,
+                repository: repository.map(str::to_string),
+            };
+            get(format!(
+                "/opsml/card/trash?{}",
+                serde_qs::to_string(&args).unwrap()
+            ))
+        };
+        let response = helper.send_as(trash(None), &token).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let deleted: DeletedCardsResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(deleted.cards.len(), 1);
+        assert_eq!(deleted.cards[0].uid, run_uids[1]);
+
+        let response = helper.send_as(trash(Some("repo1")), &token).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = helper.send_as(trash(Some("repo2")), &token).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        helper.cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_opsml_server_group_permissions() {
+        let helper = TestHelper::new().await;
+
+        // grants come from the group, not the user
+        let client = get_sql_client().await;
+        let group = Group::new("ml-team".to_string(), vec!["write:repo*".to_string()]);
+        client.insert_group(&group).await.unwrap();
+        client.add_group_member("ml-team", "member").await.unwrap();
+
+        let token = helper.create_user("member", &["read"]).await;
+
+        let create = |repository: &str| {
+            let card_request = CreateCardRequest {
+                card: ClientCard::Run(RunCardClientRecord {
+                    name: "group-run".to_string(),
+                    repository: repository.to_string(),
+                    version: "1.0.0".to_string(),
+                    contact: "member".to_string(),
+                    ..RunCardClientRecord::default()
+                }),
+                registry_type: RegistryType::Run,
+                status: None,
+            };
+            Request::builder()
+                .uri("/opsml/card/create")
+                .method("POST")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_string(&card_request).unwrap()))
+                .unwrap()
+        };
+
+        let response = helper.send_as(create("repo1"), &token).await;
         assert_eq!(response.status(), StatusCode::OK);
 
-        let body = response.into_body().collect().await.unwrap().to_bytes();
-        let repository_response: RepositoryResponse = serde_json::from_slice(&body).unwrap();
+        let response = helper.send_as(create("repo2"), &token).await;
+        assert_eq!(response.status(), StatusCode::OK);
 
-        // assert 10
-        assert_eq!(repository_response.repositories.len(), 10);
+        let response = helper.send_as(create("other"), &token).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
 
         helper.cleanup();
     }
 
     #[tokio::test]
-    async fn test_opsml_server_card_stats_and_query() {
+    async fn test_opsml_server_user_admin() {
         let helper = TestHelper::new().await;
 
-        /////////////////////// Test registry stats ///////////////////////
-
-        let params = RegistryStatsRequest {
-            registry_type: RegistryType::Model,
-            search_term: None,
+        let json_request = |method: &str, uri: &str, body: serde_json::Value| {
+            Request::builder()
+                .uri(uri)
+                .method(method)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let healthcheck = || {
+            Request::builder()
+                .uri("/opsml/healthcheck")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap()
+        };
+        let login = |username: &str, password: &str| {
+            Request::builder()
+                .uri("/opsml/auth/api/login")
+                .header("Username", username)
+                .header("Password", password)
+                .body(Body::empty())
+                .unwrap()
         };
 
-        let query_string = serde_qs::to_string(&params).unwrap();
+        // administration is limited to admins
+        let intern = helper.create_user("intern", &["read"]).await;
         let request = Request::builder()
-            .uri(format!("/opsml/card/registry/stats?{}", query_string))
+            .uri("/opsml/auth/users")
             .method("GET")
             .body(Body::empty())
             .unwrap();
+        let response = helper.send_as(request, &intern).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let create_user = |password: &str| {
+            json_request(
+                "POST",
+                "/opsml/auth/users",
+                serde_json::json!({
+                    "username": "analyst",
+                    "password": password,
+                    "permissions": ["read"],
+                }),
+            )
+        };
+        let response = helper.send_oneshot(create_user("short"), true).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
-        let response = helper.send_oneshot(request, true).await;
+        let response = helper
+            .send_oneshot(create_user("analyst_password"), true)
+            .await;
         assert_eq!(response.status(), StatusCode::OK);
-
         let body = response.into_body().collect().await.unwrap().to_bytes();
-        let stats_response: RegistryStatsResponse = serde_json::from_slice(&body).unwrap();
-        assert_eq!(stats_response.stats.nbr_names, 10);
-
-        let params = RegistryStatsRequest {
-            registry_type: RegistryType::Model,
-            search_term: Some("Model1".to_string()),
+        let user: UserResponse = serde_json::from_slice(&body).unwrap();
+        assert!(user.active);
+        assert_eq!(user.permissions, vec!["read"]);
+
+        let response = helper
+            .send_oneshot(create_user("analyst_password"), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        // passwords are hashed and usable for login
+        let token = TestHelper::login_as(&helper.app, "analyst", "analyst_password").await;
+
+        // self-service password change needs the current password
+        let change_password = |current: &str| {
+            json_request(
+                "PUT",
+                "/opsml/auth/users/password",
+                serde_json::json!({
+                    "current_password": current,
+                    "new_password": "new_analyst_password",
+                }),
+            )
         };
-
-        let query_string = serde_qs::to_string(&params).unwrap();
-        let request = Request::builder()
-            .uri(format!("/opsml/card/registry/stats?{}", query_string))
-            .method("GET")
-            .body(Body::empty())
-            .unwrap();
-
-        let response = helper.send_oneshot(request, true).await;
+        let response = helper.send_as(change_password("wrong"), &token).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = helper
+            .send_as(change_password("analyst_password"), &token)
+            .await;
         assert_eq!(response.status(), StatusCode::OK);
 
+        let response = helper
+            .send_oneshot(login("analyst", "analyst_password"), false)
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let token = TestHelper::login_as(&helper.app, "analyst", "new_analyst_password").await;
+
+        // groups only take existing users
+        let create_group = |members: Vec<&str>| {
+            json_request(
+                "POST",
+                "/opsml/auth/groups",
+                serde_json::json!({
+                    "name": "analysts",
+                    "permissions": ["write:repo1"],
+                    "members": members,
+                }),
+            )
+        };
+        let response = helper
+            .send_oneshot(create_group(vec!["missing"]), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = helper
+            .send_oneshot(create_group(vec!["analyst"]), true)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
         let body = response.into_body().collect().await.unwrap().to_bytes();
-        let stats_response: RegistryStatsResponse = serde_json::from_slice(&body).unwrap();
-        assert_eq!(stats_response.stats.nbr_names, 2);
+        let group: GroupResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(group.members, vec!["analyst"]);
 
-        /////////////////////// Test query page ///////////////////////
+        // deactivation invalidates outstanding tokens
+        let response = helper.send_as(healthcheck(), &token).await;
+        assert_eq!(response.status(), StatusCode::OK);
 
-        let args = QueryPageRequest {
-            registry_type: RegistryType::Model,
-            sort_by: None,
-            repository: None,
-            search_term: None,
-            page: None,
-        };
+        let response = helper
+            .send_oneshot(
+                json_request(
+                    "PUT",
+                    "/opsml/auth/users",
+                    serde_json::json!({ "username": "analyst", "active": false }),
+                ),
+                true,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
 
-        let query_string = serde_qs::to_string(&args).unwrap();
+        let response = helper.send_as(healthcheck(), &token).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = helper
+            .send_oneshot(login("analyst", "new_analyst_password"), false)
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 
+        // admins cannot lock themselves out
         let request = Request::builder()
-            .uri(format!("/opsml/card/registry/page?{}", query_string))
-            .method("GET")
+            .uri("/opsml/auth/users?username=admin")
+            .method("DELETE")
             .body(Body::empty())
             .unwrap();
-
         let response = helper.send_oneshot(request, true).await;
-        assert_eq!(response.status(), StatusCode::OK);
-
-        let body = response.into_body().collect().await.unwrap().to_bytes();
-        let page_response: QueryPageResponse = serde_json::from_slice(&body).unwrap();
-
-        assert_eq!(page_response.summaries.len(), 10);
-
-        let args = QueryPageRequest {
-            registry_type: RegistryType::Model,
-            sort_by: None,
-            repository: None,
-            search_term: Some("Model2".to_string()),
-            page: None,
-        };
-
-        let query_string = serde_qs::to_string(&args).unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
         let request = Request::builder()
-            .uri(format!("/opsml/card/registry/page?{}", query_string))
-            .method("GET")
+            .uri("/opsml/auth/users?username=analyst")
+            .method("DELETE")
             .body(Body::empty())
             .unwrap();
-
         let response = helper.send_oneshot(request, true).await;
         assert_eq!(response.status(), StatusCode::OK);
 
-        let body = response.into_body().collect().await.unwrap().to_bytes();
-        let page_response: QueryPageResponse = serde_json::from_slice(&body).unwrap();
-
-        assert_eq!(page_response.summaries.len(), 1);
-
-        helper.cleanup();
-    }
-
-    #[tokio::test]
-    async fn test_opsml_server_card_versions() {
-        let helper = TestHelper::new().await;
-
-        /////////////////////////// Card Versions/////////////////////
-        let args = CardVersionRequest {
-            registry_type: RegistryType::Data,
-            name: "Data1".to_string(),
-            repository: "repo1".to_string(),
-            version: None,
-            version_type: VersionType::Minor,
-            pre_tag: None,
-            build_tag: None,
-        };
-
-        let query_string = serde_qs::to_string(&args).unwrap();
-
         let request = Request::builder()
-            .uri(format!("/opsml/card/version?{}", query_string))
+            .uri("/opsml/auth/groups")
             .method("GET")
             .body(Body::empty())
             .unwrap();
-
         let response = helper.send_oneshot(request, true).await;
         assert_eq!(response.status(), StatusCode::OK);
-
         let body = response.into_body().collect().await.unwrap().to_bytes();
-        let version_response: CardVersionResponse = serde_json::from_slice(&body).unwrap();
-
-        assert_eq!(version_response.version, "3.1.0");
+        let groups: GroupsResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(groups.groups.len(), 1);
+        assert!(groups.groups[0].members.is_empty());
 
         helper.cleanup();
     }
 
     #[tokio::test]
-    async fn test_opsml_server_list_cards() {
+    async fn test_opsml_server_admin_bootstrap() {
         let helper = TestHelper::new().await;
+        let client = get_sql_client().await;
 
-        let args = ListCardRequest {
-            uid: None,
-            name: None,
-            repository: None,
-            version: None,
-            max_date: None,
-            tags: None,
-            limit: None,
-            sort_by_timestamp: None,
-            registry_type: RegistryType::Data,
+        // existing users are left untouched
+        let mut config = OpsmlConfig {
+            opsml_auth: true,
+            opsml_username: Some("ops_admin".to_string()),
+            opsml_password: Some("ops_password".to_string()),
+            ..Default::default()
         };
 
-        let query_string = serde_qs::to_string(&args).unwrap();
-
-        let request = Request::builder()
-            .uri(format!("/opsml/card/list?{}", query_string))
-            .method("GET")
-            .body(Body::empty())
-            .unwrap();
-
-        let response = helper.send_oneshot(request, true).await;
-        assert_eq!(response.status(), StatusCode::OK);
-
-        let body = response.into_body().collect().await.unwrap().to_bytes();
-        let card_results: CardResults = serde_json::from_slice(&body).unwrap();
-
-        assert_eq!(card_results.len(), 10);
-
-        let args = ListCardRequest {
-            uid: None,
-            name: None,
-            repository: Some("repo1".to_string()),
-            version: None,
-            max_date: None,
-            tags: None,
-            limit: None,
-            sort_by_timestamp: None,
-            registry_type: RegistryType::Model,
-        };
+        initialize_default_user(&config, &client).await.unwrap();
+        assert!(client.get_user("ops_admin").await.is_err());
 
-        let query_string = serde_qs::to_string(&args).unwrap();
+        // an empty users table gets an admin from the configured credentials
+        client.delete_user("admin").await.unwrap();
+        assert!(client.get_users().await.unwrap().is_empty());
 
-        let request = Request::builder()
-            .uri(format!("/opsml/card/list?{}", query_string))
-            .method("GET")
-            .body(Body::empty())
-            .unwrap();
+        initialize_default_user(&config, &client).await.unwrap();
+        let user = client.get_user("ops_admin").await.unwrap();
+        assert_eq!(user.group_permissions, vec!["admin"]);
+        TestHelper::login_as(&helper.app, "ops_admin", "ops_password").await;
 
-        let response = helper.send_oneshot(request, true).await;
-        assert_eq!(response.status(), StatusCode::OK);
+        // running again is a no-op
+        initialize_default_user(&config, &client).await.unwrap();
+        assert_eq!(client.get_users().await.unwrap().len(), 1);
 
-        let body = response.into_body().collect().await.unwrap().to_bytes();
-        let card_results: CardResults = serde_json::from_slice(&body).unwrap();
+        // without a password a one-time password is generated
+        client.delete_user("ops_admin").await.unwrap();
+        config.opsml_username = None;
+        config.opsml_password = None;
 
-        assert_eq!(card_results.len(), 1);
+        initialize_default_user(&config, &client).await.unwrap();
+        let user = client.get_user("admin").await.unwrap();
+        assert!(password_auth::verify_password("test_password", &user.password_hash).is_err());
 
         helper.cleanup();
     }
@@ -555,6 +2554,7 @@ mod tests {
                 ..DataCardClientRecord::default()
             }),
             registry_type: RegistryType::Data,
+            status: None,
         };
 
         let body = serde_json::to_string(&card_request).unwrap();
@@ -602,6 +2602,23 @@ mod tests {
             _ => panic!("Card not found"),
         };
 
+        // updates keep the lifecycle status of the card
+        let status_request = CardStatusRequest {
+            registry_type: RegistryType::Data,
+            uid: card.uid.clone(),
+            status: CardStatus::Deprecated,
+        };
+
+        let request = Request::builder()
+            .uri("/opsml/card/status")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&status_request).unwrap()))
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
         let card_request = UpdateCardRequest {
             registry_type: RegistryType::Data,
             card: ClientCard::Data(DataCardClientRecord {
@@ -611,7 +2628,7 @@ mod tests {
                 contact: "test".to_string(),
                 uid: Some(card.uid.clone()),
                 app_env: Some(card.app_env),
-                created_at: Some(card.created_at.unwrap()),
+                created_at: None,
                 runcard_uid: Some(card.runcard_uid),
                 pipelinecard_uid: Some(card.pipelinecard_uid),
                 auditcard_uid: Some(card.auditcard_uid),
@@ -658,6 +2675,91 @@ mod tests {
 
         assert!(!delete_response.exists);
 
+        // deleted card is in the trash
+        let trash_args = DeletedCardsRequest {
+            registry_type: RegistryType::Data,
+            repository: None,
+        };
+
+        let query_string = serde_qs::to_string(&trash_args).unwrap();
+
+        let request = Request::builder()
+            .uri(format!("/opsml/card/trash?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let trash_response: DeletedCardsResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(trash_response.cards.len(), 1);
+        assert_eq!(trash_response.cards[0].uid, card.uid);
+        assert_eq!(trash_response.cards[0].deleted_by, "admin");
+
+        // restore the card
+        let body = serde_json::to_string(&delete_args).unwrap();
+
+        let request = Request::builder()
+            .uri("/opsml/card/restore")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.clone()))
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // card is no longer in the trash
+        let request = Request::builder()
+            .uri("/opsml/card/restore")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // every change is recorded in the card history
+        let history_args = CardHistoryRequest {
+            uid: card.uid.clone(),
+            registry_type: RegistryType::Data,
+        };
+
+        let query_string = serde_qs::to_string(&history_args).unwrap();
+
+        let request = Request::builder()
+            .uri(format!("/opsml/card/history?{}", query_string))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let history = serde_json::from_slice::<CardHistoryResponse>(&body)
+            .unwrap()
+            .history;
+
+        let actions: Vec<&str> = history.iter().map(|h| h.action.as_str()).collect();
+        assert_eq!(
+            actions,
+            vec!["insert", "status", "update", "delete", "restore"]
+        );
+        assert!(history.iter().all(|h| h.username == "admin"));
+        assert_eq!(history[2].changes["version"]["old"], "1.0.0");
+        assert_eq!(history[2].changes["version"]["new"], "1.0.1");
+        assert!(history[2].changes.get("name").is_none());
+
+        // fields an update does not write are not reported as changed
+        assert!(history[2].changes.get("status").is_none());
+        assert!(history[2].changes.get("yanked").is_none());
+        assert!(history[2].changes.get("created_at").is_none());
+        assert_eq!(history[3].changes["uid"]["new"], serde_json::Value::Null);
+
         helper.cleanup();
     }
 
@@ -675,6 +2777,7 @@ mod tests {
                 ..ModelCardClientRecord::default()
             }),
             registry_type: RegistryType::Model,
+            status: None,
         };
 
         let body = serde_json::to_string(&card_request).unwrap();
@@ -798,6 +2901,7 @@ mod tests {
                 ..RunCardClientRecord::default()
             }),
             registry_type: RegistryType::Run,
+            status: None,
         };
 
         let body = serde_json::to_string(&card_request).unwrap();
@@ -861,6 +2965,7 @@ mod tests {
                 modelcard_uids: Some(card.modelcard_uids.0),
                 compute_environment: Some(card.compute_environment.0),
                 project: card.project,
+                parent_uid: card.parent_uid,
                 tags: card.tags.0,
             }),
         };
@@ -919,6 +3024,7 @@ mod tests {
                 ..PipelineCardClientRecord::default()
             }),
             registry_type: RegistryType::Pipeline,
+            status: None,
         };
 
         let body = serde_json::to_string(&card_request).unwrap();
@@ -1038,6 +3144,7 @@ mod tests {
                 ..AuditCardClientRecord::default()
             }),
             registry_type: RegistryType::Audit,
+            status: None,
         };
 
         let body = serde_json::to_string(&card_request).unwrap();
@@ -1157,6 +3264,7 @@ mod tests {
                 ..Default::default()
             }),
             registry_type: RegistryType::Project,
+            status: None,
         };
 
         let body = serde_json::to_string(&card_request).unwrap();
@@ -1316,6 +3424,48 @@ mod tests {
 
         assert_eq!(metrics.len(), 1);
 
+        // summarize metrics
+        let body = GetMetricRequest::new(run_uid.clone(), Some(vec!["metric1".to_string()]));
+
+        let request = Request::builder()
+            .uri("/opsml/run/metrics/summary")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&body).unwrap()))
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let summaries: Vec<MetricSummary> = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].name, "metric1");
+        assert_eq!(summaries[0].count, 1);
+        assert_eq!(summaries[0].last, 1.0);
+
+        // downsampled metrics
+        let body = GetMetricRequest {
+            max_points: Some(2),
+            ..GetMetricRequest::new(run_uid.clone(), None)
+        };
+
+        let request = Request::builder()
+            .uri("/opsml/run/metrics")
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&body).unwrap()))
+            .unwrap();
+
+        let response = helper.send_oneshot(request, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let metrics: Vec<Metric> = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(metrics.len(), 2);
+
         // insert parameter
 
         let request = ParameterRequest {
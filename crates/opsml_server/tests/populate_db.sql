@@ -52,5 +52,5 @@ INSERT INTO opsml_audit_registry (uid, app_env, name, repository, major, minor,
 
 
 
-INSERT INTO opsml_users (username, password_hash, permissions, group_permissions, refresh_token) VALUES
+INSERT OR IGNORE INTO opsml_users (username, password_hash, permissions, group_permissions, refresh_token) VALUES
 ('admin', '$argon2id$v=19$m=19456,t=2,p=1$+OB+o3Q2x9jwj0Tz1Y8vcA$TXAyajadxyCdaYwjU3zvEylBt9KMosfwfx7xC6PERgI', '["read", "write"]', '["admin"]', NULL)
\ No newline at end of file
@@ -70,6 +70,8 @@ pub struct OpsmlConfig {
     pub scouter_password: Option<String>,
     pub scouter_auth: bool,
     pub opsml_auth: bool,
+    pub opsml_trash_retention_days: i64,
+    pub opsml_run_heartbeat_timeout_secs: i64,
     pub client_mode: bool,
 }
 
@@ -133,6 +135,14 @@ impl Default for OpsmlConfig {
                 .unwrap_or_else(|_| "false".to_string())
                 .parse()
                 .unwrap_or(false),
+            opsml_trash_retention_days: env::var("OPSML_TRASH_RETENTION_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            opsml_run_heartbeat_timeout_secs: env::var("OPSML_RUN_HEARTBEAT_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .unwrap_or(300),
             client_mode: using_client,
         }
     }
@@ -0,0 +1,7 @@
+// generated by `sqlx migrate build-script`
+fn main() {
+    // trigger recompilation when a new migration is added
+    println!("cargo:rerun-if-changed=src/sqlite/migrations");
+    println!("cargo:rerun-if-changed=src/postgres/migrations");
+    println!("cargo:rerun-if-changed=src/mysql/migrations");
+}
@@ -0,0 +1,586 @@
+use crate::base::{get_registry_type, SqlClient, CARD_REGISTRIES};
+use crate::enums::client::SqlClientEnum;
+use crate::schemas::schema::{
+    AuditCardRecord, Card, CardHistoryRecord, CardResults, DataCardRecord, HardwareMetricsRecord,
+    MetricRecord, ModelCardRecord, ParameterRecord, PipelineCardRecord, ProjectCardRecord,
+    RunCardRecord, User,
+};
+use chrono::NaiveDateTime;
+use opsml_error::error::SqlError;
+use opsml_types::{CardQueryArgs, CardSQLTableNames, CardStatus};
+use opsml_utils::utils::get_utc_datetime;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use tracing::info;
+
+/// Version of the archive line format. Bumped whenever a record changes shape
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+/// Number of cards read per page during export
+const EXPORT_PAGE_SIZE: i32 = 100;
+
+/// Number of metric, parameter or hardware metric rows inserted per statement during import
+const IMPORT_BATCH_SIZE: usize = 500;
+
+/// A single line of a registry archive. Archives are newline-delimited json where every line
+/// is tagged with its `kind`. The header is always the first line
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ArchiveRecord {
+    Header {
+        format_version: u32,
+        source: String,
+        exported_at: NaiveDateTime,
+    },
+    DataCard(DataCardRecord),
+    ModelCard(ModelCardRecord),
+    RunCard(RunCardRecord),
+    AuditCard(AuditCardRecord),
+    PipelineCard(PipelineCardRecord),
+    ProjectCard(ProjectCardRecord),
+    DeletedCard {
+        table: CardSQLTableNames,
+        uid: String,
+        deleted_by: String,
+    },
+    CardHistory(CardHistoryRecord),
+    Metric(MetricRecord),
+    Parameter(ParameterRecord),
+    HardwareMetric(HardwareMetricsRecord),
+    User(User),
+}
+
+/// Number of records written to or read from an archive
+///
+/// # Fields
+///
+/// * `cards` - Cards per registry type (data, model, run, audit, pipeline, project)
+/// * `deleted_cards` - Cards that are in the trash
+/// * `card_history` - Card history entries
+/// * `metrics` - Run metrics
+/// * `parameters` - Run parameters
+/// * `hardware_metrics` - Run hardware metrics
+/// * `users` - Users
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveCounts {
+    pub cards: BTreeMap<String, usize>,
+    pub deleted_cards: usize,
+    pub card_history: usize,
+    pub metrics: usize,
+    pub parameters: usize,
+    pub hardware_metrics: usize,
+    pub users: usize,
+}
+
+impl ArchiveCounts {
+    fn add_card(&mut self, table: &CardSQLTableNames) {
+        *self.cards.entry(get_registry_type(table)).or_insert(0) += 1;
+    }
+
+    pub fn total_cards(&self) -> usize {
+        self.cards.values().sum()
+    }
+}
+
+impl std::fmt::Display for ArchiveCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (registry, count) in &self.cards {
+            writeln!(f, "{} cards: {}", registry, count)?;
+        }
+        writeln!(f, "deleted cards: {}", self.deleted_cards)?;
+        writeln!(f, "card history: {}", self.card_history)?;
+        writeln!(f, "metrics: {}", self.metrics)?;
+        writeln!(f, "parameters: {}", self.parameters)?;
+        writeln!(f, "hardware metrics: {}", self.hardware_metrics)?;
+        write!(f, "users: {}", self.users)
+    }
+}
+
+fn write_record<W: Write>(writer: &mut W, record: &ArchiveRecord) -> Result<(), SqlError> {
+    let line = serde_json::to_string(record)
+        .map_err(|e| SqlError::FileError(format!("Failed to serialize record: {}", e)))?;
+
+    writeln!(writer, "{}", line)
+        .map_err(|e| SqlError::FileError(format!("Failed to write archive: {}", e)))
+}
+
+/// Split a page of card results into archive records and the uids of the cards
+fn card_records(results: CardResults) -> (Vec<String>, Vec<ArchiveRecord>) {
+    match results {
+        CardResults::Data(cards) => (
+            cards.iter().map(|c| c.uid.clone()).collect(),
+            cards.into_iter().map(ArchiveRecord::DataCard).collect(),
+        ),
+        CardResults::Model(cards) => (
+            cards.iter().map(|c| c.uid.clone()).collect(),
+            cards.into_iter().map(ArchiveRecord::ModelCard).collect(),
+        ),
+        CardResults::Run(cards) => (
+            cards.iter().map(|c| c.uid.clone()).collect(),
+            cards.into_iter().map(ArchiveRecord::RunCard).collect(),
+        ),
+        CardResults::Audit(cards) => (
+            cards.iter().map(|c| c.uid.clone()).collect(),
+            cards.into_iter().map(ArchiveRecord::AuditCard).collect(),
+        ),
+        CardResults::Pipeline(cards) => (
+            cards.iter().map(|c| c.uid.clone()).collect(),
+            cards.into_iter().map(ArchiveRecord::PipelineCard).collect(),
+        ),
+        CardResults::Project(cards) => (
+            cards.iter().map(|c| c.uid.clone()).collect(),
+            cards.into_iter().map(ArchiveRecord::ProjectCard).collect(),
+        ),
+    }
+}
+
+/// Stream every registry table, run metric, run parameter, hardware metric and user to a
+/// newline-delimited json archive. Cards are read a page at a time (trashed cards included)
+/// and the run data of each page of run cards is written right after it
+///
+/// # Arguments
+///
+/// * `client` - The client to export from
+/// * `writer` - Where to write the archive
+///
+/// # Returns
+///
+/// * `ArchiveCounts` - The number of records written
+pub async fn export_registry<W: Write>(
+    client: &SqlClientEnum,
+    writer: &mut W,
+) -> Result<ArchiveCounts, SqlError> {
+    let mut counts = ArchiveCounts::default();
+
+    write_record(
+        writer,
+        &ArchiveRecord::Header {
+            format_version: ARCHIVE_FORMAT_VERSION,
+            source: client.name(),
+            exported_at: get_utc_datetime(),
+        },
+    )?;
+
+    for (_, table) in CARD_REGISTRIES.iter() {
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let args = CardQueryArgs {
+                limit: Some(EXPORT_PAGE_SIZE),
+                sort_by_timestamp: Some(true),
+                cursor: cursor.clone(),
+                include_deleted: true,
+                status: Some(CardStatus::all()),
+                ..Default::default()
+            };
+
+            let results = client.query_cards(table, &args).await?;
+            cursor = results.next_cursor(&args);
+
+            let (uids, records) = card_records(results);
+            for record in &records {
+                write_record(writer, record)?;
+                counts.add_card(table);
+            }
+
+            for uid in &uids {
+                for history in client.get_card_history(uid).await? {
+                    write_record(writer, &ArchiveRecord::CardHistory(history))?;
+                    counts.card_history += 1;
+                }
+            }
+
+            if *table == CardSQLTableNames::Run && !uids.is_empty() {
+                for metric in client.get_runs_metrics(&uids, &[]).await? {
+                    write_record(writer, &ArchiveRecord::Metric(metric))?;
+                    counts.metrics += 1;
+                }
+
+                for parameter in client.get_runs_parameters(&uids, &[]).await? {
+                    write_record(writer, &ArchiveRecord::Parameter(parameter))?;
+                    counts.parameters += 1;
+                }
+
+                for uid in &uids {
+                    for metric in client.get_hardware_metric(uid).await? {
+                        write_record(writer, &ArchiveRecord::HardwareMetric(metric))?;
+                        counts.hardware_metrics += 1;
+                    }
+                }
+            }
+
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        // trash markers come after every card of the table so import can replay them in place
+        for deleted in client.get_deleted_cards(table, None).await? {
+            write_record(
+                writer,
+                &ArchiveRecord::DeletedCard {
+                    table: table.clone(),
+                    uid: deleted.uid,
+                    deleted_by: deleted.deleted_by,
+                },
+            )?;
+            counts.deleted_cards += 1;
+        }
+    }
+
+    for user in client.get_users().await? {
+        write_record(writer, &ArchiveRecord::User(user))?;
+        counts.users += 1;
+    }
+
+    writer
+        .flush()
+        .map_err(|e| SqlError::FileError(format!("Failed to write archive: {}", e)))?;
+
+    info!("Exported registry from {}:\n{}", client.name(), counts);
+
+    Ok(counts)
+}
+
+/// Count what `export_registry` would write without writing anything. Run against the
+/// target after an import to verify it against the export counts
+///
+/// # Arguments
+///
+/// * `client` - The client to count
+pub async fn count_registry(client: &SqlClientEnum) -> Result<ArchiveCounts, SqlError> {
+    export_registry(client, &mut std::io::sink()).await
+}
+
+/// Buffered run data waiting to be inserted in batches
+#[derive(Default)]
+struct ImportBuffer {
+    metrics: Vec<MetricRecord>,
+    parameters: Vec<ParameterRecord>,
+    hardware_metrics: Vec<HardwareMetricsRecord>,
+}
+
+impl ImportBuffer {
+    async fn flush(&mut self, client: &SqlClientEnum, force: bool) -> Result<(), SqlError> {
+        if !self.metrics.is_empty() && (force || self.metrics.len() >= IMPORT_BATCH_SIZE) {
+            client.insert_run_metrics(&self.metrics).await?;
+            self.metrics.clear();
+        }
+
+        if !self.parameters.is_empty() && (force || self.parameters.len() >= IMPORT_BATCH_SIZE) {
+            client.insert_run_parameters(&self.parameters).await?;
+            self.parameters.clear();
+        }
+
+        if !self.hardware_metrics.is_empty()
+            && (force || self.hardware_metrics.len() >= IMPORT_BATCH_SIZE)
+        {
+            client
+                .insert_hardware_metrics(&self.hardware_metrics)
+                .await?;
+            self.hardware_metrics.clear();
+        }
+
+        Ok(())
+    }
+}
+
+/// Replay an archive written by `export_registry` into any backend. Uids, versions and
+/// created_at timestamps are inserted as-is, so the target registry should be empty.
+/// Trashed cards are moved back to the trash and users keep their password hash,
+/// permissions and active flag. User ids are reassigned by the target
+///
+/// # Arguments
+///
+/// * `client` - The client to import into
+/// * `reader` - The archive to read
+///
+/// # Returns
+///
+/// * `ArchiveCounts` - The number of records imported
+pub async fn import_registry<R: BufRead>(
+    client: &SqlClientEnum,
+    reader: R,
+) -> Result<ArchiveCounts, SqlError> {
+    let mut counts = ArchiveCounts::default();
+    let mut buffer = ImportBuffer::default();
+    let mut has_header = false;
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line =
+            line.map_err(|e| SqlError::FileError(format!("Failed to read archive: {}", e)))?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record: ArchiveRecord = serde_json::from_str(&line).map_err(|e| {
+            SqlError::FileError(format!("Invalid archive record on line {}: {}", idx + 1, e))
+        })?;
+
+        if !has_header {
+            match record {
+                ArchiveRecord::Header { format_version, .. }
+                    if format_version == ARCHIVE_FORMAT_VERSION =>
+                {
+                    has_header = true;
+                    continue;
+                }
+                ArchiveRecord::Header { format_version, .. } => {
+                    return Err(SqlError::FileError(format!(
+                        "Unsupported archive format version {}",
+                        format_version
+                    )));
+                }
+                _ => {
+                    return Err(SqlError::FileError(
+                        "Archive is missing its header line".to_string(),
+                    ));
+                }
+            }
+        }
+
+        let card = match record {
+            ArchiveRecord::Header { .. } => {
+                return Err(SqlError::FileError(format!(
+                    "Unexpected header on line {}",
+                    idx + 1
+                )));
+            }
+            ArchiveRecord::DataCard(card) => Some((CardSQLTableNames::Data, Card::Data(card))),
+            ArchiveRecord::ModelCard(card) => Some((CardSQLTableNames::Model, Card::Model(card))),
+            ArchiveRecord::RunCard(card) => Some((CardSQLTableNames::Run, Card::Run(card))),
+            ArchiveRecord::AuditCard(card) => Some((CardSQLTableNames::Audit, Card::Audit(card))),
+            ArchiveRecord::PipelineCard(card) => {
+                Some((CardSQLTableNames::Pipeline, Card::Pipeline(card)))
+            }
+            ArchiveRecord::ProjectCard(card) => {
+                Some((CardSQLTableNames::Project, Card::Project(card)))
+            }
+            ArchiveRecord::DeletedCard {
+                table,
+                uid,
+                deleted_by,
+            } => {
+                client.delete_card(&table, &uid, &deleted_by).await?;
+                counts.deleted_cards += 1;
+                None
+            }
+            ArchiveRecord::CardHistory(history) => {
+                client.insert_card_history(&history).await?;
+                counts.card_history += 1;
+                None
+            }
+            ArchiveRecord::Metric(metric) => {
+                buffer.metrics.push(metric);
+                counts.metrics += 1;
+                None
+            }
+            ArchiveRecord::Parameter(parameter) => {
+                buffer.parameters.push(parameter);
+                counts.parameters += 1;
+                None
+            }
+            ArchiveRecord::HardwareMetric(metric) => {
+                buffer.hardware_metrics.push(metric);
+                counts.hardware_metrics += 1;
+                None
+            }
+            ArchiveRecord::User(user) => {
+                // insert only sets credentials and permissions, the update carries the rest
+                client.insert_user(&user).await?;
+                client.update_user(&user).await?;
+                counts.users += 1;
+                None
+            }
+        };
+
+        if let Some((table, card)) = card {
+            client.insert_card(&table, &card).await?;
+            counts.add_card(&table);
+        }
+
+        buffer.flush(client, false).await?;
+    }
+
+    if !has_header {
+        return Err(SqlError::FileError("Archive is empty".to_string()));
+    }
+
+    buffer.flush(client, true).await?;
+
+    info!("Imported registry into {}:\n{}", client.name(), counts);
+
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opsml_settings::config::OpsmlDatabaseSettings;
+    use opsml_types::SqlType;
+    use sqlx::types::Json as SqlxJson;
+
+    async fn memory_client() -> SqlClientEnum {
+        let config = OpsmlDatabaseSettings {
+            connection_uri: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        SqlClientEnum::new(&config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_registry_export_import_roundtrip() {
+        let source = memory_client().await;
+
+        let created_at =
+            NaiveDateTime::parse_from_str("2023-01-02 03:04:05", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        let data_card = DataCardRecord {
+            created_at: Some(created_at),
+            ..DataCardRecord::default()
+        };
+        source
+            .insert_card(&CardSQLTableNames::Data, &Card::Data(data_card.clone()))
+            .await
+            .unwrap();
+
+        let trashed_card = DataCardRecord {
+            version: "1.1.0".to_string(),
+            minor: 1,
+            ..DataCardRecord::default()
+        };
+        source
+            .insert_card(&CardSQLTableNames::Data, &Card::Data(trashed_card.clone()))
+            .await
+            .unwrap();
+        source
+            .delete_card(&CardSQLTableNames::Data, &trashed_card.uid, "admin")
+            .await
+            .unwrap();
+
+        let run_card = RunCardRecord {
+            created_at: Some(created_at),
+            ..RunCardRecord::default()
+        };
+        source
+            .insert_card(&CardSQLTableNames::Run, &Card::Run(run_card.clone()))
+            .await
+            .unwrap();
+
+        source
+            .insert_card_history(&CardHistoryRecord::new(
+                &CardSQLTableNames::Data,
+                &data_card.uid,
+                "insert",
+                "admin",
+                None,
+                Some(&Card::Data(data_card.clone()).to_value()),
+            ))
+            .await
+            .unwrap();
+
+        let metrics: Vec<MetricRecord> = (0..3)
+            .map(|step| MetricRecord {
+                created_at: Some(created_at),
+                ..MetricRecord::new(
+                    run_card.uid.clone(),
+                    "loss".to_string(),
+                    step as f64,
+                    Some(step),
+                    None,
+                )
+            })
+            .collect();
+        source.insert_run_metrics(&metrics).await.unwrap();
+
+        let parameters = [ParameterRecord::new(
+            run_card.uid.clone(),
+            "lr".to_string(),
+            "0.01".to_string(),
+        )];
+        source.insert_run_parameters(&parameters).await.unwrap();
+
+        let hardware = [HardwareMetricsRecord {
+            run_uid: run_card.uid.clone(),
+            cpu_percent_per_core: Some(SqlxJson(vec![0.5, 0.25])),
+            ..HardwareMetricsRecord::default()
+        }];
+        source.insert_hardware_metrics(&hardware).await.unwrap();
+
+        let mut user = User::new("archive_user".to_string(), "hash".to_string(), None, None);
+        user.created_at = Some(created_at);
+        user.active = false;
+        source.insert_user(&user).await.unwrap();
+        source.update_user(&user).await.unwrap();
+
+        let mut archive: Vec<u8> = Vec::new();
+        let exported = export_registry(&source, &mut archive).await.unwrap();
+
+        assert_eq!(exported.cards.get("data"), Some(&2));
+        assert_eq!(exported.cards.get("run"), Some(&1));
+        assert_eq!(exported.total_cards(), 3);
+        assert_eq!(exported.deleted_cards, 1);
+        assert_eq!(exported.card_history, 1);
+        assert_eq!(exported.metrics, 3);
+        assert_eq!(exported.parameters, 1);
+        assert_eq!(exported.hardware_metrics, 1);
+        assert_eq!(exported.users, 1);
+
+        let target = memory_client().await;
+        let imported = import_registry(&target, archive.as_slice()).await.unwrap();
+        assert_eq!(imported, exported);
+
+        // the target now holds exactly what was exported
+        assert_eq!(count_registry(&target).await.unwrap(), exported);
+
+        let args = CardQueryArgs {
+            uid: Some(data_card.uid.clone()),
+            ..Default::default()
+        };
+        let cards = target
+            .query_cards(&CardSQLTableNames::Data, &args)
+            .await
+            .unwrap();
+        match cards {
+            CardResults::Data(cards) => {
+                assert_eq!(cards.len(), 1);
+                assert_eq!(cards[0].version, data_card.version);
+                assert_eq!(cards[0].created_at, Some(created_at));
+            }
+            _ => panic!("expected data cards"),
+        }
+
+        // trashed card stays in the trash
+        let deleted = target
+            .get_deleted_cards(&CardSQLTableNames::Data, None)
+            .await
+            .unwrap();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].uid, trashed_card.uid);
+
+        let metrics = target.get_run_metric(&run_card.uid, &[]).await.unwrap();
+        assert_eq!(metrics.len(), 3);
+        assert!(metrics.iter().all(|m| m.created_at == Some(created_at)));
+
+        let imported_user = target.get_user("archive_user").await.unwrap();
+        assert_eq!(imported_user.created_at, Some(created_at));
+        assert!(!imported_user.active);
+        assert_eq!(imported_user.password_hash, "hash");
+    }
+
+    #[tokio::test]
+    async fn test_registry_import_requires_header() {
+        let target = memory_client().await;
+
+        let archive = r#"{"kind":"parameter","run_uid":"abc","name":"lr","value":"0.1","created_at":null,"idx":null}"#;
+        let result = import_registry(&target, archive.as_bytes()).await;
+        assert!(result.is_err());
+
+        let result = import_registry(&target, "".as_bytes()).await;
+        assert!(result.is_err());
+    }
+}
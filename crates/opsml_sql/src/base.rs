@@ -1,63 +1,334 @@
 use crate::schemas::schema::{
-    Card, CardResults, CardSummary, HardwareMetricsRecord, MetricRecord, ParameterRecord,
-    QueryStats, User,
+    Card, CardAlias, CardAliasHistoryRecord, CardHistoryRecord, CardResults, CardSearchResult,
+    CardSummary, ChildMetricSummaryRecord, DeletedCard, Group, HardwareMetricsRecord, LineageEdge,
+    LineageNode, MetricRecord, MetricSummaryRecord, ParameterRecord, QueryStats,
+    RefreshTokenFamily, RunCardRecord, RunQueryResult, RunStatusRecord, User,
 };
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use opsml_error::error::SqlError;
 use opsml_settings::config::OpsmlDatabaseSettings;
-use opsml_types::{CardQueryArgs, CardSQLTableNames};
-use opsml_utils::semver::VersionParser;
-
-pub fn add_version_bounds(builder: &mut String, version: &str) -> Result<(), SqlError> {
-    let version_bounds = VersionParser::get_version_to_search(version)
-        .map_err(|e| SqlError::VersionError(format!("{}", e)))?;
-
-    // construct lower bound (already validated)
-    builder.push_str(
-        format!(
-            " AND (major >= {} AND minor >= {} and patch >= {})",
-            version_bounds.lower_bound.major,
-            version_bounds.lower_bound.minor,
-            version_bounds.lower_bound.patch
-        )
-        .as_str(),
-    );
-
-    if !version_bounds.no_upper_bound {
-        // construct upper bound based on number of components
-        if version_bounds.num_parts == 1 {
-            builder
-                .push_str(format!(" AND (major < {})", version_bounds.upper_bound.major).as_str());
-        } else if version_bounds.num_parts == 2
-            || version_bounds.num_parts == 3 && version_bounds.parser_type == VersionParser::Tilde
-            || version_bounds.num_parts == 3 && version_bounds.parser_type == VersionParser::Caret
-        {
-            builder.push_str(
-                format!(
-                    " AND (major = {} AND minor < {})",
-                    version_bounds.upper_bound.major, version_bounds.upper_bound.minor
-                )
-                .as_str(),
-            );
-        } else {
-            builder.push_str(
-                format!(
-                    " AND (major = {} AND minor = {} AND patch < {})",
-                    version_bounds.upper_bound.major,
-                    version_bounds.upper_bound.minor,
-                    version_bounds.upper_bound.patch
-                )
-                .as_str(),
-            );
+use opsml_types::{
+    CardQueryArgs, CardSQLTableNames, CardStatus, GetMetricRequest, RunQueryRequest, RunStatus,
+    VersionScheme,
+};
+use opsml_utils::calver::CalVer;
+use opsml_utils::semver::{VersionBound, VersionBounds, VersionParser};
+use semver::Version;
+
+const RELEASE: &str = "(pre_tag IS NULL OR pre_tag = '')";
+const PRE_RELEASE: &str = "(pre_tag IS NOT NULL AND pre_tag <> '')";
+
+/// (major, minor, patch) compared as a tuple against a version
+fn tuple_comparison(op: &str, version: &Version) -> String {
+    if op == "=" {
+        return format!(
+            "(major = {} AND minor = {} AND patch = {})",
+            version.major, version.minor, version.patch
+        );
+    }
+
+    format!(
+        "(major {op} {} OR (major = {} AND (minor {op} {} OR (minor = {} AND patch {op} {}))))",
+        version.major,
+        version.major,
+        version.minor,
+        version.minor,
+        version.patch,
+        op = op
+    )
+}
+
+/// SQL for one end of a version range. Releases sort after their pre-releases. Pre-release
+/// identifiers are not comparable in SQL, so a bound that carries one matches every
+/// pre-release of its (major, minor, patch) and callers needing exact precedence filter
+/// with `VersionBounds::matches`
+fn bound_clause(bound: &VersionBound, lower: bool) -> String {
+    let version = &bound.version;
+    let equal = tuple_comparison("=", version);
+    let beyond = tuple_comparison(if lower { ">" } else { "<" }, version);
+
+    match (lower, version.pre.is_empty(), bound.inclusive) {
+        (true, true, true) => format!("({} OR ({} AND {}))", beyond, equal, RELEASE),
+        (true, true, false) => beyond,
+        (true, false, _) => format!("({} OR {})", beyond, equal),
+        (false, true, true) => format!("({} OR {})", beyond, equal),
+        (false, true, false) => format!("({} OR ({} AND {}))", beyond, equal, PRE_RELEASE),
+        // nothing sorts before the `-0` pre-release
+        (false, false, false) if version.pre.as_str() == "0" => beyond,
+        (false, false, _) => format!("({} OR ({} AND {}))", beyond, equal, PRE_RELEASE),
+    }
+}
+
+fn parse_version_bounds(version: &str, scheme: &VersionScheme) -> Result<VersionBounds, SqlError> {
+    match scheme {
+        VersionScheme::SemVer => VersionParser::get_version_to_search(version),
+        VersionScheme::CalVer => CalVer::get_version_to_search(version),
+    }
+    .map_err(|e| SqlError::VersionError(format!("{}", e)))
+}
+
+/// Bounds of a card query that SQL can only approximate. A range end carrying a
+/// pre-release matches every pre-release of its (major, minor, patch), so the rows have to
+/// be re-checked with `VersionBounds::matches`
+pub fn pre_release_bounds(query_args: &CardQueryArgs) -> Result<Option<VersionBounds>, SqlError> {
+    let Some(version) = &query_args.version else {
+        return Ok(None);
+    };
+    // uid lookups skip the version filter entirely
+    if query_args.uid.is_some() {
+        return Ok(None);
+    }
+
+    let bounds = parse_version_bounds(
+        version,
+        &query_args.version_scheme.clone().unwrap_or_default(),
+    )?;
+    let has_pre = [&bounds.lower_bound, &bounds.upper_bound]
+        .into_iter()
+        .flatten()
+        .any(|bound| !bound.version.pre.is_empty());
+
+    if bounds.parser_type != VersionParser::Exact && has_pre {
+        Ok(Some(bounds))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Restrict a card query to the versions matching `version`. Calendar versions are stored
+/// as `YYYY.MMDD.N`, so both schemes share the (major, minor, patch) comparisons.
+/// An exact pre-release is pushed to `bindings`, and `placeholder` maps the number of
+/// bindings after the push to the backend's parameter for it
+pub fn add_version_bounds(
+    builder: &mut String,
+    bindings: &mut Vec<String>,
+    placeholder: impl Fn(usize) -> String,
+    version: &str,
+    scheme: &VersionScheme,
+    include_yanked: bool,
+) -> Result<(), SqlError> {
+    let version_bounds = parse_version_bounds(version, scheme)?;
+
+    match (&version_bounds.lower_bound, &version_bounds.upper_bound) {
+        // exact versions also match on the pre-release
+        (Some(lower), Some(upper)) if lower == upper => {
+            let pre = &lower.version.pre;
+            let pre_clause = if pre.is_empty() {
+                RELEASE.to_string()
+            } else {
+                bindings.push(pre.to_string());
+                format!("pre_tag = {}", placeholder(bindings.len()))
+            };
+            builder.push_str(&format!(
+                " AND {} AND {}",
+                tuple_comparison("=", &lower.version),
+                pre_clause
+            ));
+        }
+        (lower, upper) => {
+            if let Some(lower) = lower {
+                builder.push_str(&format!(" AND {}", bound_clause(lower, true)));
+            }
+            if let Some(upper) = upper {
+                builder.push_str(&format!(" AND {}", bound_clause(upper, false)));
+            }
         }
     }
+
+    // yanked versions stay reachable by exact version but are never picked by a range
+    if !include_yanked && version_bounds.parser_type != VersionParser::Exact {
+        builder.push_str(" AND yanked = FALSE");
+    }
     Ok(())
 }
 
+/// Migration adding the unique (repository, name, version) index to the card registries
+pub const UNIQUE_VERSION_MIGRATION: i64 = 20241210120000;
+
+/// Migration adding soft deletes. Before it every card is live
+pub const SOFT_DELETE_MIGRATION: i64 = 20241204120000;
+
+/// Map a failed write to a conflict when it broke a unique constraint, e.g. a second live
+/// card with the same version
+pub fn map_write_error(e: sqlx::Error) -> SqlError {
+    match e.as_database_error() {
+        Some(db_error) if db_error.is_unique_violation() => {
+            SqlError::ConflictError(db_error.message().to_string())
+        }
+        _ => SqlError::QueryError(format!("{}", e)),
+    }
+}
+
+/// Error listing the duplicate card versions that block the unique version index
+pub fn duplicate_versions_error(duplicates: &[String]) -> SqlError {
+    SqlError::MigrationError(format!(
+        "Found {} card versions registered more than once: {}. Delete or re-version the extra cards before upgrading",
+        duplicates.len(),
+        duplicates.join(", ")
+    ))
+}
+
+/// Card registries paired with the label returned as `registry_type` in search and lineage results
+pub const CARD_REGISTRIES: [(&str, CardSQLTableNames); 6] = [
+    ("data", CardSQLTableNames::Data),
+    ("model", CardSQLTableNames::Model),
+    ("run", CardSQLTableNames::Run),
+    ("audit", CardSQLTableNames::Audit),
+    ("pipeline", CardSQLTableNames::Pipeline),
+    ("project", CardSQLTableNames::Project),
+];
+
+/// Label of a card registry as stored in `registry_type` columns. Non-card tables fall back
+/// to their table name
+pub fn get_registry_type(table: &CardSQLTableNames) -> String {
+    CARD_REGISTRIES
+        .iter()
+        .find(|(_, registry_table)| registry_table == table)
+        .map(|(registry_type, _)| registry_type.to_string())
+        .unwrap_or_else(|| table.to_string())
+}
+
+/// Split a free-text search term into lowercase alphanumeric tokens.
+/// Tokens are safe to embed in backend full-text query expressions
+pub fn get_search_tokens(search_term: &str) -> Vec<String> {
+    search_term
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+/// Writes that succeed or fail together. Nothing is visible to other connections until
+/// `commit`. Dropping a transaction without committing rolls it back
+#[async_trait]
+pub trait SqlTransaction: Sized + Send {
+    /// Insert a card and its lineage edges. Fails with a conflict if a live card already
+    /// has the card's version
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to insert the card into
+    /// * `card` - The card to insert
+    async fn insert_card(&mut self, table: &CardSQLTableNames, card: &Card)
+        -> Result<(), SqlError>;
+
+    /// Append a change to the card history
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The history record
+    async fn insert_card_history(&mut self, record: &CardHistoryRecord) -> Result<(), SqlError>;
+
+    /// Query cards, including changes made earlier in this transaction
+    async fn query_cards(
+        &mut self,
+        table: &CardSQLTableNames,
+        query_args: &CardQueryArgs,
+    ) -> Result<CardResults, SqlError>;
+
+    /// Update a card and replace the lineage edges it declares
+    async fn update_card(&mut self, table: &CardSQLTableNames, card: &Card)
+        -> Result<(), SqlError>;
+
+    /// Move a card to the trash. See `SqlClient::delete_card`
+    async fn delete_card(
+        &mut self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        deleted_by: &str,
+    ) -> Result<(), SqlError>;
+
+    /// Restore a card from the trash. See `SqlClient::restore_card`
+    async fn restore_card(&mut self, table: &CardSQLTableNames, uid: &str) -> Result<(), SqlError>;
+
+    /// Move a card to a new lifecycle status. See `SqlClient::update_card_status`
+    async fn update_card_status(
+        &mut self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        status: &CardStatus,
+    ) -> Result<CardStatus, SqlError>;
+
+    /// Yank or un-yank a card version. See `SqlClient::yank_card`
+    async fn yank_card(
+        &mut self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        yanked: bool,
+    ) -> Result<(), SqlError>;
+
+    /// Check if uid exists, including cards written earlier in this transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `uid` - The unique identifier of the card
+    /// * `table` - The table to search
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the uid exists
+    async fn check_uid_exists(
+        &mut self,
+        uid: &str,
+        table: &CardSQLTableNames,
+    ) -> Result<bool, SqlError>;
+
+    /// Get the id of a project, including projects registered earlier in this transaction
+    async fn get_project_id(
+        &mut self,
+        project_name: &str,
+        repository: &str,
+    ) -> Result<i32, SqlError>;
+
+    /// Insert run metrics
+    async fn insert_run_metrics<'life1>(
+        &mut self,
+        records: &'life1 [MetricRecord],
+    ) -> Result<(), SqlError>;
+
+    /// Insert run parameters
+    async fn insert_run_parameters<'life1>(
+        &mut self,
+        records: &'life1 [ParameterRecord],
+    ) -> Result<(), SqlError>;
+
+    /// Insert hardware metrics
+    async fn insert_hardware_metrics<'life1>(
+        &mut self,
+        records: &'life1 [HardwareMetricsRecord],
+    ) -> Result<(), SqlError>;
+
+    /// Insert a group. Fails with a conflict if the name is taken
+    async fn insert_group(&mut self, group: &Group) -> Result<(), SqlError>;
+
+    /// Add a user to a group. Adding an existing member is a no-op
+    async fn add_group_member(&mut self, name: &str, username: &str) -> Result<(), SqlError>;
+
+    /// Make every write in the transaction visible
+    async fn commit(self) -> Result<(), SqlError>;
+
+    /// Discard every write in the transaction
+    async fn rollback(self) -> Result<(), SqlError>;
+}
+
 #[async_trait]
 pub trait SqlClient: Sized {
+    type Transaction: SqlTransaction;
+
     async fn new(settings: &OpsmlDatabaseSettings) -> Result<Self, SqlError>;
     async fn run_migrations(&self) -> Result<(), SqlError>;
+
+    /// Start a transaction on a connection taken from the pool. The connection is held until
+    /// the transaction is committed, rolled back or dropped
+    ///
+    /// # Returns
+    ///
+    /// * `Self::Transaction` - The open transaction
+    async fn begin(&self) -> Result<Self::Transaction, SqlError>;
+
+    /// Versions of a card, newest first, used to allocate the next version. Yanked versions
+    /// are included since their versions stay taken
     async fn get_versions(
         &self,
         table: &CardSQLTableNames,
@@ -84,16 +355,229 @@ pub trait SqlClient: Sized {
         search_term: Option<&str>,
     ) -> Result<QueryStats, SqlError>;
 
+    /// Page through card summaries. Without a cursor, `page` selects a block of summaries
+    /// ranked by `sort_by`. With a cursor, summaries are keyset paged by updated_at and
+    /// `sort_by`/`page` are ignored; an empty cursor starts from the first page
+    #[allow(clippy::too_many_arguments)]
     async fn query_page(
         &self,
         sort_by: &str,
         page: i32,
+        cursor: Option<&str>,
         search_term: Option<&str>,
         repository: Option<&str>,
+        status: Option<&CardStatus>,
         table: &CardSQLTableNames,
     ) -> Result<Vec<CardSummary>, SqlError>;
 
-    async fn delete_card(&self, table: &CardSQLTableNames, uid: &str) -> Result<(), SqlError>;
+    /// Move a card to the trash. Trashed cards are hidden from queries until restored or purged
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table the card belongs to
+    /// * `uid` - The uid of the card
+    /// * `deleted_by` - The user deleting the card
+    async fn delete_card(
+        &self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        deleted_by: &str,
+    ) -> Result<(), SqlError>;
+
+    /// Restore a card from the trash. Fails with a conflict if the card's version was
+    /// registered again while it was in the trash
+    async fn restore_card(&self, table: &CardSQLTableNames, uid: &str) -> Result<(), SqlError>;
+
+    /// Move a card to a new lifecycle status. Fails with a conflict if the transition is not allowed
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table the card belongs to
+    /// * `uid` - The uid of the card
+    /// * `status` - The status to move the card to
+    ///
+    /// # Returns
+    ///
+    /// * `CardStatus` - The status the card had before the update
+    async fn update_card_status(
+        &self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        status: &CardStatus,
+    ) -> Result<CardStatus, SqlError>;
+
+    /// Yank or un-yank a card version. Yanked versions are skipped when resolving caret,
+    /// tilde and star ranges but can still be fetched by uid or exact version
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table the card belongs to
+    /// * `uid` - The uid of the card
+    /// * `yanked` - Whether the version is yanked
+    async fn yank_card(
+        &self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        yanked: bool,
+    ) -> Result<(), SqlError>;
+
+    /// Versioning scheme of a repository. Repositories use semver until set otherwise
+    ///
+    /// # Arguments
+    ///
+    /// * `repository` - The repository to look up
+    async fn get_version_scheme(&self, repository: &str) -> Result<VersionScheme, SqlError>;
+
+    /// Set the versioning scheme of a repository
+    ///
+    /// # Arguments
+    ///
+    /// * `repository` - The repository to update
+    /// * `version_scheme` - The scheme used for new versions and version queries
+    async fn set_version_scheme(
+        &self,
+        repository: &str,
+        version_scheme: &VersionScheme,
+    ) -> Result<(), SqlError>;
+
+    /// List cards in the trash
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to list
+    /// * `deleted_before` - Only return cards deleted before this timestamp
+    async fn get_deleted_cards(
+        &self,
+        table: &CardSQLTableNames,
+        deleted_before: Option<NaiveDateTime>,
+    ) -> Result<Vec<DeletedCard>, SqlError>;
+
+    /// Permanently remove a card and the lineage edges it declared
+    async fn purge_card(&self, table: &CardSQLTableNames, uid: &str) -> Result<(), SqlError>;
+
+    /// Full-text search across every card registry. Each token of the search term is
+    /// prefix-matched against card name, repository, contact and tags
+    ///
+    /// # Arguments
+    ///
+    /// * `search_term` - The free-text search term
+    /// * `limit` - The maximum number of results to return
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<CardSearchResult>` - Matching cards ordered by descending relevance
+    async fn search_cards(
+        &self,
+        search_term: &str,
+        limit: i32,
+    ) -> Result<Vec<CardSearchResult>, SqlError>;
+
+    /// Append a change to the card history
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The history record
+    async fn insert_card_history(&self, record: &CardHistoryRecord) -> Result<(), SqlError>;
+
+    /// Get the change history of a card, oldest first
+    ///
+    /// # Arguments
+    ///
+    /// * `uid` - The uid of the card
+    async fn get_card_history(&self, uid: &str) -> Result<Vec<CardHistoryRecord>, SqlError>;
+
+    /// Point an alias of a (repository, name) at one of its cards, creating the alias or
+    /// moving it from the card it currently points to. Every move is recorded in the alias history
+    ///
+    /// # Arguments
+    ///
+    /// * `alias` - The alias and the uid it should point to
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The uid the alias pointed to before the change, if any
+    async fn set_card_alias(&self, alias: &CardAlias) -> Result<Option<String>, SqlError>;
+
+    /// Get an alias of a (repository, name)
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table the cards belong to
+    /// * `repository` - The repository of the card
+    /// * `name` - The name of the card
+    /// * `alias` - The alias to resolve
+    async fn get_card_alias(
+        &self,
+        table: &CardSQLTableNames,
+        repository: &str,
+        name: &str,
+        alias: &str,
+    ) -> Result<Option<CardAlias>, SqlError>;
+
+    /// List the aliases of a (repository, name) ordered by alias
+    async fn get_card_aliases(
+        &self,
+        table: &CardSQLTableNames,
+        repository: &str,
+        name: &str,
+    ) -> Result<Vec<CardAlias>, SqlError>;
+
+    /// Alias moves for a (repository, name), oldest first
+    async fn get_card_alias_history(
+        &self,
+        table: &CardSQLTableNames,
+        repository: &str,
+        name: &str,
+    ) -> Result<Vec<CardAliasHistoryRecord>, SqlError>;
+
+    /// Replace the lineage edges declared by a card. Called on card insert and update
+    ///
+    /// # Arguments
+    ///
+    /// * `card` - The card declaring the edges
+    async fn insert_card_lineage(&self, card: &Card) -> Result<(), SqlError>;
+
+    /// Recursively walk the cards a card was derived from
+    ///
+    /// # Arguments
+    ///
+    /// * `uid` - The uid of the card to start from
+    /// * `depth` - The maximum number of hops to walk
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<LineageEdge>` - Edges reachable upstream of the card
+    async fn get_upstream_lineage(
+        &self,
+        uid: &str,
+        depth: i32,
+    ) -> Result<Vec<LineageEdge>, SqlError>;
+
+    /// Recursively walk the cards derived from a card
+    ///
+    /// # Arguments
+    ///
+    /// * `uid` - The uid of the card to start from
+    /// * `depth` - The maximum number of hops to walk
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<LineageEdge>` - Edges reachable downstream of the card
+    async fn get_downstream_lineage(
+        &self,
+        uid: &str,
+        depth: i32,
+    ) -> Result<Vec<LineageEdge>, SqlError>;
+
+    /// Get name, repository and version for lineage nodes
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - (registry_type, uid) pairs taken from lineage edges
+    async fn get_lineage_nodes<'life1>(
+        &self,
+        nodes: &'life1 [(String, String)],
+    ) -> Result<Vec<LineageNode>, SqlError>;
 
     // db specific functions
     // get project_id
@@ -145,6 +629,34 @@ pub trait SqlClient: Sized {
         names: &'life2 [String],
     ) -> Result<Vec<MetricRecord>, SqlError>;
 
+    /// Get run metric points filtered by step range, optionally downsampled
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The run uid, metric names, step range and max points per metric
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<MetricRecord>` - The metric points ordered by name and step
+    async fn query_run_metrics(
+        &self,
+        args: &GetMetricRequest,
+    ) -> Result<Vec<MetricRecord>, SqlError>;
+
+    /// Get min, max, mean and last value of each run metric within the step range
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The run uid, metric names and step range. `max_points` is ignored
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<MetricSummaryRecord>` - One summary per metric name
+    async fn get_run_metric_summary(
+        &self,
+        args: &GetMetricRequest,
+    ) -> Result<Vec<MetricSummaryRecord>, SqlError>;
+
     /// Get run metric names
     ///
     /// # Arguments
@@ -173,6 +685,49 @@ pub trait SqlClient: Sized {
         names: &'life2 [String],
     ) -> Result<Vec<ParameterRecord>, SqlError>;
 
+    /// Get metrics for many runs in a single query
+    ///
+    /// # Arguments
+    ///
+    /// * `uids` - The run uids
+    /// * `names` - The names of the metrics. All metrics are returned when empty
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<MetricRecord>` - The metrics ordered by run, name and step
+    async fn get_runs_metrics<'life1, 'life2>(
+        &self,
+        uids: &'life1 [String],
+        names: &'life2 [String],
+    ) -> Result<Vec<MetricRecord>, SqlError>;
+
+    /// Get parameters for many runs in a single query
+    ///
+    /// # Arguments
+    ///
+    /// * `uids` - The run uids
+    /// * `names` - The names of the parameters. All parameters are returned when empty
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<ParameterRecord>` - The parameters ordered by run and name
+    async fn get_runs_parameters<'life1, 'life2>(
+        &self,
+        uids: &'life1 [String],
+        names: &'life2 [String],
+    ) -> Result<Vec<ParameterRecord>, SqlError>;
+
+    /// Search runs by parameter values and metric thresholds
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The filters, sort metric and limit
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<RunQueryResult>` - The matching runs
+    async fn query_runs(&self, args: &RunQueryRequest) -> Result<Vec<RunQueryResult>, SqlError>;
+
     /// Insert hardware metrics
     ///
     /// # Arguments
@@ -195,7 +750,70 @@ pub trait SqlClient: Sized {
     /// * `HardwareMetricsRecord` - The hardware metrics
     async fn get_hardware_metric(&self, uid: &str) -> Result<Vec<HardwareMetricsRecord>, SqlError>;
 
-    /// Insert user
+    /// Get the runs nested under a parent run, oldest first
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_uid` - The uid of the parent run
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<RunCardRecord>` - The child runs
+    async fn get_child_runs(&self, parent_uid: &str) -> Result<Vec<RunCardRecord>, SqlError>;
+
+    /// Aggregate the metrics of the runs nested under a parent run
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_uid` - The uid of the parent run
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<ChildMetricSummaryRecord>` - One summary per metric name
+    async fn get_child_metric_summary(
+        &self,
+        parent_uid: &str,
+    ) -> Result<Vec<ChildMetricSummaryRecord>, SqlError>;
+
+    /// Start tracking the lifecycle of a run. Fails with a conflict if the run already started
+    ///
+    /// # Arguments
+    ///
+    /// * `run_uid` - The uid of the run card
+    ///
+    /// # Returns
+    ///
+    /// * `RunStatusRecord` - The running run
+    async fn start_run(&self, run_uid: &str) -> Result<RunStatusRecord, SqlError>;
+
+    /// Get the lifecycle of a run, if it has started
+    async fn get_run_status(&self, run_uid: &str) -> Result<Option<RunStatusRecord>, SqlError>;
+
+    /// Move a run to a new status and record a heartbeat. Moving to `Running` is a heartbeat,
+    /// any other status ends the run. Fails with a conflict if the transition is not allowed
+    ///
+    /// # Arguments
+    ///
+    /// * `run_uid` - The uid of the run card
+    /// * `status` - The status to move the run to
+    ///
+    /// # Returns
+    ///
+    /// * `RunStatusRecord` - The run after the update
+    async fn update_run_status(
+        &self,
+        run_uid: &str,
+        status: &RunStatus,
+    ) -> Result<RunStatusRecord, SqlError>;
+
+    /// Mark running runs whose last heartbeat is older than `heartbeat_before` as crashed
+    ///
+    /// # Returns
+    ///
+    /// * `u64` - The number of runs marked as crashed
+    async fn mark_crashed_runs(&self, heartbeat_before: NaiveDateTime) -> Result<u64, SqlError>;
+
+    /// Insert user. Fails with a conflict if the username is taken
     ///
     /// # Arguments
     ///
@@ -203,7 +821,7 @@ pub trait SqlClient: Sized {
     ///
     async fn insert_user(&self, user: &User) -> Result<(), SqlError>;
 
-    /// Get user
+    /// Get user. Fails with a not found error if the user does not exist
     ///
     /// # Arguments
     ///
@@ -214,6 +832,13 @@ pub trait SqlClient: Sized {
     /// * `User` - The user
     async fn get_user(&self, username: &str) -> Result<User, SqlError>;
 
+    /// Get all users ordered by id
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<User>` - The users
+    async fn get_users(&self) -> Result<Vec<User>, SqlError>;
+
     /// update user
     ///
     /// # Arguments
@@ -225,6 +850,178 @@ pub trait SqlClient: Sized {
     /// * `Result<(), SqlError>` - The result of the operation
     async fn update_user(&self, user: &User) -> Result<(), SqlError>;
 
+    /// Store the hashed refresh token of a new login session
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The refresh token family
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SqlError>` - The result of the operation
+    async fn insert_refresh_token(&self, token: &RefreshTokenFamily) -> Result<(), SqlError>;
+
+    /// Get the current hashed refresh token of a family
+    ///
+    /// # Arguments
+    ///
+    /// * `family` - The refresh token family
+    ///
+    /// # Returns
+    ///
+    /// * `Option<RefreshTokenFamily>` - The family, or `None` if it was revoked
+    async fn get_refresh_token(&self, family: &str)
+        -> Result<Option<RefreshTokenFamily>, SqlError>;
+
+    /// Replace a family's hashed refresh token only if it is still `current`, so two
+    /// refreshes with the same token cannot both rotate it
+    ///
+    /// # Arguments
+    ///
+    /// * `family` - The refresh token family
+    /// * `current` - The hashed refresh token that was read and verified
+    /// * `token_hash` - The new hashed refresh token
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether the token was rotated
+    async fn rotate_refresh_token(
+        &self,
+        family: &str,
+        current: &str,
+        token_hash: &str,
+    ) -> Result<bool, SqlError>;
+
+    /// Revoke a single refresh token family
+    ///
+    /// # Arguments
+    ///
+    /// * `family` - The refresh token family
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SqlError>` - The result of the operation
+    async fn delete_refresh_token(&self, family: &str) -> Result<(), SqlError>;
+
+    /// Revoke every refresh token family of a user
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SqlError>` - The result of the operation
+    async fn delete_user_refresh_tokens(&self, username: &str) -> Result<(), SqlError>;
+
+    /// Delete a user, their group memberships and their refresh tokens
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SqlError>` - The result of the operation
+    async fn delete_user(&self, username: &str) -> Result<(), SqlError>;
+
+    /// Insert a group. Fails with a conflict if the name is taken
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The group
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SqlError>` - The result of the operation
+    async fn insert_group(&self, group: &Group) -> Result<(), SqlError>;
+
+    /// Get a group by name
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The group name
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Group>` - The group, if it exists
+    async fn get_group(&self, name: &str) -> Result<Option<Group>, SqlError>;
+
+    /// Get all groups ordered by name
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Group>` - The groups
+    async fn get_groups(&self) -> Result<Vec<Group>, SqlError>;
+
+    /// Replace the permission grants of a group
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The group
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SqlError>` - The result of the operation
+    async fn update_group(&self, group: &Group) -> Result<(), SqlError>;
+
+    /// Delete a group and its memberships
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The group name
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SqlError>` - The result of the operation
+    async fn delete_group(&self, name: &str) -> Result<(), SqlError>;
+
+    /// Add a user to a group. Adding an existing member is a no-op
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The group name
+    /// * `username` - The member username
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SqlError>` - The result of the operation
+    async fn add_group_member(&self, name: &str, username: &str) -> Result<(), SqlError>;
+
+    /// Remove a user from a group
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The group name
+    /// * `username` - The member username
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SqlError>` - The result of the operation
+    async fn remove_group_member(&self, name: &str, username: &str) -> Result<(), SqlError>;
+
+    /// Get the usernames of a group's members
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The group name
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<String>` - The member usernames
+    async fn get_group_members(&self, name: &str) -> Result<Vec<String>, SqlError>;
+
+    /// Get the groups a user belongs to
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Group>` - The user's groups
+    async fn get_user_groups(&self, username: &str) -> Result<Vec<Group>, SqlError>;
+
     /// Check if uid exists
     ///
     /// # Arguments
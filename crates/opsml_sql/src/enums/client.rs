@@ -1,17 +1,23 @@
-use crate::base::SqlClient;
-use crate::mysql::client::MySqlClient;
-use crate::postgres::client::PostgresClient;
+use crate::base::{SqlClient, SqlTransaction};
+use crate::mysql::client::{MySqlClient, MySqlTransaction};
+use crate::postgres::client::{PostgresClient, PostgresTransaction};
 use crate::schemas::schema::{
-    Card, CardResults, CardSummary, HardwareMetricsRecord, MetricRecord, ParameterRecord,
-    QueryStats, User,
+    Card, CardAlias, CardAliasHistoryRecord, CardHistoryRecord, CardResults, CardSearchResult,
+    CardSummary, ChildMetricSummaryRecord, DeletedCard, Group, HardwareMetricsRecord, LineageEdge,
+    LineageNode, MetricRecord, MetricSummaryRecord, ParameterRecord, QueryStats,
+    RefreshTokenFamily, RunCardRecord, RunQueryResult, RunStatusRecord, User,
 };
-use crate::sqlite::client::SqliteClient;
+use crate::sqlite::client::{SqliteClient, SqliteTransaction};
 use anyhow::Context;
 use anyhow::Result as AnyhowResult;
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use opsml_error::error::SqlError;
 use opsml_settings::config::{OpsmlConfig, OpsmlDatabaseSettings};
-use opsml_types::{CardQueryArgs, CardSQLTableNames, SqlType};
+use opsml_types::{
+    CardQueryArgs, CardSQLTableNames, CardStatus, GetMetricRequest, RunQueryRequest, RunStatus,
+    SqlType, VersionScheme,
+};
 
 #[derive(Debug, Clone)]
 pub enum SqlClientEnum {
@@ -43,8 +49,199 @@ impl SqlClientEnum {
     }
 }
 
+pub enum SqlTransactionEnum {
+    Postgres(Box<PostgresTransaction>),
+    Sqlite(SqliteTransaction),
+    MySql(MySqlTransaction),
+}
+
+#[async_trait]
+impl SqlTransaction for SqlTransactionEnum {
+    async fn insert_card(
+        &mut self,
+        table: &CardSQLTableNames,
+        card: &Card,
+    ) -> Result<(), SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.insert_card(table, card).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.insert_card(table, card).await,
+            SqlTransactionEnum::MySql(tx) => tx.insert_card(table, card).await,
+        }
+    }
+
+    async fn insert_card_history(&mut self, record: &CardHistoryRecord) -> Result<(), SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.insert_card_history(record).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.insert_card_history(record).await,
+            SqlTransactionEnum::MySql(tx) => tx.insert_card_history(record).await,
+        }
+    }
+
+    async fn query_cards(
+        &mut self,
+        table: &CardSQLTableNames,
+        query_args: &CardQueryArgs,
+    ) -> Result<CardResults, SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.query_cards(table, query_args).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.query_cards(table, query_args).await,
+            SqlTransactionEnum::MySql(tx) => tx.query_cards(table, query_args).await,
+        }
+    }
+
+    async fn update_card(
+        &mut self,
+        table: &CardSQLTableNames,
+        card: &Card,
+    ) -> Result<(), SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.update_card(table, card).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.update_card(table, card).await,
+            SqlTransactionEnum::MySql(tx) => tx.update_card(table, card).await,
+        }
+    }
+
+    async fn delete_card(
+        &mut self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        deleted_by: &str,
+    ) -> Result<(), SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.delete_card(table, uid, deleted_by).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.delete_card(table, uid, deleted_by).await,
+            SqlTransactionEnum::MySql(tx) => tx.delete_card(table, uid, deleted_by).await,
+        }
+    }
+
+    async fn restore_card(&mut self, table: &CardSQLTableNames, uid: &str) -> Result<(), SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.restore_card(table, uid).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.restore_card(table, uid).await,
+            SqlTransactionEnum::MySql(tx) => tx.restore_card(table, uid).await,
+        }
+    }
+
+    async fn update_card_status(
+        &mut self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        status: &CardStatus,
+    ) -> Result<CardStatus, SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.update_card_status(table, uid, status).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.update_card_status(table, uid, status).await,
+            SqlTransactionEnum::MySql(tx) => tx.update_card_status(table, uid, status).await,
+        }
+    }
+
+    async fn yank_card(
+        &mut self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        yanked: bool,
+    ) -> Result<(), SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.yank_card(table, uid, yanked).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.yank_card(table, uid, yanked).await,
+            SqlTransactionEnum::MySql(tx) => tx.yank_card(table, uid, yanked).await,
+        }
+    }
+
+    async fn check_uid_exists(
+        &mut self,
+        uid: &str,
+        table: &CardSQLTableNames,
+    ) -> Result<bool, SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.check_uid_exists(uid, table).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.check_uid_exists(uid, table).await,
+            SqlTransactionEnum::MySql(tx) => tx.check_uid_exists(uid, table).await,
+        }
+    }
+
+    async fn get_project_id(
+        &mut self,
+        project_name: &str,
+        repository: &str,
+    ) -> Result<i32, SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.get_project_id(project_name, repository).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.get_project_id(project_name, repository).await,
+            SqlTransactionEnum::MySql(tx) => tx.get_project_id(project_name, repository).await,
+        }
+    }
+
+    async fn insert_run_metrics<'life1>(
+        &mut self,
+        records: &'life1 [MetricRecord],
+    ) -> Result<(), SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.insert_run_metrics(records).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.insert_run_metrics(records).await,
+            SqlTransactionEnum::MySql(tx) => tx.insert_run_metrics(records).await,
+        }
+    }
+
+    async fn insert_run_parameters<'life1>(
+        &mut self,
+        records: &'life1 [ParameterRecord],
+    ) -> Result<(), SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.insert_run_parameters(records).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.insert_run_parameters(records).await,
+            SqlTransactionEnum::MySql(tx) => tx.insert_run_parameters(records).await,
+        }
+    }
+
+    async fn insert_hardware_metrics<'life1>(
+        &mut self,
+        records: &'life1 [HardwareMetricsRecord],
+    ) -> Result<(), SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.insert_hardware_metrics(records).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.insert_hardware_metrics(records).await,
+            SqlTransactionEnum::MySql(tx) => tx.insert_hardware_metrics(records).await,
+        }
+    }
+
+    async fn insert_group(&mut self, group: &Group) -> Result<(), SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.insert_group(group).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.insert_group(group).await,
+            SqlTransactionEnum::MySql(tx) => tx.insert_group(group).await,
+        }
+    }
+
+    async fn add_group_member(&mut self, name: &str, username: &str) -> Result<(), SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.add_group_member(name, username).await,
+            SqlTransactionEnum::Sqlite(tx) => tx.add_group_member(name, username).await,
+            SqlTransactionEnum::MySql(tx) => tx.add_group_member(name, username).await,
+        }
+    }
+
+    async fn commit(self) -> Result<(), SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.commit().await,
+            SqlTransactionEnum::Sqlite(tx) => tx.commit().await,
+            SqlTransactionEnum::MySql(tx) => tx.commit().await,
+        }
+    }
+
+    async fn rollback(self) -> Result<(), SqlError> {
+        match self {
+            SqlTransactionEnum::Postgres(tx) => tx.rollback().await,
+            SqlTransactionEnum::Sqlite(tx) => tx.rollback().await,
+            SqlTransactionEnum::MySql(tx) => tx.rollback().await,
+        }
+    }
+}
+
 #[async_trait]
 impl SqlClient for SqlClientEnum {
+    type Transaction = SqlTransactionEnum;
+
     async fn new(settings: &OpsmlDatabaseSettings) -> Result<Self, SqlError> {
         match settings.sql_type {
             SqlType::Postgres => {
@@ -62,6 +259,16 @@ impl SqlClient for SqlClientEnum {
         }
     }
 
+    async fn begin(&self) -> Result<SqlTransactionEnum, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => Ok(SqlTransactionEnum::Postgres(Box::new(
+                client.begin().await?,
+            ))),
+            SqlClientEnum::Sqlite(client) => Ok(SqlTransactionEnum::Sqlite(client.begin().await?)),
+            SqlClientEnum::MySql(client) => Ok(SqlTransactionEnum::MySql(client.begin().await?)),
+        }
+    }
+
     async fn check_uid_exists(
         &self,
         uid: &str,
@@ -106,34 +313,279 @@ impl SqlClient for SqlClientEnum {
         &self,
         sort_by: &str,
         page: i32,
+        cursor: Option<&str>,
         search_term: Option<&str>,
         repository: Option<&str>,
+        status: Option<&CardStatus>,
         table: &CardSQLTableNames,
     ) -> Result<Vec<CardSummary>, SqlError> {
         match self {
             SqlClientEnum::Postgres(client) => {
                 client
-                    .query_page(sort_by, page, search_term, repository, table)
+                    .query_page(
+                        sort_by,
+                        page,
+                        cursor,
+                        search_term,
+                        repository,
+                        status,
+                        table,
+                    )
                     .await
             }
             SqlClientEnum::Sqlite(client) => {
                 client
-                    .query_page(sort_by, page, search_term, repository, table)
+                    .query_page(
+                        sort_by,
+                        page,
+                        cursor,
+                        search_term,
+                        repository,
+                        status,
+                        table,
+                    )
                     .await
             }
             SqlClientEnum::MySql(client) => {
                 client
-                    .query_page(sort_by, page, search_term, repository, table)
+                    .query_page(
+                        sort_by,
+                        page,
+                        cursor,
+                        search_term,
+                        repository,
+                        status,
+                        table,
+                    )
                     .await
             }
         }
     }
 
-    async fn delete_card(&self, table: &CardSQLTableNames, uid: &str) -> Result<(), SqlError> {
+    async fn delete_card(
+        &self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        deleted_by: &str,
+    ) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.delete_card(table, uid, deleted_by).await,
+            SqlClientEnum::Sqlite(client) => client.delete_card(table, uid, deleted_by).await,
+            SqlClientEnum::MySql(client) => client.delete_card(table, uid, deleted_by).await,
+        }
+    }
+
+    async fn restore_card(&self, table: &CardSQLTableNames, uid: &str) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.restore_card(table, uid).await,
+            SqlClientEnum::Sqlite(client) => client.restore_card(table, uid).await,
+            SqlClientEnum::MySql(client) => client.restore_card(table, uid).await,
+        }
+    }
+
+    async fn update_card_status(
+        &self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        status: &CardStatus,
+    ) -> Result<CardStatus, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.update_card_status(table, uid, status).await,
+            SqlClientEnum::Sqlite(client) => client.update_card_status(table, uid, status).await,
+            SqlClientEnum::MySql(client) => client.update_card_status(table, uid, status).await,
+        }
+    }
+
+    async fn yank_card(
+        &self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        yanked: bool,
+    ) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.yank_card(table, uid, yanked).await,
+            SqlClientEnum::Sqlite(client) => client.yank_card(table, uid, yanked).await,
+            SqlClientEnum::MySql(client) => client.yank_card(table, uid, yanked).await,
+        }
+    }
+    async fn get_version_scheme(&self, repository: &str) -> Result<VersionScheme, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_version_scheme(repository).await,
+            SqlClientEnum::Sqlite(client) => client.get_version_scheme(repository).await,
+            SqlClientEnum::MySql(client) => client.get_version_scheme(repository).await,
+        }
+    }
+
+    async fn set_version_scheme(
+        &self,
+        repository: &str,
+        version_scheme: &VersionScheme,
+    ) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => {
+                client.set_version_scheme(repository, version_scheme).await
+            }
+            SqlClientEnum::Sqlite(client) => {
+                client.set_version_scheme(repository, version_scheme).await
+            }
+            SqlClientEnum::MySql(client) => {
+                client.set_version_scheme(repository, version_scheme).await
+            }
+        }
+    }
+
+    async fn get_deleted_cards(
+        &self,
+        table: &CardSQLTableNames,
+        deleted_before: Option<NaiveDateTime>,
+    ) -> Result<Vec<DeletedCard>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => {
+                client.get_deleted_cards(table, deleted_before).await
+            }
+            SqlClientEnum::Sqlite(client) => client.get_deleted_cards(table, deleted_before).await,
+            SqlClientEnum::MySql(client) => client.get_deleted_cards(table, deleted_before).await,
+        }
+    }
+
+    async fn purge_card(&self, table: &CardSQLTableNames, uid: &str) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.purge_card(table, uid).await,
+            SqlClientEnum::Sqlite(client) => client.purge_card(table, uid).await,
+            SqlClientEnum::MySql(client) => client.purge_card(table, uid).await,
+        }
+    }
+
+    async fn search_cards(
+        &self,
+        search_term: &str,
+        limit: i32,
+    ) -> Result<Vec<CardSearchResult>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.search_cards(search_term, limit).await,
+            SqlClientEnum::Sqlite(client) => client.search_cards(search_term, limit).await,
+            SqlClientEnum::MySql(client) => client.search_cards(search_term, limit).await,
+        }
+    }
+
+    async fn insert_card_lineage(&self, card: &Card) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.insert_card_lineage(card).await,
+            SqlClientEnum::Sqlite(client) => client.insert_card_lineage(card).await,
+            SqlClientEnum::MySql(client) => client.insert_card_lineage(card).await,
+        }
+    }
+
+    async fn insert_card_history(&self, record: &CardHistoryRecord) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.insert_card_history(record).await,
+            SqlClientEnum::Sqlite(client) => client.insert_card_history(record).await,
+            SqlClientEnum::MySql(client) => client.insert_card_history(record).await,
+        }
+    }
+
+    async fn get_card_history(&self, uid: &str) -> Result<Vec<CardHistoryRecord>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_card_history(uid).await,
+            SqlClientEnum::Sqlite(client) => client.get_card_history(uid).await,
+            SqlClientEnum::MySql(client) => client.get_card_history(uid).await,
+        }
+    }
+
+    async fn set_card_alias(&self, alias: &CardAlias) -> Result<Option<String>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.set_card_alias(alias).await,
+            SqlClientEnum::Sqlite(client) => client.set_card_alias(alias).await,
+            SqlClientEnum::MySql(client) => client.set_card_alias(alias).await,
+        }
+    }
+
+    async fn get_card_alias(
+        &self,
+        table: &CardSQLTableNames,
+        repository: &str,
+        name: &str,
+        alias: &str,
+    ) -> Result<Option<CardAlias>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => {
+                client.get_card_alias(table, repository, name, alias).await
+            }
+            SqlClientEnum::Sqlite(client) => {
+                client.get_card_alias(table, repository, name, alias).await
+            }
+            SqlClientEnum::MySql(client) => {
+                client.get_card_alias(table, repository, name, alias).await
+            }
+        }
+    }
+
+    async fn get_card_aliases(
+        &self,
+        table: &CardSQLTableNames,
+        repository: &str,
+        name: &str,
+    ) -> Result<Vec<CardAlias>, SqlError> {
         match self {
-            SqlClientEnum::Postgres(client) => client.delete_card(table, uid).await,
-            SqlClientEnum::Sqlite(client) => client.delete_card(table, uid).await,
-            SqlClientEnum::MySql(client) => client.delete_card(table, uid).await,
+            SqlClientEnum::Postgres(client) => {
+                client.get_card_aliases(table, repository, name).await
+            }
+            SqlClientEnum::Sqlite(client) => client.get_card_aliases(table, repository, name).await,
+            SqlClientEnum::MySql(client) => client.get_card_aliases(table, repository, name).await,
+        }
+    }
+
+    async fn get_card_alias_history(
+        &self,
+        table: &CardSQLTableNames,
+        repository: &str,
+        name: &str,
+    ) -> Result<Vec<CardAliasHistoryRecord>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => {
+                client.get_card_alias_history(table, repository, name).await
+            }
+            SqlClientEnum::Sqlite(client) => {
+                client.get_card_alias_history(table, repository, name).await
+            }
+            SqlClientEnum::MySql(client) => {
+                client.get_card_alias_history(table, repository, name).await
+            }
+        }
+    }
+
+    async fn get_upstream_lineage(
+        &self,
+        uid: &str,
+        depth: i32,
+    ) -> Result<Vec<LineageEdge>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_upstream_lineage(uid, depth).await,
+            SqlClientEnum::Sqlite(client) => client.get_upstream_lineage(uid, depth).await,
+            SqlClientEnum::MySql(client) => client.get_upstream_lineage(uid, depth).await,
+        }
+    }
+
+    async fn get_downstream_lineage(
+        &self,
+        uid: &str,
+        depth: i32,
+    ) -> Result<Vec<LineageEdge>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_downstream_lineage(uid, depth).await,
+            SqlClientEnum::Sqlite(client) => client.get_downstream_lineage(uid, depth).await,
+            SqlClientEnum::MySql(client) => client.get_downstream_lineage(uid, depth).await,
+        }
+    }
+
+    async fn get_lineage_nodes<'life1>(
+        &self,
+        nodes: &'life1 [(String, String)],
+    ) -> Result<Vec<LineageNode>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_lineage_nodes(nodes).await,
+            SqlClientEnum::Sqlite(client) => client.get_lineage_nodes(nodes).await,
+            SqlClientEnum::MySql(client) => client.get_lineage_nodes(nodes).await,
         }
     }
 
@@ -228,6 +680,28 @@ impl SqlClient for SqlClientEnum {
         }
     }
 
+    async fn query_run_metrics(
+        &self,
+        args: &GetMetricRequest,
+    ) -> Result<Vec<MetricRecord>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.query_run_metrics(args).await,
+            SqlClientEnum::Sqlite(client) => client.query_run_metrics(args).await,
+            SqlClientEnum::MySql(client) => client.query_run_metrics(args).await,
+        }
+    }
+
+    async fn get_run_metric_summary(
+        &self,
+        args: &GetMetricRequest,
+    ) -> Result<Vec<MetricSummaryRecord>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_run_metric_summary(args).await,
+            SqlClientEnum::Sqlite(client) => client.get_run_metric_summary(args).await,
+            SqlClientEnum::MySql(client) => client.get_run_metric_summary(args).await,
+        }
+    }
+
     async fn get_run_metric_names(&self, uid: &str) -> Result<Vec<String>, SqlError> {
         match self {
             SqlClientEnum::Postgres(client) => client.get_run_metric_names(uid).await,
@@ -236,6 +710,38 @@ impl SqlClient for SqlClientEnum {
         }
     }
 
+    async fn get_runs_metrics<'life1, 'life2>(
+        &self,
+        uids: &'life1 [String],
+        names: &'life2 [String],
+    ) -> Result<Vec<MetricRecord>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_runs_metrics(uids, names).await,
+            SqlClientEnum::Sqlite(client) => client.get_runs_metrics(uids, names).await,
+            SqlClientEnum::MySql(client) => client.get_runs_metrics(uids, names).await,
+        }
+    }
+
+    async fn get_runs_parameters<'life1, 'life2>(
+        &self,
+        uids: &'life1 [String],
+        names: &'life2 [String],
+    ) -> Result<Vec<ParameterRecord>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_runs_parameters(uids, names).await,
+            SqlClientEnum::Sqlite(client) => client.get_runs_parameters(uids, names).await,
+            SqlClientEnum::MySql(client) => client.get_runs_parameters(uids, names).await,
+        }
+    }
+
+    async fn query_runs(&self, args: &RunQueryRequest) -> Result<Vec<RunQueryResult>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.query_runs(args).await,
+            SqlClientEnum::Sqlite(client) => client.query_runs(args).await,
+            SqlClientEnum::MySql(client) => client.query_runs(args).await,
+        }
+    }
+
     async fn insert_hardware_metrics<'life1>(
         &self,
         record: &'life1 [HardwareMetricsRecord],
@@ -255,6 +761,61 @@ impl SqlClient for SqlClientEnum {
         }
     }
 
+    async fn get_child_runs(&self, parent_uid: &str) -> Result<Vec<RunCardRecord>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_child_runs(parent_uid).await,
+            SqlClientEnum::Sqlite(client) => client.get_child_runs(parent_uid).await,
+            SqlClientEnum::MySql(client) => client.get_child_runs(parent_uid).await,
+        }
+    }
+
+    async fn get_child_metric_summary(
+        &self,
+        parent_uid: &str,
+    ) -> Result<Vec<ChildMetricSummaryRecord>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_child_metric_summary(parent_uid).await,
+            SqlClientEnum::Sqlite(client) => client.get_child_metric_summary(parent_uid).await,
+            SqlClientEnum::MySql(client) => client.get_child_metric_summary(parent_uid).await,
+        }
+    }
+
+    async fn start_run(&self, run_uid: &str) -> Result<RunStatusRecord, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.start_run(run_uid).await,
+            SqlClientEnum::Sqlite(client) => client.start_run(run_uid).await,
+            SqlClientEnum::MySql(client) => client.start_run(run_uid).await,
+        }
+    }
+
+    async fn get_run_status(&self, run_uid: &str) -> Result<Option<RunStatusRecord>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_run_status(run_uid).await,
+            SqlClientEnum::Sqlite(client) => client.get_run_status(run_uid).await,
+            SqlClientEnum::MySql(client) => client.get_run_status(run_uid).await,
+        }
+    }
+
+    async fn update_run_status(
+        &self,
+        run_uid: &str,
+        status: &RunStatus,
+    ) -> Result<RunStatusRecord, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.update_run_status(run_uid, status).await,
+            SqlClientEnum::Sqlite(client) => client.update_run_status(run_uid, status).await,
+            SqlClientEnum::MySql(client) => client.update_run_status(run_uid, status).await,
+        }
+    }
+
+    async fn mark_crashed_runs(&self, heartbeat_before: NaiveDateTime) -> Result<u64, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.mark_crashed_runs(heartbeat_before).await,
+            SqlClientEnum::Sqlite(client) => client.mark_crashed_runs(heartbeat_before).await,
+            SqlClientEnum::MySql(client) => client.mark_crashed_runs(heartbeat_before).await,
+        }
+    }
+
     async fn insert_run_parameters<'life1>(
         &self,
         record: &'life1 [ParameterRecord],
@@ -294,6 +855,14 @@ impl SqlClient for SqlClientEnum {
         }
     }
 
+    async fn get_users(&self) -> Result<Vec<User>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_users().await,
+            SqlClientEnum::Sqlite(client) => client.get_users().await,
+            SqlClientEnum::MySql(client) => client.get_users().await,
+        }
+    }
+
     async fn update_user(&self, user: &User) -> Result<(), SqlError> {
         match self {
             SqlClientEnum::Postgres(client) => client.update_user(user).await,
@@ -301,6 +870,146 @@ impl SqlClient for SqlClientEnum {
             SqlClientEnum::MySql(client) => client.update_user(user).await,
         }
     }
+
+    async fn insert_refresh_token(&self, token: &RefreshTokenFamily) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.insert_refresh_token(token).await,
+            SqlClientEnum::Sqlite(client) => client.insert_refresh_token(token).await,
+            SqlClientEnum::MySql(client) => client.insert_refresh_token(token).await,
+        }
+    }
+
+    async fn get_refresh_token(
+        &self,
+        family: &str,
+    ) -> Result<Option<RefreshTokenFamily>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_refresh_token(family).await,
+            SqlClientEnum::Sqlite(client) => client.get_refresh_token(family).await,
+            SqlClientEnum::MySql(client) => client.get_refresh_token(family).await,
+        }
+    }
+
+    async fn rotate_refresh_token(
+        &self,
+        family: &str,
+        current: &str,
+        token_hash: &str,
+    ) -> Result<bool, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => {
+                client
+                    .rotate_refresh_token(family, current, token_hash)
+                    .await
+            }
+            SqlClientEnum::Sqlite(client) => {
+                client
+                    .rotate_refresh_token(family, current, token_hash)
+                    .await
+            }
+            SqlClientEnum::MySql(client) => {
+                client
+                    .rotate_refresh_token(family, current, token_hash)
+                    .await
+            }
+        }
+    }
+
+    async fn delete_refresh_token(&self, family: &str) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.delete_refresh_token(family).await,
+            SqlClientEnum::Sqlite(client) => client.delete_refresh_token(family).await,
+            SqlClientEnum::MySql(client) => client.delete_refresh_token(family).await,
+        }
+    }
+
+    async fn delete_user_refresh_tokens(&self, username: &str) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.delete_user_refresh_tokens(username).await,
+            SqlClientEnum::Sqlite(client) => client.delete_user_refresh_tokens(username).await,
+            SqlClientEnum::MySql(client) => client.delete_user_refresh_tokens(username).await,
+        }
+    }
+
+    async fn delete_user(&self, username: &str) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.delete_user(username).await,
+            SqlClientEnum::Sqlite(client) => client.delete_user(username).await,
+            SqlClientEnum::MySql(client) => client.delete_user(username).await,
+        }
+    }
+
+    async fn insert_group(&self, group: &Group) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.insert_group(group).await,
+            SqlClientEnum::Sqlite(client) => client.insert_group(group).await,
+            SqlClientEnum::MySql(client) => client.insert_group(group).await,
+        }
+    }
+
+    async fn get_group(&self, name: &str) -> Result<Option<Group>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_group(name).await,
+            SqlClientEnum::Sqlite(client) => client.get_group(name).await,
+            SqlClientEnum::MySql(client) => client.get_group(name).await,
+        }
+    }
+
+    async fn get_groups(&self) -> Result<Vec<Group>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_groups().await,
+            SqlClientEnum::Sqlite(client) => client.get_groups().await,
+            SqlClientEnum::MySql(client) => client.get_groups().await,
+        }
+    }
+
+    async fn update_group(&self, group: &Group) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.update_group(group).await,
+            SqlClientEnum::Sqlite(client) => client.update_group(group).await,
+            SqlClientEnum::MySql(client) => client.update_group(group).await,
+        }
+    }
+
+    async fn delete_group(&self, name: &str) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.delete_group(name).await,
+            SqlClientEnum::Sqlite(client) => client.delete_group(name).await,
+            SqlClientEnum::MySql(client) => client.delete_group(name).await,
+        }
+    }
+
+    async fn add_group_member(&self, name: &str, username: &str) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.add_group_member(name, username).await,
+            SqlClientEnum::Sqlite(client) => client.add_group_member(name, username).await,
+            SqlClientEnum::MySql(client) => client.add_group_member(name, username).await,
+        }
+    }
+
+    async fn remove_group_member(&self, name: &str, username: &str) -> Result<(), SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.remove_group_member(name, username).await,
+            SqlClientEnum::Sqlite(client) => client.remove_group_member(name, username).await,
+            SqlClientEnum::MySql(client) => client.remove_group_member(name, username).await,
+        }
+    }
+
+    async fn get_group_members(&self, name: &str) -> Result<Vec<String>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_group_members(name).await,
+            SqlClientEnum::Sqlite(client) => client.get_group_members(name).await,
+            SqlClientEnum::MySql(client) => client.get_group_members(name).await,
+        }
+    }
+
+    async fn get_user_groups(&self, username: &str) -> Result<Vec<Group>, SqlError> {
+        match self {
+            SqlClientEnum::Postgres(client) => client.get_user_groups(username).await,
+            SqlClientEnum::Sqlite(client) => client.get_user_groups(username).await,
+            SqlClientEnum::MySql(client) => client.get_user_groups(username).await,
+        }
+    }
 }
 
 pub async fn get_sql_client(config: &OpsmlConfig) -> AnyhowResult<SqlClientEnum> {
@@ -420,7 +1129,7 @@ mod tests {
             .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("^2.0.0"))
             .await
             .unwrap();
-        assert_eq!(versions.len(), 2);
+        assert_eq!(versions.len(), 4);
 
         cleanup();
     }
@@ -909,7 +1618,7 @@ mod tests {
 
         // query page
         let results = client
-            .query_page("name", 0, None, None, &CardSQLTableNames::Data)
+            .query_page("name", 0, None, None, None, None, &CardSQLTableNames::Data)
             .await
             .unwrap();
 
@@ -917,7 +1626,7 @@ mod tests {
 
         // query page
         let results = client
-            .query_page("name", 0, None, None, &CardSQLTableNames::Model)
+            .query_page("name", 0, None, None, None, None, &CardSQLTableNames::Model)
             .await
             .unwrap();
 
@@ -925,7 +1634,15 @@ mod tests {
 
         // query page
         let results = client
-            .query_page("name", 0, None, Some("repo3"), &CardSQLTableNames::Model)
+            .query_page(
+                "name",
+                0,
+                None,
+                None,
+                Some("repo3"),
+                None,
+                &CardSQLTableNames::Model,
+            )
             .await
             .unwrap();
 
@@ -961,7 +1678,7 @@ mod tests {
 
         // delete the card
         client
-            .delete_card(&CardSQLTableNames::Data, &uid)
+            .delete_card(&CardSQLTableNames::Data, &uid, "test_user")
             .await
             .unwrap();
 
@@ -1,3 +1,4 @@
+pub mod archive;
 pub mod base;
 pub mod database;
 pub mod enums;
@@ -1,7 +1,75 @@
-use crate::base::add_version_bounds;
+use crate::base::{add_version_bounds, get_registry_type, CARD_REGISTRIES};
 use opsml_error::error::SqlError;
-use opsml_types::{CardQueryArgs, CardSQLTableNames};
+use opsml_types::{
+    CardQueryArgs, CardSQLTableNames, GetMetricRequest, RunQueryRequest, TagFilter, TagOperator,
+    VersionScheme,
+};
 use opsml_utils::utils::is_valid_uuid4;
+/// Add tag filters to a query. Keys are bound as quoted json paths and values are
+/// compared against the unquoted json value, so nothing is spliced into the sql.
+///
+/// # Arguments
+///
+/// * `builder` - The query to append to
+/// * `bindings` - The bindings for the query, in parameter order
+/// * `filters` - The tag filters to apply
+pub fn add_tag_filters(
+    builder: &mut String,
+    bindings: &mut Vec<String>,
+    filters: &[TagFilter],
+) -> Result<(), SqlError> {
+    for filter in filters {
+        filter
+            .validate()
+            .map_err(|e| SqlError::GeneralError(e.to_string()))?;
+
+        // quote the key so that any character is valid as a path member
+        let path = format!(
+            "$.\"{}\"",
+            filter.key.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        let value = "JSON_UNQUOTE(JSON_EXTRACT(tags, ?))";
+
+        match filter.operator {
+            TagOperator::Eq => {
+                builder.push_str(format!(" AND {} = ?", value).as_str());
+                bindings.push(path);
+                bindings.push(filter.values[0].clone());
+            }
+            TagOperator::Ne => {
+                builder.push_str(
+                    format!(" AND (JSON_EXTRACT(tags, ?) IS NULL OR {} <> ?)", value).as_str(),
+                );
+                bindings.push(path.clone());
+                bindings.push(path);
+                bindings.push(filter.values[0].clone());
+            }
+            TagOperator::Exists => {
+                builder.push_str(" AND JSON_EXTRACT(tags, ?) IS NOT NULL");
+                bindings.push(path);
+            }
+            TagOperator::NotExists => {
+                builder.push_str(" AND JSON_EXTRACT(tags, ?) IS NULL");
+                bindings.push(path);
+            }
+            TagOperator::Prefix => {
+                builder.push_str(format!(" AND LEFT({}, CHAR_LENGTH(?)) = ?", value).as_str());
+                bindings.push(path);
+                bindings.push(filter.values[0].clone());
+                bindings.push(filter.values[0].clone());
+            }
+            TagOperator::In => {
+                let params = vec!["?"; filter.values.len()].join(", ");
+                builder.push_str(format!(" AND {} IN ({})", value, params).as_str());
+                bindings.push(path);
+                bindings.extend(filter.values.iter().cloned());
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub struct MySQLQueryHelper;
 
 impl MySQLQueryHelper {
@@ -11,7 +79,7 @@ impl MySQLQueryHelper {
 
     pub fn get_user_insert_query() -> String {
         format!(
-            "INSERT INTO {} (username, password_hash, permissions, group_permissions) VALUES (?, ?, ?, ?)",
+            "INSERT INTO {} (username, password_hash, permissions, group_permissions, created_at) VALUES (?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))",
             CardSQLTableNames::Users
         )
         .to_string()
@@ -25,6 +93,14 @@ impl MySQLQueryHelper {
         .to_string()
     }
 
+    pub fn get_users_query() -> String {
+        format!(
+            "SELECT id, created_at, active, username, password_hash, permissions, group_permissions, refresh_token FROM {} ORDER BY id",
+            CardSQLTableNames::Users
+        )
+        .to_string()
+    }
+
     pub fn get_user_update_query() -> String {
         format!(
             "UPDATE {} SET 
@@ -38,6 +114,128 @@ impl MySQLQueryHelper {
         )
         .to_string()
     }
+
+    pub fn get_refresh_token_insert_query() -> String {
+        format!(
+            "INSERT INTO {} (family, username, token_hash) VALUES (?, ?, ?)",
+            CardSQLTableNames::RefreshTokens
+        )
+    }
+
+    pub fn get_refresh_token_query() -> String {
+        format!(
+            "SELECT family, username, token_hash, created_at FROM {} WHERE family = ?",
+            CardSQLTableNames::RefreshTokens
+        )
+    }
+
+    pub fn get_refresh_token_rotate_query() -> String {
+        format!(
+            "UPDATE {} SET token_hash = ? WHERE family = ? AND token_hash = ?",
+            CardSQLTableNames::RefreshTokens
+        )
+    }
+
+    pub fn get_refresh_token_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE family = ?",
+            CardSQLTableNames::RefreshTokens
+        )
+    }
+
+    pub fn get_user_refresh_tokens_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE username = ?",
+            CardSQLTableNames::RefreshTokens
+        )
+    }
+
+    pub fn get_user_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE username = ?",
+            CardSQLTableNames::Users
+        )
+    }
+
+    pub fn get_user_memberships_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE username = ?",
+            CardSQLTableNames::GroupMembers
+        )
+    }
+
+    pub fn get_group_insert_query() -> String {
+        format!(
+            "INSERT INTO {} (name, permissions, created_at) VALUES (?, ?, COALESCE(?, CURRENT_TIMESTAMP))",
+            CardSQLTableNames::Groups
+        )
+    }
+
+    pub fn get_group_query() -> String {
+        format!(
+            "SELECT id, created_at, name, permissions FROM {} WHERE name = ?",
+            CardSQLTableNames::Groups
+        )
+    }
+
+    pub fn get_groups_query() -> String {
+        format!(
+            "SELECT id, created_at, name, permissions FROM {} ORDER BY name",
+            CardSQLTableNames::Groups
+        )
+    }
+
+    pub fn get_group_update_query() -> String {
+        format!(
+            "UPDATE {} SET permissions = ? WHERE name = ?",
+            CardSQLTableNames::Groups
+        )
+    }
+
+    pub fn get_group_delete_query() -> String {
+        format!("DELETE FROM {} WHERE name = ?", CardSQLTableNames::Groups)
+    }
+
+    pub fn get_group_members_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE group_name = ?",
+            CardSQLTableNames::GroupMembers
+        )
+    }
+
+    /// Adding an existing member is a no-op
+    pub fn get_group_member_insert_query() -> String {
+        format!(
+            "INSERT IGNORE INTO {} (group_name, username) VALUES (?, ?)",
+            CardSQLTableNames::GroupMembers
+        )
+    }
+
+    pub fn get_group_member_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE group_name = ? AND username = ?",
+            CardSQLTableNames::GroupMembers
+        )
+    }
+
+    pub fn get_group_members_query() -> String {
+        format!(
+            "SELECT username FROM {} WHERE group_name = ? ORDER BY username",
+            CardSQLTableNames::GroupMembers
+        )
+    }
+
+    pub fn get_user_groups_query() -> String {
+        format!(
+            "SELECT g.id, g.created_at, g.name, g.permissions
+            FROM {} g
+            INNER JOIN {} m ON m.group_name = g.name
+            WHERE m.username = ?
+            ORDER BY g.name",
+            CardSQLTableNames::Groups,
+            CardSQLTableNames::GroupMembers
+        )
+    }
     pub fn get_hardware_metric_query() -> String {
         let query = format!(
             "SELECT * FROM {} WHERE run_uid = ?",
@@ -53,8 +251,9 @@ impl MySQLQueryHelper {
                 name, 
                 value,
                 step,
-                timestamp
-            ) VALUES (?, ?, ?, ?, ?)",
+                timestamp,
+                created_at
+            ) VALUES (?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))",
             CardSQLTableNames::Metrics
         )
         .to_string()
@@ -68,14 +267,15 @@ impl MySQLQueryHelper {
                 name, 
                 value,
                 step,
-                timestamp
+                timestamp,
+                created_at
             ) VALUES ",
             CardSQLTableNames::Metrics
         )
         .to_string();
 
         for i in 0..nbr_records {
-            query.push_str("(?, ?, ?, ?, ?) ");
+            query.push_str("(?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP)) ");
 
             // add comma if not last record
             if i < nbr_records - 1 {
@@ -117,6 +317,263 @@ impl MySQLQueryHelper {
         (query, bindings)
     }
 
+    /// Metric rows for a run, filtered by name and step range. Names are returned as bindings;
+    /// `start_step` and `end_step` placeholders follow them when set
+    fn get_run_metric_filter_query(args: &GetMetricRequest) -> (String, Vec<String>) {
+        let mut query = format!(
+            "SELECT run_uid, name, value, step, timestamp, created_at, idx
+            FROM {}
+            WHERE run_uid = ?",
+            CardSQLTableNames::Metrics
+        );
+
+        let bindings: Vec<String> = args.names.to_vec();
+
+        if !bindings.is_empty() {
+            let placeholders = vec!["?"; bindings.len()].join(", ");
+            query.push_str(&format!(" AND name IN ({})", placeholders));
+        }
+
+        if args.start_step.is_some() {
+            query.push_str(" AND step >= ?");
+        }
+
+        if args.end_step.is_some() {
+            query.push_str(" AND step <= ?");
+        }
+
+        (query, bindings)
+    }
+
+    /// Metric points for a run. When `max_points` is set, each metric is split into
+    /// `max_points / 2` buckets of consecutive steps and only the minimum and maximum point of
+    /// every bucket is kept, which preserves spikes while bounding the number of points
+    pub fn get_run_metric_points_query(args: &GetMetricRequest) -> (String, Vec<String>) {
+        let (filtered, bindings) = Self::get_run_metric_filter_query(args);
+
+        let query = match args.max_points {
+            Some(max_points) => {
+                let buckets = (max_points / 2).max(1);
+                format!(
+                    "WITH filtered AS ({}),
+                    bucketed AS (
+                        SELECT *, NTILE({}) OVER (PARTITION BY name ORDER BY step, idx) AS bucket
+                        FROM filtered
+                    ),
+                    ranked AS (
+                        SELECT *,
+                            ROW_NUMBER() OVER (PARTITION BY name, bucket ORDER BY value ASC, step, idx) AS min_rank,
+                            ROW_NUMBER() OVER (PARTITION BY name, bucket ORDER BY value DESC, step, idx) AS max_rank
+                        FROM bucketed
+                    )
+                    SELECT run_uid, name, value, step, timestamp, created_at, idx
+                    FROM ranked
+                    WHERE min_rank = 1 OR max_rank = 1
+                    ORDER BY name, step, idx",
+                    filtered, buckets
+                )
+            }
+            None => format!("{} ORDER BY name, step, idx", filtered),
+        };
+
+        (query, bindings)
+    }
+
+    pub fn get_run_metric_summary_query(args: &GetMetricRequest) -> (String, Vec<String>) {
+        let (filtered, bindings) = Self::get_run_metric_filter_query(args);
+
+        let query = format!(
+            "WITH filtered AS ({}),
+            ranked AS (
+                SELECT name, value, step,
+                    ROW_NUMBER() OVER (PARTITION BY name ORDER BY step DESC, idx DESC) AS last_rank
+                FROM filtered
+            )
+            SELECT
+                name,
+                COUNT(*) AS count,
+                MIN(value) AS min_value,
+                MAX(value) AS max_value,
+                AVG(value) AS mean_value,
+                MAX(CASE WHEN last_rank = 1 THEN value END) AS last_value,
+                MAX(step) AS last_step
+            FROM ranked
+            GROUP BY name
+            ORDER BY name",
+            filtered
+        );
+
+        (query, bindings)
+    }
+
+    /// Rows of a run table for many runs, optionally restricted to a set of names
+    fn get_runs_query(
+        table: CardSQLTableNames,
+        uids: &[String],
+        names: &[String],
+        order_by: &str,
+    ) -> (String, Vec<String>) {
+        let mut bindings: Vec<String> = uids.to_vec();
+
+        let mut query = format!(
+            "SELECT * FROM {} WHERE run_uid IN ({})",
+            table,
+            vec!["?"; uids.len()].join(", ")
+        );
+
+        if !names.is_empty() {
+            query.push_str(&format!(
+                " AND name IN ({})",
+                vec!["?"; names.len()].join(", ")
+            ));
+            bindings.extend(names.iter().cloned());
+        }
+
+        query.push_str(&format!(" ORDER BY {}", order_by));
+
+        (query, bindings)
+    }
+
+    pub fn get_runs_metric_query(uids: &[String], names: &[String]) -> (String, Vec<String>) {
+        Self::get_runs_query(
+            CardSQLTableNames::Metrics,
+            uids,
+            names,
+            "run_uid, name, step, idx",
+        )
+    }
+
+    pub fn get_runs_parameter_query(uids: &[String], names: &[String]) -> (String, Vec<String>) {
+        Self::get_runs_query(
+            CardSQLTableNames::Parameters,
+            uids,
+            names,
+            "run_uid, name, idx",
+        )
+    }
+
+    /// Search runs by parameter values and metric thresholds. Metrics are reduced to their
+    /// last, min and max value per run before filtering and sorting. The limit is the final
+    /// placeholder and is bound by the caller
+    pub fn get_run_query(args: &RunQueryRequest) -> (String, Vec<String>) {
+        let next_param = || "?".to_string();
+        let mut bindings: Vec<String> = Vec::new();
+        let metric_names = args.metric_names();
+
+        let mut query = String::new();
+        if !metric_names.is_empty() {
+            let placeholders: Vec<String> = metric_names.iter().map(|_| next_param()).collect();
+            bindings.extend(metric_names.iter().cloned());
+
+            query.push_str(&format!(
+                "WITH ranked_metrics AS (
+                    SELECT run_uid, name, value,
+                        ROW_NUMBER() OVER (PARTITION BY run_uid, name ORDER BY step DESC, idx DESC) AS last_rank
+                    FROM {}
+                    WHERE name IN ({})
+                ),
+                metric_values AS (
+                    SELECT run_uid, name,
+                        MIN(value) AS min_value,
+                        MAX(value) AS max_value,
+                        MAX(CASE WHEN last_rank = 1 THEN value END) AS last_value
+                    FROM ranked_metrics
+                    GROUP BY run_uid, name
+                ) ",
+                CardSQLTableNames::Metrics,
+                placeholders.join(", ")
+            ));
+        }
+
+        let sort_column = args
+            .sort_by
+            .as_ref()
+            .map(|sort| format!("sort_metric.{}", sort.aggregation.column()));
+
+        query.push_str(&format!(
+            "SELECT run_card.uid, run_card.name, run_card.repository, run_card.version, run_card.project, run_card.created_at, {} AS sort_value
+            FROM {} run_card",
+            sort_column
+                .clone()
+                .unwrap_or_else(|| "CAST(NULL AS DOUBLE)".to_string()),
+            CardSQLTableNames::Run
+        ));
+
+        if let Some(sort) = &args.sort_by {
+            query.push_str(&format!(
+                " LEFT JOIN metric_values sort_metric ON sort_metric.run_uid = run_card.uid AND sort_metric.name = {}",
+                next_param()
+            ));
+            bindings.push(sort.name.clone());
+        }
+
+        query.push_str(" WHERE run_card.deleted_at IS NULL");
+
+        if let Some(project) = &args.project {
+            query.push_str(&format!(" AND run_card.project = {}", next_param()));
+            bindings.push(project.clone());
+        }
+
+        if let Some(repository) = &args.repository {
+            query.push_str(&format!(" AND run_card.repository = {}", next_param()));
+            bindings.push(repository.clone());
+        }
+
+        for filter in &args.parameters {
+            let name_param = next_param();
+            let value_param = next_param();
+            let comparison = if filter.value.parse::<f64>().is_ok() {
+                format!(
+                    "CASE WHEN parameter.value REGEXP '^[-+]?([0-9]+[.]?[0-9]*|[.][0-9]+)([eE][-+]?[0-9]+)?$' THEN CAST(parameter.value AS DOUBLE) END {} CAST({} AS DOUBLE)",
+                    filter.operator.as_sql(),
+                    value_param
+                )
+            } else {
+                format!(
+                    "parameter.value {} {}",
+                    filter.operator.as_sql(),
+                    value_param
+                )
+            };
+
+            query.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM {} parameter WHERE parameter.run_uid = run_card.uid AND parameter.name = {} AND {})",
+                CardSQLTableNames::Parameters,
+                name_param,
+                comparison
+            ));
+            bindings.push(filter.name.clone());
+            bindings.push(filter.value.clone());
+        }
+
+        for filter in &args.metrics {
+            query.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM metric_values metric WHERE metric.run_uid = run_card.uid AND metric.name = {} AND metric.{} {} CAST({} AS DOUBLE))",
+                next_param(),
+                filter.aggregation.column(),
+                filter.operator.as_sql(),
+                next_param()
+            ));
+            bindings.push(filter.name.clone());
+            bindings.push(filter.value.to_string());
+        }
+
+        match (&args.sort_by, sort_column) {
+            (Some(sort), Some(column)) => {
+                let direction = if sort.descending { "DESC" } else { "ASC" };
+                query.push_str(&format!(
+                    " ORDER BY ({} IS NULL), {} {}, run_card.created_at DESC",
+                    column, column, direction
+                ));
+            }
+            _ => query.push_str(" ORDER BY run_card.created_at DESC"),
+        }
+
+        query.push_str(&format!(" LIMIT {}", next_param()));
+
+        (query, bindings)
+    }
+
     pub fn get_project_id_query() -> String {
         format!(
             "WITH max_project AS (
@@ -131,18 +588,27 @@ impl MySQLQueryHelper {
         )
         .to_string()
     }
-    pub fn get_query_page_query(table: &CardSQLTableNames, sort_by: &str) -> String {
+    /// Query for a page of card summaries. Pages are selected either by row number
+    /// (offset paging) or by a keyset cursor over (updated_at, repository, name)
+    pub fn get_query_page_query(
+        table: &CardSQLTableNames,
+        sort_by: &str,
+        use_cursor: bool,
+    ) -> String {
         let versions_cte = format!(
             "WITH versions AS (
                 SELECT 
                     repository, 
                     name, 
                     version, 
+                    status,
                     ROW_NUMBER() OVER (PARTITION BY repository, name ORDER BY created_at DESC) AS row_num
                 FROM {}
                 WHERE 1=1
                 AND (? IS NULL OR repository = ?)
                 AND (? IS NULL OR name LIKE ? OR repository LIKE ?)
+                AND (? IS NULL OR status = ?)
+                AND deleted_at IS NULL
             )", table
         );
 
@@ -158,6 +624,8 @@ impl MySQLQueryHelper {
                 WHERE 1=1
                 AND (? IS NULL OR repository = ?)
                 AND (? IS NULL OR name LIKE ? OR repository LIKE ?)
+                AND (? IS NULL OR status = ?)
+                AND deleted_at IS NULL
                 GROUP BY repository, name
             )",
             table
@@ -168,6 +636,7 @@ impl MySQLQueryHelper {
                 repository, 
                 name, 
                 version, 
+                status,
                 row_num
             FROM versions 
             WHERE row_num = 1
@@ -179,6 +648,7 @@ impl MySQLQueryHelper {
                     stats.repository, 
                     stats.name, 
                     filtered_versions.version, 
+                    filtered_versions.status,
                     stats.versions, 
                     stats.updated_at, 
                     stats.created_at, 
@@ -191,20 +661,29 @@ impl MySQLQueryHelper {
             sort_by
         );
 
+        let page_filter = if use_cursor {
+            "WHERE (? IS NULL OR updated_at < ? OR (updated_at = ? AND (repository > ? OR (repository = ? AND name > ?))))
+            ORDER BY updated_at DESC, repository, name
+            LIMIT ?"
+        } else {
+            "WHERE row_num BETWEEN ? AND ?
+            ORDER BY updated_at DESC"
+        };
+
         let combined_query = format!(
             "{}{}{}{} 
             SELECT
             repository,
             name,
             version,
+            status,
             versions,
             updated_at,
             created_at,
             CAST(row_num AS SIGNED) AS row_num
             FROM joined 
-            WHERE row_num BETWEEN ? AND ?
-            ORDER BY updated_at DESC;",
-            versions_cte, stats_cte, filtered_versions_cte, joined_cte
+            {};",
+            versions_cte, stats_cte, filtered_versions_cte, joined_cte, page_filter
         );
 
         combined_query
@@ -218,16 +697,360 @@ impl MySQLQueryHelper {
                 FROM {}
                 WHERE 1=1
                 AND (? IS NULL OR name LIKE ? OR repository LIKE ?)
+                AND deleted_at IS NULL
                 ",
             table
         );
 
         base_query
     }
+    /// Full-text search over the FULLTEXT indexed search_text column of each registry.
+    /// Each registry binds the boolean search expression twice, followed by the limit
+    pub fn get_search_cards_query() -> String {
+        let registries = CARD_REGISTRIES
+            .iter()
+            .map(|(registry_type, table)| {
+                format!(
+                    "SELECT
+                        '{}' AS registry_type,
+                        uid,
+                        name,
+                        repository,
+                        version,
+                        created_at,
+                        MATCH(search_text) AGAINST (? IN BOOLEAN MODE) AS score
+                    FROM {}
+                    WHERE MATCH(search_text) AGAINST (? IN BOOLEAN MODE)
+                    AND deleted_at IS NULL",
+                    registry_type, table
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(" UNION ALL ");
+
+        format!(
+            "{}
+            ORDER BY score DESC, created_at DESC
+            LIMIT ?",
+            registries
+        )
+    }
+    pub fn get_card_history_insert_query() -> String {
+        format!(
+            "INSERT INTO {} (uid, registry_type, action, username, changes, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+            CardSQLTableNames::History
+        )
+    }
+
+    pub fn get_card_history_query() -> String {
+        format!(
+            "SELECT uid, registry_type, action, username, changes, created_at FROM {} WHERE uid = ? ORDER BY idx ASC",
+            CardSQLTableNames::History
+        )
+    }
+
+    pub fn get_card_alias_query() -> String {
+        format!(
+            "SELECT registry_type, repository, name, alias, uid, updated_by, updated_at FROM {} WHERE registry_type = ? AND repository = ? AND name = ? AND alias = ?",
+            CardSQLTableNames::Aliases
+        )
+    }
+
+    pub fn get_card_aliases_query() -> String {
+        format!(
+            "SELECT registry_type, repository, name, alias, uid, updated_by, updated_at FROM {} WHERE registry_type = ? AND repository = ? AND name = ? ORDER BY alias",
+            CardSQLTableNames::Aliases
+        )
+    }
+
+    pub fn get_card_alias_upsert_query() -> String {
+        format!(
+            "INSERT INTO {} (registry_type, repository, name, alias, uid, updated_by, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?) ON DUPLICATE KEY UPDATE uid = VALUES(uid), updated_by = VALUES(updated_by), updated_at = VALUES(updated_at)",
+            CardSQLTableNames::Aliases
+        )
+    }
+
+    pub fn get_card_alias_history_insert_query() -> String {
+        format!(
+            "INSERT INTO {} (registry_type, repository, name, alias, previous_uid, uid, updated_by, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            CardSQLTableNames::AliasHistory
+        )
+    }
+
+    pub fn get_card_alias_history_query() -> String {
+        format!(
+            "SELECT registry_type, repository, name, alias, previous_uid, uid, updated_by, created_at FROM {} WHERE registry_type = ? AND repository = ? AND name = ? ORDER BY idx ASC",
+            CardSQLTableNames::AliasHistory
+        )
+    }
+
+    pub fn get_lineage_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE declared_by = ?",
+            CardSQLTableNames::Lineage
+        )
+    }
+
+    pub fn get_lineage_insert_query(nbr_records: usize) -> String {
+        let mut query = format!(
+            "INSERT IGNORE INTO {} (
+                upstream_uid,
+                upstream_type,
+                downstream_uid,
+                downstream_type,
+                declared_by
+            ) VALUES ",
+            CardSQLTableNames::Lineage
+        );
+
+        for i in 0..nbr_records {
+            if i > 0 {
+                query.push_str(", ");
+            }
+            query.push_str("(?, ?, ?, ?, ?)");
+        }
+
+        query
+    }
+
+    /// Recursive lineage traversal up to a max depth. Edges reachable through more than one
+    /// path are reported once at their shallowest depth. Each path tracks the uids it has
+    /// visited and stops expanding once it closes a cycle
+    pub fn get_lineage_query(upstream: bool) -> String {
+        // upstream walks from downstream_uid to upstream_uid, downstream the reverse
+        let (start_col, next_col) = if upstream {
+            ("downstream_uid", "upstream_uid")
+        } else {
+            ("upstream_uid", "downstream_uid")
+        };
+        let table = CardSQLTableNames::Lineage;
+
+        format!(
+            "WITH RECURSIVE lineage (upstream_uid, upstream_type, downstream_uid, downstream_type, depth, visited, is_cycle) AS (
+                SELECT upstream_uid, upstream_type, downstream_uid, downstream_type, 1,
+                    CAST(CONCAT(',', {start_col}, ',', {next_col}, ',') AS CHAR(1024)), 0
+                FROM {table}
+                WHERE {start_col} = ?
+                UNION
+                SELECT edge.upstream_uid, edge.upstream_type, edge.downstream_uid, edge.downstream_type, lineage.depth + 1,
+                    CONCAT(lineage.visited, edge.{next_col}, ','),
+                    LOCATE(CONCAT(',', edge.{next_col}, ','), lineage.visited) > 0
+                FROM {table} AS edge
+                INNER JOIN lineage ON edge.{start_col} = lineage.{next_col}
+                WHERE lineage.depth < ? AND lineage.is_cycle = 0
+            )
+            SELECT upstream_uid, upstream_type, downstream_uid, downstream_type, MIN(depth) AS depth
+            FROM lineage
+            GROUP BY upstream_uid, upstream_type, downstream_uid, downstream_type
+            ORDER BY depth, upstream_uid, downstream_uid"
+        )
+    }
+
+    /// Node details for (registry_type, uid) pairs. Each uid is only looked up in its own registry
+    pub fn get_lineage_nodes_query(nodes: &[(String, String)]) -> (String, Vec<String>) {
+        let mut bindings: Vec<String> = Vec::new();
+
+        let query = CARD_REGISTRIES
+            .iter()
+            .filter_map(|(registry_type, table)| {
+                let uids: Vec<String> = nodes
+                    .iter()
+                    .filter(|(node_type, _)| node_type == registry_type)
+                    .map(|(_, uid)| uid.clone())
+                    .collect();
+
+                if uids.is_empty() {
+                    return None;
+                }
+
+                let placeholders = vec!["?"; uids.len()].join(", ");
+                bindings.extend(uids);
+
+                Some(format!(
+                    "SELECT '{}' AS registry_type, uid, name, repository, version FROM {} WHERE uid IN ({})",
+                    registry_type, table, placeholders
+                ))
+            })
+            .collect::<Vec<String>>()
+            .join(" UNION ALL ");
+
+        (query, bindings)
+    }
+
+    pub fn get_card_delete_query(table: &CardSQLTableNames) -> String {
+        format!(
+            "UPDATE {}
+            SET deleted_at = ?, deleted_by = ?
+            WHERE uid = ?
+            AND deleted_at IS NULL",
+            table
+        )
+    }
+
+    pub fn get_card_restore_query(table: &CardSQLTableNames) -> String {
+        format!(
+            "UPDATE {} SET deleted_at = NULL, deleted_by = NULL WHERE uid = ?",
+            table
+        )
+    }
+
+    pub fn get_card_purge_query(table: &CardSQLTableNames) -> String {
+        format!("DELETE FROM {} WHERE uid = ?", table)
+    }
+
+    pub fn get_card_status_query(table: &CardSQLTableNames) -> String {
+        format!("SELECT status FROM {} WHERE uid = ?", table)
+    }
+
+    pub fn get_card_status_update_query(table: &CardSQLTableNames) -> String {
+        format!(
+            "UPDATE {} SET status = ? WHERE uid = ? AND status = ?",
+            table
+        )
+    }
+
+    pub fn get_card_yank_query(table: &CardSQLTableNames) -> String {
+        format!("UPDATE {} SET yanked = ? WHERE uid = ?", table)
+    }
+
+    pub fn get_version_scheme_query() -> String {
+        format!(
+            "SELECT version_scheme FROM {} WHERE repository = ?",
+            CardSQLTableNames::RepositorySettings
+        )
+    }
+
+    pub fn get_version_scheme_upsert_query() -> String {
+        format!(
+            "INSERT INTO {} (repository, version_scheme) VALUES (?, ?) ON DUPLICATE KEY UPDATE version_scheme = VALUES(version_scheme), updated_at = CURRENT_TIMESTAMP",
+            CardSQLTableNames::RepositorySettings
+        )
+    }
+
+    pub fn get_child_runs_query() -> String {
+        format!(
+            "SELECT * FROM {} WHERE parent_uid = ? AND deleted_at IS NULL ORDER BY created_at, uid",
+            CardSQLTableNames::Run
+        )
+    }
+
+    /// Summarize the last value each child run logged per metric
+    pub fn get_child_metric_summary_query() -> String {
+        format!(
+            "WITH children AS (
+                SELECT uid FROM {} WHERE parent_uid = ? AND deleted_at IS NULL
+            ),
+            last_values AS (
+                SELECT metrics.run_uid, metrics.name, metrics.value,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY metrics.run_uid, metrics.name
+                        ORDER BY metrics.step DESC, metrics.idx DESC
+                    ) AS last_rank
+                FROM {} metrics
+                INNER JOIN children ON metrics.run_uid = children.uid
+            ),
+            ranked AS (
+                SELECT run_uid, name, value,
+                    ROW_NUMBER() OVER (PARTITION BY name ORDER BY value ASC, run_uid) AS min_rank,
+                    ROW_NUMBER() OVER (PARTITION BY name ORDER BY value DESC, run_uid) AS max_rank
+                FROM last_values
+                WHERE last_rank = 1
+            )
+            SELECT
+                name,
+                COUNT(*) AS run_count,
+                MIN(value) AS min_value,
+                MAX(CASE WHEN min_rank = 1 THEN run_uid END) AS min_run_uid,
+                MAX(value) AS max_value,
+                MAX(CASE WHEN max_rank = 1 THEN run_uid END) AS max_run_uid,
+                AVG(value) AS mean_value
+            FROM ranked
+            GROUP BY name
+            ORDER BY name",
+            CardSQLTableNames::Run,
+            CardSQLTableNames::Metrics
+        )
+    }
+
+    pub fn get_run_status_query() -> String {
+        format!(
+            "SELECT run_uid, status, started_at, ended_at, heartbeat_at FROM {} WHERE run_uid = ?",
+            CardSQLTableNames::RunStatus
+        )
+    }
+
+    pub fn get_run_start_query() -> String {
+        format!(
+            "INSERT INTO {} (run_uid, status, started_at, ended_at, heartbeat_at) VALUES (?, ?, ?, ?, ?)",
+            CardSQLTableNames::RunStatus
+        )
+    }
+
+    pub fn get_run_status_update_query() -> String {
+        format!(
+            "UPDATE {} SET status = ?, ended_at = ?, heartbeat_at = ? WHERE run_uid = ? AND status = ?",
+            CardSQLTableNames::RunStatus
+        )
+    }
+
+    /// Runs that missed their heartbeat end at the last heartbeat they sent
+    pub fn get_crashed_runs_update_query() -> String {
+        format!(
+            "UPDATE {} SET status = ?, ended_at = heartbeat_at WHERE status = ? AND heartbeat_at < ?",
+            CardSQLTableNames::RunStatus
+        )
+    }
+
+    pub fn get_deleted_cards_query(table: &CardSQLTableNames) -> String {
+        format!(
+            "SELECT uid, name, repository, version, deleted_at, deleted_by
+            FROM {}
+            WHERE deleted_at IS NOT NULL
+                AND (? IS NULL OR deleted_at < ?)
+                AND (? IS NULL OR uid = ?)
+            ORDER BY deleted_at DESC",
+            table
+        )
+    }
+
+    pub fn get_migration_applied_query() -> String {
+        "SELECT COUNT(*) FROM _sqlx_migrations WHERE version = ? AND success".to_string()
+    }
+
+    pub fn get_duplicate_versions_query(table: &CardSQLTableNames, live_only: bool) -> String {
+        format!(
+            "SELECT repository, name, version, COUNT(*)
+            FROM {}
+            WHERE repository IS NOT NULL
+                AND name IS NOT NULL
+                AND version IS NOT NULL{}
+            GROUP BY repository, name, version
+            HAVING COUNT(*) > 1",
+            table,
+            if live_only {
+                " AND deleted_at IS NULL"
+            } else {
+                ""
+            }
+        )
+    }
+
+    pub fn get_active_version_count_query(table: &CardSQLTableNames) -> String {
+        format!(
+            "SELECT COUNT(*)
+            FROM {}
+            WHERE name = ?
+            AND repository = ?
+            AND version = ?
+            AND deleted_at IS NULL",
+            table
+        )
+    }
+
     pub fn get_versions_query(
         table: &CardSQLTableNames,
         version: Option<&str>,
-    ) -> Result<String, SqlError> {
+    ) -> Result<(String, Vec<String>), SqlError> {
         let mut query = format!(
             "
             SELECT
@@ -244,23 +1067,34 @@ impl MySQLQueryHelper {
              WHERE 1=1
                 AND name = ?
                 AND repository = ?
+                AND deleted_at IS NULL
             ",
             table
         );
 
+        let mut bindings: Vec<String> = Vec::new();
+
+        // yanked versions are still taken, so new versions are allocated past them
         if let Some(version) = version {
-            add_version_bounds(&mut query, version)?;
+            add_version_bounds(
+                &mut query,
+                &mut bindings,
+                |_| "?".to_string(),
+                version,
+                &VersionScheme::SemVer,
+                true,
+            )?;
         }
 
         query.push_str(" ORDER BY created_at DESC LIMIT 20;");
 
-        Ok(query)
+        Ok((query, bindings))
     }
 
     pub fn get_query_cards_query(
         table: &CardSQLTableNames,
         query_args: &CardQueryArgs,
-    ) -> Result<String, SqlError> {
+    ) -> Result<(String, Vec<String>), SqlError> {
         let mut query = format!(
             "
         SELECT * FROM {}
@@ -269,33 +1103,63 @@ impl MySQLQueryHelper {
         AND (? IS NULL OR name = ?)
         AND (? IS NULL OR repository = ?)
         AND (? IS NULL OR created_at <= STR_TO_DATE(?, '%Y-%m-%d'))
+        AND (? IS NULL OR created_at < ? OR (created_at = ? AND uid < ?))
         ",
             table
         );
 
+        let mut bindings: Vec<String> = Vec::new();
+
+        // cards in the trash are hidden unless explicitly requested
+        if !query_args.include_deleted {
+            query.push_str(" AND deleted_at IS NULL");
+        }
+
         // check for uid. If uid is present, we only return that card
-        if query_args.uid.is_some() {
+        if let Some(uid) = &query_args.uid {
             // validate uid
-            is_valid_uuid4(query_args.uid.as_ref().unwrap())
-                .map_err(|e| SqlError::GeneralError(e.to_string()))?;
+            is_valid_uuid4(uid).map_err(|e| SqlError::GeneralError(e.to_string()))?;
         } else {
             // add where clause due to multiple combinations
 
-            if query_args.version.is_some() {
-                add_version_bounds(&mut query, query_args.version.as_ref().unwrap())?;
+            if let Some(version) = &query_args.version {
+                add_version_bounds(
+                    &mut query,
+                    &mut bindings,
+                    |_| "?".to_string(),
+                    version,
+                    &query_args.version_scheme.clone().unwrap_or_default(),
+                    false,
+                )?;
             }
 
-            if query_args.tags.is_some() {
-                let tags = query_args.tags.as_ref().unwrap();
-                for (key, value) in tags.iter() {
-                    query.push_str(
-                        format!(" AND json_extract(tags, '$.{}') = '{}'", key, value).as_str(),
-                    );
-                }
+            // aliases point at a single card of the (repository, name)
+            if let Some(alias) = &query_args.alias {
+                query.push_str(&format!(
+                    " AND uid IN (SELECT uid FROM {} WHERE registry_type = ? AND alias = ?)",
+                    CardSQLTableNames::Aliases
+                ));
+                bindings.push(get_registry_type(table));
+                bindings.push(alias.clone());
             }
 
-            if query_args.sort_by_timestamp.unwrap_or(false) {
-                query.push_str(" ORDER BY created_at DESC");
+            // deprecated and archived cards are left out of version resolution unless asked for
+            let status_params = query_args
+                .get_statuses()
+                .iter()
+                .map(|status| {
+                    bindings.push(status.to_string());
+                    "?".to_string()
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+            query.push_str(&format!(" AND status IN ({})", status_params));
+
+            add_tag_filters(&mut query, &mut bindings, &query_args.get_tag_filters())?;
+
+            // cursors are keyed on (created_at, uid) so they always use timestamp ordering
+            if query_args.uses_cursor_order() {
+                query.push_str(" ORDER BY created_at DESC, uid DESC");
             } else {
                 // sort by major, minor, patch
                 query.push_str(" ORDER BY major DESC, minor DESC, patch DESC");
@@ -304,7 +1168,7 @@ impl MySQLQueryHelper {
 
         query.push_str(" LIMIT ?");
 
-        Ok(query)
+        Ok((query, bindings))
     }
 
     pub fn get_run_parameters_insert_query(nbr_records: usize) -> String {
@@ -312,14 +1176,15 @@ impl MySQLQueryHelper {
             "INSERT INTO {} (
                 run_uid, 
                 name, 
-                value
+                value,
+                created_at
             ) VALUES ",
             CardSQLTableNames::Parameters
         )
         .to_string();
 
         for i in 0..nbr_records {
-            query.push_str("(?, ?, ?)");
+            query.push_str("(?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))");
 
             // add comma if not last record
             if i < nbr_records - 1 {
@@ -399,11 +1264,11 @@ impl MySQLQueryHelper {
     }
 
     pub fn get_projectcard_insert_query() -> String {
-        "INSERT INTO opsml_project_registry (uid, name, repository, project_id, major, minor, patch, version, pre_tag, build_tag) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)".to_string()
+        "INSERT INTO opsml_project_registry (uid, name, repository, project_id, major, minor, patch, version, pre_tag, build_tag, created_at, status, yanked) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), ?, ?)".to_string()
     }
 
     pub fn get_datacard_insert_query() -> String {
-        "INSERT INTO opsml_data_registry (uid, app_env, name, repository, major, minor, patch, version, contact, data_type, interface_type, tags, runcard_uid, pipelinecard_uid, auditcard_uid, pre_tag, build_tag) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)".to_string()
+        "INSERT INTO opsml_data_registry (uid, app_env, name, repository, major, minor, patch, version, contact, data_type, interface_type, tags, runcard_uid, pipelinecard_uid, auditcard_uid, pre_tag, build_tag, created_at, status, yanked) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), ?, ?)".to_string()
     }
 
     pub fn get_modelcard_insert_query() -> String {
@@ -427,9 +1292,12 @@ impl MySQLQueryHelper {
         pipelinecard_uid, 
         auditcard_uid, 
         pre_tag, 
-        build_tag
+        build_tag,
+        created_at,
+        status,
+        yanked
         ) 
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), ?, ?)"
             .to_string()
     }
 
@@ -452,9 +1320,13 @@ impl MySQLQueryHelper {
         artifact_uris, 
         compute_environment, 
         pre_tag, 
-        build_tag
+        build_tag,
+        created_at,
+        status,
+        yanked,
+        parent_uid
         ) 
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), ?, ?, ?)"
             .to_string()
     }
 
@@ -475,9 +1347,12 @@ impl MySQLQueryHelper {
         modelcard_uids, 
         runcard_uids, 
         pre_tag, 
-        build_tag
+        build_tag,
+        created_at,
+        status,
+        yanked
         ) 
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), ?, ?)"
             .to_string()
     }
 
@@ -498,9 +1373,12 @@ impl MySQLQueryHelper {
         modelcard_uids, 
         runcard_uids, 
         pre_tag, 
-        build_tag
+        build_tag,
+        created_at,
+        status,
+        yanked
         ) 
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), ?, ?)"
             .to_string()
     }
 
@@ -569,7 +1447,8 @@ impl MySQLQueryHelper {
         artifact_uris = ?, 
         compute_environment = ?, 
         pre_tag = ?, 
-        build_tag = ? 
+        build_tag = ?,
+        parent_uid = ?
         WHERE uid = ?"
             .to_string()
     }
@@ -0,0 +1,27 @@
+-- Add migration script here
+-- MySQL Migration Script
+
+-- Full-text search columns for each card registry
+ALTER TABLE opsml_data_registry
+    ADD COLUMN search_text TEXT GENERATED ALWAYS AS (CONCAT_WS(' ', name, repository, contact, CAST(tags AS CHAR))) STORED;
+CREATE FULLTEXT INDEX idx_data_registry_search ON opsml_data_registry (search_text);
+
+ALTER TABLE opsml_model_registry
+    ADD COLUMN search_text TEXT GENERATED ALWAYS AS (CONCAT_WS(' ', name, repository, contact, CAST(tags AS CHAR))) STORED;
+CREATE FULLTEXT INDEX idx_model_registry_search ON opsml_model_registry (search_text);
+
+ALTER TABLE opsml_run_registry
+    ADD COLUMN search_text TEXT GENERATED ALWAYS AS (CONCAT_WS(' ', name, repository, contact, CAST(tags AS CHAR))) STORED;
+CREATE FULLTEXT INDEX idx_run_registry_search ON opsml_run_registry (search_text);
+
+ALTER TABLE opsml_audit_registry
+    ADD COLUMN search_text TEXT GENERATED ALWAYS AS (CONCAT_WS(' ', name, repository, contact, CAST(tags AS CHAR))) STORED;
+CREATE FULLTEXT INDEX idx_audit_registry_search ON opsml_audit_registry (search_text);
+
+ALTER TABLE opsml_pipeline_registry
+    ADD COLUMN search_text TEXT GENERATED ALWAYS AS (CONCAT_WS(' ', name, repository, contact, CAST(tags AS CHAR))) STORED;
+CREATE FULLTEXT INDEX idx_pipeline_registry_search ON opsml_pipeline_registry (search_text);
+
+ALTER TABLE opsml_project_registry
+    ADD COLUMN search_text TEXT GENERATED ALWAYS AS (CONCAT_WS(' ', name, repository)) STORED;
+CREATE FULLTEXT INDEX idx_project_registry_search ON opsml_project_registry (search_text);
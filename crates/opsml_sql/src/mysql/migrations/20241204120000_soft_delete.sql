@@ -0,0 +1,27 @@
+-- Add migration script here
+-- MySQL Migration Script
+
+-- Soft delete columns for each card registry
+ALTER TABLE opsml_data_registry
+    ADD COLUMN deleted_at TIMESTAMP NULL DEFAULT NULL,
+    ADD COLUMN deleted_by VARCHAR(255);
+
+ALTER TABLE opsml_model_registry
+    ADD COLUMN deleted_at TIMESTAMP NULL DEFAULT NULL,
+    ADD COLUMN deleted_by VARCHAR(255);
+
+ALTER TABLE opsml_run_registry
+    ADD COLUMN deleted_at TIMESTAMP NULL DEFAULT NULL,
+    ADD COLUMN deleted_by VARCHAR(255);
+
+ALTER TABLE opsml_audit_registry
+    ADD COLUMN deleted_at TIMESTAMP NULL DEFAULT NULL,
+    ADD COLUMN deleted_by VARCHAR(255);
+
+ALTER TABLE opsml_pipeline_registry
+    ADD COLUMN deleted_at TIMESTAMP NULL DEFAULT NULL,
+    ADD COLUMN deleted_by VARCHAR(255);
+
+ALTER TABLE opsml_project_registry
+    ADD COLUMN deleted_at TIMESTAMP NULL DEFAULT NULL,
+    ADD COLUMN deleted_by VARCHAR(255);
@@ -0,0 +1,14 @@
+-- Add migration script here
+-- MySQL Migration Script
+
+-- CardHistorySchema
+CREATE TABLE IF NOT EXISTS opsml_card_history (
+    idx INT PRIMARY KEY AUTO_INCREMENT,
+    uid VARCHAR(64) NOT NULL,
+    registry_type VARCHAR(32) NOT NULL,
+    action VARCHAR(32) NOT NULL,
+    username VARCHAR(255) NOT NULL,
+    changes JSON NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_card_history_uid (uid)
+);
@@ -0,0 +1,29 @@
+-- Add migration script here
+-- MySQL Migration Script
+
+-- CardAliasSchema
+CREATE TABLE IF NOT EXISTS opsml_card_aliases (
+    registry_type VARCHAR(32) NOT NULL,
+    repository VARCHAR(128) NOT NULL,
+    name VARCHAR(128) NOT NULL,
+    alias VARCHAR(64) NOT NULL,
+    uid VARCHAR(64) NOT NULL,
+    updated_by VARCHAR(255) NOT NULL,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (registry_type, repository, name, alias),
+    INDEX idx_card_aliases_uid (uid)
+);
+
+-- CardAliasHistorySchema
+CREATE TABLE IF NOT EXISTS opsml_card_alias_history (
+    idx INT PRIMARY KEY AUTO_INCREMENT,
+    registry_type VARCHAR(32) NOT NULL,
+    repository VARCHAR(128) NOT NULL,
+    name VARCHAR(128) NOT NULL,
+    alias VARCHAR(64) NOT NULL,
+    previous_uid VARCHAR(64),
+    uid VARCHAR(64) NOT NULL,
+    updated_by VARCHAR(255) NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_card_alias_history_name (registry_type, repository, name)
+);
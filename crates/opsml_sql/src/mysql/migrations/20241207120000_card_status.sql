@@ -0,0 +1,27 @@
+-- Add migration script here
+-- MySQL Migration Script
+
+-- Lifecycle status for each card registry
+ALTER TABLE opsml_data_registry
+    ADD COLUMN status VARCHAR(32) NOT NULL DEFAULT 'active',
+    ADD INDEX idx_data_registry_status (status);
+
+ALTER TABLE opsml_model_registry
+    ADD COLUMN status VARCHAR(32) NOT NULL DEFAULT 'active',
+    ADD INDEX idx_model_registry_status (status);
+
+ALTER TABLE opsml_run_registry
+    ADD COLUMN status VARCHAR(32) NOT NULL DEFAULT 'active',
+    ADD INDEX idx_run_registry_status (status);
+
+ALTER TABLE opsml_audit_registry
+    ADD COLUMN status VARCHAR(32) NOT NULL DEFAULT 'active',
+    ADD INDEX idx_audit_registry_status (status);
+
+ALTER TABLE opsml_pipeline_registry
+    ADD COLUMN status VARCHAR(32) NOT NULL DEFAULT 'active',
+    ADD INDEX idx_pipeline_registry_status (status);
+
+ALTER TABLE opsml_project_registry
+    ADD COLUMN status VARCHAR(32) NOT NULL DEFAULT 'active',
+    ADD INDEX idx_project_registry_status (status);
@@ -0,0 +1,21 @@
+-- Add migration script here
+-- MySQL Migration Script
+
+-- Yanked versions are skipped by version range resolution
+ALTER TABLE opsml_data_registry
+    ADD COLUMN yanked BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE opsml_model_registry
+    ADD COLUMN yanked BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE opsml_run_registry
+    ADD COLUMN yanked BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE opsml_audit_registry
+    ADD COLUMN yanked BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE opsml_pipeline_registry
+    ADD COLUMN yanked BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE opsml_project_registry
+    ADD COLUMN yanked BOOLEAN NOT NULL DEFAULT FALSE;
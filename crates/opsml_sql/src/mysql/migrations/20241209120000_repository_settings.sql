@@ -0,0 +1,9 @@
+-- Add migration script here
+-- MySQL Migration Script
+
+-- RepositorySettingsSchema
+CREATE TABLE IF NOT EXISTS opsml_repository_settings (
+    repository VARCHAR(128) PRIMARY KEY,
+    version_scheme VARCHAR(16) NOT NULL DEFAULT 'semver',
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,52 @@
+-- Add migration script here
+-- MySQL Migration Script
+
+-- Lookup indexes for each card registry, and one live card per (repository, name, version).
+-- MySQL has no partial indexes, so the unique index is on a generated column that is NULL for
+-- cards in the trash
+ALTER TABLE opsml_data_registry
+    ADD COLUMN live_version VARCHAR(64) GENERATED ALWAYS AS (IF(deleted_at IS NULL, version, NULL)) VIRTUAL,
+    ADD INDEX idx_data_registry_name (repository, name),
+    ADD INDEX idx_data_registry_version (version),
+    ADD INDEX idx_data_registry_created_at (created_at),
+    ADD UNIQUE INDEX idx_data_registry_unique_version (repository, name, live_version);
+
+ALTER TABLE opsml_model_registry
+    ADD COLUMN live_version VARCHAR(64) GENERATED ALWAYS AS (IF(deleted_at IS NULL, version, NULL)) VIRTUAL,
+    ADD INDEX idx_model_registry_name (repository, name),
+    ADD INDEX idx_model_registry_version (version),
+    ADD INDEX idx_model_registry_created_at (created_at),
+    ADD UNIQUE INDEX idx_model_registry_unique_version (repository, name, live_version);
+
+ALTER TABLE opsml_run_registry
+    ADD COLUMN live_version VARCHAR(64) GENERATED ALWAYS AS (IF(deleted_at IS NULL, version, NULL)) VIRTUAL,
+    ADD INDEX idx_run_registry_name (repository, name),
+    ADD INDEX idx_run_registry_version (version),
+    ADD INDEX idx_run_registry_created_at (created_at),
+    ADD UNIQUE INDEX idx_run_registry_unique_version (repository, name, live_version);
+
+ALTER TABLE opsml_audit_registry
+    ADD COLUMN live_version VARCHAR(64) GENERATED ALWAYS AS (IF(deleted_at IS NULL, version, NULL)) VIRTUAL,
+    ADD INDEX idx_audit_registry_name (repository, name),
+    ADD INDEX idx_audit_registry_version (version),
+    ADD INDEX idx_audit_registry_created_at (created_at),
+    ADD UNIQUE INDEX idx_audit_registry_unique_version (repository, name, live_version);
+
+ALTER TABLE opsml_pipeline_registry
+    ADD COLUMN live_version VARCHAR(64) GENERATED ALWAYS AS (IF(deleted_at IS NULL, version, NULL)) VIRTUAL,
+    ADD INDEX idx_pipeline_registry_name (repository, name),
+    ADD INDEX idx_pipeline_registry_version (version),
+    ADD INDEX idx_pipeline_registry_created_at (created_at),
+    ADD UNIQUE INDEX idx_pipeline_registry_unique_version (repository, name, live_version);
+
+ALTER TABLE opsml_project_registry
+    ADD COLUMN live_version VARCHAR(64) GENERATED ALWAYS AS (IF(deleted_at IS NULL, version, NULL)) VIRTUAL,
+    ADD INDEX idx_project_registry_name (repository, name),
+    ADD INDEX idx_project_registry_version (version),
+    ADD INDEX idx_project_registry_created_at (created_at),
+    ADD UNIQUE INDEX idx_project_registry_unique_version (repository, name, live_version);
+
+-- Run metrics are always read by run
+CREATE INDEX idx_run_metrics_run_uid ON opsml_run_metrics (run_uid);
+CREATE INDEX idx_run_parameters_run_uid ON opsml_run_parameters (run_uid);
+CREATE INDEX idx_run_hardware_metrics_run_uid ON opsml_run_hardware_metrics (run_uid);
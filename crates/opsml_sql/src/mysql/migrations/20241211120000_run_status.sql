@@ -0,0 +1,12 @@
+-- Add migration script here
+-- MySQL Migration Script
+
+-- RunStatusSchema
+CREATE TABLE IF NOT EXISTS opsml_run_status (
+    run_uid VARCHAR(64) PRIMARY KEY,
+    status VARCHAR(16) NOT NULL DEFAULT 'running',
+    started_at DATETIME NOT NULL,
+    ended_at DATETIME,
+    heartbeat_at DATETIME NOT NULL,
+    INDEX idx_run_status_heartbeat (status, heartbeat_at)
+);
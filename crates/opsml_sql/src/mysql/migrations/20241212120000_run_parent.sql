@@ -0,0 +1,7 @@
+-- Add migration script here
+-- MySQL Migration Script
+
+-- Runs nested under a parent run, such as sweep trials or cross-validation folds
+ALTER TABLE opsml_run_registry
+    ADD COLUMN parent_uid VARCHAR(64),
+    ADD INDEX idx_run_registry_parent_uid (parent_uid);
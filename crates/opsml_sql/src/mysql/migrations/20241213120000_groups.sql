@@ -0,0 +1,19 @@
+-- Add migration script here
+-- MySQL Migration Script
+
+-- GroupSchema
+CREATE TABLE IF NOT EXISTS opsml_groups (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    permissions JSON NOT NULL
+);
+
+-- GroupMemberSchema
+CREATE TABLE IF NOT EXISTS opsml_group_members (
+    group_name VARCHAR(255) NOT NULL,
+    username VARCHAR(255) NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (group_name, username),
+    INDEX idx_group_members_username (username)
+);
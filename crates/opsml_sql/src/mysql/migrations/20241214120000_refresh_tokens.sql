@@ -0,0 +1,11 @@
+-- Add migration script here
+-- MySQL Migration Script
+
+-- RefreshTokenSchema
+CREATE TABLE IF NOT EXISTS opsml_refresh_tokens (
+    family VARCHAR(64) PRIMARY KEY,
+    username VARCHAR(255) NOT NULL,
+    token_hash TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_refresh_tokens_username (username)
+);
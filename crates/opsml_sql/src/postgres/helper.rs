@@ -1,55 +1,68 @@
+use crate::base::{add_version_bounds, get_registry_type, CARD_REGISTRIES};
 use opsml_error::error::SqlError;
 
 /// this file contains helper logic for generating sql queries across different databases
-use opsml_types::{CardQueryArgs, CardSQLTableNames};
-use opsml_utils::semver::VersionParser;
+use opsml_types::{
+    CardQueryArgs, CardSQLTableNames, GetMetricRequest, RunQueryRequest, TagFilter, TagOperator,
+    VersionScheme,
+};
 use opsml_utils::utils::is_valid_uuid4;
 
-pub fn add_version_bounds(builder: &mut String, version: &str) -> Result<(), SqlError> {
-    let version_bounds = VersionParser::get_version_to_search(version)
-        .map_err(|e| SqlError::VersionError(format!("{}", e)))?;
-
-    // construct lower bound (already validated)
-    builder.push_str(
-        format!(
-            " AND (major >= {} AND minor >= {} and patch >= {})",
-            version_bounds.lower_bound.major,
-            version_bounds.lower_bound.minor,
-            version_bounds.lower_bound.patch
-        )
-        .as_str(),
-    );
-
-    if !version_bounds.no_upper_bound {
-        // construct upper bound based on number of components
-        if version_bounds.num_parts == 1 {
-            builder
-                .push_str(format!(" AND (major < {})", version_bounds.upper_bound.major).as_str());
-        } else if version_bounds.num_parts == 2
-            || version_bounds.num_parts == 3 && version_bounds.parser_type == VersionParser::Tilde
-            || version_bounds.num_parts == 3 && version_bounds.parser_type == VersionParser::Caret
-        {
-            builder.push_str(
-                format!(
-                    " AND (major = {} AND minor < {})",
-                    version_bounds.upper_bound.major, version_bounds.upper_bound.minor
-                )
-                .as_str(),
-            );
-        } else {
-            builder.push_str(
-                format!(
-                    " AND (major = {} AND minor = {} AND patch < {})",
-                    version_bounds.upper_bound.major,
-                    version_bounds.upper_bound.minor,
-                    version_bounds.upper_bound.patch
-                )
-                .as_str(),
-            );
+/// Add tag filters to a query. Keys and values are bound as numbered parameters and
+/// compared against the text value of the key in the tags jsonb column.
+///
+/// # Arguments
+///
+/// * `builder` - The query to append to
+/// * `bindings` - The bindings for the query, in parameter order
+/// * `filters` - The tag filters to apply
+/// * `start_idx` - The index of the first numbered parameter to use
+pub fn add_tag_filters(
+    builder: &mut String,
+    bindings: &mut Vec<String>,
+    filters: &[TagFilter],
+    start_idx: usize,
+) -> Result<(), SqlError> {
+    let mut idx = start_idx;
+
+    for filter in filters {
+        filter
+            .validate()
+            .map_err(|e| SqlError::GeneralError(e.to_string()))?;
+
+        let key_idx = idx;
+        bindings.push(filter.key.clone());
+        idx += 1;
+
+        let clause = match filter.operator {
+            TagOperator::Eq => format!(" AND tags->>${} = ${}", key_idx, idx),
+            TagOperator::Ne => format!(
+                " AND (tags->>${0} IS NULL OR tags->>${0} <> ${1})",
+                key_idx, idx
+            ),
+            TagOperator::Exists => format!(" AND tags->>${} IS NOT NULL", key_idx),
+            TagOperator::NotExists => format!(" AND tags->>${} IS NULL", key_idx),
+            TagOperator::Prefix => format!(" AND starts_with(tags->>${}, ${})", key_idx, idx),
+            TagOperator::In => {
+                let params = (idx..idx + filter.values.len())
+                    .map(|i| format!("${}", i))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                format!(" AND tags->>${} IN ({})", key_idx, params)
+            }
+        };
+
+        for value in &filter.values {
+            bindings.push(value.clone());
+            idx += 1;
         }
+
+        builder.push_str(clause.as_str());
     }
+
     Ok(())
 }
+
 pub struct PostgresQueryHelper;
 
 impl PostgresQueryHelper {
@@ -59,7 +72,7 @@ impl PostgresQueryHelper {
 
     pub fn get_user_insert_query() -> String {
         format!(
-            "INSERT INTO {} (username, password_hash, permissions, group_permissions) VALUES ($1, $2, $3, $4)",
+            "INSERT INTO {} (username, password_hash, permissions, group_permissions, created_at) VALUES ($1, $2, $3, $4, COALESCE($5, CURRENT_TIMESTAMP))",
             CardSQLTableNames::Users
         )
         .to_string()
@@ -73,6 +86,14 @@ impl PostgresQueryHelper {
         .to_string()
     }
 
+    pub fn get_users_query() -> String {
+        format!(
+            "SELECT id, created_at, active, username, password_hash, permissions, group_permissions, refresh_token FROM {} ORDER BY id",
+            CardSQLTableNames::Users
+        )
+        .to_string()
+    }
+
     pub fn get_user_update_query() -> String {
         format!(
             "UPDATE {} SET 
@@ -87,6 +108,128 @@ impl PostgresQueryHelper {
         .to_string()
     }
 
+    pub fn get_refresh_token_insert_query() -> String {
+        format!(
+            "INSERT INTO {} (family, username, token_hash) VALUES ($1, $2, $3)",
+            CardSQLTableNames::RefreshTokens
+        )
+    }
+
+    pub fn get_refresh_token_query() -> String {
+        format!(
+            "SELECT family, username, token_hash, created_at FROM {} WHERE family = $1",
+            CardSQLTableNames::RefreshTokens
+        )
+    }
+
+    pub fn get_refresh_token_rotate_query() -> String {
+        format!(
+            "UPDATE {} SET token_hash = $1 WHERE family = $2 AND token_hash = $3",
+            CardSQLTableNames::RefreshTokens
+        )
+    }
+
+    pub fn get_refresh_token_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE family = $1",
+            CardSQLTableNames::RefreshTokens
+        )
+    }
+
+    pub fn get_user_refresh_tokens_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE username = $1",
+            CardSQLTableNames::RefreshTokens
+        )
+    }
+
+    pub fn get_user_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE username = $1",
+            CardSQLTableNames::Users
+        )
+    }
+
+    pub fn get_user_memberships_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE username = $1",
+            CardSQLTableNames::GroupMembers
+        )
+    }
+
+    pub fn get_group_insert_query() -> String {
+        format!(
+            "INSERT INTO {} (name, permissions, created_at) VALUES ($1, $2, COALESCE($3, CURRENT_TIMESTAMP))",
+            CardSQLTableNames::Groups
+        )
+    }
+
+    pub fn get_group_query() -> String {
+        format!(
+            "SELECT id, created_at, name, permissions FROM {} WHERE name = $1",
+            CardSQLTableNames::Groups
+        )
+    }
+
+    pub fn get_groups_query() -> String {
+        format!(
+            "SELECT id, created_at, name, permissions FROM {} ORDER BY name",
+            CardSQLTableNames::Groups
+        )
+    }
+
+    pub fn get_group_update_query() -> String {
+        format!(
+            "UPDATE {} SET permissions = $1 WHERE name = $2",
+            CardSQLTableNames::Groups
+        )
+    }
+
+    pub fn get_group_delete_query() -> String {
+        format!("DELETE FROM {} WHERE name = $1", CardSQLTableNames::Groups)
+    }
+
+    pub fn get_group_members_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE group_name = $1",
+            CardSQLTableNames::GroupMembers
+        )
+    }
+
+    /// Adding an existing member is a no-op
+    pub fn get_group_member_insert_query() -> String {
+        format!(
+            "INSERT INTO {} (group_name, username) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            CardSQLTableNames::GroupMembers
+        )
+    }
+
+    pub fn get_group_member_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE group_name = $1 AND username = $2",
+            CardSQLTableNames::GroupMembers
+        )
+    }
+
+    pub fn get_group_members_query() -> String {
+        format!(
+            "SELECT username FROM {} WHERE group_name = $1 ORDER BY username",
+            CardSQLTableNames::GroupMembers
+        )
+    }
+
+    pub fn get_user_groups_query() -> String {
+        format!(
+            "SELECT g.id, g.created_at, g.name, g.permissions
+            FROM {} g
+            INNER JOIN {} m ON m.group_name = g.name
+            WHERE m.username = $1
+            ORDER BY g.name",
+            CardSQLTableNames::Groups,
+            CardSQLTableNames::GroupMembers
+        )
+    }
+
     pub fn get_hardware_metric_query() -> String {
         let query = format!(
             "SELECT * FROM {} WHERE run_uid = $1",
@@ -102,8 +245,9 @@ impl PostgresQueryHelper {
                 name, 
                 value,
                 step,
-                timestamp
-            ) VALUES ($1, $2, $3, $4, $5)",
+                timestamp,
+                created_at
+            ) VALUES ($1, $2, $3, $4, $5, COALESCE($6, CURRENT_TIMESTAMP))",
             CardSQLTableNames::Metrics
         )
         .to_string()
@@ -116,7 +260,8 @@ impl PostgresQueryHelper {
                 name, 
                 value,
                 step,
-                timestamp
+                timestamp,
+                created_at
             ) VALUES ",
             CardSQLTableNames::Metrics
         );
@@ -126,12 +271,13 @@ impl PostgresQueryHelper {
                 query.push_str(", ");
             }
             query.push_str(&format!(
-                "(${}, ${}, ${}, ${}, ${})",
-                5 * i + 1,
-                5 * i + 2,
-                5 * i + 3,
-                5 * i + 4,
-                5 * i + 5
+                "(${}, ${}, ${}, ${}, ${}, COALESCE(${}, CURRENT_TIMESTAMP))",
+                6 * i + 1,
+                6 * i + 2,
+                6 * i + 3,
+                6 * i + 4,
+                6 * i + 5,
+                6 * i + 6
             ));
         }
 
@@ -164,6 +310,278 @@ impl PostgresQueryHelper {
         (query, bindings)
     }
 
+    /// Metric rows for a run, filtered by name and step range. Names are returned as bindings;
+    /// `start_step` and `end_step` placeholders follow them when set
+    fn get_run_metric_filter_query(args: &GetMetricRequest) -> (String, Vec<String>) {
+        let mut query = format!(
+            "SELECT run_uid, name, value, step, timestamp, created_at, idx
+            FROM {}
+            WHERE run_uid = $1",
+            CardSQLTableNames::Metrics
+        );
+
+        let mut bindings: Vec<String> = Vec::new();
+        let mut param_index = 2;
+
+        if !args.names.is_empty() {
+            let placeholders: Vec<String> = args
+                .names
+                .iter()
+                .map(|name| {
+                    bindings.push(name.to_string());
+                    param_index += 1;
+                    format!("${}", param_index - 1)
+                })
+                .collect();
+            query.push_str(&format!(" AND name IN ({})", placeholders.join(", ")));
+        }
+
+        if args.start_step.is_some() {
+            query.push_str(&format!(" AND step >= ${}", param_index));
+            param_index += 1;
+        }
+
+        if args.end_step.is_some() {
+            query.push_str(&format!(" AND step <= ${}", param_index));
+        }
+
+        (query, bindings)
+    }
+
+    /// Metric points for a run. When `max_points` is set, each metric is split into
+    /// `max_points / 2` buckets of consecutive steps and only the minimum and maximum point of
+    /// every bucket is kept, which preserves spikes while bounding the number of points
+    pub fn get_run_metric_points_query(args: &GetMetricRequest) -> (String, Vec<String>) {
+        let (filtered, bindings) = Self::get_run_metric_filter_query(args);
+
+        let query = match args.max_points {
+            Some(max_points) => {
+                let buckets = (max_points / 2).max(1);
+                format!(
+                    "WITH filtered AS ({}),
+                    bucketed AS (
+                        SELECT *, NTILE({}) OVER (PARTITION BY name ORDER BY step, idx) AS bucket
+                        FROM filtered
+                    ),
+                    ranked AS (
+                        SELECT *,
+                            ROW_NUMBER() OVER (PARTITION BY name, bucket ORDER BY value ASC, step, idx) AS min_rank,
+                            ROW_NUMBER() OVER (PARTITION BY name, bucket ORDER BY value DESC, step, idx) AS max_rank
+                        FROM bucketed
+                    )
+                    SELECT run_uid, name, value, step, timestamp, created_at, idx
+                    FROM ranked
+                    WHERE min_rank = 1 OR max_rank = 1
+                    ORDER BY name, step, idx",
+                    filtered, buckets
+                )
+            }
+            None => format!("{} ORDER BY name, step, idx", filtered),
+        };
+
+        (query, bindings)
+    }
+
+    pub fn get_run_metric_summary_query(args: &GetMetricRequest) -> (String, Vec<String>) {
+        let (filtered, bindings) = Self::get_run_metric_filter_query(args);
+
+        let query = format!(
+            "WITH filtered AS ({}),
+            ranked AS (
+                SELECT name, value, step,
+                    ROW_NUMBER() OVER (PARTITION BY name ORDER BY step DESC NULLS LAST, idx DESC) AS last_rank
+                FROM filtered
+            )
+            SELECT
+                name,
+                COUNT(*) AS count,
+                MIN(value) AS min_value,
+                MAX(value) AS max_value,
+                AVG(value) AS mean_value,
+                MAX(CASE WHEN last_rank = 1 THEN value END) AS last_value,
+                MAX(step) AS last_step
+            FROM ranked
+            GROUP BY name
+            ORDER BY name",
+            filtered
+        );
+
+        (query, bindings)
+    }
+
+    /// Rows of a run table for many runs, optionally restricted to a set of names
+    fn get_runs_query(
+        table: CardSQLTableNames,
+        uids: &[String],
+        names: &[String],
+        order_by: &str,
+    ) -> (String, Vec<String>) {
+        let mut bindings: Vec<String> = uids.to_vec();
+        let uid_placeholders: Vec<String> = (1..=uids.len()).map(|i| format!("${}", i)).collect();
+
+        let mut query = format!(
+            "SELECT * FROM {} WHERE run_uid IN ({})",
+            table,
+            uid_placeholders.join(", ")
+        );
+
+        if !names.is_empty() {
+            let name_placeholders: Vec<String> = (uids.len() + 1..=uids.len() + names.len())
+                .map(|i| format!("${}", i))
+                .collect();
+            query.push_str(&format!(" AND name IN ({})", name_placeholders.join(", ")));
+            bindings.extend(names.iter().cloned());
+        }
+
+        query.push_str(&format!(" ORDER BY {}", order_by));
+
+        (query, bindings)
+    }
+
+    pub fn get_runs_metric_query(uids: &[String], names: &[String]) -> (String, Vec<String>) {
+        Self::get_runs_query(
+            CardSQLTableNames::Metrics,
+            uids,
+            names,
+            "run_uid, name, step, idx",
+        )
+    }
+
+    pub fn get_runs_parameter_query(uids: &[String], names: &[String]) -> (String, Vec<String>) {
+        Self::get_runs_query(
+            CardSQLTableNames::Parameters,
+            uids,
+            names,
+            "run_uid, name, idx",
+        )
+    }
+
+    /// Search runs by parameter values and metric thresholds. Metrics are reduced to their
+    /// last, min and max value per run before filtering and sorting. The limit is the final
+    /// placeholder and is bound by the caller
+    pub fn get_run_query(args: &RunQueryRequest) -> (String, Vec<String>) {
+        let mut param_index = 0;
+        let mut next_param = || {
+            param_index += 1;
+            format!("${}", param_index)
+        };
+        let mut bindings: Vec<String> = Vec::new();
+        let metric_names = args.metric_names();
+
+        let mut query = String::new();
+        if !metric_names.is_empty() {
+            let placeholders: Vec<String> = metric_names.iter().map(|_| next_param()).collect();
+            bindings.extend(metric_names.iter().cloned());
+
+            query.push_str(&format!(
+                "WITH ranked_metrics AS (
+                    SELECT run_uid, name, value,
+                        ROW_NUMBER() OVER (PARTITION BY run_uid, name ORDER BY step DESC NULLS LAST, idx DESC) AS last_rank
+                    FROM {}
+                    WHERE name IN ({})
+                ),
+                metric_values AS (
+                    SELECT run_uid, name,
+                        MIN(value) AS min_value,
+                        MAX(value) AS max_value,
+                        MAX(CASE WHEN last_rank = 1 THEN value END) AS last_value
+                    FROM ranked_metrics
+                    GROUP BY run_uid, name
+                ) ",
+                CardSQLTableNames::Metrics,
+                placeholders.join(", ")
+            ));
+        }
+
+        let sort_column = args
+            .sort_by
+            .as_ref()
+            .map(|sort| format!("sort_metric.{}", sort.aggregation.column()));
+
+        query.push_str(&format!(
+            "SELECT run_card.uid, run_card.name, run_card.repository, run_card.version, run_card.project, run_card.created_at, {} AS sort_value
+            FROM {} run_card",
+            sort_column
+                .clone()
+                .unwrap_or_else(|| "CAST(NULL AS DOUBLE PRECISION)".to_string()),
+            CardSQLTableNames::Run
+        ));
+
+        if let Some(sort) = &args.sort_by {
+            query.push_str(&format!(
+                " LEFT JOIN metric_values sort_metric ON sort_metric.run_uid = run_card.uid AND sort_metric.name = {}",
+                next_param()
+            ));
+            bindings.push(sort.name.clone());
+        }
+
+        query.push_str(" WHERE run_card.deleted_at IS NULL");
+
+        if let Some(project) = &args.project {
+            query.push_str(&format!(" AND run_card.project = {}", next_param()));
+            bindings.push(project.clone());
+        }
+
+        if let Some(repository) = &args.repository {
+            query.push_str(&format!(" AND run_card.repository = {}", next_param()));
+            bindings.push(repository.clone());
+        }
+
+        for filter in &args.parameters {
+            let name_param = next_param();
+            let value_param = next_param();
+            let comparison = if filter.value.parse::<f64>().is_ok() {
+                format!(
+                    "CASE WHEN parameter.value ~ '^[-+]?([0-9]+[.]?[0-9]*|[.][0-9]+)([eE][-+]?[0-9]+)?$' THEN CAST(parameter.value AS DOUBLE PRECISION) END {} CAST({} AS DOUBLE PRECISION)",
+                    filter.operator.as_sql(),
+                    value_param
+                )
+            } else {
+                format!(
+                    "parameter.value {} {}",
+                    filter.operator.as_sql(),
+                    value_param
+                )
+            };
+
+            query.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM {} parameter WHERE parameter.run_uid = run_card.uid AND parameter.name = {} AND {})",
+                CardSQLTableNames::Parameters,
+                name_param,
+                comparison
+            ));
+            bindings.push(filter.name.clone());
+            bindings.push(filter.value.clone());
+        }
+
+        for filter in &args.metrics {
+            query.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM metric_values metric WHERE metric.run_uid = run_card.uid AND metric.name = {} AND metric.{} {} CAST({} AS DOUBLE PRECISION))",
+                next_param(),
+                filter.aggregation.column(),
+                filter.operator.as_sql(),
+                next_param()
+            ));
+            bindings.push(filter.name.clone());
+            bindings.push(filter.value.to_string());
+        }
+
+        match (&args.sort_by, sort_column) {
+            (Some(sort), Some(column)) => {
+                let direction = if sort.descending { "DESC" } else { "ASC" };
+                query.push_str(&format!(
+                    " ORDER BY ({} IS NULL), {} {}, run_card.created_at DESC",
+                    column, column, direction
+                ));
+            }
+            _ => query.push_str(" ORDER BY run_card.created_at DESC"),
+        }
+
+        query.push_str(&format!(" LIMIT {}", next_param()));
+
+        (query, bindings)
+    }
+
     pub fn get_project_id_query() -> String {
         format!(
             "WITH max_project AS (
@@ -178,17 +596,26 @@ impl PostgresQueryHelper {
         )
         .to_string()
     }
-    pub fn get_query_page_query(table: &CardSQLTableNames, sort_by: &str) -> String {
+    /// Query for a page of card summaries. Pages are selected either by row number
+    /// (offset paging) or by a keyset cursor over (updated_at, repository, name)
+    pub fn get_query_page_query(
+        table: &CardSQLTableNames,
+        sort_by: &str,
+        use_cursor: bool,
+    ) -> String {
         let versions_cte = format!(
             "WITH versions AS (
                 SELECT 
                     repository, 
                     name, 
                     version, 
+                    status,
                     ROW_NUMBER() OVER (PARTITION BY repository, name ORDER BY created_at DESC) AS row_num 
                 FROM {}
                 WHERE ($1 IS NULL OR repository = $1)
                 AND ($2 IS NULL OR name LIKE $3 OR repository LIKE $3)
+                AND ($4 IS NULL OR status = $4)
+                AND deleted_at IS NULL
             )", table
         );
 
@@ -203,6 +630,8 @@ impl PostgresQueryHelper {
                 FROM {}
                 WHERE ($1 IS NULL OR repository = $1)
                 AND ($2 IS NULL OR name LIKE $3 OR repository LIKE $3)
+                AND ($4 IS NULL OR status = $4)
+                AND deleted_at IS NULL
                 GROUP BY repository, name
             )",
             table
@@ -213,6 +642,7 @@ impl PostgresQueryHelper {
                     repository, 
                     name, 
                     version, 
+                    status,
                     row_num
                 FROM versions 
                 WHERE row_num = 1
@@ -224,6 +654,7 @@ impl PostgresQueryHelper {
                     stats.repository, 
                     stats.name, 
                     filtered_versions.version, 
+                    filtered_versions.status,
                     stats.versions, 
                     stats.updated_at, 
                     stats.created_at, 
@@ -236,12 +667,20 @@ impl PostgresQueryHelper {
             sort_by
         );
 
+        let page_filter = if use_cursor {
+            "WHERE ($5 IS NULL OR updated_at < $5 OR (updated_at = $5 AND (repository > $6 OR (repository = $6 AND name > $7))))
+            ORDER BY updated_at DESC, repository, name
+            LIMIT $8"
+        } else {
+            "WHERE row_num BETWEEN $5 AND $6
+            ORDER BY updated_at DESC"
+        };
+
         let combined_query = format!(
             "{}{}{}{} 
             SELECT * FROM joined 
-            WHERE row_num BETWEEN $4 AND $5
-            ORDER BY updated_at DESC",
-            versions_cte, stats_cte, filtered_versions_cte, joined_cte
+            {}",
+            versions_cte, stats_cte, filtered_versions_cte, joined_cte, page_filter
         );
 
         combined_query
@@ -254,16 +693,374 @@ impl PostgresQueryHelper {
             COALESCE(CAST(COUNT(DISTINCT repository) AS INTEGER), 0) AS nbr_repositories 
             FROM {}
             WHERE 1=1
-            AND ($1 IS NULL OR name LIKE $1 OR repository LIKE $1)",
+            AND ($1 IS NULL OR name LIKE $1 OR repository LIKE $1)
+            AND deleted_at IS NULL",
             table
         );
 
         base_query
     }
+    /// Full-text search over the generated search_vector column of each registry
+    pub fn get_search_cards_query() -> String {
+        let registries = CARD_REGISTRIES
+            .iter()
+            .map(|(registry_type, table)| {
+                format!(
+                    "SELECT
+                        '{}'::text AS registry_type,
+                        uid,
+                        name,
+                        repository,
+                        version,
+                        created_at,
+                        ts_rank(search_vector, to_tsquery('simple', $1))::float8 AS score
+                    FROM {}
+                    WHERE search_vector @@ to_tsquery('simple', $1)
+                    AND deleted_at IS NULL",
+                    registry_type, table
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(" UNION ALL ");
+
+        format!(
+            "{}
+            ORDER BY score DESC, created_at DESC
+            LIMIT $2",
+            registries
+        )
+    }
+    pub fn get_card_history_insert_query() -> String {
+        format!(
+            "INSERT INTO {} (uid, registry_type, action, username, changes, created_at) VALUES ($1, $2, $3, $4, $5, $6)",
+            CardSQLTableNames::History
+        )
+    }
+
+    pub fn get_card_history_query() -> String {
+        format!(
+            "SELECT uid, registry_type, action, username, changes, created_at FROM {} WHERE uid = $1 ORDER BY idx ASC",
+            CardSQLTableNames::History
+        )
+    }
+
+    pub fn get_card_alias_query() -> String {
+        format!(
+            "SELECT registry_type, repository, name, alias, uid, updated_by, updated_at FROM {} WHERE registry_type = $1 AND repository = $2 AND name = $3 AND alias = $4",
+            CardSQLTableNames::Aliases
+        )
+    }
+
+    pub fn get_card_aliases_query() -> String {
+        format!(
+            "SELECT registry_type, repository, name, alias, uid, updated_by, updated_at FROM {} WHERE registry_type = $1 AND repository = $2 AND name = $3 ORDER BY alias",
+            CardSQLTableNames::Aliases
+        )
+    }
+
+    pub fn get_card_alias_upsert_query() -> String {
+        format!(
+            "INSERT INTO {} (registry_type, repository, name, alias, uid, updated_by, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7) ON CONFLICT (registry_type, repository, name, alias) DO UPDATE SET uid = EXCLUDED.uid, updated_by = EXCLUDED.updated_by, updated_at = EXCLUDED.updated_at",
+            CardSQLTableNames::Aliases
+        )
+    }
+
+    pub fn get_card_alias_history_insert_query() -> String {
+        format!(
+            "INSERT INTO {} (registry_type, repository, name, alias, previous_uid, uid, updated_by, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            CardSQLTableNames::AliasHistory
+        )
+    }
+
+    pub fn get_card_alias_history_query() -> String {
+        format!(
+            "SELECT registry_type, repository, name, alias, previous_uid, uid, updated_by, created_at FROM {} WHERE registry_type = $1 AND repository = $2 AND name = $3 ORDER BY idx ASC",
+            CardSQLTableNames::AliasHistory
+        )
+    }
+
+    pub fn get_lineage_delete_query() -> String {
+        format!(
+            "DELETE FROM {} WHERE declared_by = $1",
+            CardSQLTableNames::Lineage
+        )
+    }
+
+    pub fn get_lineage_insert_query(nbr_records: usize) -> String {
+        let mut query = format!(
+            "INSERT INTO {} (
+                upstream_uid,
+                upstream_type,
+                downstream_uid,
+                downstream_type,
+                declared_by
+            ) VALUES ",
+            CardSQLTableNames::Lineage
+        );
+
+        for i in 0..nbr_records {
+            if i > 0 {
+                query.push_str(", ");
+            }
+            query.push_str(&format!(
+                "(${}, ${}, ${}, ${}, ${})",
+                5 * i + 1,
+                5 * i + 2,
+                5 * i + 3,
+                5 * i + 4,
+                5 * i + 5
+            ));
+        }
+
+        query.push_str(" ON CONFLICT DO NOTHING");
+
+        query
+    }
+
+    /// Recursive lineage traversal up to a max depth. Edges reachable through more than one
+    /// path are reported once at their shallowest depth. Each path tracks the uids it has
+    /// visited and stops expanding once it closes a cycle
+    pub fn get_lineage_query(upstream: bool) -> String {
+        // upstream walks from downstream_uid to upstream_uid, downstream the reverse
+        let (start_col, next_col) = if upstream {
+            ("downstream_uid", "upstream_uid")
+        } else {
+            ("upstream_uid", "downstream_uid")
+        };
+        let table = CardSQLTableNames::Lineage;
+
+        format!(
+            "WITH RECURSIVE lineage (upstream_uid, upstream_type, downstream_uid, downstream_type, depth, visited, is_cycle) AS (
+                SELECT upstream_uid, upstream_type, downstream_uid, downstream_type, 1::bigint, ARRAY[{start_col}::text, {next_col}::text], false
+                FROM {table}
+                WHERE {start_col} = $1
+                UNION
+                SELECT edge.upstream_uid, edge.upstream_type, edge.downstream_uid, edge.downstream_type, lineage.depth + 1,
+                    lineage.visited || edge.{next_col}::text,
+                    edge.{next_col}::text = ANY(lineage.visited)
+                FROM {table} AS edge
+                INNER JOIN lineage ON edge.{start_col} = lineage.{next_col}
+                WHERE lineage.depth < $2 AND NOT lineage.is_cycle
+            )
+            SELECT upstream_uid, upstream_type, downstream_uid, downstream_type, MIN(depth) AS depth
+            FROM lineage
+            GROUP BY upstream_uid, upstream_type, downstream_uid, downstream_type
+            ORDER BY depth, upstream_uid, downstream_uid"
+        )
+    }
+
+    /// Node details for (registry_type, uid) pairs. Each uid is only looked up in its own registry
+    pub fn get_lineage_nodes_query(nodes: &[(String, String)]) -> (String, Vec<String>) {
+        let mut bindings: Vec<String> = Vec::new();
+
+        let query = CARD_REGISTRIES
+            .iter()
+            .filter_map(|(registry_type, table)| {
+                let uids: Vec<String> = nodes
+                    .iter()
+                    .filter(|(node_type, _)| node_type == registry_type)
+                    .map(|(_, uid)| uid.clone())
+                    .collect();
+
+                if uids.is_empty() {
+                    return None;
+                }
+
+                let placeholders = (bindings.len() + 1..=bindings.len() + uids.len())
+                    .map(|i| format!("${}", i))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                bindings.extend(uids);
+
+                Some(format!(
+                    "SELECT '{}'::text AS registry_type, uid, name, repository, version FROM {} WHERE uid IN ({})",
+                    registry_type, table, placeholders
+                ))
+            })
+            .collect::<Vec<String>>()
+            .join(" UNION ALL ");
+
+        (query, bindings)
+    }
+
+    pub fn get_card_delete_query(table: &CardSQLTableNames) -> String {
+        format!(
+            "UPDATE {}
+            SET deleted_at = $1, deleted_by = $2
+            WHERE uid = $3
+            AND deleted_at IS NULL",
+            table
+        )
+    }
+
+    pub fn get_card_restore_query(table: &CardSQLTableNames) -> String {
+        format!(
+            "UPDATE {} SET deleted_at = NULL, deleted_by = NULL WHERE uid = $1",
+            table
+        )
+    }
+
+    pub fn get_card_purge_query(table: &CardSQLTableNames) -> String {
+        format!("DELETE FROM {} WHERE uid = $1", table)
+    }
+
+    pub fn get_card_status_query(table: &CardSQLTableNames) -> String {
+        format!("SELECT status FROM {} WHERE uid = $1", table)
+    }
+
+    pub fn get_card_status_update_query(table: &CardSQLTableNames) -> String {
+        format!(
+            "UPDATE {} SET status = $1 WHERE uid = $2 AND status = $3",
+            table
+        )
+    }
+
+    pub fn get_card_yank_query(table: &CardSQLTableNames) -> String {
+        format!("UPDATE {} SET yanked = $1 WHERE uid = $2", table)
+    }
+
+    pub fn get_version_scheme_query() -> String {
+        format!(
+            "SELECT version_scheme FROM {} WHERE repository = $1",
+            CardSQLTableNames::RepositorySettings
+        )
+    }
+
+    pub fn get_version_scheme_upsert_query() -> String {
+        format!(
+            "INSERT INTO {} (repository, version_scheme) VALUES ($1, $2) ON CONFLICT (repository) DO UPDATE SET version_scheme = EXCLUDED.version_scheme, updated_at = CURRENT_TIMESTAMP",
+            CardSQLTableNames::RepositorySettings
+        )
+    }
+
+    pub fn get_child_runs_query() -> String {
+        format!(
+            "SELECT * FROM {} WHERE parent_uid = $1 AND deleted_at IS NULL ORDER BY created_at, uid",
+            CardSQLTableNames::Run
+        )
+    }
+
+    /// Summarize the last value each child run logged per metric
+    pub fn get_child_metric_summary_query() -> String {
+        format!(
+            "WITH children AS (
+                SELECT uid FROM {} WHERE parent_uid = $1 AND deleted_at IS NULL
+            ),
+            last_values AS (
+                SELECT metrics.run_uid, metrics.name, metrics.value,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY metrics.run_uid, metrics.name
+                        ORDER BY metrics.step DESC, metrics.idx DESC
+                    ) AS last_rank
+                FROM {} metrics
+                INNER JOIN children ON metrics.run_uid = children.uid
+            ),
+            ranked AS (
+                SELECT run_uid, name, value,
+                    ROW_NUMBER() OVER (PARTITION BY name ORDER BY value ASC, run_uid) AS min_rank,
+                    ROW_NUMBER() OVER (PARTITION BY name ORDER BY value DESC, run_uid) AS max_rank
+                FROM last_values
+                WHERE last_rank = 1
+            )
+            SELECT
+                name,
+                COUNT(*) AS run_count,
+                MIN(value) AS min_value,
+                MAX(CASE WHEN min_rank = 1 THEN run_uid END) AS min_run_uid,
+                MAX(value) AS max_value,
+                MAX(CASE WHEN max_rank = 1 THEN run_uid END) AS max_run_uid,
+                AVG(value) AS mean_value
+            FROM ranked
+            GROUP BY name
+            ORDER BY name",
+            CardSQLTableNames::Run,
+            CardSQLTableNames::Metrics
+        )
+    }
+
+    pub fn get_run_status_query() -> String {
+        format!(
+            "SELECT run_uid, status, started_at, ended_at, heartbeat_at FROM {} WHERE run_uid = $1",
+            CardSQLTableNames::RunStatus
+        )
+    }
+
+    pub fn get_run_start_query() -> String {
+        format!(
+            "INSERT INTO {} (run_uid, status, started_at, ended_at, heartbeat_at) VALUES ($1, $2, $3, $4, $5)",
+            CardSQLTableNames::RunStatus
+        )
+    }
+
+    pub fn get_run_status_update_query() -> String {
+        format!(
+            "UPDATE {} SET status = $1, ended_at = $2, heartbeat_at = $3 WHERE run_uid = $4 AND status = $5",
+            CardSQLTableNames::RunStatus
+        )
+    }
+
+    /// Runs that missed their heartbeat end at the last heartbeat they sent
+    pub fn get_crashed_runs_update_query() -> String {
+        format!(
+            "UPDATE {} SET status = $1, ended_at = heartbeat_at WHERE status = $2 AND heartbeat_at < $3",
+            CardSQLTableNames::RunStatus
+        )
+    }
+
+    pub fn get_deleted_cards_query(table: &CardSQLTableNames) -> String {
+        format!(
+            "SELECT uid, name, repository, version, deleted_at, deleted_by
+            FROM {}
+            WHERE deleted_at IS NOT NULL
+                AND ($1 IS NULL OR deleted_at < $1)
+                AND ($2 IS NULL OR uid = $2)
+            ORDER BY deleted_at DESC",
+            table
+        )
+    }
+
+    pub fn get_card_version_lock_query() -> String {
+        "SELECT pg_advisory_xact_lock(hashtext($1))".to_string()
+    }
+
+    pub fn get_migration_applied_query() -> String {
+        "SELECT COUNT(*) FROM _sqlx_migrations WHERE version = $1 AND success".to_string()
+    }
+
+    pub fn get_duplicate_versions_query(table: &CardSQLTableNames, live_only: bool) -> String {
+        format!(
+            "SELECT repository, name, version, COUNT(*)
+            FROM {}
+            WHERE repository IS NOT NULL
+                AND name IS NOT NULL
+                AND version IS NOT NULL{}
+            GROUP BY repository, name, version
+            HAVING COUNT(*) > 1",
+            table,
+            if live_only {
+                " AND deleted_at IS NULL"
+            } else {
+                ""
+            }
+        )
+    }
+
+    pub fn get_active_version_count_query(table: &CardSQLTableNames) -> String {
+        format!(
+            "SELECT COUNT(*)
+            FROM {}
+            WHERE name = $1
+            AND repository = $2
+            AND version = $3
+            AND deleted_at IS NULL",
+            table
+        )
+    }
+
     pub fn get_versions_query(
         table: &CardSQLTableNames,
         version: Option<&str>,
-    ) -> Result<String, SqlError> {
+    ) -> Result<(String, Vec<String>), SqlError> {
         let mut query = format!(
             "
             SELECT
@@ -280,23 +1077,34 @@ impl PostgresQueryHelper {
              WHERE 1=1
                 AND name = $1
                 AND repository = $2
+                AND deleted_at IS NULL
             ",
             table
         );
 
+        let mut bindings: Vec<String> = Vec::new();
+
+        // yanked versions are still taken, so new versions are allocated past them
         if let Some(version) = version {
-            add_version_bounds(&mut query, version)?;
+            add_version_bounds(
+                &mut query,
+                &mut bindings,
+                |n| format!("${}", 2 + n),
+                version,
+                &VersionScheme::SemVer,
+                true,
+            )?;
         }
 
         query.push_str(" ORDER BY created_at DESC LIMIT 20;");
 
-        Ok(query)
+        Ok((query, bindings))
     }
 
     pub fn get_query_cards_query(
         table: &CardSQLTableNames,
         query_args: &CardQueryArgs,
-    ) -> Result<String, SqlError> {
+    ) -> Result<(String, Vec<String>), SqlError> {
         let mut query = format!(
             "
         SELECT * FROM {}
@@ -305,31 +1113,72 @@ impl PostgresQueryHelper {
         AND ($2 IS NULL OR name = $2)
         AND ($3 IS NULL OR repository = $3)
         AND ($4 IS NULL OR created_at <= TO_DATE($4, 'YYYY-MM-DD'))
+        AND ($6 IS NULL OR created_at < $6 OR (created_at = $6 AND uid < $7))
         ",
             table
         );
 
+        let mut bindings: Vec<String> = Vec::new();
+
+        // cards in the trash are hidden unless explicitly requested
+        if !query_args.include_deleted {
+            query.push_str(" AND deleted_at IS NULL");
+        }
+
         // check for uid. If uid is present, we only return that card
-        if query_args.uid.is_some() {
+        if let Some(uid) = &query_args.uid {
             // validate uid
-            is_valid_uuid4(query_args.uid.as_ref().unwrap())
-                .map_err(|e| SqlError::GeneralError(e.to_string()))?;
+            is_valid_uuid4(uid).map_err(|e| SqlError::GeneralError(e.to_string()))?;
         } else {
             // add where clause due to multiple combinations
 
-            if query_args.version.is_some() {
-                add_version_bounds(&mut query, query_args.version.as_ref().unwrap())?;
+            if let Some(version) = &query_args.version {
+                add_version_bounds(
+                    &mut query,
+                    &mut bindings,
+                    |n| format!("${}", 7 + n),
+                    version,
+                    &query_args.version_scheme.clone().unwrap_or_default(),
+                    false,
+                )?;
             }
 
-            if query_args.tags.is_some() {
-                let tags = query_args.tags.as_ref().unwrap();
-                for (key, value) in tags.iter() {
-                    query.push_str(format!(" AND tags->>'{}' = '{}'", key, value).as_str());
-                }
+            // aliases point at a single card of the (repository, name)
+            if let Some(alias) = &query_args.alias {
+                bindings.push(get_registry_type(table));
+                bindings.push(alias.clone());
+                query.push_str(&format!(
+                    " AND uid IN (SELECT uid FROM {} WHERE registry_type = ${} AND alias = ${})",
+                    CardSQLTableNames::Aliases,
+                    6 + bindings.len(),
+                    7 + bindings.len()
+                ));
             }
 
-            if query_args.sort_by_timestamp.unwrap_or(false) {
-                query.push_str(" ORDER BY created_at DESC");
+            // deprecated and archived cards are left out of version resolution unless asked for
+            let status_params = query_args
+                .get_statuses()
+                .iter()
+                .map(|status| {
+                    bindings.push(status.to_string());
+                    format!("${}", 7 + bindings.len())
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+            query.push_str(&format!(" AND status IN ({})", status_params));
+
+            // tag params are numbered after the 7 fixed params and the version, alias and status params
+            let tag_start_idx = 8 + bindings.len();
+            add_tag_filters(
+                &mut query,
+                &mut bindings,
+                &query_args.get_tag_filters(),
+                tag_start_idx,
+            )?;
+
+            // cursors are keyed on (created_at, uid) so they always use timestamp ordering
+            if query_args.uses_cursor_order() {
+                query.push_str(" ORDER BY created_at DESC, uid DESC");
             } else {
                 // sort by major, minor, patch
                 query.push_str(" ORDER BY major DESC, minor DESC, patch DESC");
@@ -338,14 +1187,15 @@ impl PostgresQueryHelper {
 
         query.push_str(" LIMIT $5");
 
-        Ok(query)
+        Ok((query, bindings))
     }
     pub fn get_run_parameters_insert_query(nbr_records: usize) -> String {
         let mut query = format!(
             "INSERT INTO {} (
                 run_uid, 
                 name, 
-                value
+                value,
+                created_at
             ) VALUES ",
             CardSQLTableNames::Parameters
         )
@@ -355,7 +1205,13 @@ impl PostgresQueryHelper {
             if i > 0 {
                 query.push_str(", ");
             }
-            query.push_str(&format!("(${}, ${}, ${})", 3 * i + 1, 3 * i + 2, 3 * i + 3));
+            query.push_str(&format!(
+                "(${}, ${}, ${}, COALESCE(${}, CURRENT_TIMESTAMP))",
+                4 * i + 1,
+                4 * i + 2,
+                4 * i + 3,
+                4 * i + 4
+            ));
         }
 
         query
@@ -436,8 +1292,8 @@ impl PostgresQueryHelper {
         patch, 
         version, 
         pre_tag,
-        build_tag) 
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+        build_tag, created_at, status, yanked) 
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, COALESCE($11, CURRENT_TIMESTAMP), $12, $13)",
             CardSQLTableNames::Project
         )
         .to_string()
@@ -462,9 +1318,12 @@ impl PostgresQueryHelper {
         pipelinecard_uid, 
         auditcard_uid, 
         pre_tag, 
-        build_tag
+        build_tag,
+        created_at,
+        status,
+        yanked
         ) 
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)",
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, COALESCE($18, CURRENT_TIMESTAMP), $19, $20)",
             CardSQLTableNames::Data
         )
         .to_string()
@@ -491,9 +1350,12 @@ impl PostgresQueryHelper {
         pipelinecard_uid, 
         auditcard_uid, 
         pre_tag, 
-        build_tag
+        build_tag,
+        created_at,
+        status,
+        yanked
         ) 
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)", CardSQLTableNames::Model).to_string()
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, COALESCE($21, CURRENT_TIMESTAMP), $22, $23)", CardSQLTableNames::Model).to_string()
     }
 
     pub fn get_runcard_insert_query() -> String {
@@ -516,9 +1378,13 @@ impl PostgresQueryHelper {
         artifact_uris, 
         compute_environment, 
         pre_tag, 
-        build_tag
+        build_tag,
+        created_at,
+        status,
+        yanked,
+        parent_uid
         ) 
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)",
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, COALESCE($19, CURRENT_TIMESTAMP), $20, $21, $22)",
             CardSQLTableNames::Run
         )
         .to_string()
@@ -542,9 +1408,12 @@ impl PostgresQueryHelper {
         modelcard_uids, 
         runcard_uids, 
         pre_tag, 
-        build_tag
+        build_tag,
+        created_at,
+        status,
+        yanked
         ) 
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)",
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, COALESCE($17, CURRENT_TIMESTAMP), $18, $19)",
             CardSQLTableNames::Audit
         )
         .to_string()
@@ -568,9 +1437,12 @@ impl PostgresQueryHelper {
         modelcard_uids, 
         runcard_uids,
          pre_tag, 
-         build_tag
+         build_tag,
+        created_at,
+        status,
+        yanked
          ) 
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)",
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, COALESCE($17, CURRENT_TIMESTAMP), $18, $19)",
             CardSQLTableNames::Pipeline
         )
         .to_string()
@@ -648,8 +1520,9 @@ impl PostgresQueryHelper {
         artifact_uris = $14, 
         compute_environment = $15, 
         pre_tag = $16, 
-        build_tag = $17
-        WHERE uid = $18",
+        build_tag = $17,
+        parent_uid = $18
+        WHERE uid = $19",
             CardSQLTableNames::Run
         )
         .to_string()
@@ -0,0 +1,30 @@
+-- Full-text search vectors for each card registry
+ALTER TABLE opsml_data_registry
+    ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', coalesce(name, '') || ' ' || coalesce(repository, '') || ' ' || coalesce(contact, '') || ' ' || coalesce(tags::text, ''))) STORED;
+CREATE INDEX IF NOT EXISTS idx_data_registry_search ON opsml_data_registry USING GIN (search_vector);
+
+ALTER TABLE opsml_model_registry
+    ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', coalesce(name, '') || ' ' || coalesce(repository, '') || ' ' || coalesce(contact, '') || ' ' || coalesce(tags::text, ''))) STORED;
+CREATE INDEX IF NOT EXISTS idx_model_registry_search ON opsml_model_registry USING GIN (search_vector);
+
+ALTER TABLE opsml_run_registry
+    ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', coalesce(name, '') || ' ' || coalesce(repository, '') || ' ' || coalesce(contact, '') || ' ' || coalesce(tags::text, ''))) STORED;
+CREATE INDEX IF NOT EXISTS idx_run_registry_search ON opsml_run_registry USING GIN (search_vector);
+
+ALTER TABLE opsml_audit_registry
+    ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', coalesce(name, '') || ' ' || coalesce(repository, '') || ' ' || coalesce(contact, '') || ' ' || coalesce(tags::text, ''))) STORED;
+CREATE INDEX IF NOT EXISTS idx_audit_registry_search ON opsml_audit_registry USING GIN (search_vector);
+
+ALTER TABLE opsml_pipeline_registry
+    ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', coalesce(name, '') || ' ' || coalesce(repository, '') || ' ' || coalesce(contact, '') || ' ' || coalesce(tags::text, ''))) STORED;
+CREATE INDEX IF NOT EXISTS idx_pipeline_registry_search ON opsml_pipeline_registry USING GIN (search_vector);
+
+ALTER TABLE opsml_project_registry
+    ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', coalesce(name, '') || ' ' || coalesce(repository, ''))) STORED;
+CREATE INDEX IF NOT EXISTS idx_project_registry_search ON opsml_project_registry USING GIN (search_vector);
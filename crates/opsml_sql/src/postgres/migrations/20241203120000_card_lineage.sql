@@ -0,0 +1,13 @@
+-- LineageSchema
+CREATE TABLE IF NOT EXISTS opsml_card_lineage (
+    upstream_uid VARCHAR(64) NOT NULL,
+    upstream_type VARCHAR(32) NOT NULL,
+    downstream_uid VARCHAR(64) NOT NULL,
+    downstream_type VARCHAR(32) NOT NULL,
+    declared_by VARCHAR(64) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (upstream_uid, downstream_uid, declared_by)
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_lineage_downstream ON opsml_card_lineage (downstream_uid);
+CREATE INDEX IF NOT EXISTS idx_card_lineage_declared_by ON opsml_card_lineage (declared_by);
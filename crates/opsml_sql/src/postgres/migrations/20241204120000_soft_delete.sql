@@ -0,0 +1,24 @@
+-- Soft delete columns for each card registry
+ALTER TABLE opsml_data_registry
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS deleted_by VARCHAR(255);
+
+ALTER TABLE opsml_model_registry
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS deleted_by VARCHAR(255);
+
+ALTER TABLE opsml_run_registry
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS deleted_by VARCHAR(255);
+
+ALTER TABLE opsml_audit_registry
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS deleted_by VARCHAR(255);
+
+ALTER TABLE opsml_pipeline_registry
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS deleted_by VARCHAR(255);
+
+ALTER TABLE opsml_project_registry
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS deleted_by VARCHAR(255);
@@ -0,0 +1,12 @@
+-- CardHistorySchema
+CREATE TABLE IF NOT EXISTS opsml_card_history (
+    idx SERIAL PRIMARY KEY,
+    uid VARCHAR(64) NOT NULL,
+    registry_type VARCHAR(32) NOT NULL,
+    action VARCHAR(32) NOT NULL,
+    username VARCHAR(255) NOT NULL,
+    changes JSONB NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_history_uid ON opsml_card_history (uid);
@@ -0,0 +1,28 @@
+-- CardAliasSchema
+CREATE TABLE IF NOT EXISTS opsml_card_aliases (
+    registry_type VARCHAR(32) NOT NULL,
+    repository VARCHAR(128) NOT NULL,
+    name VARCHAR(128) NOT NULL,
+    alias VARCHAR(64) NOT NULL,
+    uid VARCHAR(64) NOT NULL,
+    updated_by VARCHAR(255) NOT NULL,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (registry_type, repository, name, alias)
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_aliases_uid ON opsml_card_aliases (uid);
+
+-- CardAliasHistorySchema
+CREATE TABLE IF NOT EXISTS opsml_card_alias_history (
+    idx SERIAL PRIMARY KEY,
+    registry_type VARCHAR(32) NOT NULL,
+    repository VARCHAR(128) NOT NULL,
+    name VARCHAR(128) NOT NULL,
+    alias VARCHAR(64) NOT NULL,
+    previous_uid VARCHAR(64),
+    uid VARCHAR(64) NOT NULL,
+    updated_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_alias_history_name ON opsml_card_alias_history (registry_type, repository, name);
@@ -0,0 +1,25 @@
+-- Lifecycle status for each card registry
+ALTER TABLE opsml_data_registry
+    ADD COLUMN IF NOT EXISTS status VARCHAR(32) NOT NULL DEFAULT 'active';
+
+ALTER TABLE opsml_model_registry
+    ADD COLUMN IF NOT EXISTS status VARCHAR(32) NOT NULL DEFAULT 'active';
+
+ALTER TABLE opsml_run_registry
+    ADD COLUMN IF NOT EXISTS status VARCHAR(32) NOT NULL DEFAULT 'active';
+
+ALTER TABLE opsml_audit_registry
+    ADD COLUMN IF NOT EXISTS status VARCHAR(32) NOT NULL DEFAULT 'active';
+
+ALTER TABLE opsml_pipeline_registry
+    ADD COLUMN IF NOT EXISTS status VARCHAR(32) NOT NULL DEFAULT 'active';
+
+ALTER TABLE opsml_project_registry
+    ADD COLUMN IF NOT EXISTS status VARCHAR(32) NOT NULL DEFAULT 'active';
+
+CREATE INDEX IF NOT EXISTS idx_data_registry_status ON opsml_data_registry (status);
+CREATE INDEX IF NOT EXISTS idx_model_registry_status ON opsml_model_registry (status);
+CREATE INDEX IF NOT EXISTS idx_run_registry_status ON opsml_run_registry (status);
+CREATE INDEX IF NOT EXISTS idx_audit_registry_status ON opsml_audit_registry (status);
+CREATE INDEX IF NOT EXISTS idx_pipeline_registry_status ON opsml_pipeline_registry (status);
+CREATE INDEX IF NOT EXISTS idx_project_registry_status ON opsml_project_registry (status);
@@ -0,0 +1,18 @@
+-- Yanked versions are skipped by version range resolution
+ALTER TABLE opsml_data_registry
+    ADD COLUMN IF NOT EXISTS yanked BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE opsml_model_registry
+    ADD COLUMN IF NOT EXISTS yanked BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE opsml_run_registry
+    ADD COLUMN IF NOT EXISTS yanked BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE opsml_audit_registry
+    ADD COLUMN IF NOT EXISTS yanked BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE opsml_pipeline_registry
+    ADD COLUMN IF NOT EXISTS yanked BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE opsml_project_registry
+    ADD COLUMN IF NOT EXISTS yanked BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,6 @@
+-- RepositorySettingsSchema
+CREATE TABLE IF NOT EXISTS opsml_repository_settings (
+    repository VARCHAR(128) PRIMARY KEY,
+    version_scheme VARCHAR(16) NOT NULL DEFAULT 'semver',
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,37 @@
+-- Lookup indexes for each card registry
+CREATE INDEX IF NOT EXISTS idx_data_registry_name ON opsml_data_registry (repository, name);
+CREATE INDEX IF NOT EXISTS idx_data_registry_version ON opsml_data_registry (version);
+CREATE INDEX IF NOT EXISTS idx_data_registry_created_at ON opsml_data_registry (created_at);
+
+CREATE INDEX IF NOT EXISTS idx_model_registry_name ON opsml_model_registry (repository, name);
+CREATE INDEX IF NOT EXISTS idx_model_registry_version ON opsml_model_registry (version);
+CREATE INDEX IF NOT EXISTS idx_model_registry_created_at ON opsml_model_registry (created_at);
+
+CREATE INDEX IF NOT EXISTS idx_run_registry_name ON opsml_run_registry (repository, name);
+CREATE INDEX IF NOT EXISTS idx_run_registry_version ON opsml_run_registry (version);
+CREATE INDEX IF NOT EXISTS idx_run_registry_created_at ON opsml_run_registry (created_at);
+
+CREATE INDEX IF NOT EXISTS idx_audit_registry_name ON opsml_audit_registry (repository, name);
+CREATE INDEX IF NOT EXISTS idx_audit_registry_version ON opsml_audit_registry (version);
+CREATE INDEX IF NOT EXISTS idx_audit_registry_created_at ON opsml_audit_registry (created_at);
+
+CREATE INDEX IF NOT EXISTS idx_pipeline_registry_name ON opsml_pipeline_registry (repository, name);
+CREATE INDEX IF NOT EXISTS idx_pipeline_registry_version ON opsml_pipeline_registry (version);
+CREATE INDEX IF NOT EXISTS idx_pipeline_registry_created_at ON opsml_pipeline_registry (created_at);
+
+CREATE INDEX IF NOT EXISTS idx_project_registry_name ON opsml_project_registry (repository, name);
+CREATE INDEX IF NOT EXISTS idx_project_registry_version ON opsml_project_registry (version);
+CREATE INDEX IF NOT EXISTS idx_project_registry_created_at ON opsml_project_registry (created_at);
+
+-- One live card per (repository, name, version). Cards in the trash may share a version
+CREATE UNIQUE INDEX IF NOT EXISTS idx_data_registry_unique_version ON opsml_data_registry (repository, name, version) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_model_registry_unique_version ON opsml_model_registry (repository, name, version) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_run_registry_unique_version ON opsml_run_registry (repository, name, version) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_audit_registry_unique_version ON opsml_audit_registry (repository, name, version) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_pipeline_registry_unique_version ON opsml_pipeline_registry (repository, name, version) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_project_registry_unique_version ON opsml_project_registry (repository, name, version) WHERE deleted_at IS NULL;
+
+-- Run metrics are always read by run
+CREATE INDEX IF NOT EXISTS idx_run_metrics_run_uid ON opsml_run_metrics (run_uid);
+CREATE INDEX IF NOT EXISTS idx_run_parameters_run_uid ON opsml_run_parameters (run_uid);
+CREATE INDEX IF NOT EXISTS idx_run_hardware_metrics_run_uid ON opsml_run_hardware_metrics (run_uid);
@@ -0,0 +1,10 @@
+-- RunStatusSchema
+CREATE TABLE IF NOT EXISTS opsml_run_status (
+    run_uid VARCHAR(64) PRIMARY KEY,
+    status VARCHAR(16) NOT NULL DEFAULT 'running',
+    started_at TIMESTAMP NOT NULL,
+    ended_at TIMESTAMP,
+    heartbeat_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_run_status_heartbeat ON opsml_run_status (status, heartbeat_at);
@@ -0,0 +1,4 @@
+-- Runs nested under a parent run, such as sweep trials or cross-validation folds
+ALTER TABLE opsml_run_registry ADD COLUMN IF NOT EXISTS parent_uid VARCHAR(64);
+
+CREATE INDEX IF NOT EXISTS idx_run_registry_parent_uid ON opsml_run_registry (parent_uid);
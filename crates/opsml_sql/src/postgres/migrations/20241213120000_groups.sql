@@ -0,0 +1,17 @@
+-- GroupSchema
+CREATE TABLE IF NOT EXISTS opsml_groups (
+    id SERIAL PRIMARY KEY,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    permissions JSONB NOT NULL
+);
+
+-- GroupMemberSchema
+CREATE TABLE IF NOT EXISTS opsml_group_members (
+    group_name VARCHAR(255) NOT NULL,
+    username VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (group_name, username)
+);
+
+CREATE INDEX IF NOT EXISTS idx_group_members_username ON opsml_group_members (username);
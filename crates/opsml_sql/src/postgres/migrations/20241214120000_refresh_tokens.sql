@@ -0,0 +1,9 @@
+-- RefreshTokenSchema
+CREATE TABLE IF NOT EXISTS opsml_refresh_tokens (
+    family VARCHAR(64) PRIMARY KEY,
+    username VARCHAR(255) NOT NULL,
+    token_hash TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_username ON opsml_refresh_tokens (username);
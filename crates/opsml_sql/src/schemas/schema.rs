@@ -1,9 +1,13 @@
+use crate::base::get_registry_type;
+use base64::prelude::*;
 use chrono::NaiveDateTime;
-use opsml_error::error::VersionError;
+use opsml_error::error::{SqlError, VersionError};
 use opsml_types::enums::CommonKwargs;
-use opsml_utils::utils::get_utc_datetime;
+use opsml_types::{CardQueryArgs, CardSQLTableNames, CardStatus, RunStatus};
+use opsml_utils::semver::VersionBounds;
+use opsml_utils::utils::{get_utc_datetime, is_valid_uuid4};
 use semver::{BuildMetadata, Prerelease, Version};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sqlx::{prelude::FromRow, types::Json};
 use std::collections::HashMap;
 use std::env;
@@ -54,6 +58,42 @@ impl Default for MetricRecord {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MetricSummaryRecord {
+    pub name: String,
+    pub count: i64,
+    pub min_value: f64,
+    pub max_value: f64,
+    pub mean_value: f64,
+    pub last_value: f64,
+    pub last_step: Option<i32>,
+}
+
+/// A metric aggregated over the child runs of a parent. Each child contributes the last value
+/// it logged, and the min and max carry the child that logged them
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ChildMetricSummaryRecord {
+    pub name: String,
+    pub run_count: i64,
+    pub min_value: f64,
+    pub min_run_uid: String,
+    pub max_value: f64,
+    pub max_run_uid: String,
+    pub mean_value: f64,
+}
+
+/// Run matching a run search. `sort_value` is the run's value of the sort metric, if any
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RunQueryResult {
+    pub uid: String,
+    pub name: String,
+    pub repository: String,
+    pub version: String,
+    pub project: String,
+    pub created_at: Option<NaiveDateTime>,
+    pub sort_value: Option<f64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ParameterRecord {
     pub run_uid: String,
@@ -103,13 +143,13 @@ impl VersionResult {
     pub fn to_version(&self) -> Result<Version, VersionError> {
         let mut version = Version::new(self.major as u64, self.minor as u64, self.patch as u64);
 
-        if self.pre_tag.is_some() {
-            version.pre = Prerelease::new(self.pre_tag.as_ref().unwrap())
+        if let Some(pre_tag) = &self.pre_tag {
+            version.pre = Prerelease::new(pre_tag)
                 .map_err(|e| VersionError::InvalidPreRelease(format!("{}", e)))?;
         }
 
-        if self.build_tag.is_some() {
-            version.build = BuildMetadata::new(self.build_tag.as_ref().unwrap())
+        if let Some(build_tag) = &self.build_tag {
+            version.build = BuildMetadata::new(build_tag)
                 .map_err(|e| VersionError::InvalidBuild(format!("{}", e)))?;
         }
 
@@ -117,6 +157,17 @@ impl VersionResult {
     }
 }
 
+/// Precedence-relevant part of a stored card version. Build metadata is ignored and pre-release
+/// tags were validated on insert
+fn record_version(major: i32, minor: i32, patch: i32, pre_tag: &Option<String>) -> Version {
+    let mut version = Version::new(major as u64, minor as u64, patch as u64);
+    version.pre = pre_tag
+        .as_deref()
+        .and_then(|tag| Prerelease::new(tag).ok())
+        .unwrap_or_default();
+    version
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Repository {
     pub repository: String,
@@ -134,12 +185,183 @@ pub struct CardSummary {
     pub repository: String,
     pub name: String,
     pub version: String,
+    pub status: String,
     pub versions: i64,
     pub updated_at: NaiveDateTime,
     pub created_at: NaiveDateTime,
     pub row_num: i64,
 }
 
+/// Ranked full-text search hit. `registry_type` is the lowercase registry the card belongs to
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CardSearchResult {
+    pub registry_type: String,
+    pub uid: String,
+    pub name: String,
+    pub repository: String,
+    pub version: String,
+    pub created_at: NaiveDateTime,
+    pub score: f64,
+}
+
+/// Directed lineage edge where `downstream_uid` was derived from or consumes `upstream_uid`.
+/// `depth` is the number of hops from the card a traversal started at (0 for declared edges)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LineageEdge {
+    pub upstream_uid: String,
+    pub upstream_type: String,
+    pub downstream_uid: String,
+    pub downstream_type: String,
+    pub depth: i64,
+}
+
+impl LineageEdge {
+    pub fn new(
+        upstream_uid: &str,
+        upstream_type: &str,
+        downstream_uid: &str,
+        downstream_type: &str,
+    ) -> Self {
+        LineageEdge {
+            upstream_uid: upstream_uid.to_string(),
+            upstream_type: upstream_type.to_string(),
+            downstream_uid: downstream_uid.to_string(),
+            downstream_type: downstream_type.to_string(),
+            depth: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LineageNode {
+    pub registry_type: String,
+    pub uid: String,
+    pub name: String,
+    pub repository: String,
+    pub version: String,
+}
+
+/// Card in the trash, awaiting restore or purge
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DeletedCard {
+    pub uid: String,
+    pub name: String,
+    pub repository: String,
+    pub version: String,
+    pub deleted_at: NaiveDateTime,
+    pub deleted_by: String,
+}
+
+/// Append-only record of a change made to a card. `changes` maps every field that changed
+/// to its `old` and `new` value
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CardHistoryRecord {
+    pub uid: String,
+    pub registry_type: String,
+    pub action: String,
+    pub username: String,
+    pub changes: Json<serde_json::Value>,
+    pub created_at: NaiveDateTime,
+}
+
+impl CardHistoryRecord {
+    /// Create a history record by diffing the card state before and after the change
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The registry table the card belongs to
+    /// * `uid` - The uid of the card
+    /// * `action` - The change made (insert, update, delete or restore)
+    /// * `username` - The user making the change
+    /// * `before` - The card state before the change, if it existed
+    /// * `after` - The card state after the change, if it still exists
+    pub fn new(
+        table: &CardSQLTableNames,
+        uid: &str,
+        action: &str,
+        username: &str,
+        before: Option<&serde_json::Value>,
+        after: Option<&serde_json::Value>,
+    ) -> Self {
+        CardHistoryRecord {
+            uid: uid.to_string(),
+            registry_type: get_registry_type(table),
+            action: action.to_string(),
+            username: username.to_string(),
+            changes: Json(Self::diff(before, after)),
+            created_at: get_utc_datetime(),
+        }
+    }
+
+    /// Field-level diff of two card json objects. Fields missing on one side are treated as null
+    pub fn diff(
+        before: Option<&serde_json::Value>,
+        after: Option<&serde_json::Value>,
+    ) -> serde_json::Value {
+        let empty = serde_json::Map::new();
+        let before = before.and_then(|v| v.as_object()).unwrap_or(&empty);
+        let after = after.and_then(|v| v.as_object()).unwrap_or(&empty);
+
+        let mut changes = serde_json::Map::new();
+        for key in before.keys().chain(after.keys()) {
+            let old = before.get(key).unwrap_or(&serde_json::Value::Null);
+            let new = after.get(key).unwrap_or(&serde_json::Value::Null);
+
+            if old != new && !changes.contains_key(key) {
+                changes.insert(key.clone(), serde_json::json!({ "old": old, "new": new }));
+            }
+        }
+
+        serde_json::Value::Object(changes)
+    }
+}
+
+/// Named pointer, such as `production` or `champion`, from a (repository, name) to one of its cards
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq)]
+pub struct CardAlias {
+    pub registry_type: String,
+    pub repository: String,
+    pub name: String,
+    pub alias: String,
+    pub uid: String,
+    pub updated_by: String,
+    pub updated_at: NaiveDateTime,
+}
+
+impl CardAlias {
+    pub fn new(
+        table: &CardSQLTableNames,
+        repository: &str,
+        name: &str,
+        alias: &str,
+        uid: &str,
+        updated_by: &str,
+    ) -> Self {
+        CardAlias {
+            registry_type: get_registry_type(table),
+            repository: repository.to_string(),
+            name: name.to_string(),
+            alias: alias.to_string(),
+            uid: uid.to_string(),
+            updated_by: updated_by.to_string(),
+            updated_at: get_utc_datetime(),
+        }
+    }
+}
+
+/// Record of an alias being set or moved. `previous_uid` is empty when the alias was created
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CardAliasHistoryRecord {
+    pub registry_type: String,
+    pub repository: String,
+    pub name: String,
+    pub alias: String,
+    pub previous_uid: Option<String>,
+    pub uid: String,
+    pub updated_by: String,
+    pub created_at: NaiveDateTime,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct DataCardRecord {
     pub uid: String,
@@ -160,6 +382,8 @@ pub struct DataCardRecord {
     pub pipelinecard_uid: String,
     pub auditcard_uid: String,
     pub interface_type: String,
+    pub status: String,
+    pub yanked: bool,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -183,6 +407,8 @@ impl DataCardRecord {
         DataCardRecord {
             uid,
             created_at,
+            status: CardStatus::Active.to_string(),
+            yanked: false,
             app_env,
             name,
             repository,
@@ -212,6 +438,8 @@ impl Default for DataCardRecord {
         DataCardRecord {
             uid: Uuid::new_v4().to_string(),
             created_at: Some(get_utc_datetime()),
+            status: CardStatus::Active.to_string(),
+            yanked: false,
             app_env: env::var("APP_ENV").unwrap_or_else(|_| "development".to_string()),
             name: CommonKwargs::Undefined.as_string().to_string(),
             repository: CommonKwargs::Undefined.as_string().to_string(),
@@ -255,6 +483,8 @@ pub struct ModelCardRecord {
     pub auditcard_uid: String,
     pub interface_type: String,
     pub task_type: String,
+    pub status: String,
+    pub yanked: bool,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -281,6 +511,8 @@ impl ModelCardRecord {
         ModelCardRecord {
             uid,
             created_at,
+            status: CardStatus::Active.to_string(),
+            yanked: false,
             app_env,
             name,
             repository,
@@ -314,6 +546,8 @@ impl Default for ModelCardRecord {
         ModelCardRecord {
             uid: Uuid::new_v4().to_string(),
             created_at: Some(get_utc_datetime()),
+            status: CardStatus::Active.to_string(),
+            yanked: false,
             app_env: env::var("APP_ENV").unwrap_or_else(|_| "development".to_string()),
             name: CommonKwargs::Undefined.as_string().to_string(),
             repository: CommonKwargs::Undefined.as_string().to_string(),
@@ -358,6 +592,9 @@ pub struct RunCardRecord {
     pub project: String,
     pub artifact_uris: Json<HashMap<String, String>>,
     pub compute_environment: Json<HashMap<String, String>>,
+    pub status: String,
+    pub yanked: bool,
+    pub parent_uid: Option<String>,
 }
 
 impl Default for RunCardRecord {
@@ -365,6 +602,8 @@ impl Default for RunCardRecord {
         RunCardRecord {
             uid: Uuid::new_v4().to_string(),
             created_at: Some(get_utc_datetime()),
+            status: CardStatus::Active.to_string(),
+            yanked: false,
             app_env: env::var("APP_ENV").unwrap_or_else(|_| "development".to_string()),
             name: CommonKwargs::Undefined.as_string().to_string(),
             repository: CommonKwargs::Undefined.as_string().to_string(),
@@ -382,6 +621,7 @@ impl Default for RunCardRecord {
             project: CommonKwargs::Undefined.as_string().to_string(),
             artifact_uris: Json(HashMap::new()),
             compute_environment: Json(HashMap::new()),
+            parent_uid: None,
         }
     }
 }
@@ -400,6 +640,7 @@ impl RunCardRecord {
         project: String,
         artifact_uris: Option<HashMap<String, String>>,
         compute_environment: Option<HashMap<String, String>>,
+        parent_uid: Option<String>,
     ) -> Self {
         let created_at = Some(get_utc_datetime());
         let app_env = env::var("APP_ENV").unwrap_or_else(|_| "development".to_string());
@@ -408,6 +649,8 @@ impl RunCardRecord {
         RunCardRecord {
             uid,
             created_at,
+            status: CardStatus::Active.to_string(),
+            yanked: false,
             app_env,
             name,
             repository,
@@ -426,6 +669,7 @@ impl RunCardRecord {
             project,
             artifact_uris: Json(artifact_uris.unwrap_or_default()),
             compute_environment: Json(compute_environment.unwrap_or_default()),
+            parent_uid,
         }
     }
 }
@@ -449,6 +693,8 @@ pub struct AuditCardRecord {
     pub datacard_uids: Json<Vec<String>>,
     pub modelcard_uids: Json<Vec<String>>,
     pub runcard_uids: Json<Vec<String>>,
+    pub status: String,
+    pub yanked: bool,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -471,6 +717,8 @@ impl AuditCardRecord {
         AuditCardRecord {
             uid,
             created_at,
+            status: CardStatus::Active.to_string(),
+            yanked: false,
             app_env,
             name,
             repository,
@@ -495,6 +743,8 @@ impl Default for AuditCardRecord {
         AuditCardRecord {
             uid: Uuid::new_v4().to_string(),
             created_at: Some(get_utc_datetime()),
+            status: CardStatus::Active.to_string(),
+            yanked: false,
             app_env: env::var("APP_ENV").unwrap_or_else(|_| "development".to_string()),
             name: CommonKwargs::Undefined.as_string().to_string(),
             repository: CommonKwargs::Undefined.as_string().to_string(),
@@ -533,6 +783,8 @@ pub struct PipelineCardRecord {
     pub datacard_uids: Json<Vec<String>>,
     pub modelcard_uids: Json<Vec<String>>,
     pub runcard_uids: Json<Vec<String>>,
+    pub status: String,
+    pub yanked: bool,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -555,6 +807,8 @@ impl PipelineCardRecord {
         PipelineCardRecord {
             uid,
             created_at,
+            status: CardStatus::Active.to_string(),
+            yanked: false,
             app_env,
             name,
             repository,
@@ -579,6 +833,8 @@ impl Default for PipelineCardRecord {
         PipelineCardRecord {
             uid: Uuid::new_v4().to_string(),
             created_at: Some(get_utc_datetime()),
+            status: CardStatus::Active.to_string(),
+            yanked: false,
             app_env: env::var("APP_ENV").unwrap_or_else(|_| "development".to_string()),
             name: CommonKwargs::Undefined.as_string().to_string(),
             repository: CommonKwargs::Undefined.as_string().to_string(),
@@ -611,6 +867,8 @@ pub struct ProjectCardRecord {
     pub pre_tag: Option<String>,
     pub build_tag: Option<String>,
     pub version: String,
+    pub status: String,
+    pub yanked: bool,
 }
 
 impl Default for ProjectCardRecord {
@@ -618,6 +876,8 @@ impl Default for ProjectCardRecord {
         ProjectCardRecord {
             uid: Uuid::new_v4().to_string(),
             created_at: Some(get_utc_datetime()),
+            status: CardStatus::Active.to_string(),
+            yanked: false,
             name: CommonKwargs::Undefined.as_string().to_string(),
             repository: CommonKwargs::Undefined.as_string().to_string(),
             project_id: 1,
@@ -636,6 +896,8 @@ impl ProjectCardRecord {
         ProjectCardRecord {
             uid: Uuid::new_v4().to_string(),
             created_at: Some(get_utc_datetime()),
+            status: CardStatus::Active.to_string(),
+            yanked: false,
             name,
             repository,
             project_id,
@@ -649,6 +911,76 @@ impl ProjectCardRecord {
     }
 }
 
+fn encode_cursor<T: Serialize>(cursor: &T) -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(serde_json::to_vec(cursor).unwrap_or_default())
+}
+
+fn decode_cursor<T: DeserializeOwned>(cursor: &str) -> Result<T, SqlError> {
+    let bytes = BASE64_URL_SAFE_NO_PAD
+        .decode(cursor)
+        .map_err(|e| SqlError::GeneralError(format!("Invalid cursor: {}", e)))?;
+
+    serde_json::from_slice(&bytes)
+        .map_err(|e| SqlError::GeneralError(format!("Invalid cursor: {}", e)))
+}
+
+/// Keyset cursor for paging through cards ordered by (created_at, uid) descending
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CardCursor {
+    pub created_at: NaiveDateTime,
+    pub uid: String,
+}
+
+impl CardCursor {
+    pub fn encode(&self) -> String {
+        encode_cursor(self)
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, SqlError> {
+        decode_cursor(cursor)
+    }
+}
+
+/// Keyset cursor for paging through card summaries ordered by updated_at descending
+/// and then (repository, name) ascending
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SummaryCursor {
+    pub updated_at: NaiveDateTime,
+    pub repository: String,
+    pub name: String,
+}
+
+impl SummaryCursor {
+    pub fn encode(&self) -> String {
+        encode_cursor(self)
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, SqlError> {
+        decode_cursor(cursor)
+    }
+
+    /// Cursor pointing after the last summary. Only returned when a full page was fetched
+    ///
+    /// # Arguments
+    ///
+    /// * `summaries` - The summaries returned by the query
+    /// * `page_size` - The page size used for the query
+    pub fn next_cursor(summaries: &[CardSummary], page_size: usize) -> Option<String> {
+        if summaries.len() < page_size {
+            return None;
+        }
+
+        summaries.last().map(|summary| {
+            SummaryCursor {
+                updated_at: summary.updated_at,
+                repository: summary.repository.clone(),
+                name: summary.name.clone(),
+            }
+            .encode()
+        })
+    }
+}
+
 // create enum that takes vec of cards
 // TODO: There should also be a client side enum that matches this (don't want to install opsml_sql on client)
 #[derive(Debug, Serialize, Deserialize)]
@@ -682,6 +1014,97 @@ impl CardResults {
             CardResults::Project(cards) => cards.is_empty(),
         }
     }
+    /// Keep the cards whose version falls within `bounds`, up to `limit` cards
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - Version bounds the cards must match
+    /// * `limit` - Maximum number of cards to keep
+    pub fn retain_versions(&mut self, bounds: &VersionBounds, limit: usize) {
+        match self {
+            CardResults::Data(cards) => {
+                cards.retain(|c| {
+                    bounds.matches(&record_version(c.major, c.minor, c.patch, &c.pre_tag))
+                });
+                cards.truncate(limit);
+            }
+            CardResults::Model(cards) => {
+                cards.retain(|c| {
+                    bounds.matches(&record_version(c.major, c.minor, c.patch, &c.pre_tag))
+                });
+                cards.truncate(limit);
+            }
+            CardResults::Run(cards) => {
+                cards.retain(|c| {
+                    bounds.matches(&record_version(c.major, c.minor, c.patch, &c.pre_tag))
+                });
+                cards.truncate(limit);
+            }
+            CardResults::Audit(cards) => {
+                cards.retain(|c| {
+                    bounds.matches(&record_version(c.major, c.minor, c.patch, &c.pre_tag))
+                });
+                cards.truncate(limit);
+            }
+            CardResults::Pipeline(cards) => {
+                cards.retain(|c| {
+                    bounds.matches(&record_version(c.major, c.minor, c.patch, &c.pre_tag))
+                });
+                cards.truncate(limit);
+            }
+            CardResults::Project(cards) => {
+                cards.retain(|c| {
+                    bounds.matches(&record_version(c.major, c.minor, c.patch, &c.pre_tag))
+                });
+                cards.truncate(limit);
+            }
+        }
+    }
+
+    /// Created_at and uid of the last card in the results
+    fn last_key(&self) -> Option<(Option<NaiveDateTime>, String)> {
+        match self {
+            CardResults::Data(cards) => cards.last().map(|c| (c.created_at, c.uid.clone())),
+            CardResults::Model(cards) => cards.last().map(|c| (c.created_at, c.uid.clone())),
+            CardResults::Run(cards) => cards.last().map(|c| (c.created_at, c.uid.clone())),
+            CardResults::Audit(cards) => cards.last().map(|c| (c.created_at, c.uid.clone())),
+            CardResults::Pipeline(cards) => cards.last().map(|c| (c.created_at, c.uid.clone())),
+            CardResults::Project(cards) => cards.last().map(|c| (c.created_at, c.uid.clone())),
+        }
+    }
+
+    /// Cursor pointing after the last card. Only returned when a full page was fetched in
+    /// (created_at, uid) order; version ordered pages cannot be resumed from a cursor
+    ///
+    /// # Arguments
+    ///
+    /// * `query_args` - The query arguments used for the query
+    pub fn next_cursor(&self, query_args: &CardQueryArgs) -> Option<String> {
+        if !query_args.uses_cursor_order() || self.len() < query_args.limit.unwrap_or(50) as usize {
+            return None;
+        }
+
+        match self.last_key() {
+            Some((Some(created_at), uid)) => Some(CardCursor { created_at, uid }.encode()),
+            _ => None,
+        }
+    }
+
+    pub fn to_values(&self) -> Vec<serde_json::Value> {
+        match self {
+            CardResults::Data(cards) => cards.iter().map(|card| serde_json::json!(card)).collect(),
+            CardResults::Model(cards) => cards.iter().map(|card| serde_json::json!(card)).collect(),
+            CardResults::Run(cards) => cards.iter().map(|card| serde_json::json!(card)).collect(),
+            CardResults::Audit(cards) => cards.iter().map(|card| serde_json::json!(card)).collect(),
+            CardResults::Pipeline(cards) => {
+                cards.iter().map(|card| serde_json::json!(card)).collect()
+            }
+            CardResults::Project(cards) => {
+                cards.iter().map(|card| serde_json::json!(card)).collect()
+            }
+        }
+    }
+
     pub fn to_json(&self) -> Vec<String> {
         match self {
             CardResults::Data(cards) => cards
@@ -723,6 +1146,100 @@ pub enum Card {
 }
 
 impl Card {
+    /// Lineage edges declared by this card's uid references. Data flows into runs,
+    /// runs produce models, and pipelines and audits consume every other card type.
+    /// Unset references are skipped
+    pub fn lineage_edges(&self) -> Vec<LineageEdge> {
+        let uid = self.uid();
+        let mut edges = Vec::new();
+
+        let add_upstream = |edges: &mut Vec<LineageEdge>,
+                            uids: &[String],
+                            upstream_type: &str,
+                            downstream_type: &str| {
+            for upstream_uid in uids {
+                edges.push(LineageEdge::new(
+                    upstream_uid,
+                    upstream_type,
+                    &uid,
+                    downstream_type,
+                ));
+            }
+        };
+
+        match self {
+            Card::Data(card) => {
+                edges.push(LineageEdge::new(&uid, "data", &card.runcard_uid, "run"));
+                edges.push(LineageEdge::new(
+                    &uid,
+                    "data",
+                    &card.pipelinecard_uid,
+                    "pipeline",
+                ));
+                edges.push(LineageEdge::new(&uid, "data", &card.auditcard_uid, "audit"));
+            }
+            Card::Model(card) => {
+                edges.push(LineageEdge::new(&card.datacard_uid, "data", &uid, "model"));
+                edges.push(LineageEdge::new(&card.runcard_uid, "run", &uid, "model"));
+                edges.push(LineageEdge::new(
+                    &uid,
+                    "model",
+                    &card.pipelinecard_uid,
+                    "pipeline",
+                ));
+                edges.push(LineageEdge::new(
+                    &uid,
+                    "model",
+                    &card.auditcard_uid,
+                    "audit",
+                ));
+            }
+            Card::Run(card) => {
+                add_upstream(&mut edges, &card.datacard_uids, "data", "run");
+                for model_uid in card.modelcard_uids.iter() {
+                    edges.push(LineageEdge::new(&uid, "run", model_uid, "model"));
+                }
+                edges.push(LineageEdge::new(
+                    &uid,
+                    "run",
+                    &card.pipelinecard_uid,
+                    "pipeline",
+                ));
+            }
+            Card::Audit(card) => {
+                add_upstream(&mut edges, &card.datacard_uids, "data", "audit");
+                add_upstream(&mut edges, &card.modelcard_uids, "model", "audit");
+                add_upstream(&mut edges, &card.runcard_uids, "run", "audit");
+            }
+            Card::Pipeline(card) => {
+                add_upstream(&mut edges, &card.datacard_uids, "data", "pipeline");
+                add_upstream(&mut edges, &card.modelcard_uids, "model", "pipeline");
+                add_upstream(&mut edges, &card.runcard_uids, "run", "pipeline");
+            }
+            Card::Project(_) => {}
+        }
+
+        edges.retain(|edge| {
+            edge.upstream_uid != edge.downstream_uid
+                && is_valid_uuid4(&edge.upstream_uid).unwrap_or(false)
+                && is_valid_uuid4(&edge.downstream_uid).unwrap_or(false)
+        });
+        edges.sort();
+        edges.dedup();
+        edges
+    }
+
+    pub fn to_value(&self) -> serde_json::Value {
+        match self {
+            Card::Data(card) => serde_json::json!(card),
+            Card::Model(card) => serde_json::json!(card),
+            Card::Run(card) => serde_json::json!(card),
+            Card::Audit(card) => serde_json::json!(card),
+            Card::Pipeline(card) => serde_json::json!(card),
+            Card::Project(card) => serde_json::json!(card),
+        }
+    }
+
     pub fn uid(&self) -> String {
         match self {
             Card::Data(card) => card.uid.clone(),
@@ -733,6 +1250,64 @@ impl Card {
             Card::Project(card) => card.uid.clone(),
         }
     }
+
+    pub fn name(&self) -> String {
+        match self {
+            Card::Data(card) => card.name.clone(),
+            Card::Model(card) => card.name.clone(),
+            Card::Run(card) => card.name.clone(),
+            Card::Audit(card) => card.name.clone(),
+            Card::Pipeline(card) => card.name.clone(),
+            Card::Project(card) => card.name.clone(),
+        }
+    }
+
+    pub fn repository(&self) -> String {
+        match self {
+            Card::Data(card) => card.repository.clone(),
+            Card::Model(card) => card.repository.clone(),
+            Card::Run(card) => card.repository.clone(),
+            Card::Audit(card) => card.repository.clone(),
+            Card::Pipeline(card) => card.repository.clone(),
+            Card::Project(card) => card.repository.clone(),
+        }
+    }
+
+    pub fn version(&self) -> String {
+        match self {
+            Card::Data(card) => card.version.clone(),
+            Card::Model(card) => card.version.clone(),
+            Card::Run(card) => card.version.clone(),
+            Card::Audit(card) => card.version.clone(),
+            Card::Pipeline(card) => card.version.clone(),
+            Card::Project(card) => card.version.clone(),
+        }
+    }
+
+    pub fn set_status(&mut self, status: &CardStatus) {
+        let status = status.to_string();
+        match self {
+            Card::Data(card) => card.status = status,
+            Card::Model(card) => card.status = status,
+            Card::Run(card) => card.status = status,
+            Card::Audit(card) => card.status = status,
+            Card::Pipeline(card) => card.status = status,
+            Card::Project(card) => card.status = status,
+        }
+    }
+
+    /// Replace the version label, e.g. with the calendar form of an encoded calendar version
+    pub fn set_version(&mut self, version: &str) {
+        let version = version.to_string();
+        match self {
+            Card::Data(card) => card.version = version,
+            Card::Model(card) => card.version = version,
+            Card::Run(card) => card.version = version,
+            Card::Audit(card) => card.version = version,
+            Card::Pipeline(card) => card.version = version,
+            Card::Project(card) => card.version = version,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -784,6 +1359,36 @@ impl Default for HardwareMetricsRecord {
     }
 }
 
+/// Lifecycle of a run. `heartbeat_at` is the last time the run reported in
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq)]
+pub struct RunStatusRecord {
+    pub run_uid: String,
+    pub status: String,
+    pub started_at: NaiveDateTime,
+    pub ended_at: Option<NaiveDateTime>,
+    pub heartbeat_at: NaiveDateTime,
+}
+
+impl RunStatusRecord {
+    pub fn new(run_uid: &str) -> Self {
+        let now = get_utc_datetime();
+
+        RunStatusRecord {
+            run_uid: run_uid.to_string(),
+            status: RunStatus::Running.as_str().to_string(),
+            started_at: now,
+            ended_at: None,
+            heartbeat_at: now,
+        }
+    }
+
+    /// Seconds from the start of the run to its end, or to now while it is running
+    pub fn duration_seconds(&self) -> i64 {
+        let end = self.ended_at.unwrap_or_else(get_utc_datetime);
+        (end - self.started_at).num_seconds()
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct User {
     pub id: Option<i32>,
@@ -828,3 +1433,44 @@ impl std::fmt::Debug for User {
             .finish()
     }
 }
+
+/// Current refresh token of one login session. Every login starts a new family, and each
+/// refresh replaces the stored hash, so reuse of a rotated-out token only revokes its own family
+#[derive(Serialize, Deserialize, Clone, FromRow)]
+pub struct RefreshTokenFamily {
+    pub family: String,
+    pub username: String,
+    pub token_hash: String,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl RefreshTokenFamily {
+    pub fn new(family: String, username: String, token_hash: String) -> Self {
+        RefreshTokenFamily {
+            family,
+            username,
+            token_hash,
+            created_at: None,
+        }
+    }
+}
+
+/// Named set of permission grants shared by its member users
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Group {
+    pub id: Option<i32>,
+    pub created_at: Option<NaiveDateTime>,
+    pub name: String,
+    pub permissions: Vec<String>,
+}
+
+impl Group {
+    pub fn new(name: String, permissions: Vec<String>) -> Self {
+        Group {
+            id: None,
+            created_at: None,
+            name,
+            permissions,
+        }
+    }
+}
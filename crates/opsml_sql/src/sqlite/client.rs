@@ -1,23 +1,35 @@
-use crate::base::SqlClient;
+use crate::base::{
+    duplicate_versions_error, get_registry_type, get_search_tokens, map_write_error,
+    pre_release_bounds, SqlClient, SqlTransaction, CARD_REGISTRIES, SOFT_DELETE_MIGRATION,
+    UNIQUE_VERSION_MIGRATION,
+};
 
 use crate::schemas::schema::ProjectCardRecord;
 use crate::schemas::schema::{
-    AuditCardRecord, Card, CardResults, CardSummary, DataCardRecord, HardwareMetricsRecord,
-    MetricRecord, ModelCardRecord, ParameterRecord, PipelineCardRecord, QueryStats, Repository,
-    RunCardRecord, User, VersionResult,
+    AuditCardRecord, Card, CardAlias, CardAliasHistoryRecord, CardCursor, CardHistoryRecord,
+    CardResults, CardSearchResult, CardSummary, ChildMetricSummaryRecord, DataCardRecord,
+    DeletedCard, Group, HardwareMetricsRecord, LineageEdge, LineageNode, MetricRecord,
+    MetricSummaryRecord, ModelCardRecord, ParameterRecord, PipelineCardRecord, QueryStats,
+    RefreshTokenFamily, Repository, RunCardRecord, RunQueryResult, RunStatusRecord, SummaryCursor,
+    User, VersionResult,
 };
 use crate::sqlite::helper::SqliteQueryHelper;
 use async_trait::async_trait;
 use opsml_error::error::SqlError;
 use opsml_logging::logging::setup_logging;
 use opsml_settings::config::OpsmlDatabaseSettings;
-use opsml_types::{CardQueryArgs, CardSQLTableNames};
-use opsml_utils::semver::VersionValidator;
+use opsml_types::{
+    CardQueryArgs, CardSQLTableNames, CardStatus, GetMetricRequest, RunQueryRequest, RunStatus,
+    VersionScheme, REGISTRY_PAGE_SIZE,
+};
+use opsml_utils::semver::{VersionParser, VersionValidator};
+use opsml_utils::utils::get_utc_datetime;
 use semver::Version;
 use sqlx::{
-    sqlite::{SqlitePoolOptions, SqliteRow},
+    pool::PoolConnection,
+    sqlite::{SqliteConnection, SqlitePoolOptions, SqliteRow},
     types::chrono::NaiveDateTime,
-    FromRow, Pool, Row, Sqlite,
+    FromRow, Pool, Row, Sqlite, Transaction,
 };
 use tracing::info;
 
@@ -52,246 +64,96 @@ impl FromRow<'_, SqliteRow> for User {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct SqliteClient {
-    pub pool: Pool<Sqlite>,
-}
-
-#[async_trait]
-impl SqlClient for SqliteClient {
-    async fn new(settings: &OpsmlDatabaseSettings) -> Result<Self, SqlError> {
-        // if the connection uri is not in memory, create the file
-        if !settings.connection_uri.contains(":memory:") {
-            // strip "sqlite://" from the connection uri
-            let uri = settings.connection_uri.replace("sqlite://", "");
-            let path = std::path::Path::new(&uri);
-
-            // check if the file exists
-            if !path.exists() {
-                // Ensure the parent directory exists
-                if let Some(parent) = path.parent() {
-                    if !parent.exists() {
-                        std::fs::create_dir_all(parent)
-                            .map_err(|e| {
-                                SqlError::FileError(format!(
-                                    "Failed to create SQLite file parents: {} with error: {}",
-                                    &uri, e
-                                ))
-                            })
-                            .unwrap();
-                    }
-                }
-
-                // create the file
-                std::fs::File::create(&uri)
-                    .map_err(|e| {
-                        SqlError::FileError(format!(
-                            "Failed to create SQLite file: {} with error: {}",
-                            &uri, e
-                        ))
-                    })
-                    .unwrap();
-            }
-        }
-
-        let pool = SqlitePoolOptions::new()
-            .max_connections(settings.max_connections)
-            .connect(&settings.connection_uri)
-            .await
-            .map_err(|e| SqlError::ConnectionError(format!("{}", e)))?;
-
-        // attempt to start logging, silently fail if it fails
-        let _ = (setup_logging().await).is_ok();
-
-        let client = Self { pool };
+impl FromRow<'_, SqliteRow> for Group {
+    fn from_row(row: &SqliteRow) -> Result<Self, sqlx::Error> {
+        let id: Option<i32> = row.try_get("id")?;
+        let created_at: Option<NaiveDateTime> = row.try_get("created_at")?;
+        let name: String = row.try_get("name")?;
 
-        // run migrations
-        client.run_migrations().await?;
+        let permissions: String = row.try_get("permissions")?;
+        let permissions: Vec<String> = serde_json::from_str(&permissions).unwrap_or_default();
 
-        Ok(client)
+        Ok(Group {
+            id,
+            created_at,
+            name,
+            permissions,
+        })
     }
-    async fn run_migrations(&self) -> Result<(), SqlError> {
-        info!("Running migrations");
-        sqlx::migrate!("src/sqlite/migrations")
-            .run(&self.pool)
-            .await
-            .map_err(|e| SqlError::MigrationError(format!("{}", e)))?;
+}
 
-        Ok(())
-    }
+#[derive(Debug, Clone)]
+pub struct SqliteClient {
+    pub pool: Pool<Sqlite>,
+}
 
-    /// Check if uid exists in the database for a table
-    ///
-    /// # Arguments
-    ///
-    /// * `table` - The table to query
-    /// * `uid` - The uid to check
-    ///
-    /// # Returns
-    ///
-    /// * `bool` - True if the uid exists, false otherwise
-    async fn check_uid_exists(
-        &self,
-        uid: &str,
+impl SqliteClient {
+    async fn query_deleted_cards(
+        conn: &mut SqliteConnection,
         table: &CardSQLTableNames,
-    ) -> Result<bool, SqlError> {
-        let query = SqliteQueryHelper::get_uid_query(table);
-        let exists: Option<String> = sqlx::query_scalar(&query)
+        deleted_before: Option<NaiveDateTime>,
+        uid: Option<&str>,
+    ) -> Result<Vec<DeletedCard>, SqlError> {
+        let query = SqliteQueryHelper::get_deleted_cards_query(table);
+        let cards: Vec<DeletedCard> = sqlx::query_as(&query)
+            .bind(deleted_before)
             .bind(uid)
-            .fetch_optional(&self.pool)
+            .fetch_all(&mut *conn)
             .await
             .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
 
-        Ok(exists.is_some())
-    }
-
-    /// Primary query for retrieving versions from the database. Mainly used to get most recent version when determining version increment
-    ///
-    /// # Arguments
-    ///
-    /// * `table` - The table to query
-    /// * `name` - The name of the card
-    /// * `repository` - The repository of the card
-    /// * `version` - The version of the card
-    ///
-    /// # Returns
-    ///
-    /// * `Vec<String>` - A vector of strings representing the sorted (desc) versions of the card
-    async fn get_versions(
-        &self,
-        table: &CardSQLTableNames,
-        name: &str,
-        repository: &str,
-        version: Option<&str>,
-    ) -> Result<Vec<String>, SqlError> {
-        // if version is None, get the latest version
-        let query = SqliteQueryHelper::get_versions_query(table, version)?;
-        let cards: Vec<VersionResult> = sqlx::query_as(&query)
-            .bind(name)
-            .bind(repository)
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
-
-        let versions = cards
-            .iter()
-            .map(|c| {
-                c.to_version()
-                    .map_err(|e| SqlError::VersionError(format!("{}", e)))
-            })
-            .collect::<Result<Vec<Version>, SqlError>>()?;
-
-        // sort semvers
-        VersionValidator::sort_semver_versions(versions, true)
-            .map_err(|e| SqlError::VersionError(format!("{}", e)))
+        Ok(cards)
     }
 
-    /// Query cards based on the query arguments
-    ///
-    /// # Arguments
-    ///
-    /// * `table` - The table to query
-    /// * `query_args` - The query arguments
-    ///
-    /// # Returns
-    ///
-    /// * `CardResults` - The results of the query
-    async fn query_cards(
-        &self,
-        table: &CardSQLTableNames,
-        query_args: &CardQueryArgs,
-    ) -> Result<CardResults, SqlError> {
-        let query = SqliteQueryHelper::get_query_cards_query(table, query_args)?;
-
-        match table {
-            CardSQLTableNames::Data => {
-                let card: Vec<DataCardRecord> = sqlx::query_as(&query)
-                    .bind(query_args.uid.as_ref())
-                    .bind(query_args.name.as_ref())
-                    .bind(query_args.repository.as_ref())
-                    .bind(query_args.max_date.as_ref())
-                    .bind(query_args.limit.unwrap_or(50))
-                    .fetch_all(&self.pool)
-                    .await
-                    .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
-
-                return Ok(CardResults::Data(card));
-            }
-            CardSQLTableNames::Model => {
-                let card: Vec<ModelCardRecord> = sqlx::query_as(&query)
-                    .bind(query_args.uid.as_ref())
-                    .bind(query_args.name.as_ref())
-                    .bind(query_args.repository.as_ref())
-                    .bind(query_args.max_date.as_ref())
-                    .bind(query_args.limit.unwrap_or(50))
-                    .fetch_all(&self.pool)
-                    .await
-                    .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
-
-                return Ok(CardResults::Model(card));
-            }
-            CardSQLTableNames::Run => {
-                let card: Vec<RunCardRecord> = sqlx::query_as(&query)
-                    .bind(query_args.uid.as_ref())
-                    .bind(query_args.name.as_ref())
-                    .bind(query_args.repository.as_ref())
-                    .bind(query_args.max_date.as_ref())
-                    .bind(query_args.limit.unwrap_or(50))
-                    .fetch_all(&self.pool)
-                    .await
-                    .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
-
-                return Ok(CardResults::Run(card));
-            }
-
-            CardSQLTableNames::Audit => {
-                let card: Vec<AuditCardRecord> = sqlx::query_as(&query)
-                    .bind(query_args.uid.as_ref())
-                    .bind(query_args.name.as_ref())
-                    .bind(query_args.repository.as_ref())
-                    .bind(query_args.max_date.as_ref())
-                    .bind(query_args.limit.unwrap_or(50))
-                    .fetch_all(&self.pool)
-                    .await
-                    .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+    /// Cards sharing a live version block the unique version index. Report them before
+    /// migrating instead of failing partway through the migration
+    async fn check_duplicate_versions(&self) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_migration_applied_query();
+        let applied = |version: i64| {
+            sqlx::query_scalar::<_, i64>(&query)
+                .bind(version)
+                .fetch_one(&self.pool)
+        };
 
-                return Ok(CardResults::Audit(card));
-            }
-            CardSQLTableNames::Pipeline => {
-                let card: Vec<PipelineCardRecord> = sqlx::query_as(&query)
-                    .bind(query_args.uid.as_ref())
-                    .bind(query_args.name.as_ref())
-                    .bind(query_args.repository.as_ref())
-                    .bind(query_args.max_date.as_ref())
-                    .bind(query_args.limit.unwrap_or(50))
-                    .fetch_all(&self.pool)
-                    .await
-                    .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+        // a new database has no migration table and no cards
+        match applied(UNIQUE_VERSION_MIGRATION).await {
+            Ok(0) => {}
+            _ => return Ok(()),
+        }
 
-                return Ok(CardResults::Pipeline(card));
-            }
-            CardSQLTableNames::Project => {
-                let card: Vec<ProjectCardRecord> = sqlx::query_as(&query)
-                    .bind(query_args.uid.as_ref())
-                    .bind(query_args.name.as_ref())
-                    .bind(query_args.repository.as_ref())
-                    .bind(query_args.max_date.as_ref())
-                    .bind(query_args.limit.unwrap_or(50))
-                    .fetch_all(&self.pool)
-                    .await
-                    .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+        let live_only = applied(SOFT_DELETE_MIGRATION)
+            .await
+            .map_err(|e| SqlError::MigrationError(format!("{}", e)))?
+            > 0;
+
+        let mut duplicates = Vec::new();
+        for (_, table) in CARD_REGISTRIES.iter() {
+            let query = SqliteQueryHelper::get_duplicate_versions_query(table, live_only);
+            let rows: Vec<(String, String, String, i64)> = sqlx::query_as(&query)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| SqlError::MigrationError(format!("{}", e)))?;
+
+            duplicates.extend(rows.into_iter().map(|(repository, name, version, count)| {
+                format!(
+                    "{} {}/{} {} ({} cards)",
+                    table, repository, name, version, count
+                )
+            }));
+        }
 
-                return Ok(CardResults::Project(card));
-            }
-            _ => {
-                return Err(SqlError::QueryError(
-                    "Invalid table name for query".to_string(),
-                ));
-            }
+        if duplicates.is_empty() {
+            Ok(())
+        } else {
+            Err(duplicate_versions_error(&duplicates))
         }
     }
 
-    async fn insert_card(&self, table: &CardSQLTableNames, card: &Card) -> Result<(), SqlError> {
+    async fn insert_card_record(
+        conn: &mut SqliteConnection,
+        table: &CardSQLTableNames,
+        card: &Card,
+    ) -> Result<(), SqlError> {
         match table {
             CardSQLTableNames::Data => match card {
                 Card::Data(data) => {
@@ -314,9 +176,12 @@ impl SqlClient for SqliteClient {
                         .bind(&data.auditcard_uid)
                         .bind(&data.pre_tag)
                         .bind(&data.build_tag)
-                        .execute(&self.pool)
+                        .bind(data.created_at)
+                        .bind(&data.status)
+                        .bind(data.yanked)
+                        .execute(&mut *conn)
                         .await
-                        .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+                        .map_err(map_write_error)?;
                     Ok(())
                 }
                 _ => {
@@ -349,9 +214,12 @@ impl SqlClient for SqliteClient {
                         .bind(&model.auditcard_uid)
                         .bind(&model.pre_tag)
                         .bind(&model.build_tag)
-                        .execute(&self.pool)
+                        .bind(model.created_at)
+                        .bind(&model.status)
+                        .bind(model.yanked)
+                        .execute(&mut *conn)
                         .await
-                        .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+                        .map_err(map_write_error)?;
                     Ok(())
                 }
                 _ => {
@@ -382,9 +250,13 @@ impl SqlClient for SqliteClient {
                         .bind(&run.compute_environment)
                         .bind(&run.pre_tag)
                         .bind(&run.build_tag)
-                        .execute(&self.pool)
+                        .bind(run.created_at)
+                        .bind(&run.status)
+                        .bind(run.yanked)
+                        .bind(&run.parent_uid)
+                        .execute(&mut *conn)
                         .await
-                        .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+                        .map_err(map_write_error)?;
                     Ok(())
                 }
                 _ => {
@@ -413,9 +285,12 @@ impl SqlClient for SqliteClient {
                         .bind(&audit.runcard_uids)
                         .bind(&audit.pre_tag)
                         .bind(&audit.build_tag)
-                        .execute(&self.pool)
+                        .bind(audit.created_at)
+                        .bind(&audit.status)
+                        .bind(audit.yanked)
+                        .execute(&mut *conn)
                         .await
-                        .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+                        .map_err(map_write_error)?;
                     Ok(())
                 }
                 _ => {
@@ -444,9 +319,12 @@ impl SqlClient for SqliteClient {
                         .bind(&pipeline.runcard_uids)
                         .bind(&pipeline.pre_tag)
                         .bind(&pipeline.build_tag)
-                        .execute(&self.pool)
+                        .bind(pipeline.created_at)
+                        .bind(&pipeline.status)
+                        .bind(pipeline.yanked)
+                        .execute(&mut *conn)
                         .await
-                        .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+                        .map_err(map_write_error)?;
                     Ok(())
                 }
                 _ => {
@@ -469,9 +347,12 @@ impl SqlClient for SqliteClient {
                         .bind(&project.version)
                         .bind(&project.pre_tag)
                         .bind(&project.build_tag)
-                        .execute(&self.pool)
+                        .bind(project.created_at)
+                        .bind(&project.status)
+                        .bind(project.yanked)
+                        .execute(&mut *conn)
                         .await
-                        .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+                        .map_err(map_write_error)?;
                     Ok(())
                 }
                 _ => {
@@ -486,39 +367,419 @@ impl SqlClient for SqliteClient {
                     "Invalid table name for insert".to_string(),
                 ));
             }
+        }?;
+
+        Ok(())
+    }
+
+    /// Fail with a conflict if a live card already has the card's version
+    async fn check_version_available(
+        conn: &mut SqliteConnection,
+        table: &CardSQLTableNames,
+        card: &Card,
+    ) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_active_version_count_query(table);
+        let active: i64 = sqlx::query_scalar(&query)
+            .bind(card.name())
+            .bind(card.repository())
+            .bind(card.version())
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        if active > 0 {
+            return Err(SqlError::ConflictError(format!(
+                "{}/{} version {} already exists",
+                card.repository(),
+                card.name(),
+                card.version()
+            )));
         }
+
+        Ok(())
     }
 
-    async fn update_card(&self, table: &CardSQLTableNames, card: &Card) -> Result<(), SqlError> {
-        match table {
-            CardSQLTableNames::Data => match card {
-                Card::Data(data) => {
-                    let query = SqliteQueryHelper::get_datacard_update_query();
-                    sqlx::query(&query)
-                        .bind(&data.app_env)
-                        .bind(&data.name)
-                        .bind(&data.repository)
-                        .bind(data.major)
-                        .bind(data.minor)
-                        .bind(data.patch)
-                        .bind(&data.version)
-                        .bind(&data.contact)
-                        .bind(&data.data_type)
-                        .bind(&data.interface_type)
-                        .bind(&data.tags)
-                        .bind(&data.runcard_uid)
-                        .bind(&data.pipelinecard_uid)
-                        .bind(&data.auditcard_uid)
-                        .bind(&data.pre_tag)
-                        .bind(&data.build_tag)
-                        .bind(&data.uid)
-                        .execute(&self.pool)
-                        .await
-                        .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
-                    Ok(())
-                }
-                _ => {
-                    return Err(SqlError::QueryError(
+    async fn acquire(&self) -> Result<PoolConnection<Sqlite>, SqlError> {
+        self.pool
+            .acquire()
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))
+    }
+
+    async fn insert_lineage_records(
+        conn: &mut SqliteConnection,
+        card: &Card,
+    ) -> Result<(), SqlError> {
+        let uid = card.uid();
+
+        sqlx::query(&SqliteQueryHelper::get_lineage_delete_query())
+            .bind(&uid)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        let edges = card.lineage_edges();
+        if edges.is_empty() {
+            return Ok(());
+        }
+
+        let query = SqliteQueryHelper::get_lineage_insert_query(edges.len());
+        let mut query_builder = sqlx::query(&query);
+
+        for edge in &edges {
+            query_builder = query_builder
+                .bind(&edge.upstream_uid)
+                .bind(&edge.upstream_type)
+                .bind(&edge.downstream_uid)
+                .bind(&edge.downstream_type)
+                .bind(&uid);
+        }
+
+        query_builder
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn insert_history_record(
+        conn: &mut SqliteConnection,
+        record: &CardHistoryRecord,
+    ) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_card_history_insert_query();
+        sqlx::query(&query)
+            .bind(&record.uid)
+            .bind(&record.registry_type)
+            .bind(&record.action)
+            .bind(&record.username)
+            .bind(&record.changes)
+            .bind(record.created_at)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn query_uid_exists(
+        conn: &mut SqliteConnection,
+        uid: &str,
+        table: &CardSQLTableNames,
+    ) -> Result<bool, SqlError> {
+        let query = SqliteQueryHelper::get_uid_query(table);
+        let exists: Option<String> = sqlx::query_scalar(&query)
+            .bind(uid)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(exists.is_some())
+    }
+
+    async fn query_project_id(
+        conn: &mut SqliteConnection,
+        project_name: &str,
+        repository: &str,
+    ) -> Result<i32, SqlError> {
+        let query = SqliteQueryHelper::get_project_id_query();
+
+        let project_id: i32 = sqlx::query_scalar(&query)
+            .bind(project_name)
+            .bind(repository)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(project_id)
+    }
+
+    async fn insert_metric_records(
+        conn: &mut SqliteConnection,
+        records: &[MetricRecord],
+    ) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_run_metrics_insert_query(records.len());
+
+        let mut query_builder = sqlx::query(&query);
+
+        for r in records {
+            query_builder = query_builder
+                .bind(&r.run_uid)
+                .bind(&r.name)
+                .bind(r.value)
+                .bind(r.step)
+                .bind(r.timestamp)
+                .bind(r.created_at);
+        }
+
+        query_builder
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn insert_hardware_records(
+        conn: &mut SqliteConnection,
+        record: &[HardwareMetricsRecord],
+    ) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_hardware_metrics_insert_query(record.len());
+
+        let mut query_builder = sqlx::query(&query);
+
+        for r in record {
+            query_builder = query_builder
+                .bind(&r.run_uid)
+                .bind(r.created_at)
+                .bind(r.cpu_percent_utilization)
+                .bind(&r.cpu_percent_per_core)
+                .bind(r.compute_overall)
+                .bind(r.compute_utilized)
+                .bind(r.load_avg)
+                .bind(r.sys_ram_total)
+                .bind(r.sys_ram_used)
+                .bind(r.sys_ram_available)
+                .bind(r.sys_ram_percent_used)
+                .bind(r.sys_swap_total)
+                .bind(r.sys_swap_used)
+                .bind(r.sys_swap_free)
+                .bind(r.sys_swap_percent)
+                .bind(r.bytes_recv)
+                .bind(r.bytes_sent)
+                .bind(r.gpu_percent_utilization)
+                .bind(&r.gpu_percent_per_core);
+        }
+
+        query_builder
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn insert_parameter_records(
+        conn: &mut SqliteConnection,
+        records: &[ParameterRecord],
+    ) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_run_parameters_insert_query(records.len());
+
+        let mut query_builder = sqlx::query(&query);
+
+        for record in records {
+            query_builder = query_builder
+                .bind(&record.run_uid)
+                .bind(&record.name)
+                .bind(&record.value)
+                .bind(record.created_at);
+        }
+
+        query_builder
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn query_card_records(
+        conn: &mut SqliteConnection,
+        table: &CardSQLTableNames,
+        query_args: &CardQueryArgs,
+    ) -> Result<CardResults, SqlError> {
+        let (query, bindings) = SqliteQueryHelper::get_query_cards_query(table, query_args)?;
+        // pre-release precedence is checked after the query, so fetch every candidate
+        let version_filter = pre_release_bounds(query_args)?;
+        let limit = query_args.limit.unwrap_or(50);
+        let fetch_limit = match version_filter {
+            Some(_) => i32::MAX,
+            None => limit,
+        };
+        let cursor = query_args
+            .cursor
+            .as_deref()
+            .map(CardCursor::decode)
+            .transpose()?;
+        let cursor_created_at = cursor.as_ref().map(|c| c.created_at);
+        let cursor_uid = cursor.as_ref().map(|c| c.uid.as_str());
+
+        let mut results = match table {
+            CardSQLTableNames::Data => {
+                let mut query_builder = sqlx::query_as::<sqlx::Sqlite, DataCardRecord>(&query)
+                    .bind(query_args.uid.as_ref())
+                    .bind(query_args.name.as_ref())
+                    .bind(query_args.repository.as_ref())
+                    .bind(query_args.max_date.as_ref())
+                    .bind(fetch_limit)
+                    .bind(cursor_created_at)
+                    .bind(cursor_uid);
+
+                for binding in &bindings {
+                    query_builder = query_builder.bind(binding);
+                }
+
+                let card: Vec<DataCardRecord> = query_builder
+                    .fetch_all(&mut *conn)
+                    .await
+                    .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+                CardResults::Data(card)
+            }
+            CardSQLTableNames::Model => {
+                let mut query_builder = sqlx::query_as::<sqlx::Sqlite, ModelCardRecord>(&query)
+                    .bind(query_args.uid.as_ref())
+                    .bind(query_args.name.as_ref())
+                    .bind(query_args.repository.as_ref())
+                    .bind(query_args.max_date.as_ref())
+                    .bind(fetch_limit)
+                    .bind(cursor_created_at)
+                    .bind(cursor_uid);
+
+                for binding in &bindings {
+                    query_builder = query_builder.bind(binding);
+                }
+
+                let card: Vec<ModelCardRecord> = query_builder
+                    .fetch_all(&mut *conn)
+                    .await
+                    .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+                CardResults::Model(card)
+            }
+            CardSQLTableNames::Run => {
+                let mut query_builder = sqlx::query_as::<sqlx::Sqlite, RunCardRecord>(&query)
+                    .bind(query_args.uid.as_ref())
+                    .bind(query_args.name.as_ref())
+                    .bind(query_args.repository.as_ref())
+                    .bind(query_args.max_date.as_ref())
+                    .bind(fetch_limit)
+                    .bind(cursor_created_at)
+                    .bind(cursor_uid);
+
+                for binding in &bindings {
+                    query_builder = query_builder.bind(binding);
+                }
+
+                let card: Vec<RunCardRecord> = query_builder
+                    .fetch_all(&mut *conn)
+                    .await
+                    .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+                CardResults::Run(card)
+            }
+
+            CardSQLTableNames::Audit => {
+                let mut query_builder = sqlx::query_as::<sqlx::Sqlite, AuditCardRecord>(&query)
+                    .bind(query_args.uid.as_ref())
+                    .bind(query_args.name.as_ref())
+                    .bind(query_args.repository.as_ref())
+                    .bind(query_args.max_date.as_ref())
+                    .bind(fetch_limit)
+                    .bind(cursor_created_at)
+                    .bind(cursor_uid);
+
+                for binding in &bindings {
+                    query_builder = query_builder.bind(binding);
+                }
+
+                let card: Vec<AuditCardRecord> = query_builder
+                    .fetch_all(&mut *conn)
+                    .await
+                    .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+                CardResults::Audit(card)
+            }
+            CardSQLTableNames::Pipeline => {
+                let mut query_builder = sqlx::query_as::<sqlx::Sqlite, PipelineCardRecord>(&query)
+                    .bind(query_args.uid.as_ref())
+                    .bind(query_args.name.as_ref())
+                    .bind(query_args.repository.as_ref())
+                    .bind(query_args.max_date.as_ref())
+                    .bind(fetch_limit)
+                    .bind(cursor_created_at)
+                    .bind(cursor_uid);
+
+                for binding in &bindings {
+                    query_builder = query_builder.bind(binding);
+                }
+
+                let card: Vec<PipelineCardRecord> = query_builder
+                    .fetch_all(&mut *conn)
+                    .await
+                    .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+                CardResults::Pipeline(card)
+            }
+            CardSQLTableNames::Project => {
+                let mut query_builder = sqlx::query_as::<sqlx::Sqlite, ProjectCardRecord>(&query)
+                    .bind(query_args.uid.as_ref())
+                    .bind(query_args.name.as_ref())
+                    .bind(query_args.repository.as_ref())
+                    .bind(query_args.max_date.as_ref())
+                    .bind(fetch_limit)
+                    .bind(cursor_created_at)
+                    .bind(cursor_uid);
+
+                for binding in &bindings {
+                    query_builder = query_builder.bind(binding);
+                }
+
+                let card: Vec<ProjectCardRecord> = query_builder
+                    .fetch_all(&mut *conn)
+                    .await
+                    .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+                CardResults::Project(card)
+            }
+            _ => {
+                return Err(SqlError::QueryError(
+                    "Invalid table name for query".to_string(),
+                ));
+            }
+        };
+
+        if let Some(bounds) = version_filter {
+            results.retain_versions(&bounds, limit as usize);
+        }
+
+        Ok(results)
+    }
+
+    async fn update_card_record(
+        conn: &mut SqliteConnection,
+        table: &CardSQLTableNames,
+        card: &Card,
+    ) -> Result<(), SqlError> {
+        match table {
+            CardSQLTableNames::Data => match card {
+                Card::Data(data) => {
+                    let query = SqliteQueryHelper::get_datacard_update_query();
+                    sqlx::query(&query)
+                        .bind(&data.app_env)
+                        .bind(&data.name)
+                        .bind(&data.repository)
+                        .bind(data.major)
+                        .bind(data.minor)
+                        .bind(data.patch)
+                        .bind(&data.version)
+                        .bind(&data.contact)
+                        .bind(&data.data_type)
+                        .bind(&data.interface_type)
+                        .bind(&data.tags)
+                        .bind(&data.runcard_uid)
+                        .bind(&data.pipelinecard_uid)
+                        .bind(&data.auditcard_uid)
+                        .bind(&data.pre_tag)
+                        .bind(&data.build_tag)
+                        .bind(&data.uid)
+                        .execute(&mut *conn)
+                        .await
+                        .map_err(map_write_error)?;
+                    Ok(())
+                }
+                _ => {
+                    return Err(SqlError::QueryError(
                         "Invalid card type for insert".to_string(),
                     ));
                 }
@@ -547,9 +808,9 @@ impl SqlClient for SqliteClient {
                         .bind(&model.pre_tag)
                         .bind(&model.build_tag)
                         .bind(&model.uid)
-                        .execute(&self.pool)
+                        .execute(&mut *conn)
                         .await
-                        .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+                        .map_err(map_write_error)?;
                     Ok(())
                 }
                 _ => {
@@ -579,10 +840,11 @@ impl SqlClient for SqliteClient {
                         .bind(&run.compute_environment)
                         .bind(&run.pre_tag)
                         .bind(&run.build_tag)
+                        .bind(&run.parent_uid)
                         .bind(&run.uid)
-                        .execute(&self.pool)
+                        .execute(&mut *conn)
                         .await
-                        .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+                        .map_err(map_write_error)?;
                     Ok(())
                 }
                 _ => {
@@ -611,9 +873,9 @@ impl SqlClient for SqliteClient {
                         .bind(&audit.pre_tag)
                         .bind(&audit.build_tag)
                         .bind(&audit.uid)
-                        .execute(&self.pool)
+                        .execute(&mut *conn)
                         .await
-                        .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+                        .map_err(map_write_error)?;
                     Ok(())
                 }
                 _ => {
@@ -642,9 +904,9 @@ impl SqlClient for SqliteClient {
                         .bind(&pipeline.pre_tag)
                         .bind(&pipeline.build_tag)
                         .bind(&pipeline.uid)
-                        .execute(&self.pool)
+                        .execute(&mut *conn)
                         .await
-                        .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+                        .map_err(map_write_error)?;
                     Ok(())
                 }
                 _ => {
@@ -659,389 +921,2504 @@ impl SqlClient for SqliteClient {
                     "Invalid table name for insert".to_string(),
                 ));
             }
-        }
+        }?;
+
+        Self::insert_lineage_records(conn, card).await
     }
 
-    /// Get unique repository names
-    ///
-    /// # Arguments
-    ///
-    /// * `table` - The table to query
-    ///
-    /// # Returns
-    ///
-    /// * `Vec<String>` - A vector of unique repository names
-    async fn get_unique_repository_names(
-        &self,
+    async fn delete_card_record(
+        conn: &mut SqliteConnection,
         table: &CardSQLTableNames,
-    ) -> Result<Vec<String>, SqlError> {
-        let query = format!("SELECT DISTINCT repository FROM {}", table);
-        let repos: Vec<Repository> = sqlx::query_as(&query)
-            .fetch_all(&self.pool)
+        uid: &str,
+        deleted_by: &str,
+    ) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_card_delete_query(table);
+        sqlx::query(&query)
+            .bind(get_utc_datetime())
+            .bind(deleted_by)
+            .bind(uid)
+            .execute(&mut *conn)
             .await
             .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
 
-        Ok(repos.iter().map(|r| r.repository.clone()).collect())
+        Ok(())
     }
 
-    /// Query stats for a table
-    ///
-    /// # Arguments
-    ///
-    /// * `table` - The table to query
-    /// * `search_term` - The search term to query
-    ///
-    /// # Returns
-    ///
-    /// * `HashMap<String, i32>` - A hashmap of the stats
-    ///
-    async fn query_stats(
-        &self,
+    async fn restore_card_record(
+        conn: &mut SqliteConnection,
         table: &CardSQLTableNames,
-        search_term: Option<&str>,
-    ) -> Result<QueryStats, SqlError> {
-        let query = SqliteQueryHelper::get_query_stats_query(table);
-
-        // if search_term is not None, format with %search_term%, else None
-        let stats: QueryStats = sqlx::query_as(&query)
-            .bind(search_term.map(|term| format!("%{}%", term)))
-            .fetch_one(&self.pool)
+        uid: &str,
+    ) -> Result<(), SqlError> {
+        let card = Self::query_deleted_cards(conn, table, None, Some(uid))
+            .await?
+            .pop()
+            .ok_or_else(|| SqlError::NotFoundError(format!("Card {} is not in the trash", uid)))?;
+
+        // the version may have been re-registered while the card was in the trash
+        let query = SqliteQueryHelper::get_active_version_count_query(table);
+        let active: i64 = sqlx::query_scalar(&query)
+            .bind(&card.name)
+            .bind(&card.repository)
+            .bind(&card.version)
+            .fetch_one(&mut *conn)
             .await
             .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
 
-        Ok(stats)
+        if active > 0 {
+            return Err(SqlError::ConflictError(format!(
+                "{}/{} version {} already exists",
+                card.repository, card.name, card.version
+            )));
+        }
+
+        let query = SqliteQueryHelper::get_card_restore_query(table);
+        sqlx::query(&query)
+            .bind(uid)
+            .execute(&mut *conn)
+            .await
+            .map_err(map_write_error)?;
+
+        Ok(())
     }
 
-    /// Query a page of cards
-    ///
-    /// # Arguments
-    ///
-    /// * `sort_by` - The field to sort by
-    /// * `page` - The page number
-    /// * `search_term` - The search term to query
-    /// * `repository` - The repository to query
-    /// * `table` - The table to query
-    ///
-    /// # Returns
-    ///
-    /// * `Vec<CardSummary>` - A vector of card summaries
-    async fn query_page(
-        &self,
-        sort_by: &str,
-        page: i32,
-        search_term: Option<&str>,
-        repository: Option<&str>,
+    async fn update_card_status_record(
+        conn: &mut SqliteConnection,
         table: &CardSQLTableNames,
-    ) -> Result<Vec<CardSummary>, SqlError> {
-        let query = SqliteQueryHelper::get_query_page_query(table, sort_by);
+        uid: &str,
+        status: &CardStatus,
+    ) -> Result<CardStatus, SqlError> {
+        let query = SqliteQueryHelper::get_card_status_query(table);
+        let current: Option<String> = sqlx::query_scalar(&query)
+            .bind(uid)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
 
-        let lower_bound = page * 30;
-        let upper_bound = lower_bound + 30;
+        let current = current
+            .and_then(|status| CardStatus::from_string(&status))
+            .ok_or_else(|| SqlError::NotFoundError(format!("Card {} not found", uid)))?;
 
-        let records: Vec<CardSummary> = sqlx::query_as(&query)
-            .bind(repository)
-            .bind(search_term)
-            .bind(search_term.map(|term| format!("%{}%", term)))
-            .bind(lower_bound)
-            .bind(upper_bound)
-            .fetch_all(&self.pool)
+        if !current.can_transition_to(status) {
+            return Err(SqlError::ConflictError(format!(
+                "Card {} cannot move from {} to {}",
+                uid, current, status
+            )));
+        }
+
+        // compare-and-set so a concurrent transition is not silently overwritten
+        let query = SqliteQueryHelper::get_card_status_update_query(table);
+        let result = sqlx::query(&query)
+            .bind(status.as_str())
+            .bind(uid)
+            .bind(current.as_str())
+            .execute(&mut *conn)
             .await
             .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
 
-        Ok(records)
+        if result.rows_affected() == 0 {
+            return Err(SqlError::ConflictError(format!(
+                "Status of card {} changed during the update",
+                uid
+            )));
+        }
+
+        Ok(current)
     }
 
-    async fn delete_card(&self, table: &CardSQLTableNames, uid: &str) -> Result<(), SqlError> {
-        let query = format!("DELETE FROM {} WHERE uid = ?1", table);
-        sqlx::query(&query)
+    async fn yank_card_record(
+        conn: &mut SqliteConnection,
+        table: &CardSQLTableNames,
+        uid: &str,
+        yanked: bool,
+    ) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_card_yank_query(table);
+        let result = sqlx::query(&query)
+            .bind(yanked)
             .bind(uid)
-            .execute(&self.pool)
+            .execute(&mut *conn)
             .await
             .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
 
+        if result.rows_affected() == 0 {
+            return Err(SqlError::NotFoundError(format!("Card {} not found", uid)));
+        }
+
         Ok(())
     }
 
-    async fn get_project_id(&self, project_name: &str, repository: &str) -> Result<i32, SqlError> {
-        let query = SqliteQueryHelper::get_project_id_query();
+    async fn insert_group_record(
+        conn: &mut SqliteConnection,
+        group: &Group,
+    ) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_group_insert_query();
 
-        let project_id: i32 = sqlx::query_scalar(&query)
-            .bind(project_name)
-            .bind(repository)
-            .fetch_one(&self.pool)
-            .await
+        let permissions = serde_json::to_string(&group.permissions)
             .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
 
-        Ok(project_id)
-    }
-
-    async fn insert_run_metric(&self, record: &MetricRecord) -> Result<(), SqlError> {
-        let query = SqliteQueryHelper::get_run_metric_insert_query();
-
         sqlx::query(&query)
-            .bind(&record.run_uid)
-            .bind(&record.name)
-            .bind(record.value)
-            .bind(record.step)
-            .bind(record.timestamp)
-            .execute(&self.pool)
+            .bind(&group.name)
+            .bind(&permissions)
+            .bind(group.created_at)
+            .execute(&mut *conn)
             .await
-            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+            .map_err(map_write_error)?;
 
         Ok(())
     }
 
-    async fn insert_run_metrics<'life1>(
-        &self,
-        records: &'life1 [MetricRecord],
+    async fn add_group_member_record(
+        conn: &mut SqliteConnection,
+        name: &str,
+        username: &str,
     ) -> Result<(), SqlError> {
-        let query = SqliteQueryHelper::get_run_metrics_insert_query(records.len());
-
-        let mut query_builder = sqlx::query(&query);
+        let query = SqliteQueryHelper::get_group_member_insert_query();
 
-        for r in records {
-            query_builder = query_builder
-                .bind(&r.run_uid)
-                .bind(&r.name)
-                .bind(r.value)
-                .bind(r.step)
-                .bind(r.timestamp);
-        }
-
-        query_builder
-            .execute(&self.pool)
+        sqlx::query(&query)
+            .bind(name)
+            .bind(username)
+            .execute(&mut *conn)
             .await
             .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
 
         Ok(())
     }
+}
 
-    async fn get_run_metric<'life2>(
-        &self,
-        uid: &str,
-        names: &'life2 [String],
-    ) -> Result<Vec<MetricRecord>, SqlError> {
-        let (query, bindings) = SqliteQueryHelper::get_run_metric_query(names);
-        let mut query_builder = sqlx::query_as::<sqlx::Sqlite, MetricRecord>(&query).bind(uid);
+pub struct SqliteTransaction {
+    tx: Transaction<'static, Sqlite>,
+}
 
-        for binding in bindings {
-            query_builder = query_builder.bind(binding);
-        }
+#[async_trait]
+impl SqlTransaction for SqliteTransaction {
+    async fn insert_card(
+        &mut self,
+        table: &CardSQLTableNames,
+        card: &Card,
+    ) -> Result<(), SqlError> {
+        SqliteClient::check_version_available(&mut self.tx, table, card).await?;
+        SqliteClient::insert_card_record(&mut self.tx, table, card).await?;
+        SqliteClient::insert_lineage_records(&mut self.tx, card).await
+    }
 
-        let records: Vec<MetricRecord> = query_builder
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+    async fn insert_card_history(&mut self, record: &CardHistoryRecord) -> Result<(), SqlError> {
+        SqliteClient::insert_history_record(&mut self.tx, record).await
+    }
 
-        Ok(records)
+    async fn check_uid_exists(
+        &mut self,
+        uid: &str,
+        table: &CardSQLTableNames,
+    ) -> Result<bool, SqlError> {
+        SqliteClient::query_uid_exists(&mut self.tx, uid, table).await
     }
 
-    async fn get_run_metric_names(&self, uid: &str) -> Result<Vec<String>, SqlError> {
-        let query = format!(
-            "SELECT DISTINCT name FROM {} WHERE run_uid = ?1",
-            CardSQLTableNames::Metrics
-        );
+    async fn get_project_id(
+        &mut self,
+        project_name: &str,
+        repository: &str,
+    ) -> Result<i32, SqlError> {
+        SqliteClient::query_project_id(&mut self.tx, project_name, repository).await
+    }
 
-        let records: Vec<String> = sqlx::query_scalar(&query)
-            .bind(uid)
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+    async fn insert_run_metrics<'life1>(
+        &mut self,
+        records: &'life1 [MetricRecord],
+    ) -> Result<(), SqlError> {
+        SqliteClient::insert_metric_records(&mut self.tx, records).await
+    }
 
-        Ok(records)
+    async fn insert_run_parameters<'life1>(
+        &mut self,
+        records: &'life1 [ParameterRecord],
+    ) -> Result<(), SqlError> {
+        SqliteClient::insert_parameter_records(&mut self.tx, records).await
     }
 
     async fn insert_hardware_metrics<'life1>(
-        &self,
-        record: &'life1 [HardwareMetricsRecord],
+        &mut self,
+        records: &'life1 [HardwareMetricsRecord],
     ) -> Result<(), SqlError> {
-        let query = SqliteQueryHelper::get_hardware_metrics_insert_query(record.len());
-
-        let mut query_builder = sqlx::query(&query);
-
-        for r in record {
-            query_builder = query_builder
-                .bind(&r.run_uid)
-                .bind(r.created_at)
-                .bind(r.cpu_percent_utilization)
-                .bind(&r.cpu_percent_per_core)
-                .bind(r.compute_overall)
-                .bind(r.compute_utilized)
-                .bind(r.load_avg)
-                .bind(r.sys_ram_total)
-                .bind(r.sys_ram_used)
-                .bind(r.sys_ram_available)
-                .bind(r.sys_ram_percent_used)
-                .bind(r.sys_swap_total)
-                .bind(r.sys_swap_used)
-                .bind(r.sys_swap_free)
-                .bind(r.sys_swap_percent)
-                .bind(r.bytes_recv)
-                .bind(r.bytes_sent)
-                .bind(r.gpu_percent_utilization)
-                .bind(&r.gpu_percent_per_core);
-        }
+        SqliteClient::insert_hardware_records(&mut self.tx, records).await
+    }
 
-        query_builder
-            .execute(&self.pool)
-            .await
-            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+    async fn query_cards(
+        &mut self,
+        table: &CardSQLTableNames,
+        query_args: &CardQueryArgs,
+    ) -> Result<CardResults, SqlError> {
+        SqliteClient::query_card_records(&mut self.tx, table, query_args).await
+    }
 
-        Ok(())
+    async fn update_card(
+        &mut self,
+        table: &CardSQLTableNames,
+        card: &Card,
+    ) -> Result<(), SqlError> {
+        SqliteClient::update_card_record(&mut self.tx, table, card).await
     }
 
-    async fn get_hardware_metric(&self, uid: &str) -> Result<Vec<HardwareMetricsRecord>, SqlError> {
-        let query = SqliteQueryHelper::get_hardware_metric_query();
+    async fn delete_card(
+        &mut self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        deleted_by: &str,
+    ) -> Result<(), SqlError> {
+        SqliteClient::delete_card_record(&mut self.tx, table, uid, deleted_by).await
+    }
 
-        let records: Vec<HardwareMetricsRecord> = sqlx::query_as(&query)
-            .bind(uid)
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+    async fn restore_card(&mut self, table: &CardSQLTableNames, uid: &str) -> Result<(), SqlError> {
+        SqliteClient::restore_card_record(&mut self.tx, table, uid).await
+    }
 
-        Ok(records)
+    async fn update_card_status(
+        &mut self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        status: &CardStatus,
+    ) -> Result<CardStatus, SqlError> {
+        SqliteClient::update_card_status_record(&mut self.tx, table, uid, status).await
     }
 
-    async fn insert_run_parameters<'life1>(
-        &self,
-        records: &'life1 [ParameterRecord],
+    async fn yank_card(
+        &mut self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        yanked: bool,
     ) -> Result<(), SqlError> {
-        let query = SqliteQueryHelper::get_run_parameters_insert_query(records.len());
+        SqliteClient::yank_card_record(&mut self.tx, table, uid, yanked).await
+    }
 
-        let mut query_builder = sqlx::query(&query);
+    async fn insert_group(&mut self, group: &Group) -> Result<(), SqlError> {
+        SqliteClient::insert_group_record(&mut self.tx, group).await
+    }
 
-        for record in records {
-            query_builder = query_builder
-                .bind(&record.run_uid)
-                .bind(&record.name)
-                .bind(&record.value);
-        }
+    async fn add_group_member(&mut self, name: &str, username: &str) -> Result<(), SqlError> {
+        SqliteClient::add_group_member_record(&mut self.tx, name, username).await
+    }
 
-        query_builder
-            .execute(&self.pool)
+    async fn commit(self) -> Result<(), SqlError> {
+        self.tx
+            .commit()
             .await
-            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))
+    }
 
-        Ok(())
+    async fn rollback(self) -> Result<(), SqlError> {
+        self.tx
+            .rollback()
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))
     }
+}
 
-    async fn get_run_parameter<'life2>(
-        &self,
-        uid: &str,
-        names: &'life2 [String],
-    ) -> Result<Vec<ParameterRecord>, SqlError> {
-        let (query, bindings) = SqliteQueryHelper::get_run_parameter_query(names);
-        let mut query_builder = sqlx::query_as::<_, ParameterRecord>(&query).bind(uid);
+#[async_trait]
+impl SqlClient for SqliteClient {
+    type Transaction = SqliteTransaction;
 
-        for binding in bindings {
-            query_builder = query_builder.bind(binding);
+    async fn new(settings: &OpsmlDatabaseSettings) -> Result<Self, SqlError> {
+        // if the connection uri is not in memory, create the file
+        if !settings.connection_uri.contains(":memory:") {
+            // strip "sqlite://" from the connection uri
+            let uri = settings.connection_uri.replace("sqlite://", "");
+            let path = std::path::Path::new(&uri);
+
+            // check if the file exists
+            if !path.exists() {
+                // Ensure the parent directory exists
+                if let Some(parent) = path.parent() {
+                    if !parent.exists() {
+                        std::fs::create_dir_all(parent)
+                            .map_err(|e| {
+                                SqlError::FileError(format!(
+                                    "Failed to create SQLite file parents: {} with error: {}",
+                                    &uri, e
+                                ))
+                            })
+                            .unwrap();
+                    }
+                }
+
+                // create the file
+                std::fs::File::create(&uri)
+                    .map_err(|e| {
+                        SqlError::FileError(format!(
+                            "Failed to create SQLite file: {} with error: {}",
+                            &uri, e
+                        ))
+                    })
+                    .unwrap();
+            }
         }
 
-        let records: Vec<ParameterRecord> = query_builder
-            .fetch_all(&self.pool)
+        let pool = SqlitePoolOptions::new()
+            .max_connections(settings.max_connections)
+            .connect(&settings.connection_uri)
             .await
-            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+            .map_err(|e| SqlError::ConnectionError(format!("{}", e)))?;
 
-        Ok(records)
-    }
+        // attempt to start logging, silently fail if it fails
+        let _ = (setup_logging().await).is_ok();
 
-    async fn insert_user(&self, user: &User) -> Result<(), SqlError> {
-        let query = SqliteQueryHelper::get_user_insert_query();
+        let client = Self { pool };
 
-        let group_permissions = serde_json::to_string(&user.group_permissions)
-            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+        // run migrations
+        client.run_migrations().await?;
 
-        let permissions = serde_json::to_string(&user.permissions)
-            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+        Ok(client)
+    }
+    async fn run_migrations(&self) -> Result<(), SqlError> {
+        info!("Running migrations");
+        self.check_duplicate_versions().await?;
 
-        sqlx::query(&query)
-            .bind(&user.username)
-            .bind(&user.password_hash)
-            .bind(&permissions)
-            .bind(&group_permissions)
-            .execute(&self.pool)
+        sqlx::migrate!("src/sqlite/migrations")
+            .run(&self.pool)
             .await
-            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+            .map_err(|e| SqlError::MigrationError(format!("{}", e)))?;
 
         Ok(())
     }
 
-    async fn get_user(&self, username: &str) -> Result<User, SqlError> {
-        let query = SqliteQueryHelper::get_user_query();
+    async fn begin(&self) -> Result<SqliteTransaction, SqlError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
 
-        let user: User = sqlx::query_as(&query)
-            .bind(username)
-            .fetch_one(&self.pool)
+        // take the write lock up front so a concurrent registration of the same version waits
+        // for this one and then sees its row, instead of failing on a lock upgrade
+        sqlx::query(&SqliteQueryHelper::get_write_lock_query())
+            .execute(&mut *tx)
             .await
             .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
 
-        Ok(user)
+        Ok(SqliteTransaction { tx })
     }
-    async fn update_user(&self, user: &User) -> Result<(), SqlError> {
-        let query = SqliteQueryHelper::get_user_update_query();
-        let group_permissions = serde_json::to_string(&user.group_permissions)
-            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
 
-        let permissions = serde_json::to_string(&user.permissions)
-            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+    /// Check if uid exists in the database for a table
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to query
+    /// * `uid` - The uid to check
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the uid exists, false otherwise
+    async fn check_uid_exists(
+        &self,
+        uid: &str,
+        table: &CardSQLTableNames,
+    ) -> Result<bool, SqlError> {
+        Self::query_uid_exists(&mut *self.acquire().await?, uid, table).await
+    }
 
-        sqlx::query(&query)
-            .bind(user.active)
-            .bind(&user.password_hash)
-            .bind(&permissions)
-            .bind(&group_permissions)
-            .bind(&user.refresh_token)
-            .bind(&user.username)
-            .execute(&self.pool)
+    /// Primary query for retrieving versions from the database. Mainly used to get most recent version when determining version increment
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to query
+    /// * `name` - The name of the card
+    /// * `repository` - The repository of the card
+    /// * `version` - The version of the card
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<String>` - A vector of strings representing the sorted (desc) versions of the card
+    async fn get_versions(
+        &self,
+        table: &CardSQLTableNames,
+        name: &str,
+        repository: &str,
+        version: Option<&str>,
+    ) -> Result<Vec<String>, SqlError> {
+        // if version is None, get the latest version
+        let (query, bindings) = SqliteQueryHelper::get_versions_query(table, version)?;
+        let mut query_builder = sqlx::query_as(&query).bind(name).bind(repository);
+        for binding in &bindings {
+            query_builder = query_builder.bind(binding);
+        }
+
+        let cards: Vec<VersionResult> = query_builder
+            .fetch_all(&self.pool)
             .await
             .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
 
-        Ok(())
-    }
-}
+        let versions = cards
+            .iter()
+            .map(|c| {
+                c.to_version()
+                    .map_err(|e| SqlError::VersionError(format!("{}", e)))
+            })
+            .collect::<Result<Vec<Version>, SqlError>>()?;
 
-#[cfg(test)]
-mod tests {
+        // sql matches pre-release bounds by (major, minor, patch) only
+        let versions = match version {
+            Some(version) => {
+                let bounds = VersionParser::get_version_to_search(version)
+                    .map_err(|e| SqlError::VersionError(format!("{}", e)))?;
+                versions.into_iter().filter(|v| bounds.matches(v)).collect()
+            }
+            None => versions,
+        };
 
-    use super::*;
+        // sort semvers
+        VersionValidator::sort_semver_versions(versions, true)
+            .map_err(|e| SqlError::VersionError(format!("{}", e)))
+    }
 
-    use opsml_types::SqlType;
-    use opsml_utils::utils::get_utc_datetime;
-    use std::env;
+    /// Query cards based on the query arguments
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to query
+    /// * `query_args` - The query arguments
+    ///
+    /// # Returns
+    ///
+    /// * `CardResults` - The results of the query
+    async fn query_cards(
+        &self,
+        table: &CardSQLTableNames,
+        query_args: &CardQueryArgs,
+    ) -> Result<CardResults, SqlError> {
+        Self::query_card_records(&mut *self.acquire().await?, table, query_args).await
+    }
 
-    fn get_connection_uri() -> String {
-        let mut current_dir = env::current_dir().expect("Failed to get current directory");
-        current_dir.push("test.db");
-        format!(
-            "sqlite://{}",
-            current_dir
-                .to_str()
-                .expect("Failed to convert path to string")
-        )
+    async fn insert_card(&self, table: &CardSQLTableNames, card: &Card) -> Result<(), SqlError> {
+        let mut tx = self.begin().await?;
+        tx.insert_card(table, card).await?;
+        tx.commit().await
     }
 
-    pub fn cleanup() {
-        // delete ./test.db if exists
-        let mut current_dir = env::current_dir().expect("Failed to get current directory");
-        current_dir.push("test.db");
-        let _ = std::fs::remove_file(current_dir);
+    async fn update_card(&self, table: &CardSQLTableNames, card: &Card) -> Result<(), SqlError> {
+        let mut tx = self.begin().await?;
+        tx.update_card(table, card).await?;
+        tx.commit().await
     }
 
-    #[tokio::test]
-    async fn test_sqlite() {
-        let config = OpsmlDatabaseSettings {
-            connection_uri: "sqlite::memory:".to_string(),
-            max_connections: 1,
-            sql_type: SqlType::Sqlite,
-        };
+    /// Get unique repository names
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to query
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<String>` - A vector of unique repository names
+    async fn get_unique_repository_names(
+        &self,
+        table: &CardSQLTableNames,
+    ) -> Result<Vec<String>, SqlError> {
+        let query = format!("SELECT DISTINCT repository FROM {}", table);
+        let repos: Vec<Repository> = sqlx::query_as(&query)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
 
-        let _client = SqliteClient::new(&config).await;
+        Ok(repos.iter().map(|r| r.repository.clone()).collect())
     }
 
-    // create test for non-memory sqlite
+    /// Query stats for a table
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to query
+    /// * `search_term` - The search term to query
+    ///
+    /// # Returns
+    ///
+    /// * `HashMap<String, i32>` - A hashmap of the stats
+    ///
+    async fn query_stats(
+        &self,
+        table: &CardSQLTableNames,
+        search_term: Option<&str>,
+    ) -> Result<QueryStats, SqlError> {
+        let query = SqliteQueryHelper::get_query_stats_query(table);
+
+        // if search_term is not None, format with %search_term%, else None
+        let stats: QueryStats = sqlx::query_as(&query)
+            .bind(search_term.map(|term| format!("%{}%", term)))
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(stats)
+    }
+
+    /// Query a page of cards
+    ///
+    /// # Arguments
+    ///
+    /// * `sort_by` - The field to sort by
+    /// * `page` - The page number
+    /// * `cursor` - Optional cursor from a previous page. Takes precedence over `page`
+    /// * `search_term` - The search term to query
+    /// * `repository` - The repository to query
+    /// * `table` - The table to query
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<CardSummary>` - A vector of card summaries
+    async fn query_page(
+        &self,
+        sort_by: &str,
+        page: i32,
+        cursor: Option<&str>,
+        search_term: Option<&str>,
+        repository: Option<&str>,
+        status: Option<&CardStatus>,
+        table: &CardSQLTableNames,
+    ) -> Result<Vec<CardSummary>, SqlError> {
+        // an empty cursor starts keyset paging from the most recently updated card
+        let use_cursor = cursor.is_some();
+        let cursor = cursor
+            .filter(|cursor| !cursor.is_empty())
+            .map(SummaryCursor::decode)
+            .transpose()?;
+        let query = SqliteQueryHelper::get_query_page_query(table, sort_by, use_cursor);
+
+        let query_builder = sqlx::query_as(&query)
+            .bind(repository)
+            .bind(search_term)
+            .bind(search_term.map(|term| format!("%{}%", term)))
+            .bind(status.map(|status| status.as_str()));
+
+        let query_builder = if use_cursor {
+            query_builder
+                .bind(cursor.as_ref().map(|cursor| cursor.updated_at))
+                .bind(cursor.as_ref().map(|cursor| cursor.repository.as_str()))
+                .bind(cursor.as_ref().map(|cursor| cursor.name.as_str()))
+                .bind(REGISTRY_PAGE_SIZE)
+        } else {
+            let lower_bound = page * REGISTRY_PAGE_SIZE;
+            let upper_bound = lower_bound + REGISTRY_PAGE_SIZE;
+            query_builder.bind(lower_bound).bind(upper_bound)
+        };
+
+        let records: Vec<CardSummary> = query_builder
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn delete_card(
+        &self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        deleted_by: &str,
+    ) -> Result<(), SqlError> {
+        let mut tx = self.begin().await?;
+        tx.delete_card(table, uid, deleted_by).await?;
+        tx.commit().await
+    }
+
+    async fn restore_card(&self, table: &CardSQLTableNames, uid: &str) -> Result<(), SqlError> {
+        let mut tx = self.begin().await?;
+        tx.restore_card(table, uid).await?;
+        tx.commit().await
+    }
+
+    async fn update_card_status(
+        &self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        status: &CardStatus,
+    ) -> Result<CardStatus, SqlError> {
+        let mut tx = self.begin().await?;
+        let previous = tx.update_card_status(table, uid, status).await?;
+        tx.commit().await?;
+        Ok(previous)
+    }
+
+    async fn yank_card(
+        &self,
+        table: &CardSQLTableNames,
+        uid: &str,
+        yanked: bool,
+    ) -> Result<(), SqlError> {
+        let mut tx = self.begin().await?;
+        tx.yank_card(table, uid, yanked).await?;
+        tx.commit().await
+    }
+
+    async fn get_version_scheme(&self, repository: &str) -> Result<VersionScheme, SqlError> {
+        let query = SqliteQueryHelper::get_version_scheme_query();
+        let version_scheme: Option<String> = sqlx::query_scalar(&query)
+            .bind(repository)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(version_scheme
+            .and_then(|scheme| VersionScheme::from_string(&scheme))
+            .unwrap_or_default())
+    }
+
+    async fn set_version_scheme(
+        &self,
+        repository: &str,
+        version_scheme: &VersionScheme,
+    ) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_version_scheme_upsert_query();
+        sqlx::query(&query)
+            .bind(repository)
+            .bind(version_scheme.as_str())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_deleted_cards(
+        &self,
+        table: &CardSQLTableNames,
+        deleted_before: Option<NaiveDateTime>,
+    ) -> Result<Vec<DeletedCard>, SqlError> {
+        Self::query_deleted_cards(&mut *self.acquire().await?, table, deleted_before, None).await
+    }
+
+    async fn purge_card(&self, table: &CardSQLTableNames, uid: &str) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_card_purge_query(table);
+        sqlx::query(&query)
+            .bind(uid)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        // edges declared by the card go with it
+        sqlx::query(&SqliteQueryHelper::get_lineage_delete_query())
+            .bind(uid)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn search_cards(
+        &self,
+        search_term: &str,
+        limit: i32,
+    ) -> Result<Vec<CardSearchResult>, SqlError> {
+        let tokens = get_search_tokens(search_term);
+        if tokens.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // fts5 prefix query where every token must match
+        let match_expr = tokens
+            .iter()
+            .map(|token| format!("\"{}\"*", token))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let query = SqliteQueryHelper::get_search_cards_query();
+        let records: Vec<CardSearchResult> = sqlx::query_as(&query)
+            .bind(match_expr)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn insert_card_lineage(&self, card: &Card) -> Result<(), SqlError> {
+        Self::insert_lineage_records(&mut *self.acquire().await?, card).await
+    }
+
+    async fn insert_card_history(&self, record: &CardHistoryRecord) -> Result<(), SqlError> {
+        Self::insert_history_record(&mut *self.acquire().await?, record).await
+    }
+
+    async fn get_card_history(&self, uid: &str) -> Result<Vec<CardHistoryRecord>, SqlError> {
+        let query = SqliteQueryHelper::get_card_history_query();
+        let records: Vec<CardHistoryRecord> = sqlx::query_as(&query)
+            .bind(uid)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn set_card_alias(&self, alias: &CardAlias) -> Result<Option<String>, SqlError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        let query = SqliteQueryHelper::get_card_alias_query();
+        let previous: Option<String> = sqlx::query_as::<_, CardAlias>(&query)
+            .bind(&alias.registry_type)
+            .bind(&alias.repository)
+            .bind(&alias.name)
+            .bind(&alias.alias)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?
+            .map(|current| current.uid);
+
+        // pointing an alias at the card it already points to is not a move
+        if previous.as_deref() == Some(alias.uid.as_str()) {
+            return Ok(previous);
+        }
+
+        let query = SqliteQueryHelper::get_card_alias_upsert_query();
+        sqlx::query(&query)
+            .bind(&alias.registry_type)
+            .bind(&alias.repository)
+            .bind(&alias.name)
+            .bind(&alias.alias)
+            .bind(&alias.uid)
+            .bind(&alias.updated_by)
+            .bind(alias.updated_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        let query = SqliteQueryHelper::get_card_alias_history_insert_query();
+        sqlx::query(&query)
+            .bind(&alias.registry_type)
+            .bind(&alias.repository)
+            .bind(&alias.name)
+            .bind(&alias.alias)
+            .bind(&previous)
+            .bind(&alias.uid)
+            .bind(&alias.updated_by)
+            .bind(alias.updated_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(previous)
+    }
+
+    async fn get_card_alias(
+        &self,
+        table: &CardSQLTableNames,
+        repository: &str,
+        name: &str,
+        alias: &str,
+    ) -> Result<Option<CardAlias>, SqlError> {
+        let query = SqliteQueryHelper::get_card_alias_query();
+        let alias: Option<CardAlias> = sqlx::query_as(&query)
+            .bind(get_registry_type(table))
+            .bind(repository)
+            .bind(name)
+            .bind(alias)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(alias)
+    }
+
+    async fn get_card_aliases(
+        &self,
+        table: &CardSQLTableNames,
+        repository: &str,
+        name: &str,
+    ) -> Result<Vec<CardAlias>, SqlError> {
+        let query = SqliteQueryHelper::get_card_aliases_query();
+        let aliases: Vec<CardAlias> = sqlx::query_as(&query)
+            .bind(get_registry_type(table))
+            .bind(repository)
+            .bind(name)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(aliases)
+    }
+
+    async fn get_card_alias_history(
+        &self,
+        table: &CardSQLTableNames,
+        repository: &str,
+        name: &str,
+    ) -> Result<Vec<CardAliasHistoryRecord>, SqlError> {
+        let query = SqliteQueryHelper::get_card_alias_history_query();
+        let records: Vec<CardAliasHistoryRecord> = sqlx::query_as(&query)
+            .bind(get_registry_type(table))
+            .bind(repository)
+            .bind(name)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn get_upstream_lineage(
+        &self,
+        uid: &str,
+        depth: i32,
+    ) -> Result<Vec<LineageEdge>, SqlError> {
+        let query = SqliteQueryHelper::get_lineage_query(true);
+        let edges: Vec<LineageEdge> = sqlx::query_as(&query)
+            .bind(uid)
+            .bind(depth)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(edges)
+    }
+
+    async fn get_downstream_lineage(
+        &self,
+        uid: &str,
+        depth: i32,
+    ) -> Result<Vec<LineageEdge>, SqlError> {
+        let query = SqliteQueryHelper::get_lineage_query(false);
+        let edges: Vec<LineageEdge> = sqlx::query_as(&query)
+            .bind(uid)
+            .bind(depth)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(edges)
+    }
+
+    async fn get_lineage_nodes<'life1>(
+        &self,
+        nodes: &'life1 [(String, String)],
+    ) -> Result<Vec<LineageNode>, SqlError> {
+        let (query, bindings) = SqliteQueryHelper::get_lineage_nodes_query(nodes);
+        if bindings.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut query_builder = sqlx::query_as::<_, LineageNode>(&query);
+        for binding in bindings {
+            query_builder = query_builder.bind(binding);
+        }
+
+        let nodes = query_builder
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(nodes)
+    }
+
+    async fn get_project_id(&self, project_name: &str, repository: &str) -> Result<i32, SqlError> {
+        Self::query_project_id(&mut *self.acquire().await?, project_name, repository).await
+    }
+
+    async fn insert_run_metric(&self, record: &MetricRecord) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_run_metric_insert_query();
+
+        sqlx::query(&query)
+            .bind(&record.run_uid)
+            .bind(&record.name)
+            .bind(record.value)
+            .bind(record.step)
+            .bind(record.timestamp)
+            .bind(record.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn insert_run_metrics<'life1>(
+        &self,
+        records: &'life1 [MetricRecord],
+    ) -> Result<(), SqlError> {
+        Self::insert_metric_records(&mut *self.acquire().await?, records).await
+    }
+
+    async fn get_run_metric<'life2>(
+        &self,
+        uid: &str,
+        names: &'life2 [String],
+    ) -> Result<Vec<MetricRecord>, SqlError> {
+        let (query, bindings) = SqliteQueryHelper::get_run_metric_query(names);
+        let mut query_builder = sqlx::query_as::<sqlx::Sqlite, MetricRecord>(&query).bind(uid);
+
+        for binding in bindings {
+            query_builder = query_builder.bind(binding);
+        }
+
+        let records: Vec<MetricRecord> = query_builder
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn query_run_metrics(
+        &self,
+        args: &GetMetricRequest,
+    ) -> Result<Vec<MetricRecord>, SqlError> {
+        let (query, bindings) = SqliteQueryHelper::get_run_metric_points_query(args);
+        let mut query_builder =
+            sqlx::query_as::<sqlx::Sqlite, MetricRecord>(&query).bind(&args.run_uid);
+
+        for binding in bindings {
+            query_builder = query_builder.bind(binding);
+        }
+
+        for step in [args.start_step, args.end_step].into_iter().flatten() {
+            query_builder = query_builder.bind(step);
+        }
+
+        let records: Vec<MetricRecord> = query_builder
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn get_run_metric_summary(
+        &self,
+        args: &GetMetricRequest,
+    ) -> Result<Vec<MetricSummaryRecord>, SqlError> {
+        let (query, bindings) = SqliteQueryHelper::get_run_metric_summary_query(args);
+        let mut query_builder =
+            sqlx::query_as::<sqlx::Sqlite, MetricSummaryRecord>(&query).bind(&args.run_uid);
+
+        for binding in bindings {
+            query_builder = query_builder.bind(binding);
+        }
+
+        for step in [args.start_step, args.end_step].into_iter().flatten() {
+            query_builder = query_builder.bind(step);
+        }
+
+        let records: Vec<MetricSummaryRecord> = query_builder
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn get_run_metric_names(&self, uid: &str) -> Result<Vec<String>, SqlError> {
+        let query = format!(
+            "SELECT DISTINCT name FROM {} WHERE run_uid = ?1",
+            CardSQLTableNames::Metrics
+        );
+
+        let records: Vec<String> = sqlx::query_scalar(&query)
+            .bind(uid)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn get_runs_metrics<'life1, 'life2>(
+        &self,
+        uids: &'life1 [String],
+        names: &'life2 [String],
+    ) -> Result<Vec<MetricRecord>, SqlError> {
+        if uids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (query, bindings) = SqliteQueryHelper::get_runs_metric_query(uids, names);
+        let mut query_builder = sqlx::query_as::<_, MetricRecord>(&query);
+
+        for binding in bindings {
+            query_builder = query_builder.bind(binding);
+        }
+
+        let records: Vec<MetricRecord> = query_builder
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn get_runs_parameters<'life1, 'life2>(
+        &self,
+        uids: &'life1 [String],
+        names: &'life2 [String],
+    ) -> Result<Vec<ParameterRecord>, SqlError> {
+        if uids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (query, bindings) = SqliteQueryHelper::get_runs_parameter_query(uids, names);
+        let mut query_builder = sqlx::query_as::<_, ParameterRecord>(&query);
+
+        for binding in bindings {
+            query_builder = query_builder.bind(binding);
+        }
+
+        let records: Vec<ParameterRecord> = query_builder
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn query_runs(&self, args: &RunQueryRequest) -> Result<Vec<RunQueryResult>, SqlError> {
+        let (query, bindings) = SqliteQueryHelper::get_run_query(args);
+        let mut query_builder = sqlx::query_as::<_, RunQueryResult>(&query);
+
+        for binding in bindings {
+            query_builder = query_builder.bind(binding);
+        }
+
+        let records: Vec<RunQueryResult> = query_builder
+            .bind(args.limit.unwrap_or(50))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn insert_hardware_metrics<'life1>(
+        &self,
+        record: &'life1 [HardwareMetricsRecord],
+    ) -> Result<(), SqlError> {
+        Self::insert_hardware_records(&mut *self.acquire().await?, record).await
+    }
+
+    async fn get_hardware_metric(&self, uid: &str) -> Result<Vec<HardwareMetricsRecord>, SqlError> {
+        let query = SqliteQueryHelper::get_hardware_metric_query();
+
+        let records: Vec<HardwareMetricsRecord> = sqlx::query_as(&query)
+            .bind(uid)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn get_child_runs(&self, parent_uid: &str) -> Result<Vec<RunCardRecord>, SqlError> {
+        let query = SqliteQueryHelper::get_child_runs_query();
+        let runs: Vec<RunCardRecord> = sqlx::query_as(&query)
+            .bind(parent_uid)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(runs)
+    }
+
+    async fn get_child_metric_summary(
+        &self,
+        parent_uid: &str,
+    ) -> Result<Vec<ChildMetricSummaryRecord>, SqlError> {
+        let query = SqliteQueryHelper::get_child_metric_summary_query();
+        let records: Vec<ChildMetricSummaryRecord> = sqlx::query_as(&query)
+            .bind(parent_uid)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn start_run(&self, run_uid: &str) -> Result<RunStatusRecord, SqlError> {
+        if self.get_run_status(run_uid).await?.is_some() {
+            return Err(SqlError::ConflictError(format!(
+                "Run {} has already started",
+                run_uid
+            )));
+        }
+
+        let record = RunStatusRecord::new(run_uid);
+        let query = SqliteQueryHelper::get_run_start_query();
+        sqlx::query(&query)
+            .bind(&record.run_uid)
+            .bind(&record.status)
+            .bind(record.started_at)
+            .bind(record.ended_at)
+            .bind(record.heartbeat_at)
+            .execute(&self.pool)
+            .await
+            .map_err(map_write_error)?;
+
+        Ok(record)
+    }
+
+    async fn get_run_status(&self, run_uid: &str) -> Result<Option<RunStatusRecord>, SqlError> {
+        let query = SqliteQueryHelper::get_run_status_query();
+        let record: Option<RunStatusRecord> = sqlx::query_as(&query)
+            .bind(run_uid)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(record)
+    }
+
+    async fn update_run_status(
+        &self,
+        run_uid: &str,
+        status: &RunStatus,
+    ) -> Result<RunStatusRecord, SqlError> {
+        let current = self
+            .get_run_status(run_uid)
+            .await?
+            .ok_or_else(|| SqlError::NotFoundError(format!("Run {} has not started", run_uid)))?;
+
+        let current_status = RunStatus::from_string(&current.status).ok_or_else(|| {
+            SqlError::QueryError(format!("Unknown run status {}", current.status))
+        })?;
+
+        if !current_status.can_transition_to(status) {
+            return Err(SqlError::ConflictError(format!(
+                "Run {} cannot move from {} to {}",
+                run_uid, current_status, status
+            )));
+        }
+
+        let now = get_utc_datetime();
+        let record = RunStatusRecord {
+            status: status.as_str().to_string(),
+            ended_at: status.is_ended().then_some(now),
+            heartbeat_at: now,
+            ..current
+        };
+
+        // compare-and-set so a run ended concurrently is not silently resumed
+        let query = SqliteQueryHelper::get_run_status_update_query();
+        let result = sqlx::query(&query)
+            .bind(&record.status)
+            .bind(record.ended_at)
+            .bind(record.heartbeat_at)
+            .bind(run_uid)
+            .bind(current_status.as_str())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(SqlError::ConflictError(format!(
+                "Status of run {} changed during the update",
+                run_uid
+            )));
+        }
+
+        Ok(record)
+    }
+
+    async fn mark_crashed_runs(&self, heartbeat_before: NaiveDateTime) -> Result<u64, SqlError> {
+        let query = SqliteQueryHelper::get_crashed_runs_update_query();
+        let result = sqlx::query(&query)
+            .bind(RunStatus::Crashed.as_str())
+            .bind(RunStatus::Running.as_str())
+            .bind(heartbeat_before)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn insert_run_parameters<'life1>(
+        &self,
+        records: &'life1 [ParameterRecord],
+    ) -> Result<(), SqlError> {
+        Self::insert_parameter_records(&mut *self.acquire().await?, records).await
+    }
+
+    async fn get_run_parameter<'life2>(
+        &self,
+        uid: &str,
+        names: &'life2 [String],
+    ) -> Result<Vec<ParameterRecord>, SqlError> {
+        let (query, bindings) = SqliteQueryHelper::get_run_parameter_query(names);
+        let mut query_builder = sqlx::query_as::<_, ParameterRecord>(&query).bind(uid);
+
+        for binding in bindings {
+            query_builder = query_builder.bind(binding);
+        }
+
+        let records: Vec<ParameterRecord> = query_builder
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(records)
+    }
+
+    async fn insert_user(&self, user: &User) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_user_insert_query();
+
+        let group_permissions = serde_json::to_string(&user.group_permissions)
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        let permissions = serde_json::to_string(&user.permissions)
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        sqlx::query(&query)
+            .bind(&user.username)
+            .bind(&user.password_hash)
+            .bind(&permissions)
+            .bind(&group_permissions)
+            .bind(user.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(map_write_error)?;
+
+        Ok(())
+    }
+
+    async fn get_user(&self, username: &str) -> Result<User, SqlError> {
+        let query = SqliteQueryHelper::get_user_query();
+
+        let user: Option<User> = sqlx::query_as(&query)
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        user.ok_or_else(|| SqlError::NotFoundError(format!("User {} not found", username)))
+    }
+
+    async fn get_users(&self) -> Result<Vec<User>, SqlError> {
+        let query = SqliteQueryHelper::get_users_query();
+
+        let users: Vec<User> = sqlx::query_as(&query)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(users)
+    }
+    async fn update_user(&self, user: &User) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_user_update_query();
+        let group_permissions = serde_json::to_string(&user.group_permissions)
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        let permissions = serde_json::to_string(&user.permissions)
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        sqlx::query(&query)
+            .bind(user.active)
+            .bind(&user.password_hash)
+            .bind(&permissions)
+            .bind(&group_permissions)
+            .bind(&user.refresh_token)
+            .bind(&user.username)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn insert_refresh_token(&self, token: &RefreshTokenFamily) -> Result<(), SqlError> {
+        sqlx::query(&SqliteQueryHelper::get_refresh_token_insert_query())
+            .bind(&token.family)
+            .bind(&token.username)
+            .bind(&token.token_hash)
+            .execute(&self.pool)
+            .await
+            .map_err(map_write_error)?;
+
+        Ok(())
+    }
+
+    async fn get_refresh_token(
+        &self,
+        family: &str,
+    ) -> Result<Option<RefreshTokenFamily>, SqlError> {
+        let token: Option<RefreshTokenFamily> =
+            sqlx::query_as(&SqliteQueryHelper::get_refresh_token_query())
+                .bind(family)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(token)
+    }
+
+    async fn rotate_refresh_token(
+        &self,
+        family: &str,
+        current: &str,
+        token_hash: &str,
+    ) -> Result<bool, SqlError> {
+        let result = sqlx::query(&SqliteQueryHelper::get_refresh_token_rotate_query())
+            .bind(token_hash)
+            .bind(family)
+            .bind(current)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn delete_refresh_token(&self, family: &str) -> Result<(), SqlError> {
+        sqlx::query(&SqliteQueryHelper::get_refresh_token_delete_query())
+            .bind(family)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn delete_user_refresh_tokens(&self, username: &str) -> Result<(), SqlError> {
+        sqlx::query(&SqliteQueryHelper::get_user_refresh_tokens_delete_query())
+            .bind(username)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn delete_user(&self, username: &str) -> Result<(), SqlError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        sqlx::query(&SqliteQueryHelper::get_user_memberships_delete_query())
+            .bind(username)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        sqlx::query(&SqliteQueryHelper::get_user_refresh_tokens_delete_query())
+            .bind(username)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        sqlx::query(&SqliteQueryHelper::get_user_delete_query())
+            .bind(username)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn insert_group(&self, group: &Group) -> Result<(), SqlError> {
+        Self::insert_group_record(&mut *self.acquire().await?, group).await
+    }
+
+    async fn get_group(&self, name: &str) -> Result<Option<Group>, SqlError> {
+        let query = SqliteQueryHelper::get_group_query();
+
+        let group: Option<Group> = sqlx::query_as(&query)
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(group)
+    }
+
+    async fn get_groups(&self) -> Result<Vec<Group>, SqlError> {
+        let query = SqliteQueryHelper::get_groups_query();
+
+        let groups: Vec<Group> = sqlx::query_as(&query)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(groups)
+    }
+
+    async fn update_group(&self, group: &Group) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_group_update_query();
+
+        let permissions = serde_json::to_string(&group.permissions)
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        sqlx::query(&query)
+            .bind(&permissions)
+            .bind(&group.name)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn delete_group(&self, name: &str) -> Result<(), SqlError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        sqlx::query(&SqliteQueryHelper::get_group_members_delete_query())
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        sqlx::query(&SqliteQueryHelper::get_group_delete_query())
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn add_group_member(&self, name: &str, username: &str) -> Result<(), SqlError> {
+        Self::add_group_member_record(&mut *self.acquire().await?, name, username).await
+    }
+
+    async fn remove_group_member(&self, name: &str, username: &str) -> Result<(), SqlError> {
+        let query = SqliteQueryHelper::get_group_member_delete_query();
+
+        sqlx::query(&query)
+            .bind(name)
+            .bind(username)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_group_members(&self, name: &str) -> Result<Vec<String>, SqlError> {
+        let query = SqliteQueryHelper::get_group_members_query();
+
+        let members: Vec<String> = sqlx::query_scalar(&query)
+            .bind(name)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(members)
+    }
+
+    async fn get_user_groups(&self, username: &str) -> Result<Vec<Group>, SqlError> {
+        let query = SqliteQueryHelper::get_user_groups_query();
+
+        let groups: Vec<Group> = sqlx::query_as(&query)
+            .bind(username)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqlError::QueryError(format!("{}", e)))?;
+
+        Ok(groups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use opsml_types::{
+        ComparisonOperator, MetricAggregation, RunMetricFilter, RunMetricSort, RunParameterFilter,
+        SqlType, TagFilter, TagOperator,
+    };
+    use opsml_utils::utils::get_utc_datetime;
+    use sqlx::types::Json as SqlxJson;
+    use std::env;
+
+    fn get_connection_uri() -> String {
+        let mut current_dir = env::current_dir().expect("Failed to get current directory");
+        current_dir.push("test.db");
+        format!(
+            "sqlite://{}",
+            current_dir
+                .to_str()
+                .expect("Failed to convert path to string")
+        )
+    }
+
+    pub fn cleanup() {
+        // delete ./test.db if exists
+        let mut current_dir = env::current_dir().expect("Failed to get current directory");
+        current_dir.push("test.db");
+        let _ = std::fs::remove_file(current_dir);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite() {
+        let config = OpsmlDatabaseSettings {
+            connection_uri: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let _client = SqliteClient::new(&config).await;
+    }
+
+    // create test for non-memory sqlite
+
+    #[tokio::test]
+    async fn test_sqlite_versions() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        // Run the SQL script to populate the database
+        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
+        sqlx::query(&script).execute(&client.pool).await.unwrap();
+
+        // query all versions
+        // get versions (should return 1)
+        let versions = client
+            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", None)
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 10);
+
+        // check star pattern
+        let versions = client
+            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("*"))
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 10);
+
+        let versions = client
+            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("1.*"))
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 4);
+
+        let versions = client
+            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("1.1.*"))
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 2);
+
+        // check tilde pattern
+        let versions = client
+            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("~1"))
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 4);
+
+        // check tilde pattern
+        let versions = client
+            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("~1.1"))
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 2);
+
+        // check tilde pattern
+        let versions = client
+            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("~1.1.1"))
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 1);
+
+        let versions = client
+            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("^2.0.0"))
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 4);
+
+        // range bounds compare (major, minor, patch) as a tuple
+        let versions = client
+            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some(">=1.1.1"))
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 7);
+
+        let versions = client
+            .get_versions(
+                &CardSQLTableNames::Data,
+                "Data1",
+                "repo1",
+                Some(">=1.1, <3"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 6);
+        assert_eq!(versions[0], "2.1.1-theta+build8");
+
+        let versions = client
+            .get_versions(
+                &CardSQLTableNames::Data,
+                "Data1",
+                "repo1",
+                Some("1.0.1 - 2.0"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 5);
+
+        cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_query_cards() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        // Run the SQL script to populate the database
+        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
+        sqlx::query(&script).execute(&client.pool).await.unwrap();
+
+        // check if uid exists
+        let exists = client
+            .check_uid_exists("fake", &CardSQLTableNames::Data)
+            .await
+            .unwrap();
+
+        assert!(!exists);
+
+        // try name and repository
+        let card_args = CardQueryArgs {
+            name: Some("Data1".to_string()),
+            repository: Some("repo1".to_string()),
+            ..Default::default()
+        };
+
+        // query all versions
+        // get versions (should return 1)
+        let results = client
+            .query_cards(&CardSQLTableNames::Data, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 10);
+
+        // try name and repository
+        let card_args = CardQueryArgs {
+            name: Some("Model1".to_string()),
+            repository: Some("repo1".to_string()),
+            version: Some("~1.0.0".to_string()),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Model, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        // max_date
+        let card_args = CardQueryArgs {
+            max_date: Some("2023-11-28".to_string()),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Run, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+
+        // try tags
+        let tags = [("key1".to_string(), "value1".to_string())]
+            .iter()
+            .cloned()
+            .collect();
+        let card_args = CardQueryArgs {
+            tags: Some(tags),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Data, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        let card_args = CardQueryArgs {
+            sort_by_timestamp: Some(true),
+            limit: Some(5),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Audit, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 5);
+
+        // test uid
+        let card_args = CardQueryArgs {
+            uid: Some("550e8400-e29b-41d4-a716-446655440000".to_string()),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Data, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        // check if uid exists
+        let exists = client
+            .check_uid_exists(
+                "550e8400-e29b-41d4-a716-446655440000",
+                &CardSQLTableNames::Data,
+            )
+            .await
+            .unwrap();
+
+        assert!(exists);
+
+        cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_query_cards_tag_filters() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        // Run the SQL script to populate the database
+        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
+        sqlx::query(&script).execute(&client.pool).await.unwrap();
+
+        let query = |tag_filters: Vec<TagFilter>| CardQueryArgs {
+            tag_filters: Some(tag_filters),
+            ..Default::default()
+        };
+
+        let cases = vec![
+            (
+                TagFilter::new("key1", TagOperator::Eq, vec!["value1".to_string()]),
+                1,
+            ),
+            (
+                TagFilter::new("key1", TagOperator::Eq, vec!["value2".to_string()]),
+                0,
+            ),
+            (
+                TagFilter::new("key1", TagOperator::Ne, vec!["value1".to_string()]),
+                9,
+            ),
+            (
+                TagFilter::new("key1", TagOperator::Ne, vec!["other".to_string()]),
+                10,
+            ),
+            (TagFilter::new("key1", TagOperator::Exists, vec![]), 1),
+            (TagFilter::new("key1", TagOperator::NotExists, vec![]), 9),
+            (
+                TagFilter::new(
+                    "key2",
+                    TagOperator::In,
+                    vec!["value1".to_string(), "value2".to_string()],
+                ),
+                1,
+            ),
+            (
+                TagFilter::new("key2", TagOperator::Prefix, vec!["val".to_string()]),
+                1,
+            ),
+            (
+                TagFilter::new("key2", TagOperator::Prefix, vec!["value1".to_string()]),
+                0,
+            ),
+            // values are bound, so sql in a key or value is never executed
+            (
+                TagFilter::new("key1' OR 1=1 --", TagOperator::NotExists, vec![]),
+                10,
+            ),
+            (
+                TagFilter::new("key1", TagOperator::Eq, vec!["x' OR '1'='1".to_string()]),
+                0,
+            ),
+        ];
+
+        for (filter, expected) in cases {
+            let results = client
+                .query_cards(&CardSQLTableNames::Data, &query(vec![filter.clone()]))
+                .await
+                .unwrap();
+            assert_eq!(results.len(), expected, "filter: {:?}", filter);
+        }
+
+        // exact tags and tag filters are combined
+        let card_args = CardQueryArgs {
+            tags: Some([("key1".to_string(), "value1".to_string())].into()),
+            tag_filters: Some(vec![TagFilter::new(
+                "key2",
+                TagOperator::Ne,
+                vec!["value2".to_string()],
+            )]),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Data, &card_args)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 0);
+
+        // invalid number of values
+        let result = client
+            .query_cards(
+                &CardSQLTableNames::Data,
+                &query(vec![TagFilter::new("key1", TagOperator::In, vec![])]),
+            )
+            .await;
+        assert!(result.is_err());
+
+        cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_query_cards_pre_release() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        // sql matches every pre-release of 1.0.0 against a pre-release bound, so the
+        // limit has to hold after the precedence check drops 1.0.0-alpha
+        let created_at = get_utc_datetime();
+        let pre_tags = [Some("alpha"), Some("beta"), Some("rc.1"), None];
+        for (i, pre_tag) in pre_tags.into_iter().enumerate() {
+            let mut version = Version::new(1, 0, 0);
+            if let Some(pre_tag) = pre_tag {
+                version.pre = semver::Prerelease::new(pre_tag).unwrap();
+            }
+            let card = DataCardRecord {
+                name: "PreRelease".to_string(),
+                repository: "repo_pre".to_string(),
+                major: 1,
+                pre_tag: pre_tag.map(|tag| tag.to_string()),
+                version: version.to_string(),
+                created_at: Some(created_at + chrono::Duration::seconds(i as i64)),
+                ..Default::default()
+            };
+            client
+                .insert_card(&CardSQLTableNames::Data, &Card::Data(card))
+                .await
+                .unwrap();
+        }
+
+        let card_args = CardQueryArgs {
+            repository: Some("repo_pre".to_string()),
+            version: Some(">=1.0.0-beta".to_string()),
+            limit: Some(3),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Data, &card_args)
+            .await
+            .unwrap();
+        let CardResults::Data(cards) = results else {
+            panic!("expected data cards");
+        };
+        let mut versions: Vec<String> = cards.into_iter().map(|card| card.version).collect();
+        versions.sort();
+        assert_eq!(versions, vec!["1.0.0", "1.0.0-beta", "1.0.0-rc.1"]);
+
+        // upper bounds drop the later pre-releases
+        let card_args = CardQueryArgs {
+            version: Some("<1.0.0-rc.1".to_string()),
+            ..card_args
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Data, &card_args)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+
+        // exact pre-releases match on the bound pre_tag
+        let card_args = CardQueryArgs {
+            version: Some("1.0.0-rc.1".to_string()),
+            ..card_args
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Data, &card_args)
+            .await
+            .unwrap();
+        let CardResults::Data(cards) = results else {
+            panic!("expected data cards");
+        };
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].version, "1.0.0-rc.1");
+
+        cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cursor_pagination() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        // Run the SQL script to populate the database
+        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
+        sqlx::query(&script).execute(&client.pool).await.unwrap();
+
+        // walk all datacards 3 at a time
+        let mut card_args = CardQueryArgs {
+            limit: Some(3),
+            sort_by_timestamp: Some(true),
+            ..Default::default()
+        };
+        let mut uids: Vec<String> = Vec::new();
+
+        loop {
+            let results = client
+                .query_cards(&CardSQLTableNames::Data, &card_args)
+                .await
+                .unwrap();
+
+            if let CardResults::Data(cards) = &results {
+                uids.extend(cards.iter().map(|card| card.uid.clone()));
+            }
+
+            match results.next_cursor(&card_args) {
+                Some(cursor) => card_args.cursor = Some(cursor),
+                None => break,
+            }
+        }
+
+        let mut unique_uids = uids.clone();
+        unique_uids.sort();
+        unique_uids.dedup();
+        assert_eq!(uids.len(), 10);
+        assert_eq!(unique_uids.len(), 10);
+
+        // versions registered out of order, e.g. a 1.2.1 backport after 2.0.0
+        let created_at = get_utc_datetime();
+        let versions = [(1, 0, 0), (2, 0, 0), (1, 2, 1), (3, 0, 0), (1, 1, 0)];
+        for (i, (major, minor, patch)) in versions.into_iter().enumerate() {
+            let card = DataCardRecord {
+                name: "Backport".to_string(),
+                repository: "repo_backport".to_string(),
+                major,
+                minor,
+                patch,
+                version: Version::new(major as u64, minor as u64, patch as u64).to_string(),
+                created_at: Some(created_at + chrono::Duration::seconds(i as i64)),
+                ..Default::default()
+            };
+            client
+                .insert_card(&CardSQLTableNames::Data, &Card::Data(card))
+                .await
+                .unwrap();
+        }
+
+        // version ordered pages cannot be resumed from a cursor
+        let card_args = CardQueryArgs {
+            repository: Some("repo_backport".to_string()),
+            limit: Some(2),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Data, &card_args)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.next_cursor(&card_args).is_none());
+
+        // timestamp ordered pages visit every card once, newest first
+        let mut card_args = CardQueryArgs {
+            sort_by_timestamp: Some(true),
+            ..card_args
+        };
+        let mut versions: Vec<String> = Vec::new();
+
+        loop {
+            let results = client
+                .query_cards(&CardSQLTableNames::Data, &card_args)
+                .await
+                .unwrap();
+
+            if let CardResults::Data(cards) = &results {
+                versions.extend(cards.iter().map(|card| card.version.clone()));
+            }
+
+            match results.next_cursor(&card_args) {
+                Some(cursor) => card_args.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(versions, vec!["1.1.0", "3.0.0", "1.2.1", "2.0.0", "1.0.0"]);
+
+        // invalid cursor
+        let card_args = CardQueryArgs {
+            cursor: Some("not-a-cursor".to_string()),
+            ..Default::default()
+        };
+        let result = client
+            .query_cards(&CardSQLTableNames::Data, &card_args)
+            .await;
+        assert!(result.is_err());
+
+        // summaries after a cursor
+        let nbr_summaries = client
+            .query_page("name", 0, None, None, None, None, &CardSQLTableNames::Model)
+            .await
+            .unwrap()
+            .len();
+
+        let summaries = client
+            .query_page(
+                "name",
+                0,
+                Some(""),
+                None,
+                None,
+                None,
+                &CardSQLTableNames::Model,
+            )
+            .await
+            .unwrap();
+        assert_eq!(summaries.len(), nbr_summaries);
+
+        let first = &summaries[0];
+        let cursor = SummaryCursor {
+            updated_at: first.updated_at,
+            repository: first.repository.clone(),
+            name: first.name.clone(),
+        }
+        .encode();
+
+        let next = client
+            .query_page(
+                "name",
+                0,
+                Some(&cursor),
+                None,
+                None,
+                None,
+                &CardSQLTableNames::Model,
+            )
+            .await
+            .unwrap();
+        assert_eq!(next.len(), nbr_summaries - 1);
+        assert_eq!(next[0].name, summaries[1].name);
+        assert_eq!(next[0].repository, summaries[1].repository);
+
+        cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_insert_cards() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+        let data_card = DataCardRecord::default();
+        let card = Card::Data(data_card.clone());
+
+        client
+            .insert_card(&CardSQLTableNames::Data, &card)
+            .await
+            .unwrap();
+
+        // check if the card was inserted
+        let card_args = CardQueryArgs {
+            uid: Some(data_card.uid),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Data, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        // insert modelcard
+        let model_card = ModelCardRecord::default();
+        let card = Card::Model(model_card.clone());
+
+        client
+            .insert_card(&CardSQLTableNames::Model, &card)
+            .await
+            .unwrap();
+
+        // check if the card was inserted
+        let card_args = CardQueryArgs {
+            uid: Some(model_card.uid),
+            ..Default::default()
+        };
+
+        let results = client
+            .query_cards(&CardSQLTableNames::Model, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        // insert runcard
+        let run_card = RunCardRecord::default();
+        let card = Card::Run(run_card.clone());
+
+        client
+            .insert_card(&CardSQLTableNames::Run, &card)
+            .await
+            .unwrap();
+
+        // check if the card was inserted
+
+        let card_args = CardQueryArgs {
+            uid: Some(run_card.uid),
+            ..Default::default()
+        };
+
+        let results = client
+            .query_cards(&CardSQLTableNames::Run, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        // insert auditcard
+
+        let audit_card = AuditCardRecord::default();
+        let card = Card::Audit(audit_card.clone());
+
+        client
+            .insert_card(&CardSQLTableNames::Audit, &card)
+            .await
+            .unwrap();
+
+        // check if the card was inserted
+
+        let card_args = CardQueryArgs {
+            uid: Some(audit_card.uid),
+            ..Default::default()
+        };
+
+        let results = client
+            .query_cards(&CardSQLTableNames::Audit, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        // check pipeline card
+        let pipeline_card = PipelineCardRecord::default();
+        let card = Card::Pipeline(pipeline_card.clone());
+
+        client
+            .insert_card(&CardSQLTableNames::Pipeline, &card)
+            .await
+            .unwrap();
+
+        // check if the card was inserted
+
+        let card_args = CardQueryArgs {
+            uid: Some(pipeline_card.uid),
+            ..Default::default()
+        };
+
+        let results = client
+            .query_cards(&CardSQLTableNames::Pipeline, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_update_cards() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        // Test DataCardRecord
+        let mut data_card = DataCardRecord::default();
+        let card = Card::Data(data_card.clone());
+
+        client
+            .insert_card(&CardSQLTableNames::Data, &card)
+            .await
+            .unwrap();
+
+        // check if the card was inserted
+        let card_args = CardQueryArgs {
+            uid: Some(data_card.uid.clone()),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Data, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        // update the card
+        data_card.name = "UpdatedDataName".to_string();
+        let updated_card = Card::Data(data_card.clone());
+
+        client
+            .update_card(&CardSQLTableNames::Data, &updated_card)
+            .await
+            .unwrap();
+
+        // check if the card was updated
+        let updated_results = client
+            .query_cards(&CardSQLTableNames::Data, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(updated_results.len(), 1);
+        if let CardResults::Data(cards) = updated_results {
+            assert_eq!(cards[0].name, "UpdatedDataName");
+        }
+
+        // Test ModelCardRecord
+        let mut model_card = ModelCardRecord::default();
+        let card = Card::Model(model_card.clone());
+
+        client
+            .insert_card(&CardSQLTableNames::Model, &card)
+            .await
+            .unwrap();
+
+        // check if the card was inserted
+        let card_args = CardQueryArgs {
+            uid: Some(model_card.uid.clone()),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Model, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        // update the card
+        model_card.name = "UpdatedModelName".to_string();
+        let updated_card = Card::Model(model_card.clone());
+
+        client
+            .update_card(&CardSQLTableNames::Model, &updated_card)
+            .await
+            .unwrap();
+
+        // check if the card was updated
+        let updated_results = client
+            .query_cards(&CardSQLTableNames::Model, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(updated_results.len(), 1);
+        if let CardResults::Model(cards) = updated_results {
+            assert_eq!(cards[0].name, "UpdatedModelName");
+        }
+
+        // Test RunCardRecord
+        let mut run_card = RunCardRecord::default();
+        let card = Card::Run(run_card.clone());
+
+        client
+            .insert_card(&CardSQLTableNames::Run, &card)
+            .await
+            .unwrap();
+
+        // check if the card was inserted
+        let card_args = CardQueryArgs {
+            uid: Some(run_card.uid.clone()),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Run, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        // update the card
+        run_card.name = "UpdatedRunName".to_string();
+        let updated_card = Card::Run(run_card.clone());
+
+        client
+            .update_card(&CardSQLTableNames::Run, &updated_card)
+            .await
+            .unwrap();
+
+        // check if the card was updated
+        let updated_results = client
+            .query_cards(&CardSQLTableNames::Run, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(updated_results.len(), 1);
+        if let CardResults::Run(cards) = updated_results {
+            assert_eq!(cards[0].name, "UpdatedRunName");
+        }
+
+        // Test AuditCardRecord
+        let mut audit_card = AuditCardRecord::default();
+        let card = Card::Audit(audit_card.clone());
+
+        client
+            .insert_card(&CardSQLTableNames::Audit, &card)
+            .await
+            .unwrap();
+
+        // check if the card was inserted
+        let card_args = CardQueryArgs {
+            uid: Some(audit_card.uid.clone()),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Audit, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        // update the card
+        audit_card.name = "UpdatedAuditName".to_string();
+        let updated_card = Card::Audit(audit_card.clone());
+
+        client
+            .update_card(&CardSQLTableNames::Audit, &updated_card)
+            .await
+            .unwrap();
+
+        // check if the card was updated
+        let updated_results = client
+            .query_cards(&CardSQLTableNames::Audit, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(updated_results.len(), 1);
+        if let CardResults::Audit(cards) = updated_results {
+            assert_eq!(cards[0].name, "UpdatedAuditName");
+        }
+
+        // Test PipelineCardRecord
+        let mut pipeline_card = PipelineCardRecord::default();
+        let card = Card::Pipeline(pipeline_card.clone());
+
+        client
+            .insert_card(&CardSQLTableNames::Pipeline, &card)
+            .await
+            .unwrap();
+
+        // check if the card was inserted
+        let card_args = CardQueryArgs {
+            uid: Some(pipeline_card.uid.clone()),
+            ..Default::default()
+        };
+        let results = client
+            .query_cards(&CardSQLTableNames::Pipeline, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        // update the card
+        pipeline_card.name = "UpdatedPipelineName".to_string();
+        let updated_card = Card::Pipeline(pipeline_card.clone());
+
+        client
+            .update_card(&CardSQLTableNames::Pipeline, &updated_card)
+            .await
+            .unwrap();
+
+        // check if the card was updated
+        let updated_results = client
+            .query_cards(&CardSQLTableNames::Pipeline, &card_args)
+            .await
+            .unwrap();
+
+        assert_eq!(updated_results.len(), 1);
+        if let CardResults::Pipeline(cards) = updated_results {
+            assert_eq!(cards[0].name, "UpdatedPipelineName");
+        }
+
+        cleanup();
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_unique_repos() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        // Run the SQL script to populate the database
+        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
+        sqlx::query(&script).execute(&client.pool).await.unwrap();
+
+        // get unique repository names
+        let repos = client
+            .get_unique_repository_names(&CardSQLTableNames::Model)
+            .await
+            .unwrap();
+
+        assert_eq!(repos.len(), 10);
+
+        cleanup();
+    }
 
     #[tokio::test]
-    async fn test_sqlite_versions() {
+    async fn test_sqlite_query_stats() {
         cleanup();
 
         let config = OpsmlDatabaseSettings {
@@ -1056,65 +3433,80 @@ mod tests {
         let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
         sqlx::query(&script).execute(&client.pool).await.unwrap();
 
-        // query all versions
-        // get versions (should return 1)
-        let versions = client
-            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", None)
+        // query stats
+        let stats = client
+            .query_stats(&CardSQLTableNames::Model, None)
             .await
             .unwrap();
-        assert_eq!(versions.len(), 10);
 
-        // check star pattern
-        let versions = client
-            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("*"))
-            .await
-            .unwrap();
-        assert_eq!(versions.len(), 10);
+        assert_eq!(stats.nbr_names, 10);
+        assert_eq!(stats.nbr_versions, 10);
+        assert_eq!(stats.nbr_repositories, 10);
 
-        let versions = client
-            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("1.*"))
+        // query stats with search term
+        let stats = client
+            .query_stats(&CardSQLTableNames::Model, Some("Model1"))
             .await
             .unwrap();
-        assert_eq!(versions.len(), 4);
 
-        let versions = client
-            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("1.1.*"))
-            .await
-            .unwrap();
-        assert_eq!(versions.len(), 2);
+        assert_eq!(stats.nbr_names, 2); // for Model1 and Model10
 
-        // check tilde pattern
-        let versions = client
-            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("~1"))
-            .await
-            .unwrap();
-        assert_eq!(versions.len(), 4);
+        cleanup();
+    }
 
-        // check tilde pattern
-        let versions = client
-            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("~1.1"))
+    #[tokio::test]
+    async fn test_sqlite_query_page() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        // Run the SQL script to populate the database
+        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
+        sqlx::query(&script).execute(&client.pool).await.unwrap();
+
+        // query page
+        let results = client
+            .query_page("name", 0, None, None, None, None, &CardSQLTableNames::Data)
             .await
             .unwrap();
-        assert_eq!(versions.len(), 2);
 
-        // check tilde pattern
-        let versions = client
-            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("~1.1.1"))
+        assert_eq!(results.len(), 1);
+
+        // query page
+        let results = client
+            .query_page("name", 0, None, None, None, None, &CardSQLTableNames::Model)
             .await
             .unwrap();
-        assert_eq!(versions.len(), 1);
 
-        let versions = client
-            .get_versions(&CardSQLTableNames::Data, "Data1", "repo1", Some("^2.0.0"))
+        assert_eq!(results.len(), 10);
+
+        // query page
+        let results = client
+            .query_page(
+                "name",
+                0,
+                None,
+                None,
+                Some("repo3"),
+                None,
+                &CardSQLTableNames::Model,
+            )
             .await
             .unwrap();
-        assert_eq!(versions.len(), 2);
+
+        assert_eq!(results.len(), 1);
 
         cleanup();
     }
 
     #[tokio::test]
-    async fn test_sqlite_query_cards() {
+    async fn test_sqlite_delete_card() {
         cleanup();
 
         let config = OpsmlDatabaseSettings {
@@ -1129,112 +3521,218 @@ mod tests {
         let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
         sqlx::query(&script).execute(&client.pool).await.unwrap();
 
-        // check if uid exists
-        let exists = client
-            .check_uid_exists("fake", &CardSQLTableNames::Data)
-            .await
-            .unwrap();
-
-        assert!(!exists);
+        // delete card
 
-        // try name and repository
-        let card_args = CardQueryArgs {
+        let args = CardQueryArgs {
+            uid: None,
             name: Some("Data1".to_string()),
             repository: Some("repo1".to_string()),
             ..Default::default()
         };
 
-        // query all versions
-        // get versions (should return 1)
-        let results = client
-            .query_cards(&CardSQLTableNames::Data, &card_args)
+        let cards = client
+            .query_cards(&CardSQLTableNames::Data, &args)
             .await
             .unwrap();
 
-        assert_eq!(results.len(), 10);
+        let uid = match cards {
+            CardResults::Data(cards) => cards[0].uid.clone(),
+            _ => "".to_string(),
+        };
 
-        // try name and repository
-        let card_args = CardQueryArgs {
-            name: Some("Model1".to_string()),
-            repository: Some("repo1".to_string()),
-            version: Some("~1.0.0".to_string()),
+        assert!(!uid.is_empty());
+
+        // delete the card
+        client
+            .delete_card(&CardSQLTableNames::Data, &uid, "test_user")
+            .await
+            .unwrap();
+
+        // check if the card was deleted
+        let args = CardQueryArgs {
+            uid: Some(uid.clone()),
             ..Default::default()
         };
+
         let results = client
-            .query_cards(&CardSQLTableNames::Model, &card_args)
+            .query_cards(&CardSQLTableNames::Data, &args)
             .await
             .unwrap();
 
-        assert_eq!(results.len(), 1);
+        assert_eq!(results.len(), 0);
 
-        // max_date
-        let card_args = CardQueryArgs {
-            max_date: Some("2023-11-28".to_string()),
+        // the card is in the trash and can be restored
+        let deleted = client
+            .get_deleted_cards(&CardSQLTableNames::Data, None)
+            .await
+            .unwrap();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].uid, uid);
+        assert_eq!(deleted[0].deleted_by, "test_user");
+
+        let args = CardQueryArgs {
+            uid: Some(uid.clone()),
+            include_deleted: true,
             ..Default::default()
         };
         let results = client
-            .query_cards(&CardSQLTableNames::Run, &card_args)
+            .query_cards(&CardSQLTableNames::Data, &args)
             .await
             .unwrap();
+        assert_eq!(results.len(), 1);
 
-        assert_eq!(results.len(), 2);
+        client
+            .restore_card(&CardSQLTableNames::Data, &uid)
+            .await
+            .unwrap();
 
-        // try tags
-        let tags = [("key1".to_string(), "value1".to_string())]
-            .iter()
-            .cloned()
-            .collect();
-        let card_args = CardQueryArgs {
-            tags: Some(tags),
+        let args = CardQueryArgs {
+            uid: Some(uid.clone()),
             ..Default::default()
         };
         let results = client
-            .query_cards(&CardSQLTableNames::Data, &card_args)
+            .query_cards(&CardSQLTableNames::Data, &args)
             .await
             .unwrap();
-
         assert_eq!(results.len(), 1);
 
-        let card_args = CardQueryArgs {
-            sort_by_timestamp: Some(true),
-            limit: Some(5),
-            ..Default::default()
-        };
-        let results = client
-            .query_cards(&CardSQLTableNames::Audit, &card_args)
+        // restoring a card that is not in the trash fails
+        let result = client.restore_card(&CardSQLTableNames::Data, &uid).await;
+        assert!(matches!(result, Err(SqlError::NotFoundError(_))));
+
+        // purge only returns cards deleted before the cutoff
+        client
+            .delete_card(&CardSQLTableNames::Data, &uid, "test_user")
+            .await
+            .unwrap();
+        let expired = client
+            .get_deleted_cards(
+                &CardSQLTableNames::Data,
+                Some(get_utc_datetime() - chrono::Duration::days(1)),
+            )
             .await
             .unwrap();
+        assert!(expired.is_empty());
 
-        assert_eq!(results.len(), 5);
+        client
+            .purge_card(&CardSQLTableNames::Data, &uid)
+            .await
+            .unwrap();
+        let deleted = client
+            .get_deleted_cards(&CardSQLTableNames::Data, None)
+            .await
+            .unwrap();
+        assert!(deleted.is_empty());
+    }
 
-        // test uid
-        let card_args = CardQueryArgs {
-            uid: Some("550e8400-e29b-41d4-a716-446655440000".to_string()),
+    #[tokio::test]
+    async fn test_sqlite_project_id() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        // Run the SQL script to populate the database
+        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
+        sqlx::query(&script).execute(&client.pool).await.unwrap();
+
+        // get project id
+
+        let project_id = client.get_project_id("test", "repo").await.unwrap();
+        assert_eq!(project_id, 1);
+
+        // get next project id
+        let project_id = client.get_project_id("test1", "repo").await.unwrap();
+
+        assert_eq!(project_id, 2);
+
+        let args = CardQueryArgs {
+            uid: None,
+            name: Some("test".to_string()),
+            repository: Some("repo".to_string()),
             ..Default::default()
         };
-        let results = client
-            .query_cards(&CardSQLTableNames::Data, &card_args)
+        let cards = client
+            .query_cards(&CardSQLTableNames::Project, &args)
             .await
             .unwrap();
 
-        assert_eq!(results.len(), 1);
+        assert_eq!(cards.len(), 1);
+        cleanup();
+    }
+
+    // test run metric
+    #[tokio::test]
+    async fn test_sqlite_run_metric() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        // Run the SQL script to populate the database
+        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
+
+        sqlx::query(&script).execute(&client.pool).await.unwrap();
+
+        let uid = "550e8400-e29b-41d4-a716-446655440000".to_string();
+        let metric_names = vec!["metric1", "metric2", "metric3"];
+
+        for name in metric_names {
+            let metric = MetricRecord {
+                run_uid: uid.clone(),
+                name: name.to_string(),
+                value: 1.0,
+                ..Default::default()
+            };
+
+            client.insert_run_metric(&metric).await.unwrap();
+        }
+
+        let records = client.get_run_metric(&uid, &Vec::new()).await.unwrap();
+        let names = client.get_run_metric_names(&uid).await.unwrap();
+
+        assert_eq!(records.len(), 3);
+
+        // assert names = "metric1"
+        assert_eq!(names.len(), 3);
+
+        // insert vec
+        let records = vec![
+            MetricRecord {
+                run_uid: uid.clone(),
+                name: "vec1".to_string(),
+                value: 1.0,
+                ..Default::default()
+            },
+            MetricRecord {
+                run_uid: uid.clone(),
+                name: "vec2".to_string(),
+                value: 1.0,
+                ..Default::default()
+            },
+        ];
+
+        client.insert_run_metrics(&records).await.unwrap();
 
-        // check if uid exists
-        let exists = client
-            .check_uid_exists(
-                "550e8400-e29b-41d4-a716-446655440000",
-                &CardSQLTableNames::Data,
-            )
-            .await
-            .unwrap();
+        let records = client.get_run_metric(&uid, &Vec::new()).await.unwrap();
 
-        assert!(exists);
+        assert_eq!(records.len(), 5);
 
         cleanup();
     }
 
     #[tokio::test]
-    async fn test_sqlite_insert_cards() {
+    async fn test_sqlite_hardware_metric() {
         cleanup();
 
         let config = OpsmlDatabaseSettings {
@@ -1244,123 +3742,173 @@ mod tests {
         };
 
         let client = SqliteClient::new(&config).await.unwrap();
-        let data_card = DataCardRecord::default();
-        let card = Card::Data(data_card.clone());
 
-        client
-            .insert_card(&CardSQLTableNames::Data, &card)
-            .await
-            .unwrap();
+        // Run the SQL script to populate the database
+        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
 
-        // check if the card was inserted
-        let card_args = CardQueryArgs {
-            uid: Some(data_card.uid),
-            ..Default::default()
-        };
-        let results = client
-            .query_cards(&CardSQLTableNames::Data, &card_args)
-            .await
-            .unwrap();
+        sqlx::query(&script).execute(&client.pool).await.unwrap();
 
-        assert_eq!(results.len(), 1);
+        let uid = "550e8400-e29b-41d4-a716-446655440000".to_string();
+        let mut metrics = vec![];
 
-        // insert modelcard
-        let model_card = ModelCardRecord::default();
-        let card = Card::Model(model_card.clone());
+        // create a loop of 10
+        for _ in 0..10 {
+            let metric = HardwareMetricsRecord {
+                run_uid: uid.clone(),
+                created_at: get_utc_datetime(),
+                ..Default::default()
+            };
+            metrics.push(metric.clone());
+        }
 
-        client
-            .insert_card(&CardSQLTableNames::Model, &card)
-            .await
-            .unwrap();
+        client.insert_hardware_metrics(&metrics).await.unwrap();
+        let records = client.get_hardware_metric(&uid).await.unwrap();
 
-        // check if the card was inserted
-        let card_args = CardQueryArgs {
-            uid: Some(model_card.uid),
-            ..Default::default()
-        };
+        assert_eq!(records.len(), 10);
 
-        let results = client
-            .query_cards(&CardSQLTableNames::Model, &card_args)
-            .await
-            .unwrap();
+        cleanup();
+    }
 
-        assert_eq!(results.len(), 1);
+    #[tokio::test]
+    async fn test_sqlite_parameter() {
+        cleanup();
 
-        // insert runcard
-        let run_card = RunCardRecord::default();
-        let card = Card::Run(run_card.clone());
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
 
-        client
-            .insert_card(&CardSQLTableNames::Run, &card)
-            .await
-            .unwrap();
+        let client = SqliteClient::new(&config).await.unwrap();
 
-        // check if the card was inserted
+        // Run the SQL script to populate the database
+        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
 
-        let card_args = CardQueryArgs {
-            uid: Some(run_card.uid),
-            ..Default::default()
-        };
+        sqlx::query(&script).execute(&client.pool).await.unwrap();
 
-        let results = client
-            .query_cards(&CardSQLTableNames::Run, &card_args)
-            .await
-            .unwrap();
+        let uid = "550e8400-e29b-41d4-a716-446655440000".to_string();
+        let mut params = vec![];
 
-        assert_eq!(results.len(), 1);
+        // create a loop of 10
+        for i in 0..10 {
+            let parameter = ParameterRecord {
+                run_uid: uid.clone(),
+                name: format!("param{}", i),
+                ..Default::default()
+            };
 
-        // insert auditcard
+            params.push(parameter.clone());
+        }
 
-        let audit_card = AuditCardRecord::default();
-        let card = Card::Audit(audit_card.clone());
+        client.insert_run_parameters(&params).await.unwrap();
+        let records = client.get_run_parameter(&uid, &Vec::new()).await.unwrap();
 
-        client
-            .insert_card(&CardSQLTableNames::Audit, &card)
+        assert_eq!(records.len(), 10);
+
+        let param_records = client
+            .get_run_parameter(&uid, &["param1".to_string()])
             .await
             .unwrap();
 
-        // check if the card was inserted
+        assert_eq!(param_records.len(), 1);
 
-        let card_args = CardQueryArgs {
-            uid: Some(audit_card.uid),
-            ..Default::default()
-        };
+        cleanup();
+    }
 
-        let results = client
-            .query_cards(&CardSQLTableNames::Audit, &card_args)
-            .await
-            .unwrap();
+    #[tokio::test]
+    async fn test_sqlite_user() {
+        cleanup();
 
-        assert_eq!(results.len(), 1);
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
 
-        // check pipeline card
-        let pipeline_card = PipelineCardRecord::default();
-        let card = Card::Pipeline(pipeline_card.clone());
+        let client = SqliteClient::new(&config).await.unwrap();
 
-        client
-            .insert_card(&CardSQLTableNames::Pipeline, &card)
-            .await
-            .unwrap();
+        let user = User::new("user".to_string(), "pass".to_string(), None, None);
+        client.insert_user(&user).await.unwrap();
 
-        // check if the card was inserted
+        let mut user = client.get_user("user").await.unwrap();
+        assert_eq!(user.username, "user");
 
-        let card_args = CardQueryArgs {
-            uid: Some(pipeline_card.uid),
-            ..Default::default()
-        };
+        // update user
+        user.active = false;
+        user.refresh_token = Some("token".to_string());
 
-        let results = client
-            .query_cards(&CardSQLTableNames::Pipeline, &card_args)
+        client.update_user(&user).await.unwrap();
+        let user = client.get_user("user").await.unwrap();
+        assert!(!user.active);
+        assert_eq!(user.refresh_token.unwrap(), "token");
+
+        // refresh tokens rotate per family, and only from the token that was read
+        for family in ["first", "second"] {
+            client
+                .insert_refresh_token(&RefreshTokenFamily::new(
+                    family.to_string(),
+                    "user".to_string(),
+                    "token".to_string(),
+                ))
+                .await
+                .unwrap();
+        }
+        assert!(client
+            .rotate_refresh_token("first", "token", "rotated")
+            .await
+            .unwrap());
+        assert!(!client
+            .rotate_refresh_token("first", "token", "replayed")
+            .await
+            .unwrap());
+        let first = client.get_refresh_token("first").await.unwrap().unwrap();
+        assert_eq!(first.token_hash, "rotated");
+        let second = client.get_refresh_token("second").await.unwrap().unwrap();
+        assert_eq!(second.token_hash, "token");
+
+        // revoking one family leaves the user's other sessions alone
+        client.delete_refresh_token("first").await.unwrap();
+        assert!(client.get_refresh_token("first").await.unwrap().is_none());
+        assert!(client.get_refresh_token("second").await.unwrap().is_some());
+
+        client.delete_user_refresh_tokens("user").await.unwrap();
+        assert!(client.get_refresh_token("second").await.unwrap().is_none());
+
+        // usernames are unique
+        let duplicate = client
+            .insert_user(&User::new(
+                "user".to_string(),
+                "pass".to_string(),
+                None,
+                None,
+            ))
+            .await;
+        assert!(matches!(duplicate, Err(SqlError::ConflictError(_))));
+
+        // deleting a user drops their memberships
+        let group = Group::new("team".to_string(), vec![]);
+        client.insert_group(&group).await.unwrap();
+        client.add_group_member("team", "user").await.unwrap();
+        client
+            .insert_refresh_token(&RefreshTokenFamily::new(
+                "third".to_string(),
+                "user".to_string(),
+                "token".to_string(),
+            ))
             .await
             .unwrap();
 
-        assert_eq!(results.len(), 1);
+        client.delete_user("user").await.unwrap();
+        let missing = client.get_user("user").await;
+        assert!(matches!(missing, Err(SqlError::NotFoundError(_))));
+        assert!(client.get_group_members("team").await.unwrap().is_empty());
+        assert!(client.get_refresh_token("third").await.unwrap().is_none());
 
         cleanup();
     }
 
     #[tokio::test]
-    async fn test_sqlite_update_cards() {
+    async fn test_sqlite_search_cards() {
         cleanup();
 
         let config = OpsmlDatabaseSettings {
@@ -1371,216 +3919,634 @@ mod tests {
 
         let client = SqliteClient::new(&config).await.unwrap();
 
-        // Test DataCardRecord
-        let mut data_card = DataCardRecord::default();
-        let card = Card::Data(data_card.clone());
+        // Run the SQL script to populate the database
+        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
+        sqlx::query(&script).execute(&client.pool).await.unwrap();
 
-        client
-            .insert_card(&CardSQLTableNames::Data, &card)
-            .await
-            .unwrap();
+        // tag values are searchable
+        let results = client.search_cards("value1", 50).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].registry_type, "data");
+        assert_eq!(results[0].uid, "550e8400-e29b-41d4-a716-446655440000");
 
-        // check if the card was inserted
-        let card_args = CardQueryArgs {
-            uid: Some(data_card.uid.clone()),
+        // search spans every registry
+        let results = client.search_cards("contact1", 50).await.unwrap();
+        let registry_types: Vec<&str> = results
+            .iter()
+            .map(|result| result.registry_type.as_str())
+            .collect();
+        assert!(registry_types.contains(&"data"));
+        assert!(registry_types.contains(&"model"));
+
+        // every token must prefix-match
+        let results = client.search_cards("Model1 repo1", 50).await.unwrap();
+        assert!(!results.is_empty());
+        assert!(results
+            .iter()
+            .all(|result| result.name.starts_with("Model1")));
+
+        let results = client.search_cards("repo", 2).await.unwrap();
+        assert_eq!(results.len(), 2);
+
+        let results = client.search_cards(" !! ", 50).await.unwrap();
+        assert!(results.is_empty());
+
+        // index follows inserts, updates and deletes
+        let mut data_card = DataCardRecord {
+            name: "searchable".to_string(),
             ..Default::default()
         };
-        let results = client
-            .query_cards(&CardSQLTableNames::Data, &card_args)
+        client
+            .insert_card(&CardSQLTableNames::Data, &Card::Data(data_card.clone()))
             .await
             .unwrap();
 
+        let results = client.search_cards("search", 50).await.unwrap();
         assert_eq!(results.len(), 1);
+        assert_eq!(results[0].uid, data_card.uid);
 
-        // update the card
-        data_card.name = "UpdatedDataName".to_string();
-        let updated_card = Card::Data(data_card.clone());
-
+        data_card.contact = "findme".to_string();
         client
-            .update_card(&CardSQLTableNames::Data, &updated_card)
+            .update_card(&CardSQLTableNames::Data, &Card::Data(data_card.clone()))
             .await
             .unwrap();
+        let results = client.search_cards("findme", 50).await.unwrap();
+        assert_eq!(results.len(), 1);
 
-        // check if the card was updated
-        let updated_results = client
-            .query_cards(&CardSQLTableNames::Data, &card_args)
+        client
+            .delete_card(&CardSQLTableNames::Data, &data_card.uid, "test_user")
             .await
             .unwrap();
+        let results = client.search_cards("searchable", 50).await.unwrap();
+        assert!(results.is_empty());
+    }
 
-        assert_eq!(updated_results.len(), 1);
-        if let CardResults::Data(cards) = updated_results {
-            assert_eq!(cards[0].name, "UpdatedDataName");
-        }
+    #[tokio::test]
+    async fn test_sqlite_card_lineage() {
+        cleanup();
 
-        // Test ModelCardRecord
-        let mut model_card = ModelCardRecord::default();
-        let card = Card::Model(model_card.clone());
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
 
-        client
-            .insert_card(&CardSQLTableNames::Model, &card)
-            .await
-            .unwrap();
+        let client = SqliteClient::new(&config).await.unwrap();
 
-        // check if the card was inserted
-        let card_args = CardQueryArgs {
-            uid: Some(model_card.uid.clone()),
+        let data_card = DataCardRecord::default();
+        let run_card = RunCardRecord {
+            datacard_uids: SqlxJson(vec![data_card.uid.clone()]),
             ..Default::default()
         };
-        let results = client
-            .query_cards(&CardSQLTableNames::Model, &card_args)
+        let mut model_card = ModelCardRecord {
+            datacard_uid: data_card.uid.clone(),
+            runcard_uid: run_card.uid.clone(),
+            ..Default::default()
+        };
+
+        client
+            .insert_card(&CardSQLTableNames::Data, &Card::Data(data_card.clone()))
+            .await
+            .unwrap();
+        client
+            .insert_card(&CardSQLTableNames::Run, &Card::Run(run_card.clone()))
             .await
             .unwrap();
-
-        assert_eq!(results.len(), 1);
-
-        // update the card
-        model_card.name = "UpdatedModelName".to_string();
-        let updated_card = Card::Model(model_card.clone());
-
         client
-            .update_card(&CardSQLTableNames::Model, &updated_card)
+            .insert_card(&CardSQLTableNames::Model, &Card::Model(model_card.clone()))
             .await
             .unwrap();
 
-        // check if the card was updated
-        let updated_results = client
-            .query_cards(&CardSQLTableNames::Model, &card_args)
+        // models trained on the dataset
+        let edges = client
+            .get_downstream_lineage(&data_card.uid, 1)
             .await
             .unwrap();
+        assert_eq!(edges.len(), 2);
+        assert!(edges.iter().all(|edge| edge.depth == 1));
+        assert!(edges
+            .iter()
+            .any(|edge| edge.downstream_uid == model_card.uid && edge.downstream_type == "model"));
 
-        assert_eq!(updated_results.len(), 1);
-        if let CardResults::Model(cards) = updated_results {
-            assert_eq!(cards[0].name, "UpdatedModelName");
-        }
+        // run -> model is two hops from the dataset
+        let edges = client
+            .get_downstream_lineage(&data_card.uid, 2)
+            .await
+            .unwrap();
+        assert_eq!(edges.len(), 3);
+        assert_eq!(edges.iter().filter(|edge| edge.depth == 2).count(), 1);
 
-        // Test RunCardRecord
-        let mut run_card = RunCardRecord::default();
-        let card = Card::Run(run_card.clone());
+        let edges = client
+            .get_upstream_lineage(&model_card.uid, 5)
+            .await
+            .unwrap();
+        assert_eq!(edges.len(), 3);
+
+        let nodes = client
+            .get_lineage_nodes(&[
+                ("data".to_string(), data_card.uid.clone()),
+                ("run".to_string(), run_card.uid.clone()),
+                ("model".to_string(), model_card.uid.clone()),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(nodes.len(), 3);
+        assert!(nodes
+            .iter()
+            .any(|node| node.uid == run_card.uid && node.registry_type == "run"));
 
+        // updates replace the edges declared by the card
+        model_card.datacard_uid = "undefined".to_string();
         client
-            .insert_card(&CardSQLTableNames::Run, &card)
+            .update_card(&CardSQLTableNames::Model, &Card::Model(model_card.clone()))
             .await
             .unwrap();
 
-        // check if the card was inserted
-        let card_args = CardQueryArgs {
-            uid: Some(run_card.uid.clone()),
-            ..Default::default()
-        };
-        let results = client
-            .query_cards(&CardSQLTableNames::Run, &card_args)
+        let edges = client
+            .get_upstream_lineage(&model_card.uid, 1)
             .await
             .unwrap();
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].upstream_uid, run_card.uid);
 
-        assert_eq!(results.len(), 1);
-
-        // update the card
-        run_card.name = "UpdatedRunName".to_string();
-        let updated_card = Card::Run(run_card.clone());
-
+        // purging the run removes the edges it declared
         client
-            .update_card(&CardSQLTableNames::Run, &updated_card)
+            .purge_card(&CardSQLTableNames::Run, &run_card.uid)
             .await
             .unwrap();
 
-        // check if the card was updated
-        let updated_results = client
-            .query_cards(&CardSQLTableNames::Run, &card_args)
+        let edges = client
+            .get_downstream_lineage(&data_card.uid, 5)
+            .await
+            .unwrap();
+        assert!(edges.is_empty());
+
+        // hand-written edges can close a cycle; the walk reports each edge once and stops
+        let first_uid = RunCardRecord::default().uid;
+        let second_uid = RunCardRecord::default().uid;
+        sqlx::query(&SqliteQueryHelper::get_lineage_insert_query(2))
+            .bind(&first_uid)
+            .bind("run")
+            .bind(&second_uid)
+            .bind("run")
+            .bind(&first_uid)
+            .bind(&second_uid)
+            .bind("run")
+            .bind(&first_uid)
+            .bind("run")
+            .bind(&second_uid)
+            .execute(&client.pool)
             .await
             .unwrap();
 
-        assert_eq!(updated_results.len(), 1);
-        if let CardResults::Run(cards) = updated_results {
-            assert_eq!(cards[0].name, "UpdatedRunName");
-        }
+        let edges = client.get_downstream_lineage(&first_uid, 10).await.unwrap();
+        assert_eq!(edges.len(), 2);
+        assert_eq!(edges.iter().map(|edge| edge.depth).max(), Some(2));
+        cleanup();
+    }
 
-        // Test AuditCardRecord
-        let mut audit_card = AuditCardRecord::default();
-        let card = Card::Audit(audit_card.clone());
+    #[tokio::test]
+    async fn test_sqlite_card_history() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
 
+        let card = DataCardRecord::default();
+        let inserted = Card::Data(card.clone());
         client
-            .insert_card(&CardSQLTableNames::Audit, &card)
+            .insert_card(&CardSQLTableNames::Data, &inserted)
             .await
             .unwrap();
-
-        // check if the card was inserted
-        let card_args = CardQueryArgs {
-            uid: Some(audit_card.uid.clone()),
-            ..Default::default()
-        };
-        let results = client
-            .query_cards(&CardSQLTableNames::Audit, &card_args)
+        client
+            .insert_card_history(&CardHistoryRecord::new(
+                &CardSQLTableNames::Data,
+                &card.uid,
+                "insert",
+                "test_user",
+                None,
+                Some(&inserted.to_value()),
+            ))
             .await
             .unwrap();
 
-        assert_eq!(results.len(), 1);
-
-        // update the card
-        audit_card.name = "UpdatedAuditName".to_string();
-        let updated_card = Card::Audit(audit_card.clone());
-
+        let updated = Card::Data(DataCardRecord {
+            contact: "updated_contact".to_string(),
+            ..card.clone()
+        });
         client
-            .update_card(&CardSQLTableNames::Audit, &updated_card)
+            .update_card(&CardSQLTableNames::Data, &updated)
             .await
             .unwrap();
-
-        // check if the card was updated
-        let updated_results = client
-            .query_cards(&CardSQLTableNames::Audit, &card_args)
+        client
+            .insert_card_history(&CardHistoryRecord::new(
+                &CardSQLTableNames::Data,
+                &card.uid,
+                "update",
+                "test_user",
+                Some(&inserted.to_value()),
+                Some(&updated.to_value()),
+            ))
             .await
             .unwrap();
 
-        assert_eq!(updated_results.len(), 1);
-        if let CardResults::Audit(cards) = updated_results {
-            assert_eq!(cards[0].name, "UpdatedAuditName");
+        let history = client.get_card_history(&card.uid).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].action, "insert");
+        assert_eq!(history[0].registry_type, "data");
+        assert_eq!(history[0].changes["name"]["new"], card.name);
+
+        // only the changed field is recorded on update
+        assert_eq!(history[1].action, "update");
+        assert_eq!(history[1].username, "test_user");
+        let changes = history[1].changes.as_object().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes["contact"]["old"], card.contact);
+        assert_eq!(changes["contact"]["new"], "updated_contact");
+
+        assert!(client.get_card_history("missing").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_run_metric_summary() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        let uid = "550e8400-e29b-41d4-a716-446655440000".to_string();
+
+        // decreasing loss with a single spike at step 50
+        let records = (0..100)
+            .map(|step| MetricRecord {
+                run_uid: uid.clone(),
+                name: "loss".to_string(),
+                value: if step == 50 {
+                    500.0
+                } else {
+                    (100 - step) as f64
+                },
+                step: Some(step),
+                ..Default::default()
+            })
+            .chain((0..10).map(|step| MetricRecord {
+                run_uid: uid.clone(),
+                name: "accuracy".to_string(),
+                value: step as f64 / 10.0,
+                step: Some(step),
+                ..Default::default()
+            }))
+            .collect::<Vec<_>>();
+
+        client.insert_run_metrics(&records).await.unwrap();
+
+        let args = GetMetricRequest::new(uid.clone(), None);
+        let summaries = client.get_run_metric_summary(&args).await.unwrap();
+        assert_eq!(summaries.len(), 2);
+
+        let loss = summaries.iter().find(|s| s.name == "loss").unwrap();
+        assert_eq!(loss.count, 100);
+        assert_eq!(loss.min_value, 1.0);
+        assert_eq!(loss.max_value, 500.0);
+        assert_eq!(loss.last_value, 1.0);
+        assert_eq!(loss.last_step, Some(99));
+
+        // step range
+        let args = GetMetricRequest {
+            start_step: Some(10),
+            end_step: Some(19),
+            ..GetMetricRequest::new(uid.clone(), Some(vec!["loss".to_string()]))
+        };
+        let summaries = client.get_run_metric_summary(&args).await.unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].count, 10);
+        assert_eq!(summaries[0].mean_value, 85.5);
+        assert_eq!(summaries[0].last_value, 81.0);
+
+        let points = client.query_run_metrics(&args).await.unwrap();
+        assert_eq!(points.len(), 10);
+        assert_eq!(points[0].step, Some(10));
+
+        // downsampled series keeps the spike
+        let args = GetMetricRequest {
+            max_points: Some(10),
+            ..GetMetricRequest::new(uid.clone(), Some(vec!["loss".to_string()]))
+        };
+        let points = client.query_run_metrics(&args).await.unwrap();
+        assert!(points.len() <= 10);
+        assert!(points.iter().any(|p| p.value == 500.0));
+        assert!(points.windows(2).all(|w| w[0].step < w[1].step));
+
+        // fewer points than requested returns the raw series
+        let args = GetMetricRequest {
+            max_points: Some(50),
+            ..GetMetricRequest::new(uid.clone(), Some(vec!["accuracy".to_string()]))
+        };
+        let points = client.query_run_metrics(&args).await.unwrap();
+        assert_eq!(points.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_runs_metrics_and_parameters() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        let uids = vec![
+            "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            "550e8400-e29b-41d4-a716-446655440001".to_string(),
+        ];
+
+        for (i, uid) in uids.iter().enumerate() {
+            let metrics = (0..3)
+                .map(|step| MetricRecord {
+                    run_uid: uid.clone(),
+                    name: "loss".to_string(),
+                    value: (i + step) as f64,
+                    step: Some(step as i32),
+                    ..Default::default()
+                })
+                .collect::<Vec<_>>();
+            client.insert_run_metrics(&metrics).await.unwrap();
+
+            let parameters = vec![
+                ParameterRecord::new(uid.clone(), "lr".to_string(), format!("0.{}", i + 1)),
+                ParameterRecord::new(uid.clone(), "epochs".to_string(), "10".to_string()),
+            ];
+            client.insert_run_parameters(&parameters).await.unwrap();
         }
 
-        // Test PipelineCardRecord
-        let mut pipeline_card = PipelineCardRecord::default();
-        let card = Card::Pipeline(pipeline_card.clone());
+        let metrics = client.get_runs_metrics(&uids, &[]).await.unwrap();
+        assert_eq!(metrics.len(), 6);
+        assert_eq!(metrics[0].run_uid, uids[0]);
+        assert_eq!(metrics[3].run_uid, uids[1]);
+        assert_eq!(metrics[3].step, Some(0));
 
-        client
-            .insert_card(&CardSQLTableNames::Pipeline, &card)
+        let parameters = client
+            .get_runs_parameters(&uids, &["lr".to_string()])
             .await
             .unwrap();
+        assert_eq!(parameters.len(), 2);
+        assert_eq!(parameters[1].value, "0.2");
 
-        // check if the card was inserted
-        let card_args = CardQueryArgs {
-            uid: Some(pipeline_card.uid.clone()),
+        assert!(client.get_runs_metrics(&[], &[]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_query_runs() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        // (learning rate, optimizer, auc per step)
+        let runs = [
+            ("0.001", "adam", vec![0.80, 0.95]),
+            ("0.001", "sgd", vec![0.85, 0.92]),
+            ("0.01", "adam", vec![0.99, 0.97]),
+            ("0.001", "adam", vec![0.70, 0.75]),
+        ];
+
+        let mut uids = Vec::new();
+        for (i, (lr, optimizer, auc)) in runs.iter().enumerate() {
+            let card = RunCardRecord {
+                name: format!("run{}", i),
+                project: "sweep".to_string(),
+                ..Default::default()
+            };
+            client
+                .insert_card(&CardSQLTableNames::Run, &Card::Run(card.clone()))
+                .await
+                .unwrap();
+
+            let parameters = vec![
+                ParameterRecord::new(card.uid.clone(), "lr".to_string(), lr.to_string()),
+                ParameterRecord::new(
+                    card.uid.clone(),
+                    "optimizer".to_string(),
+                    optimizer.to_string(),
+                ),
+            ];
+            client.insert_run_parameters(&parameters).await.unwrap();
+
+            let metrics = auc
+                .iter()
+                .enumerate()
+                .map(|(step, value)| MetricRecord {
+                    run_uid: card.uid.clone(),
+                    name: "auc".to_string(),
+                    value: *value,
+                    step: Some(step as i32),
+                    ..Default::default()
+                })
+                .collect::<Vec<_>>();
+            client.insert_run_metrics(&metrics).await.unwrap();
+            uids.push(card.uid);
+        }
+
+        // lr = 0.001 and last auc > 0.9, best first
+        let args = RunQueryRequest {
+            project: Some("sweep".to_string()),
+            parameters: vec![RunParameterFilter {
+                name: "lr".to_string(),
+                operator: ComparisonOperator::Eq,
+                value: "0.001".to_string(),
+            }],
+            metrics: vec![RunMetricFilter {
+                name: "auc".to_string(),
+                operator: ComparisonOperator::Gt,
+                value: 0.9,
+                aggregation: MetricAggregation::Last,
+            }],
+            sort_by: Some(RunMetricSort {
+                name: "auc".to_string(),
+                aggregation: MetricAggregation::Last,
+                descending: true,
+            }),
             ..Default::default()
         };
-        let results = client
-            .query_cards(&CardSQLTableNames::Pipeline, &card_args)
+        let results = client.query_runs(&args).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].uid, uids[0]);
+        assert_eq!(results[0].sort_value, Some(0.95));
+        assert_eq!(results[1].uid, uids[1]);
+
+        // numeric parameter comparison and string parameter match
+        let args = RunQueryRequest {
+            parameters: vec![
+                RunParameterFilter {
+                    name: "lr".to_string(),
+                    operator: ComparisonOperator::Lt,
+                    value: "0.005".to_string(),
+                },
+                RunParameterFilter {
+                    name: "optimizer".to_string(),
+                    operator: ComparisonOperator::Eq,
+                    value: "adam".to_string(),
+                },
+            ],
+            ..Default::default()
+        };
+        let results = client.query_runs(&args).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.sort_value.is_none()));
+
+        // leaderboard by best auc with a limit
+        let args = RunQueryRequest {
+            project: Some("sweep".to_string()),
+            sort_by: Some(RunMetricSort {
+                name: "auc".to_string(),
+                aggregation: MetricAggregation::Max,
+                descending: true,
+            }),
+            limit: Some(2),
+            ..Default::default()
+        };
+        let results = client.query_runs(&args).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].uid, uids[2]);
+        assert_eq!(results[0].sort_value, Some(0.99));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_card_aliases() {
+        cleanup();
+
+        let config = OpsmlDatabaseSettings {
+            connection_uri: get_connection_uri(),
+            max_connections: 1,
+            sql_type: SqlType::Sqlite,
+        };
+
+        let client = SqliteClient::new(&config).await.unwrap();
+
+        let first = ModelCardRecord {
+            repository: "fraud".to_string(),
+            name: "detector".to_string(),
+            ..ModelCardRecord::default()
+        };
+        let second = ModelCardRecord {
+            repository: "fraud".to_string(),
+            name: "detector".to_string(),
+            minor: 1,
+            version: "1.1.0".to_string(),
+            ..ModelCardRecord::default()
+        };
+        for card in [&first, &second] {
+            client
+                .insert_card(&CardSQLTableNames::Model, &Card::Model(card.clone()))
+                .await
+                .unwrap();
+        }
+
+        let table = CardSQLTableNames::Model;
+        let production = CardAlias::new(
+            &table,
+            "fraud",
+            "detector",
+            "production",
+            &first.uid,
+            "admin",
+        );
+        let previous = client.set_card_alias(&production).await.unwrap();
+        assert_eq!(previous, None);
+
+        // resolve through query_cards
+        let args = CardQueryArgs {
+            repository: Some("fraud".to_string()),
+            name: Some("detector".to_string()),
+            alias: Some("production".to_string()),
+            ..Default::default()
+        };
+        let cards = client.query_cards(&table, &args).await.unwrap();
+        assert_eq!(cards.len(), 1);
+        if let CardResults::Model(cards) = cards {
+            assert_eq!(cards[0].uid, first.uid);
+        }
+
+        // move the alias to the newer version
+        let moved = CardAlias::new(
+            &table,
+            "fraud",
+            "detector",
+            "production",
+            &second.uid,
+            "admin",
+        );
+        let previous = client.set_card_alias(&moved).await.unwrap();
+        assert_eq!(previous, Some(first.uid.clone()));
+
+        // setting the same target again is not recorded as a move
+        client.set_card_alias(&moved).await.unwrap();
+
+        let resolved = client
+            .get_card_alias(&table, "fraud", "detector", "production")
             .await
+            .unwrap()
             .unwrap();
+        assert_eq!(resolved.uid, second.uid);
 
-        assert_eq!(results.len(), 1);
+        let cards = client.query_cards(&table, &args).await.unwrap();
+        assert_eq!(cards.len(), 1);
+        if let CardResults::Model(cards) = cards {
+            assert_eq!(cards[0].uid, second.uid);
+        }
 
-        // update the card
-        pipeline_card.name = "UpdatedPipelineName".to_string();
-        let updated_card = Card::Pipeline(pipeline_card.clone());
+        let staging = CardAlias::new(&table, "fraud", "detector", "staging", &first.uid, "admin");
+        client.set_card_alias(&staging).await.unwrap();
 
-        client
-            .update_card(&CardSQLTableNames::Pipeline, &updated_card)
+        let aliases = client
+            .get_card_aliases(&table, "fraud", "detector")
             .await
             .unwrap();
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases[0].alias, "production");
+        assert_eq!(aliases[1].alias, "staging");
 
-        // check if the card was updated
-        let updated_results = client
-            .query_cards(&CardSQLTableNames::Pipeline, &card_args)
+        let history = client
+            .get_card_alias_history(&table, "fraud", "detector")
             .await
             .unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[1].previous_uid, Some(first.uid.clone()));
+        assert_eq!(history[1].uid, second.uid);
 
-        assert_eq!(updated_results.len(), 1);
-        if let CardResults::Pipeline(cards) = updated_results {
-            assert_eq!(cards[0].name, "UpdatedPipelineName");
-        }
-
-        cleanup();
+        // unknown aliases resolve to nothing
+        let args = CardQueryArgs {
+            alias: Some("champion".to_string()),
+            ..args
+        };
+        assert!(client.query_cards(&table, &args).await.unwrap().is_empty());
+        assert!(client
+            .get_card_alias(&table, "fraud", "detector", "champion")
+            .await
+            .unwrap()
+            .is_none());
     }
 
     #[tokio::test]
-    async fn test_sqlite_unique_repos() {
+    async fn test_sqlite_card_status() {
         cleanup();
 
         let config = OpsmlDatabaseSettings {
@@ -1591,23 +4557,98 @@ mod tests {
 
         let client = SqliteClient::new(&config).await.unwrap();
 
-        // Run the SQL script to populate the database
-        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
-        sqlx::query(&script).execute(&client.pool).await.unwrap();
+        let table = CardSQLTableNames::Model;
+        let deprecated = ModelCardRecord {
+            repository: "fraud".to_string(),
+            name: "detector".to_string(),
+            ..ModelCardRecord::default()
+        };
+        let active = ModelCardRecord {
+            repository: "fraud".to_string(),
+            name: "detector".to_string(),
+            minor: 1,
+            version: "1.1.0".to_string(),
+            ..ModelCardRecord::default()
+        };
+        let draft = ModelCardRecord {
+            repository: "fraud".to_string(),
+            name: "detector".to_string(),
+            minor: 2,
+            version: "1.2.0".to_string(),
+            status: CardStatus::Draft.to_string(),
+            ..ModelCardRecord::default()
+        };
+        for card in [&deprecated, &active, &draft] {
+            client
+                .insert_card(&table, &Card::Model(card.clone()))
+                .await
+                .unwrap();
+        }
 
-        // get unique repository names
-        let repos = client
-            .get_unique_repository_names(&CardSQLTableNames::Model)
+        let previous = client
+            .update_card_status(&table, &deprecated.uid, &CardStatus::Deprecated)
             .await
             .unwrap();
+        assert_eq!(previous, CardStatus::Active);
 
-        assert_eq!(repos.len(), 10);
+        // deprecated cards can't go back to draft
+        let result = client
+            .update_card_status(&table, &deprecated.uid, &CardStatus::Draft)
+            .await;
+        assert!(matches!(result, Err(SqlError::ConflictError(_))));
 
-        cleanup();
+        let result = client
+            .update_card_status(&table, "missing", &CardStatus::Active)
+            .await;
+        assert!(matches!(result, Err(SqlError::NotFoundError(_))));
+
+        // deprecated versions are skipped by default
+        let args = CardQueryArgs {
+            repository: Some("fraud".to_string()),
+            name: Some("detector".to_string()),
+            ..Default::default()
+        };
+        let cards = client.query_cards(&table, &args).await.unwrap();
+        assert_eq!(cards.len(), 2);
+
+        let args = CardQueryArgs {
+            status: Some(vec![CardStatus::Deprecated]),
+            ..args
+        };
+        let cards = client.query_cards(&table, &args).await.unwrap();
+        assert_eq!(cards.len(), 1);
+        if let CardResults::Model(cards) = cards {
+            assert_eq!(cards[0].uid, deprecated.uid);
+            assert_eq!(cards[0].status, "deprecated");
+        }
+
+        // exact uid lookups return any status
+        let args = CardQueryArgs {
+            uid: Some(deprecated.uid.clone()),
+            ..Default::default()
+        };
+        assert_eq!(client.query_cards(&table, &args).await.unwrap().len(), 1);
+
+        let page = client
+            .query_page(
+                "name",
+                0,
+                None,
+                None,
+                Some("fraud"),
+                Some(&CardStatus::Deprecated),
+                &table,
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].version, "1.0.0");
+        assert_eq!(page[0].status, "deprecated");
+        assert_eq!(page[0].versions, 1);
     }
 
     #[tokio::test]
-    async fn test_sqlite_query_stats() {
+    async fn test_sqlite_card_yank() {
         cleanup();
 
         let config = OpsmlDatabaseSettings {
@@ -1618,33 +4659,81 @@ mod tests {
 
         let client = SqliteClient::new(&config).await.unwrap();
 
-        // Run the SQL script to populate the database
-        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
-        sqlx::query(&script).execute(&client.pool).await.unwrap();
-
-        // query stats
-        let stats = client
-            .query_stats(&CardSQLTableNames::Model, None)
-            .await
-            .unwrap();
+        let table = CardSQLTableNames::Data;
+        let good = DataCardRecord {
+            repository: "snapshots".to_string(),
+            name: "daily".to_string(),
+            major: 2,
+            minor: 1,
+            patch: 0,
+            version: "2.1.0".to_string(),
+            ..DataCardRecord::default()
+        };
+        let bad = DataCardRecord {
+            repository: "snapshots".to_string(),
+            name: "daily".to_string(),
+            major: 2,
+            minor: 1,
+            patch: 1,
+            version: "2.1.1".to_string(),
+            ..DataCardRecord::default()
+        };
+        for card in [&good, &bad] {
+            client
+                .insert_card(&table, &Card::Data(card.clone()))
+                .await
+                .unwrap();
+        }
 
-        assert_eq!(stats.nbr_names, 10);
-        assert_eq!(stats.nbr_versions, 10);
-        assert_eq!(stats.nbr_repositories, 10);
+        client.yank_card(&table, &bad.uid, true).await.unwrap();
 
-        // query stats with search term
-        let stats = client
-            .query_stats(&CardSQLTableNames::Model, Some("Model1"))
+        // yanked versions stay taken when allocating the next version
+        let versions = client
+            .get_versions(&table, "daily", "snapshots", Some("^2.1.0"))
             .await
             .unwrap();
+        assert_eq!(versions, vec!["2.1.1".to_string(), "2.1.0".to_string()]);
 
-        assert_eq!(stats.nbr_names, 2); // for Model1 and Model10
+        // ranges skip the yanked version
 
-        cleanup();
+        let args = CardQueryArgs {
+            repository: Some("snapshots".to_string()),
+            name: Some("daily".to_string()),
+            version: Some("^2.1.0".to_string()),
+            ..Default::default()
+        };
+        let cards = client.query_cards(&table, &args).await.unwrap();
+        assert_eq!(cards.len(), 1);
+        if let CardResults::Data(cards) = cards {
+            assert_eq!(cards[0].uid, good.uid);
+        }
+
+        // the exact version is still fetchable
+        let exact_args = CardQueryArgs {
+            repository: Some("snapshots".to_string()),
+            name: Some("daily".to_string()),
+            version: Some("2.1.1".to_string()),
+            ..Default::default()
+        };
+        let cards = client.query_cards(&table, &exact_args).await.unwrap();
+        assert_eq!(cards.len(), 1);
+        if let CardResults::Data(cards) = cards {
+            assert_eq!(cards[0].uid, bad.uid);
+            assert!(cards[0].yanked);
+        }
+
+        client.yank_card(&table, &bad.uid, false).await.unwrap();
+        let cards = client.query_cards(&table, &args).await.unwrap();
+        assert_eq!(cards.len(), 2);
+
+        let result = client.yank_card(&table, "missing", true).await;
+        assert!(matches!(result, Err(SqlError::NotFoundError(_))));
     }
 
     #[tokio::test]
-    async fn test_sqlite_query_page() {
+    async fn test_sqlite_calver_versions() {
+        use opsml_utils::calver::CalVer;
+
         cleanup();
 
         let config = OpsmlDatabaseSettings {
@@ -1655,96 +4744,163 @@ mod tests {
 
         let client = SqliteClient::new(&config).await.unwrap();
 
-        // Run the SQL script to populate the database
-        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
-        sqlx::query(&script).execute(&client.pool).await.unwrap();
+        let table = CardSQLTableNames::Data;
+        let calver = |version: &str| {
+            let semver = CalVer::parse(version).unwrap().to_semver();
+            DataCardRecord {
+                repository: "nightly".to_string(),
+                name: "snapshot".to_string(),
+                major: semver.major as i32,
+                minor: semver.minor as i32,
+                patch: semver.patch as i32,
+                version: version.to_string(),
+                ..DataCardRecord::default()
+            }
+        };
+        let cards = [
+            calver("2024.11.30.2"),
+            calver("2024.12.07.9"),
+            calver("2024.12.07.10"),
+            calver("2025.01.02.1"),
+        ];
+        for card in &cards {
+            client
+                .insert_card(&table, &Card::Data(card.clone()))
+                .await
+                .unwrap();
+        }
 
-        // query page
-        let results = client
-            .query_page("name", 0, None, None, &CardSQLTableNames::Data)
+        // repositories use semver until set otherwise
+        assert_eq!(
+            client.get_version_scheme("nightly").await.unwrap(),
+            VersionScheme::SemVer
+        );
+        client
+            .set_version_scheme("nightly", &VersionScheme::CalVer)
             .await
             .unwrap();
+        assert_eq!(
+            client.get_version_scheme("nightly").await.unwrap(),
+            VersionScheme::CalVer
+        );
 
-        assert_eq!(results.len(), 1);
-
-        // query page
-        let results = client
-            .query_page("name", 0, None, None, &CardSQLTableNames::Model)
+        // build numbers and dates order numerically
+        let versions = client
+            .get_versions(&table, "snapshot", "nightly", None)
             .await
             .unwrap();
+        let versions = versions
+            .iter()
+            .map(|v| {
+                CalVer::from_semver(&Version::parse(v).unwrap())
+                    .unwrap()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            versions,
+            vec![
+                "2025.01.02.1",
+                "2024.12.07.10",
+                "2024.12.07.9",
+                "2024.11.30.2"
+            ]
+        );
 
-        assert_eq!(results.len(), 10);
+        let query = |version: &str| CardQueryArgs {
+            repository: Some("nightly".to_string()),
+            name: Some("snapshot".to_string()),
+            version: Some(version.to_string()),
+            version_scheme: Some(VersionScheme::CalVer),
+            ..Default::default()
+        };
+
+        for (version, expected) in [
+            ("2024.12", 2),
+            ("2024", 3),
+            ("2024.12.07.10", 1),
+            (">=2024.12.07.10", 2),
+            (">2024.11, <2025", 2),
+        ] {
+            let results = client.query_cards(&table, &query(version)).await.unwrap();
+            assert_eq!(results.len(), expected, "{}", version);
+        }
 
-        // query page
         let results = client
-            .query_page("name", 0, None, Some("repo3"), &CardSQLTableNames::Model)
+            .query_cards(&table, &query("2024.12.07.10"))
             .await
             .unwrap();
-
-        assert_eq!(results.len(), 1);
-
-        cleanup();
+        if let CardResults::Data(results) = results {
+            assert_eq!(results[0].uid, cards[2].uid);
+            assert_eq!(results[0].version, "2024.12.07.10");
+        }
     }
 
     #[tokio::test]
-    async fn test_sqlite_delete_card() {
+    async fn test_sqlite_card_version_conflict() {
         cleanup();
 
         let config = OpsmlDatabaseSettings {
             connection_uri: get_connection_uri(),
-            max_connections: 1,
+            max_connections: 2,
             sql_type: SqlType::Sqlite,
         };
 
         let client = SqliteClient::new(&config).await.unwrap();
 
-        // Run the SQL script to populate the database
-        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
-        sqlx::query(&script).execute(&client.pool).await.unwrap();
-
-        // delete card
+        let table = CardSQLTableNames::Data;
+        let card = || DataCardRecord {
+            repository: "ci".to_string(),
+            name: "classifier".to_string(),
+            major: 1,
+            minor: 3,
+            patch: 0,
+            version: "1.3.0".to_string(),
+            ..DataCardRecord::default()
+        };
+        let first = card();
+        let second = card();
+
+        // two jobs registering the same version at once
+        let first_card = Card::Data(first.clone());
+        let second_card = Card::Data(second.clone());
+        let (a, b) = tokio::join!(
+            client.insert_card(&table, &first_card),
+            client.insert_card(&table, &second_card)
+        );
+        let results = [a, b];
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        assert!(results
+            .iter()
+            .any(|r| matches!(r, Err(SqlError::ConflictError(_)))));
 
         let args = CardQueryArgs {
-            uid: None,
-            name: Some("Data1".to_string()),
-            repository: Some("repo1".to_string()),
+            repository: Some("ci".to_string()),
+            name: Some("classifier".to_string()),
+            version: Some("1.3.0".to_string()),
             ..Default::default()
         };
-
-        let cards = client
-            .query_cards(&CardSQLTableNames::Data, &args)
-            .await
-            .unwrap();
-
-        let uid = match cards {
+        let cards = client.query_cards(&table, &args).await.unwrap();
+        assert_eq!(cards.len(), 1);
+        let registered = match cards {
             CardResults::Data(cards) => cards[0].uid.clone(),
-            _ => "".to_string(),
+            _ => panic!("expected data cards"),
         };
 
-        assert!(!uid.is_empty());
-
-        // delete the card
+        // a version in the trash can be registered again
         client
-            .delete_card(&CardSQLTableNames::Data, &uid)
+            .delete_card(&table, &registered, "admin")
             .await
             .unwrap();
-
-        // check if the card was deleted
-        let args = CardQueryArgs {
-            uid: Some(uid),
-            ..Default::default()
-        };
-
-        let results = client
-            .query_cards(&CardSQLTableNames::Data, &args)
+        let third = card();
+        client
+            .insert_card(&table, &Card::Data(third))
             .await
             .unwrap();
-
-        assert_eq!(results.len(), 0);
     }
 
     #[tokio::test]
-    async fn test_sqlite_project_id() {
+    async fn test_sqlite_unique_versions() {
         cleanup();
 
         let config = OpsmlDatabaseSettings {
@@ -1755,38 +4911,71 @@ mod tests {
 
         let client = SqliteClient::new(&config).await.unwrap();
 
-        // Run the SQL script to populate the database
-        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
-        sqlx::query(&script).execute(&client.pool).await.unwrap();
-
-        // get project id
+        let table = CardSQLTableNames::Data;
+        let card = |version: &str, minor: i32| DataCardRecord {
+            repository: "ci".to_string(),
+            name: "features".to_string(),
+            major: 1,
+            minor,
+            patch: 0,
+            version: version.to_string(),
+            ..DataCardRecord::default()
+        };
+        let first = card("1.0.0", 0);
+        let mut second = card("1.1.0", 1);
+        for record in [&first, &second] {
+            client
+                .insert_card(&table, &Card::Data(record.clone()))
+                .await
+                .unwrap();
+        }
 
-        let project_id = client.get_project_id("test", "repo").await.unwrap();
-        assert_eq!(project_id, 1);
+        // moving a card onto a live version breaks the unique version index
+        second.minor = 0;
+        second.version = "1.0.0".to_string();
+        let result = client.update_card(&table, &Card::Data(second)).await;
+        assert!(matches!(result, Err(SqlError::ConflictError(_))));
 
-        // get next project id
-        let project_id = client.get_project_id("test1", "repo").await.unwrap();
+        // run metrics are looked up by run
+        let index: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name = 'idx_run_metrics_run_uid'",
+        )
+        .fetch_one(&client.pool)
+        .await
+        .unwrap();
+        assert_eq!(index, 1);
+
+        // duplicates left by older versions are reported before the unique index is added
+        sqlx::raw_sql(
+            "DROP INDEX idx_data_registry_unique_version;
+            DELETE FROM _sqlx_migrations WHERE version = 20241210120000;",
+        )
+        .execute(&client.pool)
+        .await
+        .unwrap();
+        let mut conn = client.pool.acquire().await.unwrap();
+        SqliteClient::insert_card_record(&mut conn, &table, &Card::Data(card("1.0.0", 0)))
+            .await
+            .unwrap();
+        drop(conn);
 
-        assert_eq!(project_id, 2);
+        let result = client.run_migrations().await;
+        match result {
+            Err(SqlError::MigrationError(message)) => {
+                assert!(message.contains("ci/features 1.0.0 (2 cards)"))
+            }
+            _ => panic!("expected the duplicate check to fail"),
+        }
 
-        let args = CardQueryArgs {
-            uid: None,
-            name: Some("test".to_string()),
-            repository: Some("repo".to_string()),
-            ..Default::default()
-        };
-        let cards = client
-            .query_cards(&CardSQLTableNames::Project, &args)
+        client
+            .delete_card(&table, &first.uid, "admin")
             .await
             .unwrap();
-
-        assert_eq!(cards.len(), 1);
-        cleanup();
+        client.run_migrations().await.unwrap();
     }
 
-    // test run metric
     #[tokio::test]
-    async fn test_sqlite_run_metric() {
+    async fn test_sqlite_transaction() {
         cleanup();
 
         let config = OpsmlDatabaseSettings {
@@ -1797,60 +4986,105 @@ mod tests {
 
         let client = SqliteClient::new(&config).await.unwrap();
 
-        // Run the SQL script to populate the database
-        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
-
-        sqlx::query(&script).execute(&client.pool).await.unwrap();
-
-        let uid = "550e8400-e29b-41d4-a716-446655440000".to_string();
-        let metric_names = vec!["metric1", "metric2", "metric3"];
-
-        for name in metric_names {
-            let metric = MetricRecord {
-                run_uid: uid.clone(),
-                name: name.to_string(),
-                value: 1.0,
-                ..Default::default()
-            };
-
-            client.insert_run_metric(&metric).await.unwrap();
+        let table = CardSQLTableNames::Run;
+        let run = RunCardRecord {
+            repository: "ci".to_string(),
+            name: "train".to_string(),
+            ..RunCardRecord::default()
+        };
+        let card = Card::Run(run.clone());
+        let metrics = vec![MetricRecord::new(
+            run.uid.clone(),
+            "loss".to_string(),
+            0.5,
+            None,
+            None,
+        )];
+
+        // writes are visible inside the transaction and discarded on rollback
+        let mut tx = client.begin().await.unwrap();
+        tx.insert_card(&table, &card).await.unwrap();
+        tx.insert_run_metrics(&metrics).await.unwrap();
+        assert!(tx.check_uid_exists(&run.uid, &table).await.unwrap());
+        tx.rollback().await.unwrap();
+
+        assert!(!client.check_uid_exists(&run.uid, &table).await.unwrap());
+        assert!(client
+            .get_run_metric(&run.uid, &[])
+            .await
+            .unwrap()
+            .is_empty());
+
+        // a transaction dropped before commit is rolled back
+        {
+            let mut tx = client.begin().await.unwrap();
+            tx.insert_card(&table, &card).await.unwrap();
+            tx.insert_run_metrics(&metrics).await.unwrap();
         }
 
-        let records = client.get_run_metric(&uid, &Vec::new()).await.unwrap();
-        let names = client.get_run_metric_names(&uid).await.unwrap();
-
-        assert_eq!(records.len(), 3);
-
-        // assert names = "metric1"
-        assert_eq!(names.len(), 3);
-
-        // insert vec
-        let records = vec![
-            MetricRecord {
-                run_uid: uid.clone(),
-                name: "vec1".to_string(),
-                value: 1.0,
-                ..Default::default()
-            },
-            MetricRecord {
-                run_uid: uid.clone(),
-                name: "vec2".to_string(),
-                value: 1.0,
-                ..Default::default()
-            },
-        ];
-
-        client.insert_run_metrics(&records).await.unwrap();
+        assert!(!client.check_uid_exists(&run.uid, &table).await.unwrap());
+        assert!(client
+            .get_run_metric(&run.uid, &[])
+            .await
+            .unwrap()
+            .is_empty());
+
+        let mut tx = client.begin().await.unwrap();
+        tx.insert_card(&table, &card).await.unwrap();
+        tx.insert_run_metrics(&metrics).await.unwrap();
+        tx.commit().await.unwrap();
+
+        assert!(client.check_uid_exists(&run.uid, &table).await.unwrap());
+        assert_eq!(client.get_run_metric(&run.uid, &[]).await.unwrap().len(), 1);
+
+        // project ids read in a transaction account for projects registered in it
+        let mut tx = client.begin().await.unwrap();
+        let project_id = tx.get_project_id("vision", "ci").await.unwrap();
+        let project = ProjectCardRecord {
+            name: "vision".to_string(),
+            repository: "ci".to_string(),
+            project_id,
+            ..ProjectCardRecord::default()
+        };
+        tx.insert_card(&CardSQLTableNames::Project, &Card::Project(project))
+            .await
+            .unwrap();
+        assert_eq!(
+            tx.get_project_id("audio", "ci").await.unwrap(),
+            project_id + 1
+        );
+        tx.commit().await.unwrap();
 
-        let records = client.get_run_metric(&uid, &Vec::new()).await.unwrap();
+        assert_eq!(
+            client.get_project_id("vision", "ci").await.unwrap(),
+            project_id
+        );
 
-        assert_eq!(records.len(), 5);
+        // a card change and its history entry are discarded together
+        let query_args = CardQueryArgs {
+            uid: Some(run.uid.clone()),
+            ..Default::default()
+        };
+        let mut tx = client.begin().await.unwrap();
+        tx.yank_card(&table, &run.uid, true).await.unwrap();
+        let CardResults::Run(cards) = tx.query_cards(&table, &query_args).await.unwrap() else {
+            panic!("expected run cards");
+        };
+        assert!(cards[0].yanked);
+        let history = CardHistoryRecord::new(&table, &run.uid, "yank", "test_user", None, None);
+        tx.insert_card_history(&history).await.unwrap();
+        tx.rollback().await.unwrap();
 
+        let CardResults::Run(cards) = client.query_cards(&table, &query_args).await.unwrap() else {
+            panic!("expected run cards");
+        };
+        assert!(!cards[0].yanked);
+        assert!(client.get_card_history(&run.uid).await.unwrap().is_empty());
         cleanup();
     }
 
     #[tokio::test]
-    async fn test_sqlite_hardware_metric() {
+    async fn test_sqlite_run_status() {
         cleanup();
 
         let config = OpsmlDatabaseSettings {
@@ -1861,34 +5095,69 @@ mod tests {
 
         let client = SqliteClient::new(&config).await.unwrap();
 
-        // Run the SQL script to populate the database
-        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
+        let run_uid = "550e8400-e29b-41d4-a716-446655440000";
 
-        sqlx::query(&script).execute(&client.pool).await.unwrap();
+        let started = client.start_run(run_uid).await.unwrap();
+        assert_eq!(started.status, "running");
+        assert!(started.ended_at.is_none());
 
-        let uid = "550e8400-e29b-41d4-a716-446655440000".to_string();
-        let mut metrics = vec![];
+        let result = client.start_run(run_uid).await;
+        assert!(matches!(result, Err(SqlError::ConflictError(_))));
 
-        // create a loop of 10
-        for _ in 0..10 {
-            let metric = HardwareMetricsRecord {
-                run_uid: uid.clone(),
-                created_at: get_utc_datetime(),
-                ..Default::default()
-            };
-            metrics.push(metric.clone());
-        }
+        let beat = client
+            .update_run_status(run_uid, &RunStatus::Running)
+            .await
+            .unwrap();
+        assert!(beat.heartbeat_at >= started.heartbeat_at);
 
-        client.insert_hardware_metrics(&metrics).await.unwrap();
-        let records = client.get_hardware_metric(&uid).await.unwrap();
+        // a recent heartbeat is within the timeout
+        let hour_ago = get_utc_datetime() - chrono::Duration::hours(1);
+        assert_eq!(client.mark_crashed_runs(hour_ago).await.unwrap(), 0);
 
-        assert_eq!(records.len(), 10);
+        let later = get_utc_datetime() + chrono::Duration::seconds(1);
+        assert_eq!(client.mark_crashed_runs(later).await.unwrap(), 1);
+
+        let crashed = client.get_run_status(run_uid).await.unwrap().unwrap();
+        assert_eq!(crashed.status, "crashed");
+        assert!(crashed.ended_at.is_some());
+
+        // a crashed run that reports back is running again
+        let resumed = client
+            .update_run_status(run_uid, &RunStatus::Running)
+            .await
+            .unwrap();
+        assert_eq!(resumed.status, "running");
+        assert!(resumed.ended_at.is_none());
 
+        let finished = client
+            .update_run_status(run_uid, &RunStatus::Finished)
+            .await
+            .unwrap();
+        assert_eq!(finished.status, "finished");
+        assert!(finished.ended_at.is_some());
+        assert_eq!(
+            client
+                .get_run_status(run_uid)
+                .await
+                .unwrap()
+                .unwrap()
+                .status,
+            "finished"
+        );
+
+        let result = client.update_run_status(run_uid, &RunStatus::Running).await;
+        assert!(matches!(result, Err(SqlError::ConflictError(_))));
+
+        let result = client
+            .update_run_status("missing", &RunStatus::Running)
+            .await;
+        assert!(matches!(result, Err(SqlError::NotFoundError(_))));
+        assert!(client.get_run_status("missing").await.unwrap().is_none());
         cleanup();
     }
 
     #[tokio::test]
-    async fn test_sqlite_parameter() {
+    async fn test_sqlite_child_runs() {
         cleanup();
 
         let config = OpsmlDatabaseSettings {
@@ -1899,42 +5168,87 @@ mod tests {
 
         let client = SqliteClient::new(&config).await.unwrap();
 
-        // Run the SQL script to populate the database
-        let script = std::fs::read_to_string("tests/populate_sqlite_test.sql").unwrap();
-
-        sqlx::query(&script).execute(&client.pool).await.unwrap();
-
-        let uid = "550e8400-e29b-41d4-a716-446655440000".to_string();
-        let mut params = vec![];
-
-        // create a loop of 10
-        for i in 0..10 {
-            let parameter = ParameterRecord {
-                run_uid: uid.clone(),
-                name: format!("param{}", i),
-                ..Default::default()
-            };
-
-            params.push(parameter.clone());
+        let table = CardSQLTableNames::Run;
+        let parent = RunCardRecord {
+            name: "sweep".to_string(),
+            ..RunCardRecord::default()
+        };
+        let child = |name: &str| RunCardRecord {
+            name: name.to_string(),
+            parent_uid: Some(parent.uid.clone()),
+            ..RunCardRecord::default()
+        };
+        let first = child("trial-1");
+        let second = child("trial-2");
+        let other = RunCardRecord::default();
+
+        for run in [&parent, &first, &second, &other] {
+            client
+                .insert_card(&table, &Card::Run(run.clone()))
+                .await
+                .unwrap();
         }
 
-        client.insert_run_parameters(&params).await.unwrap();
-        let records = client.get_run_parameter(&uid, &Vec::new()).await.unwrap();
-
-        assert_eq!(records.len(), 10);
-
-        let param_records = client
-            .get_run_parameter(&uid, &["param1".to_string()])
+        // each child contributes the last value it logged
+        let logged = [
+            (&first, "loss", 0, 0.9),
+            (&first, "loss", 1, 0.4),
+            (&first, "accuracy", 1, 0.7),
+            (&second, "loss", 0, 0.8),
+            (&second, "loss", 1, 0.6),
+            (&second, "accuracy", 1, 0.9),
+            (&other, "loss", 1, 0.1),
+        ];
+        let metrics: Vec<MetricRecord> = logged
+            .iter()
+            .map(|(run, name, step, value)| {
+                MetricRecord::new(run.uid.clone(), name.to_string(), *value, Some(*step), None)
+            })
+            .collect();
+        client.insert_run_metrics(&metrics).await.unwrap();
+
+        let children = client.get_child_runs(&parent.uid).await.unwrap();
+        let mut uids: Vec<String> = children.iter().map(|run| run.uid.clone()).collect();
+        uids.sort();
+        let mut expected = vec![first.uid.clone(), second.uid.clone()];
+        expected.sort();
+        assert_eq!(uids, expected);
+        assert!(children
+            .iter()
+            .all(|run| run.parent_uid.as_deref() == Some(parent.uid.as_str())));
+
+        let summary = client.get_child_metric_summary(&parent.uid).await.unwrap();
+        assert_eq!(summary.len(), 2);
+
+        let accuracy = &summary[0];
+        assert_eq!(accuracy.name, "accuracy");
+        assert_eq!(accuracy.max_run_uid, second.uid);
+        assert!((accuracy.mean_value - 0.8).abs() < 1e-9);
+
+        let loss = &summary[1];
+        assert_eq!(loss.name, "loss");
+        assert_eq!(loss.run_count, 2);
+        assert_eq!(loss.min_value, 0.4);
+        assert_eq!(loss.min_run_uid, first.uid);
+        assert_eq!(loss.max_value, 0.6);
+        assert_eq!(loss.max_run_uid, second.uid);
+        assert!((loss.mean_value - 0.5).abs() < 1e-9);
+
+        // trashed children drop out of the group
+        client
+            .delete_card(&table, &second.uid, "admin")
             .await
             .unwrap();
+        assert_eq!(client.get_child_runs(&parent.uid).await.unwrap().len(), 1);
+        let summary = client.get_child_metric_summary(&parent.uid).await.unwrap();
+        assert!(summary.iter().all(|metric| metric.run_count == 1));
 
-        assert_eq!(param_records.len(), 1);
-
+        assert!(client.get_child_runs(&other.uid).await.unwrap().is_empty());
         cleanup();
     }
 
     #[tokio::test]
-    async fn test_sqlite_user() {
+    async fn test_sqlite_groups() {
         cleanup();
 
         let config = OpsmlDatabaseSettings {
@@ -1945,20 +5259,55 @@ mod tests {
 
         let client = SqliteClient::new(&config).await.unwrap();
 
-        let user = User::new("user".to_string(), "pass".to_string(), None, None);
-        client.insert_user(&user).await.unwrap();
+        let nlp = Group::new("nlp".to_string(), vec!["write:nlp-*".to_string()]);
+        let platform = Group::new("platform".to_string(), vec!["delete:*".to_string()]);
+        client.insert_group(&nlp).await.unwrap();
+        client.insert_group(&platform).await.unwrap();
 
-        let mut user = client.get_user("user").await.unwrap();
-        assert_eq!(user.username, "user");
+        // group names are unique
+        let duplicate = client.insert_group(&nlp).await;
+        assert!(matches!(duplicate, Err(SqlError::ConflictError(_))));
 
-        // update user
-        user.active = false;
-        user.refresh_token = Some("token".to_string());
+        client.add_group_member("nlp", "alice").await.unwrap();
+        client.add_group_member("nlp", "bob").await.unwrap();
+        client.add_group_member("platform", "alice").await.unwrap();
+        // adding twice is a no-op
+        client.add_group_member("nlp", "alice").await.unwrap();
 
-        client.update_user(&user).await.unwrap();
-        let user = client.get_user("user").await.unwrap();
-        assert!(!user.active);
-        assert_eq!(user.refresh_token.unwrap(), "token");
+        assert_eq!(
+            client.get_group_members("nlp").await.unwrap(),
+            vec!["alice", "bob"]
+        );
+
+        let groups = client.get_user_groups("alice").await.unwrap();
+        let names: Vec<&str> = groups.iter().map(|group| group.name.as_str()).collect();
+        assert_eq!(names, vec!["nlp", "platform"]);
+        assert_eq!(groups[0].permissions, vec!["write:nlp-*"]);
+
+        let mut updated = client.get_group("nlp").await.unwrap().unwrap();
+        updated.permissions.push("read".to_string());
+        client.update_group(&updated).await.unwrap();
+        let group = client.get_group("nlp").await.unwrap().unwrap();
+        assert_eq!(group.permissions, vec!["write:nlp-*", "read"]);
+
+        client.remove_group_member("nlp", "bob").await.unwrap();
+        assert!(client.get_user_groups("bob").await.unwrap().is_empty());
+
+        // deleting a group drops its memberships
+        client.delete_group("platform").await.unwrap();
+        assert!(client.get_group("platform").await.unwrap().is_none());
+        assert_eq!(client.get_user_groups("alice").await.unwrap().len(), 1);
+        assert_eq!(client.get_groups().await.unwrap().len(), 1);
+
+        // a group and its memberships roll back together
+        let mut tx = client.begin().await.unwrap();
+        tx.insert_group(&Group::new("research".to_string(), vec![]))
+            .await
+            .unwrap();
+        tx.add_group_member("research", "alice").await.unwrap();
+        tx.rollback().await.unwrap();
+        assert!(client.get_group("research").await.unwrap().is_none());
+        assert_eq!(client.get_user_groups("alice").await.unwrap().len(), 1);
 
         cleanup();
     }
@@ -0,0 +1,145 @@
+-- Add migration script here
+-- SQLite Migration Script
+
+-- Full-text search index spanning all card registries
+CREATE VIRTUAL TABLE IF NOT EXISTS opsml_card_search USING fts5(
+    uid UNINDEXED,
+    registry_type UNINDEXED,
+    name,
+    repository,
+    contact,
+    tags,
+    tokenize = 'unicode61'
+);
+
+-- DataSchema
+INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+SELECT uid, 'data', name, repository, contact, tags FROM opsml_data_registry;
+
+CREATE TRIGGER IF NOT EXISTS opsml_data_registry_search_insert AFTER INSERT ON opsml_data_registry
+BEGIN
+    INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+    VALUES (NEW.uid, 'data', NEW.name, NEW.repository, NEW.contact, NEW.tags);
+END;
+
+CREATE TRIGGER IF NOT EXISTS opsml_data_registry_search_update AFTER UPDATE ON opsml_data_registry
+BEGIN
+    DELETE FROM opsml_card_search WHERE uid = OLD.uid AND registry_type = 'data';
+    INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+    VALUES (NEW.uid, 'data', NEW.name, NEW.repository, NEW.contact, NEW.tags);
+END;
+
+CREATE TRIGGER IF NOT EXISTS opsml_data_registry_search_delete AFTER DELETE ON opsml_data_registry
+BEGIN
+    DELETE FROM opsml_card_search WHERE uid = OLD.uid AND registry_type = 'data';
+END;
+
+-- ModelSchema
+INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+SELECT uid, 'model', name, repository, contact, tags FROM opsml_model_registry;
+
+CREATE TRIGGER IF NOT EXISTS opsml_model_registry_search_insert AFTER INSERT ON opsml_model_registry
+BEGIN
+    INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+    VALUES (NEW.uid, 'model', NEW.name, NEW.repository, NEW.contact, NEW.tags);
+END;
+
+CREATE TRIGGER IF NOT EXISTS opsml_model_registry_search_update AFTER UPDATE ON opsml_model_registry
+BEGIN
+    DELETE FROM opsml_card_search WHERE uid = OLD.uid AND registry_type = 'model';
+    INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+    VALUES (NEW.uid, 'model', NEW.name, NEW.repository, NEW.contact, NEW.tags);
+END;
+
+CREATE TRIGGER IF NOT EXISTS opsml_model_registry_search_delete AFTER DELETE ON opsml_model_registry
+BEGIN
+    DELETE FROM opsml_card_search WHERE uid = OLD.uid AND registry_type = 'model';
+END;
+
+-- RunSchema
+INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+SELECT uid, 'run', name, repository, contact, tags FROM opsml_run_registry;
+
+CREATE TRIGGER IF NOT EXISTS opsml_run_registry_search_insert AFTER INSERT ON opsml_run_registry
+BEGIN
+    INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+    VALUES (NEW.uid, 'run', NEW.name, NEW.repository, NEW.contact, NEW.tags);
+END;
+
+CREATE TRIGGER IF NOT EXISTS opsml_run_registry_search_update AFTER UPDATE ON opsml_run_registry
+BEGIN
+    DELETE FROM opsml_card_search WHERE uid = OLD.uid AND registry_type = 'run';
+    INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+    VALUES (NEW.uid, 'run', NEW.name, NEW.repository, NEW.contact, NEW.tags);
+END;
+
+CREATE TRIGGER IF NOT EXISTS opsml_run_registry_search_delete AFTER DELETE ON opsml_run_registry
+BEGIN
+    DELETE FROM opsml_card_search WHERE uid = OLD.uid AND registry_type = 'run';
+END;
+
+-- AuditSchema
+INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+SELECT uid, 'audit', name, repository, contact, tags FROM opsml_audit_registry;
+
+CREATE TRIGGER IF NOT EXISTS opsml_audit_registry_search_insert AFTER INSERT ON opsml_audit_registry
+BEGIN
+    INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+    VALUES (NEW.uid, 'audit', NEW.name, NEW.repository, NEW.contact, NEW.tags);
+END;
+
+CREATE TRIGGER IF NOT EXISTS opsml_audit_registry_search_update AFTER UPDATE ON opsml_audit_registry
+BEGIN
+    DELETE FROM opsml_card_search WHERE uid = OLD.uid AND registry_type = 'audit';
+    INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+    VALUES (NEW.uid, 'audit', NEW.name, NEW.repository, NEW.contact, NEW.tags);
+END;
+
+CREATE TRIGGER IF NOT EXISTS opsml_audit_registry_search_delete AFTER DELETE ON opsml_audit_registry
+BEGIN
+    DELETE FROM opsml_card_search WHERE uid = OLD.uid AND registry_type = 'audit';
+END;
+
+-- PipelineSchema
+INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+SELECT uid, 'pipeline', name, repository, contact, tags FROM opsml_pipeline_registry;
+
+CREATE TRIGGER IF NOT EXISTS opsml_pipeline_registry_search_insert AFTER INSERT ON opsml_pipeline_registry
+BEGIN
+    INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+    VALUES (NEW.uid, 'pipeline', NEW.name, NEW.repository, NEW.contact, NEW.tags);
+END;
+
+CREATE TRIGGER IF NOT EXISTS opsml_pipeline_registry_search_update AFTER UPDATE ON opsml_pipeline_registry
+BEGIN
+    DELETE FROM opsml_card_search WHERE uid = OLD.uid AND registry_type = 'pipeline';
+    INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+    VALUES (NEW.uid, 'pipeline', NEW.name, NEW.repository, NEW.contact, NEW.tags);
+END;
+
+CREATE TRIGGER IF NOT EXISTS opsml_pipeline_registry_search_delete AFTER DELETE ON opsml_pipeline_registry
+BEGIN
+    DELETE FROM opsml_card_search WHERE uid = OLD.uid AND registry_type = 'pipeline';
+END;
+
+-- ProjectSchema
+INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+SELECT uid, 'project', name, repository, '', '' FROM opsml_project_registry;
+
+CREATE TRIGGER IF NOT EXISTS opsml_project_registry_search_insert AFTER INSERT ON opsml_project_registry
+BEGIN
+    INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+    VALUES (NEW.uid, 'project', NEW.name, NEW.repository, '', '');
+END;
+
+CREATE TRIGGER IF NOT EXISTS opsml_project_registry_search_update AFTER UPDATE ON opsml_project_registry
+BEGIN
+    DELETE FROM opsml_card_search WHERE uid = OLD.uid AND registry_type = 'project';
+    INSERT INTO opsml_card_search (uid, registry_type, name, repository, contact, tags)
+    VALUES (NEW.uid, 'project', NEW.name, NEW.repository, '', '');
+END;
+
+CREATE TRIGGER IF NOT EXISTS opsml_project_registry_search_delete AFTER DELETE ON opsml_project_registry
+BEGIN
+    DELETE FROM opsml_card_search WHERE uid = OLD.uid AND registry_type = 'project';
+END;
@@ -0,0 +1,16 @@
+-- Add migration script here
+-- SQLite Migration Script
+
+-- LineageSchema
+CREATE TABLE IF NOT EXISTS opsml_card_lineage (
+    upstream_uid TEXT NOT NULL,
+    upstream_type TEXT NOT NULL,
+    downstream_uid TEXT NOT NULL,
+    downstream_type TEXT NOT NULL,
+    declared_by TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (upstream_uid, downstream_uid, declared_by)
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_lineage_downstream ON opsml_card_lineage (downstream_uid);
+CREATE INDEX IF NOT EXISTS idx_card_lineage_declared_by ON opsml_card_lineage (declared_by);
@@ -0,0 +1,21 @@
+-- Add migration script here
+-- SQLite Migration Script
+
+-- Soft delete columns for each card registry
+ALTER TABLE opsml_data_registry ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE opsml_data_registry ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE opsml_model_registry ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE opsml_model_registry ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE opsml_run_registry ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE opsml_run_registry ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE opsml_audit_registry ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE opsml_audit_registry ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE opsml_pipeline_registry ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE opsml_pipeline_registry ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE opsml_project_registry ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE opsml_project_registry ADD COLUMN deleted_by TEXT;
@@ -0,0 +1,15 @@
+-- Add migration script here
+-- SQLite Migration Script
+
+-- CardHistorySchema
+CREATE TABLE IF NOT EXISTS opsml_card_history (
+    idx INTEGER PRIMARY KEY AUTOINCREMENT,
+    uid TEXT NOT NULL,
+    registry_type TEXT NOT NULL,
+    action TEXT NOT NULL,
+    username TEXT NOT NULL,
+    changes TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_history_uid ON opsml_card_history (uid);
@@ -0,0 +1,31 @@
+-- Add migration script here
+-- SQLite Migration Script
+
+-- CardAliasSchema
+CREATE TABLE IF NOT EXISTS opsml_card_aliases (
+    registry_type TEXT NOT NULL,
+    repository TEXT NOT NULL,
+    name TEXT NOT NULL,
+    alias TEXT NOT NULL,
+    uid TEXT NOT NULL,
+    updated_by TEXT NOT NULL,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (registry_type, repository, name, alias)
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_aliases_uid ON opsml_card_aliases (uid);
+
+-- CardAliasHistorySchema
+CREATE TABLE IF NOT EXISTS opsml_card_alias_history (
+    idx INTEGER PRIMARY KEY AUTOINCREMENT,
+    registry_type TEXT NOT NULL,
+    repository TEXT NOT NULL,
+    name TEXT NOT NULL,
+    alias TEXT NOT NULL,
+    previous_uid TEXT,
+    uid TEXT NOT NULL,
+    updated_by TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_alias_history_name ON opsml_card_alias_history (registry_type, repository, name);
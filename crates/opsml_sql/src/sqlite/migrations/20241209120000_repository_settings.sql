@@ -0,0 +1,9 @@
+-- Add migration script here
+-- SQLite Migration Script
+
+-- RepositorySettingsSchema
+CREATE TABLE IF NOT EXISTS opsml_repository_settings (
+    repository TEXT PRIMARY KEY,
+    version_scheme TEXT NOT NULL DEFAULT 'semver',
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,13 @@
+-- Add migration script here
+-- SQLite Migration Script
+
+-- RunStatusSchema
+CREATE TABLE IF NOT EXISTS opsml_run_status (
+    run_uid TEXT PRIMARY KEY,
+    status TEXT NOT NULL DEFAULT 'running',
+    started_at TIMESTAMP NOT NULL,
+    ended_at TIMESTAMP,
+    heartbeat_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_run_status_heartbeat ON opsml_run_status (status, heartbeat_at);
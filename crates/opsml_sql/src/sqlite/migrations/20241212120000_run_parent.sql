@@ -0,0 +1,7 @@
+-- Add migration script here
+-- SQLite Migration Script
+
+-- Runs nested under a parent run, such as sweep trials or cross-validation folds
+ALTER TABLE opsml_run_registry ADD COLUMN parent_uid TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_run_registry_parent_uid ON opsml_run_registry (parent_uid);
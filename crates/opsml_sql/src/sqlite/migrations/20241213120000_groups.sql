@@ -0,0 +1,20 @@
+-- Add migration script here
+-- SQLite Migration Script
+
+-- GroupSchema
+CREATE TABLE IF NOT EXISTS opsml_groups (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    name TEXT NOT NULL UNIQUE,
+    permissions TEXT NOT NULL
+);
+
+-- GroupMemberSchema
+CREATE TABLE IF NOT EXISTS opsml_group_members (
+    group_name TEXT NOT NULL,
+    username TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (group_name, username)
+);
+
+CREATE INDEX IF NOT EXISTS idx_group_members_username ON opsml_group_members (username);
@@ -0,0 +1,12 @@
+-- Add migration script here
+-- SQLite Migration Script
+
+-- RefreshTokenSchema
+CREATE TABLE IF NOT EXISTS opsml_refresh_tokens (
+    family TEXT PRIMARY KEY,
+    username TEXT NOT NULL,
+    token_hash TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_username ON opsml_refresh_tokens (username);
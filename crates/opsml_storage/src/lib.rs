@@ -1 +1,4 @@
+// pyo3 0.22 macros trigger false positives for PyResult conversions
+#![allow(clippy::useless_conversion)]
+
 pub mod storage;
@@ -272,8 +272,8 @@ impl AWSMulitPartUpload {
             // get presigned url for the part
             // if client mode is enabled, use the api client to generate the presigned url
             // else use the storage client to generate the presigned url
-            let presigned_url = if self.api_client.is_some() {
-                let mut client = self.api_client.as_ref().unwrap().clone();
+            let presigned_url = if let Some(api_client) = &self.api_client {
+                let mut client = api_client.clone();
                 client
                     .generate_presigned_url_for_part(&self.rpath, &self.upload_id, part_number)
                     .await
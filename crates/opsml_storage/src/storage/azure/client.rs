@@ -402,7 +402,7 @@ impl StorageClient for AzureStorageClient {
                 match blob {
                     BlobItem::Blob(blob) => {
                         let name = blob.name;
-                        let suffix = name.split('.').last().unwrap().to_string();
+                        let suffix = name.split('.').next_back().unwrap().to_string();
                         let info = FileInfo {
                             name,
                             size: blob.properties.content_length as i64,
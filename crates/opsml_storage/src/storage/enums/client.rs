@@ -16,6 +16,7 @@ use crate::storage::aws::client::{AWSMulitPartUpload, S3FStorageClient};
 use crate::storage::azure::client::{AzureFSStorageClient, AzureMultipartUpload};
 use crate::storage::gcs::client::{GCSFSStorageClient, GoogleMultipartUpload};
 
+#[allow(clippy::large_enum_variant)]
 pub enum MultiPartUploader {
     Google(GoogleMultipartUpload),
     AWS(AWSMulitPartUpload),
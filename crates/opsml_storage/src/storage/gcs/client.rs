@@ -254,33 +254,39 @@ impl StorageClient for GoogleStorageClient {
         let creds = GcpCreds::new().await?;
         // If no credentials, attempt to create a default client pulling from the environment
 
-        let config = if creds.creds.is_none() {
-            // if using in client_mode, default to anonymous
-            let config = if settings.client_mode {
-                ClientConfig::default().anonymous()
-            } else {
-                let config = ClientConfig::default().with_auth().await;
-
-                // default to anonymous if unable to create client with auth
-                match config {
-                    Ok(config) => config,
-                    Err(_) => ClientConfig::default().anonymous(),
-                }
-            };
+        let config = match creds.creds {
+            None => {
+                // if using in client_mode, default to anonymous
+                let config = if settings.client_mode {
+                    ClientConfig::default().anonymous()
+                } else {
+                    let config = ClientConfig::default().with_auth().await;
+
+                    // default to anonymous if unable to create client with auth
+                    match config {
+                        Ok(config) => config,
+                        Err(_) => ClientConfig::default().anonymous(),
+                    }
+                };
+
+                Ok(config)
+            }
 
-            Ok(config)
+            // if creds are set (base64 for JSON file)
+            Some(credentials) => {
+                // try with credentials
+                let config = ClientConfig::default()
+                    .with_credentials(credentials)
+                    .await
+                    .map_err(|e| {
+                        StorageError::Error(format!(
+                            "Unable to create client with credentials: {}",
+                            e
+                        ))
+                    })?;
 
-        // if creds are set (base64 for JSON file)
-        } else {
-            // try with credentials
-            let config = ClientConfig::default()
-                .with_credentials(creds.creds.unwrap())
-                .await
-                .map_err(|e| {
-                    StorageError::Error(format!("Unable to create client with credentials: {}", e))
-                })?;
-
-            Ok(config)
+                Ok(config)
+            }
         };
 
         let config = config?;
@@ -425,7 +431,13 @@ impl StorageClient for GoogleStorageClient {
                     Some(last_modified) => last_modified.to_string(),
                     None => "".to_string(),
                 },
-                suffix: o.name.clone().split('.').last().unwrap_or("").to_string(),
+                suffix: o
+                    .name
+                    .clone()
+                    .split('.')
+                    .next_back()
+                    .unwrap_or("")
+                    .to_string(),
             })
             .collect())
     }
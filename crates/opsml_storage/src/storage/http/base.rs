@@ -85,6 +85,7 @@ pub struct OpsmlApiClient {
     pub client: Client,
     settings: OpsmlStorageSettings,
     base_path: String,
+    refresh_token: Option<String>,
 }
 
 impl OpsmlApiClient {
@@ -98,6 +99,7 @@ impl OpsmlApiClient {
                 "{}/{}",
                 settings.api_settings.base_url, settings.api_settings.opsml_dir
             ),
+            refresh_token: None,
         };
 
         if settings.api_settings.use_auth {
@@ -148,22 +150,28 @@ impl OpsmlApiClient {
             .map_err(|e| ApiError::Error(format!("Failed to parse response with error: {}", e)))?;
 
         self.settings.api_settings.auth_token = response.token;
+        self.refresh_token = response.refresh_token;
 
         Ok(())
     }
 
     /// Refresh the JWT token when it expires
-    /// This function is called with the old JWT token, which is then verified with the server refresh token
+    /// This function is called with the refresh token issued at login, which the server rotates on every use
     async fn refresh_token(&mut self) -> Result<(), ApiError> {
         if !self.settings.api_settings.use_auth {
             return Ok(());
         }
 
+        let refresh_token = self
+            .refresh_token
+            .as_ref()
+            .ok_or_else(|| ApiError::Error("No refresh token available".to_string()))?;
+
         let url = format!("{}/{}", self.base_path, Routes::AuthApiRefresh.as_str());
         let response = self
             .client
             .get(url)
-            .bearer_auth(&self.settings.api_settings.auth_token)
+            .bearer_auth(refresh_token)
             .send()
             .await
             .map_err(|e| ApiError::Error(format!("Failed to send request with error: {}", e)))?
@@ -172,6 +180,7 @@ impl OpsmlApiClient {
             .map_err(|e| ApiError::Error(format!("Failed to parse response with error: {}", e)))?;
 
         self.settings.api_settings.auth_token = response.token;
+        self.refresh_token = response.refresh_token;
 
         Ok(())
     }
@@ -1,3 +1,5 @@
 pub const UPLOAD_CHUNK_SIZE: usize = 1024 * 1024 * 5;
 pub const DOWNLOAD_CHUNK_SIZE: usize = 1024 * 1024 * 5;
 pub const MAX_FILE_SIZE: usize = 1024 * 1024 * 1024 * 50;
+pub const REGISTRY_PAGE_SIZE: i32 = 30;
+pub const MAX_LINEAGE_DEPTH: i32 = 10;
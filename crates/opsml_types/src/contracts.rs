@@ -1,8 +1,9 @@
 use crate::helper::PyHelperFuncs;
 use crate::HardwareMetrics;
 use crate::{enums::StorageType, RegistryType};
-use crate::{Metric, Parameter, VersionType};
+use crate::{AlignedMetric, Metric, Parameter, ParameterComparison, VersionType};
 use chrono::NaiveDateTime;
+use opsml_error::error::TypeError;
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -101,6 +102,155 @@ pub struct RegistryStatsRequest {
 
 // RegistryStatsResponse is sourced from sql schema
 
+/// Lifecycle status of a card
+///
+/// * `Draft` - Work in progress and not ready to be used
+/// * `Active` - Current. New cards are active unless created as a draft
+/// * `Deprecated` - Still available but should no longer be used
+/// * `Archived` - Retired for good. Archived cards cannot change status
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CardStatus {
+    Draft,
+    #[default]
+    Active,
+    Deprecated,
+    Archived,
+}
+
+impl CardStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CardStatus::Draft => "draft",
+            CardStatus::Active => "active",
+            CardStatus::Deprecated => "deprecated",
+            CardStatus::Archived => "archived",
+        }
+    }
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s {
+            "draft" => Some(CardStatus::Draft),
+            "active" => Some(CardStatus::Active),
+            "deprecated" => Some(CardStatus::Deprecated),
+            "archived" => Some(CardStatus::Archived),
+            _ => None,
+        }
+    }
+
+    pub fn all() -> Vec<CardStatus> {
+        vec![
+            CardStatus::Draft,
+            CardStatus::Active,
+            CardStatus::Deprecated,
+            CardStatus::Archived,
+        ]
+    }
+
+    /// Whether a card may move from this status to `next`
+    pub fn can_transition_to(&self, next: &CardStatus) -> bool {
+        matches!(
+            (self, next),
+            (CardStatus::Draft, CardStatus::Active)
+                | (CardStatus::Draft, CardStatus::Archived)
+                | (CardStatus::Active, CardStatus::Deprecated)
+                | (CardStatus::Active, CardStatus::Archived)
+                | (CardStatus::Deprecated, CardStatus::Active)
+                | (CardStatus::Deprecated, CardStatus::Archived)
+        )
+    }
+
+    /// Statuses returned when cards are resolved by name and version without a status filter
+    pub fn resolvable() -> Vec<CardStatus> {
+        vec![CardStatus::Draft, CardStatus::Active]
+    }
+}
+
+impl std::fmt::Display for CardStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Versioning scheme used by a repository
+///
+/// * `SemVer` - Semantic versions, e.g. `1.2.0`
+/// * `CalVer` - Calendar versions `YYYY.MM.DD.N`, where `N` counts the versions of the day
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionScheme {
+    #[default]
+    SemVer,
+    CalVer,
+}
+
+impl VersionScheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VersionScheme::SemVer => "semver",
+            VersionScheme::CalVer => "calver",
+        }
+    }
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s {
+            "semver" | "sem_ver" => Some(VersionScheme::SemVer),
+            "calver" | "cal_ver" => Some(VersionScheme::CalVer),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for VersionScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct VersionSchemeRequest {
+    pub repository: String,
+}
+
+/// Versioning scheme of a repository. Repositories use semver until set otherwise
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepositoryVersionScheme {
+    pub repository: String,
+    pub version_scheme: VersionScheme,
+}
+
+/// Move a card to a new lifecycle status
+#[derive(Serialize, Deserialize)]
+pub struct CardStatusRequest {
+    pub registry_type: RegistryType,
+    pub uid: String,
+    pub status: CardStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CardStatusResponse {
+    pub uid: String,
+    pub previous_status: CardStatus,
+    pub status: CardStatus,
+}
+
+/// Yank (or un-yank with `yanked: false`) a card version
+#[derive(Serialize, Deserialize)]
+pub struct CardYankRequest {
+    pub registry_type: RegistryType,
+    pub uid: String,
+    pub yanked: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CardYankResponse {
+    pub uid: String,
+    pub yanked: bool,
+}
+
+/// Request for a page of card summaries. When `cursor` is provided, summaries are
+/// walked by most recent update using the cursor and `page`/`sort_by` are ignored.
+/// `status` only counts versions with that status
 #[derive(Serialize, Deserialize)]
 pub struct QueryPageRequest {
     pub registry_type: RegistryType,
@@ -108,10 +258,110 @@ pub struct QueryPageRequest {
     pub repository: Option<String>,
     pub search_term: Option<String>,
     pub page: Option<i32>,
+    /// Opts into keyset paging by updated_at, which cannot be combined with `sort_by`.
+    /// Pass an empty cursor for the first page
+    pub cursor: Option<String>,
+    pub status: Option<CardStatus>,
 }
 
 // QueryPageResponse is sourced from sql schema
 
+/// Full-text search across the name, repository, contact and tags of every registry
+#[derive(Serialize, Deserialize)]
+pub struct CardSearchRequest {
+    pub search_term: String,
+    pub limit: Option<i32>,
+}
+
+// CardSearchResponse is sourced from sql schema
+
+#[derive(Serialize, Deserialize)]
+pub struct DeletedCardsRequest {
+    pub registry_type: RegistryType,
+    /// Only list cards of this repository. Without it the trash is limited to the
+    /// repositories the caller can read
+    pub repository: Option<String>,
+}
+
+// DeletedCardsResponse is sourced from sql schema
+
+#[derive(Serialize, Deserialize)]
+pub struct CardHistoryRequest {
+    pub uid: String,
+    pub registry_type: RegistryType,
+}
+
+// CardHistoryResponse is sourced from sql schema
+
+/// Point an alias, such as `production`, of a (repository, name) at one of its cards
+#[derive(Serialize, Deserialize)]
+pub struct CardAliasRequest {
+    pub registry_type: RegistryType,
+    pub repository: String,
+    pub name: String,
+    pub alias: String,
+    pub uid: String,
+}
+
+impl CardAliasRequest {
+    /// Aliases are 1 to 64 ascii letters, digits, `-` or `_`
+    pub fn validate_alias(&self) -> Result<(), String> {
+        let valid = !self.alias.is_empty()
+            && self.alias.len() <= 64
+            && self
+                .alias
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+        if valid {
+            Ok(())
+        } else {
+            Err(format!(
+                "Invalid alias '{}'. Aliases are 1 to 64 letters, digits, '-' or '_'",
+                self.alias
+            ))
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ResolveCardAliasRequest {
+    pub registry_type: RegistryType,
+    pub repository: String,
+    pub name: String,
+    pub alias: String,
+}
+
+/// Aliases or alias history of a (repository, name)
+#[derive(Serialize, Deserialize)]
+pub struct CardAliasesRequest {
+    pub registry_type: RegistryType,
+    pub repository: String,
+    pub name: String,
+}
+
+// CardAliasResponse, CardAliasesResponse and CardAliasHistoryResponse are sourced from sql schema
+
+/// Direction to walk the lineage graph from a card
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LineageDirection {
+    Upstream,
+    Downstream,
+    #[default]
+    Both,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LineageRequest {
+    pub uid: String,
+    pub registry_type: RegistryType,
+    #[serde(default)]
+    pub direction: LineageDirection,
+    /// Hops to walk in each direction. Defaults to 3 and is capped at `MAX_LINEAGE_DEPTH`
+    pub depth: Option<i32>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct CardVersionRequest {
     pub registry_type: RegistryType,
@@ -121,6 +371,8 @@ pub struct CardVersionRequest {
     pub version_type: VersionType,
     pub pre_tag: Option<String>,
     pub build_tag: Option<String>,
+    /// Overrides the versioning scheme of the repository
+    pub version_scheme: Option<VersionScheme>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -128,6 +380,77 @@ pub struct CardVersionResponse {
     pub version: String,
 }
 
+/// Operators available when filtering cards by tag
+///
+/// * `Eq` - Tag exists and equals the value
+/// * `Ne` - Tag is missing or does not equal the value
+/// * `Exists` - Tag key is present
+/// * `NotExists` - Tag key is not present
+/// * `In` - Tag exists and equals one of the values
+/// * `Prefix` - Tag exists and starts with the value
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TagOperator {
+    Eq,
+    Ne,
+    Exists,
+    NotExists,
+    In,
+    Prefix,
+}
+
+/// Filter applied to the tags of a card
+///
+/// # Fields
+///
+/// * `key` - The tag key to filter on
+/// * `operator` - The comparison to apply
+/// * `values` - The values to compare against. `Eq`, `Ne` and `Prefix` expect one value,
+///   `In` expects at least one and `Exists`/`NotExists` expect none
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TagFilter {
+    pub key: String,
+    pub operator: TagOperator,
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+impl TagFilter {
+    pub fn new(key: &str, operator: TagOperator, values: Vec<String>) -> Self {
+        Self {
+            key: key.to_string(),
+            operator,
+            values,
+        }
+    }
+
+    /// Check that the number of values matches the operator
+    pub fn validate(&self) -> Result<(), TypeError> {
+        if self.key.is_empty() {
+            return Err(TypeError::Error(
+                "Tag filter key cannot be empty".to_string(),
+            ));
+        }
+
+        let valid = match self.operator {
+            TagOperator::Eq | TagOperator::Ne | TagOperator::Prefix => self.values.len() == 1,
+            TagOperator::In => !self.values.is_empty(),
+            TagOperator::Exists | TagOperator::NotExists => self.values.is_empty(),
+        };
+
+        if !valid {
+            return Err(TypeError::Error(format!(
+                "Invalid number of values ({}) for tag filter {:?} on key {}",
+                self.values.len(),
+                self.operator,
+                self.key
+            )));
+        }
+
+        Ok(())
+    }
+}
+
 /// Arguments for querying cards
 ///
 /// # Fields
@@ -137,10 +460,19 @@ pub struct CardVersionResponse {
 /// * `repository` - The repository of the card
 /// * `version` - The version of the card
 /// * `max_date` - The maximum date of the card
-/// * `tags` - The tags of the card
+/// * `tags` - The tags of the card (exact matches)
+/// * `tag_filters` - Operator based tag filters
 /// * `limit` - The maximum number of cards to return
 /// * `query_terms` - The query terms to search for
 /// * `sort_by_timestamp` - Whether to sort by timestamp
+/// * `cursor` - Opaque cursor returned by a previous query. Cards are returned by
+///   (created_at, uid) descending when paging with a cursor, and cursors are only handed
+///   out for that order
+/// * `include_deleted` - Whether to include cards in the trash
+/// * `alias` - Only return the card the alias points to, e.g. `production`
+/// * `status` - Only return cards with one of these statuses. Without it, deprecated and
+///   archived cards are left out unless the card is requested by uid
+/// * `version_scheme` - How `version` is interpreted. Defaults to semver
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct CardQueryArgs {
@@ -150,8 +482,55 @@ pub struct CardQueryArgs {
     pub version: Option<String>,
     pub max_date: Option<String>,
     pub tags: Option<HashMap<String, String>>,
+    pub tag_filters: Option<Vec<TagFilter>>,
     pub limit: Option<i32>,
     pub sort_by_timestamp: Option<bool>,
+    pub cursor: Option<String>,
+    #[serde(default)]
+    pub include_deleted: bool,
+    pub alias: Option<String>,
+    pub status: Option<Vec<CardStatus>>,
+    pub version_scheme: Option<VersionScheme>,
+}
+
+impl CardQueryArgs {
+    /// Whether cards are ordered by (created_at, uid), the only order a cursor can resume.
+    /// Otherwise cards are ordered by version
+    pub fn uses_cursor_order(&self) -> bool {
+        self.sort_by_timestamp.unwrap_or(false) || self.cursor.is_some()
+    }
+
+    /// Statuses to filter on, falling back to the statuses used for version resolution
+    pub fn get_statuses(&self) -> Vec<CardStatus> {
+        match &self.status {
+            Some(statuses) if !statuses.is_empty() => statuses.clone(),
+            _ => CardStatus::resolvable(),
+        }
+    }
+
+    /// Combine exact `tags` matches and `tag_filters` into a single list of filters
+    pub fn get_tag_filters(&self) -> Vec<TagFilter> {
+        let mut filters: Vec<TagFilter> = Vec::new();
+
+        if let Some(tags) = &self.tags {
+            // sort keys so generated queries are deterministic
+            let mut keys: Vec<&String> = tags.keys().collect();
+            keys.sort();
+            for key in keys {
+                filters.push(TagFilter::new(
+                    key,
+                    TagOperator::Eq,
+                    vec![tags[key].clone()],
+                ));
+            }
+        }
+
+        if let Some(tag_filters) = &self.tag_filters {
+            filters.extend(tag_filters.iter().cloned());
+        }
+
+        filters
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -162,8 +541,20 @@ pub struct ListCardRequest {
     pub version: Option<String>,
     pub max_date: Option<String>,
     pub tags: Option<HashMap<String, String>>,
+    pub tag_filters: Option<Vec<TagFilter>>,
     pub limit: Option<i32>,
     pub sort_by_timestamp: Option<bool>,
+    pub cursor: Option<String>,
+    /// Page by (created_at, uid) and wrap the cards with a `next_cursor`. Implied by
+    /// `cursor`; without either the response is the bare list of cards
+    #[serde(default)]
+    pub paginate: bool,
+    #[serde(default)]
+    pub include_deleted: bool,
+    pub alias: Option<String>,
+    pub status: Option<Vec<CardStatus>>,
+    /// How `version` is interpreted. Defaults to the scheme of `repository`
+    pub version_scheme: Option<VersionScheme>,
     pub registry_type: RegistryType,
 }
 
@@ -176,8 +567,15 @@ impl Default for ListCardRequest {
             version: None,
             max_date: None,
             tags: None,
+            tag_filters: None,
             limit: None,
             sort_by_timestamp: None,
+            cursor: None,
+            paginate: false,
+            include_deleted: false,
+            alias: None,
+            status: None,
+            version_scheme: None,
             registry_type: RegistryType::Data,
         }
     }
@@ -279,6 +677,7 @@ pub struct RunCardClientRecord {
     pub project: String,
     pub artifact_uris: Option<HashMap<String, String>>,
     pub compute_environment: Option<HashMap<String, String>>,
+    pub parent_uid: Option<String>,
 }
 
 impl Default for RunCardClientRecord {
@@ -298,6 +697,7 @@ impl Default for RunCardClientRecord {
             project: "".to_string(),
             artifact_uris: None,
             compute_environment: None,
+            parent_uid: None,
         }
     }
 }
@@ -405,10 +805,26 @@ pub enum ClientCard {
     Project(ProjectCardClientRecord),
 }
 
+impl ClientCard {
+    pub fn version(&self) -> &str {
+        match self {
+            ClientCard::Data(card) => &card.version,
+            ClientCard::Model(card) => &card.version,
+            ClientCard::Run(card) => &card.version,
+            ClientCard::Audit(card) => &card.version,
+            ClientCard::Pipeline(card) => &card.version,
+            ClientCard::Project(card) => &card.version,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateCardRequest {
     pub registry_type: RegistryType,
     pub card: ClientCard,
+    /// Initial status of the card. Defaults to active
+    #[serde(default)]
+    pub status: Option<CardStatus>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -435,10 +851,22 @@ pub struct MetricRequest {
     pub metrics: Vec<Metric>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Arguments for reading run metrics
+///
+/// # Fields
+///
+/// * `run_uid` - The uid of the run
+/// * `names` - The metric names to read. All metrics are read when empty
+/// * `start_step` - Only read metrics logged at or after this step
+/// * `end_step` - Only read metrics logged at or before this step
+/// * `max_points` - Downsample each metric to at most this many points
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct GetMetricRequest {
     pub run_uid: String,
     pub names: Vec<String>,
+    pub start_step: Option<i32>,
+    pub end_step: Option<i32>,
+    pub max_points: Option<i32>,
 }
 
 impl GetMetricRequest {
@@ -446,6 +874,7 @@ impl GetMetricRequest {
         Self {
             run_uid,
             names: names.unwrap_or_default(),
+            ..Default::default()
         }
     }
 }
@@ -465,6 +894,83 @@ pub struct MetricResponse {
     pub success: bool,
 }
 
+/// Lifecycle status of a run
+///
+/// * `Running` - Started and sending heartbeats
+/// * `Finished` - Ended successfully
+/// * `Failed` - Ended with an error reported by the run
+/// * `Crashed` - Stopped sending heartbeats. A crashed run that reports back is running again
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Running,
+    Finished,
+    Failed,
+    Crashed,
+}
+
+impl RunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunStatus::Running => "running",
+            RunStatus::Finished => "finished",
+            RunStatus::Failed => "failed",
+            RunStatus::Crashed => "crashed",
+        }
+    }
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s {
+            "running" => Some(RunStatus::Running),
+            "finished" => Some(RunStatus::Finished),
+            "failed" => Some(RunStatus::Failed),
+            "crashed" => Some(RunStatus::Crashed),
+            _ => None,
+        }
+    }
+
+    /// Whether the run has ended, either on its own or by missing heartbeats
+    pub fn is_ended(&self) -> bool {
+        !matches!(self, RunStatus::Running)
+    }
+
+    /// Whether a run may move from this status to `next`. A heartbeat is a move to `Running`
+    pub fn can_transition_to(&self, next: &RunStatus) -> bool {
+        matches!(
+            (self, next),
+            (RunStatus::Running, _)
+                | (RunStatus::Crashed, RunStatus::Running)
+                | (RunStatus::Crashed, RunStatus::Finished)
+                | (RunStatus::Crashed, RunStatus::Failed)
+        )
+    }
+}
+
+impl std::fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// List the runs nested under a parent run, or aggregate their metrics
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChildRunsRequest {
+    pub parent_uid: String,
+}
+
+/// Identify a run for the start, heartbeat and status routes
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunStatusRequest {
+    pub run_uid: String,
+}
+
+/// End a run with `Finished` or `Failed`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FinishRunRequest {
+    pub run_uid: String,
+    pub status: RunStatus,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ParameterRequest {
     pub run_uid: String,
@@ -491,6 +997,163 @@ pub struct ParameterResponse {
     pub success: bool,
 }
 
+/// Arguments for comparing runs
+///
+/// # Fields
+///
+/// * `run_uids` - The runs to compare
+/// * `metric_names` - The metrics to compare. All metrics are compared when empty
+/// * `parameter_names` - The parameters to compare. All parameters are compared when empty
+/// * `differs_only` - Only return metrics and parameters that differ between runs
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct CompareRunsRequest {
+    pub run_uids: Vec<String>,
+    #[serde(default)]
+    pub metric_names: Vec<String>,
+    #[serde(default)]
+    pub parameter_names: Vec<String>,
+    #[serde(default)]
+    pub differs_only: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompareRunsResponse {
+    pub metrics: Vec<AlignedMetric>,
+    pub parameters: Vec<ParameterComparison>,
+}
+
+/// Comparison applied to a run parameter or metric
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonOperator {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl ComparisonOperator {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            ComparisonOperator::Eq => "=",
+            ComparisonOperator::Ne => "<>",
+            ComparisonOperator::Gt => ">",
+            ComparisonOperator::Gte => ">=",
+            ComparisonOperator::Lt => "<",
+            ComparisonOperator::Lte => "<=",
+        }
+    }
+}
+
+/// Which value of a metric series represents the run
+///
+/// * `Last` - The value logged at the highest step
+/// * `Min` - The lowest value logged
+/// * `Max` - The highest value logged
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricAggregation {
+    #[default]
+    Last,
+    Min,
+    Max,
+}
+
+impl MetricAggregation {
+    /// Column of the per-run metric aggregate holding this value
+    pub fn column(&self) -> &'static str {
+        match self {
+            MetricAggregation::Last => "last_value",
+            MetricAggregation::Min => "min_value",
+            MetricAggregation::Max => "max_value",
+        }
+    }
+}
+
+/// Filter on a run parameter. Parameters are stored as text; when `value` parses as a number
+/// the comparison is numeric, otherwise it is a string comparison
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RunParameterFilter {
+    pub name: String,
+    pub operator: ComparisonOperator,
+    pub value: String,
+}
+
+/// Threshold on a run metric
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RunMetricFilter {
+    pub name: String,
+    pub operator: ComparisonOperator,
+    pub value: f64,
+    #[serde(default)]
+    pub aggregation: MetricAggregation,
+}
+
+/// Order runs by a metric. Runs that did not log the metric are returned last
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RunMetricSort {
+    pub name: String,
+    #[serde(default)]
+    pub aggregation: MetricAggregation,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// Arguments for searching runs
+///
+/// # Fields
+///
+/// * `project` - Only return runs of this project
+/// * `repository` - Only return runs of this repository
+/// * `parameters` - Parameter filters. A run must match all of them
+/// * `metrics` - Metric thresholds. A run must match all of them
+/// * `sort_by` - Metric to order the runs by. Newest runs first when not set
+/// * `limit` - The maximum number of runs to return
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RunQueryRequest {
+    pub project: Option<String>,
+    pub repository: Option<String>,
+    #[serde(default)]
+    pub parameters: Vec<RunParameterFilter>,
+    #[serde(default)]
+    pub metrics: Vec<RunMetricFilter>,
+    pub sort_by: Option<RunMetricSort>,
+    pub limit: Option<i32>,
+}
+
+impl RunQueryRequest {
+    /// Names of every metric referenced by the filters and sort
+    pub fn metric_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .metrics
+            .iter()
+            .map(|filter| filter.name.clone())
+            .chain(self.sort_by.iter().map(|sort| sort.name.clone()))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+// RunQueryResponse is sourced from sql schema
+
+/// Best runs of a project ranked by a metric
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunLeaderboardRequest {
+    pub project: String,
+    /// Repository of the project. Resolved from the project card when its name is unique
+    pub repository: Option<String>,
+    pub metric: String,
+    #[serde(default)]
+    pub aggregation: MetricAggregation,
+    #[serde(default)]
+    pub descending: bool,
+    pub limit: Option<i32>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct HardwareMetricRequest {
     pub run_uid: String,
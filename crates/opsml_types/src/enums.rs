@@ -490,6 +490,15 @@ pub enum CardSQLTableNames {
     HardwareMetrics,
     Parameters,
     Users,
+    Lineage,
+    History,
+    Aliases,
+    AliasHistory,
+    RepositorySettings,
+    RunStatus,
+    Groups,
+    GroupMembers,
+    RefreshTokens,
 }
 
 impl fmt::Display for CardSQLTableNames {
@@ -505,6 +514,15 @@ impl fmt::Display for CardSQLTableNames {
             CardSQLTableNames::HardwareMetrics => "opsml_run_hardware_metrics",
             CardSQLTableNames::Parameters => "opsml_run_parameters",
             CardSQLTableNames::Users => "opsml_users",
+            CardSQLTableNames::Lineage => "opsml_card_lineage",
+            CardSQLTableNames::History => "opsml_card_history",
+            CardSQLTableNames::Aliases => "opsml_card_aliases",
+            CardSQLTableNames::AliasHistory => "opsml_card_alias_history",
+            CardSQLTableNames::RepositorySettings => "opsml_repository_settings",
+            CardSQLTableNames::RunStatus => "opsml_run_status",
+            CardSQLTableNames::Groups => "opsml_groups",
+            CardSQLTableNames::GroupMembers => "opsml_group_members",
+            CardSQLTableNames::RefreshTokens => "opsml_refresh_tokens",
         };
         write!(f, "{}", table_name)
     }
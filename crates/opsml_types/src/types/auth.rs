@@ -1,6 +1,99 @@
 use serde::{Deserialize, Serialize};
 
+/// Access token, plus the refresh token issued alongside it on login and refresh
 #[derive(Serialize, Deserialize)]
 pub struct JwtToken {
     pub token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogoutResponse {
+    pub logged_out: bool,
+}
+
+/// Create a user. Permissions default to `read` and groups to `user`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateUserRequest {
+    pub username: String,
+    pub password: String,
+    pub permissions: Option<Vec<String>>,
+    pub group_permissions: Option<Vec<String>>,
+}
+
+/// Update a user. Unset fields are left as they are. Setting `active` to false
+/// invalidates the user's outstanding tokens
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateUserRequest {
+    pub username: String,
+    pub password: Option<String>,
+    pub permissions: Option<Vec<String>>,
+    pub group_permissions: Option<Vec<String>>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserRequest {
+    pub username: String,
+}
+
+/// A user without their password hash or refresh token
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserResponse {
+    pub username: String,
+    pub active: bool,
+    pub permissions: Vec<String>,
+    pub group_permissions: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsersResponse {
+    pub users: Vec<UserResponse>,
+}
+
+/// Change the password of the calling user
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// Create a group with its permission grants and, optionally, its first members
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateGroupRequest {
+    pub name: String,
+    pub permissions: Vec<String>,
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+/// Replace the permission grants of a group
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateGroupRequest {
+    pub name: String,
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupMemberRequest {
+    pub name: String,
+    pub username: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupResponse {
+    pub name: String,
+    pub permissions: Vec<String>,
+    pub members: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupsResponse {
+    pub groups: Vec<GroupResponse>,
 }
@@ -68,6 +68,57 @@ impl Default for Metric {
     }
 }
 
+/// Aggregate of a single metric over the requested step range. `last` is the value
+/// logged at the highest step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSummary {
+    pub name: String,
+    pub count: i64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub last: f64,
+    pub last_step: Option<i32>,
+}
+
+/// Metric series of several runs aligned on a shared step axis. `values[run_uid][i]` is the
+/// value the run logged at `steps[i]`, or None if the run did not log that step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlignedMetric {
+    pub name: String,
+    pub steps: Vec<i32>,
+    pub values: HashMap<String, Vec<Option<f64>>>,
+}
+
+impl AlignedMetric {
+    /// Whether any run logged a different series than the others
+    pub fn differs(&self) -> bool {
+        let mut series = self.values.values();
+        match series.next() {
+            Some(first) => series.any(|s| s != first),
+            None => false,
+        }
+    }
+}
+
+/// Value of a single parameter across runs. Runs that did not log the parameter map to None
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParameterComparison {
+    pub name: String,
+    pub values: HashMap<String, Option<String>>,
+}
+
+impl ParameterComparison {
+    /// Whether any run logged a different value than the others
+    pub fn differs(&self) -> bool {
+        let mut values = self.values.values();
+        match values.next() {
+            Some(first) => values.any(|v| v != first),
+            None => false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Parameter {
     pub name: String,
@@ -128,12 +179,12 @@ impl RunGraph {
             ));
         }
 
-        let graph_type = if y.is_some() {
+        let graph_type = if let Some(y_values) = &y {
             // assert length of y matches length of x
-            if y.as_ref().unwrap().len() != x.len() {
+            if y_values.len() != x.len() {
                 return Err(anyhow::anyhow!(
                     "Length of y must match length of x. Length of y: {}, Length of x: {}",
-                    y.as_ref().unwrap().len(),
+                    y_values.len(),
                     x.len()
                 ));
             }
@@ -0,0 +1,297 @@
+use crate::semver::{
+    split_comparators, split_operator, VersionBound, VersionBounds, VersionParser,
+};
+use chrono::{Datelike, NaiveDate};
+use opsml_error::error::VersionError;
+use semver::Version;
+use std::fmt;
+
+/// Calendar version `YYYY.MM.DD.N`, where `N` counts the versions registered on a day starting
+/// at 1. Cards store it in the semver columns as `YYYY.MMDD.N`, so ordering on
+/// (major, minor, patch) is calendar ordering
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CalVer {
+    pub date: NaiveDate,
+    pub build: u64,
+}
+
+impl CalVer {
+    pub fn new(date: NaiveDate, build: u64) -> Self {
+        CalVer { date, build }
+    }
+
+    fn date_from_parts(year: u64, month: u64, day: u64) -> Option<NaiveDate> {
+        if !(1000..=9999).contains(&year) {
+            return None;
+        }
+        NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
+    }
+
+    pub fn parse(version: &str) -> Result<Self, VersionError> {
+        let parts = version
+            .trim()
+            .split('.')
+            .map(|part| part.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| VersionError::InvalidVersion(format!("{}: {}", version, e)))?;
+
+        match parts[..] {
+            [year, month, day, build] => Self::date_from_parts(year, month, day)
+                .map(|date| CalVer::new(date, build))
+                .ok_or_else(|| {
+                    VersionError::InvalidVersion(format!("{} is not a valid date", version))
+                }),
+            _ => Err(VersionError::InvalidVersion(format!(
+                "Calendar versions have the form YYYY.MM.DD.N: {}",
+                version
+            ))),
+        }
+    }
+
+    /// Semver form stored in the registry (`2024.12.07.3` -> `2024.1207.3`)
+    pub fn to_semver(&self) -> Version {
+        Version::new(
+            self.date.year() as u64,
+            self.date.month() as u64 * 100 + self.date.day() as u64,
+            self.build,
+        )
+    }
+
+    /// Decode a stored version. Returns None for versions that are not calendar versions
+    pub fn from_semver(version: &Version) -> Option<Self> {
+        Self::date_from_parts(version.major, version.minor / 100, version.minor % 100)
+            .map(|date| CalVer::new(date, version.patch))
+    }
+
+    /// Next version to register on `today`. Counting continues from the latest version if it
+    /// is from today (or later, if clocks disagree)
+    pub fn next(latest: Option<&CalVer>, today: NaiveDate) -> Self {
+        match latest {
+            Some(latest) if latest.date >= today => CalVer::new(latest.date, latest.build + 1),
+            _ => CalVer::new(today, 1),
+        }
+    }
+
+    /// Parse a calendar version expression into bounds on the stored semver form.
+    ///
+    /// Supported forms are exact versions (`2024.12.07.3`), partial dates matching every
+    /// version in the period (`2024`, `2024.12`, `2024.12.07`, `2024.12.*`), the comparisons
+    /// `>`, `>=`, `<` and `<=`, and conjunctions (`>=2024.11, <2024.12.15`)
+    pub fn get_version_to_search(version: &str) -> Result<VersionBounds, VersionError> {
+        let mut bounds = VersionBounds {
+            lower_bound: None,
+            upper_bound: None,
+            parser_type: VersionParser::Range,
+        };
+
+        let comparators = version
+            .split(',')
+            .flat_map(split_comparators)
+            .collect::<Vec<_>>();
+
+        if let [comparator] = &comparators[..] {
+            let (op, rest) = split_operator(comparator);
+            let period = CalVerPeriod::parse(rest)?;
+            bounds.parser_type = match op {
+                "" | "=" if period.is_exact() => VersionParser::Exact,
+                "" | "=" => VersionParser::Star,
+                _ => VersionParser::Range,
+            };
+        }
+
+        for comparator in comparators {
+            let (op, rest) = split_operator(&comparator);
+            let (start, end) = match CalVerPeriod::parse(rest)?.range() {
+                Some(range) => range,
+                None => continue,
+            };
+            let exact = start == end;
+
+            match op {
+                "" | "=" => {
+                    bounds.restrict_lower(VersionBound::new(start, true));
+                    bounds.restrict_upper(VersionBound::new(end, exact));
+                }
+                ">" => bounds.restrict_lower(VersionBound::new(end, !exact)),
+                ">=" => bounds.restrict_lower(VersionBound::new(start, true)),
+                "<" => bounds.restrict_upper(VersionBound::new(start, false)),
+                "<=" => bounds.restrict_upper(VersionBound::new(end, exact)),
+                _ => {
+                    return Err(VersionError::InvalidVersion(format!(
+                        "Unsupported operator for calendar versions in {}",
+                        comparator
+                    )))
+                }
+            }
+        }
+
+        Ok(bounds)
+    }
+}
+
+impl fmt::Display for CalVer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}.{:02}.{:02}.{}",
+            self.date.year(),
+            self.date.month(),
+            self.date.day(),
+            self.build
+        )
+    }
+}
+
+/// A period named by a partial calendar version
+enum CalVerPeriod {
+    Any,
+    Year(u64),
+    Month(u64, u64),
+    Day(NaiveDate),
+    Exact(CalVer),
+}
+
+impl CalVerPeriod {
+    fn parse(version: &str) -> Result<Self, VersionError> {
+        let parts = version
+            .split('.')
+            .filter(|part| !part.is_empty())
+            .take_while(|part| !matches!(*part, "*" | "x" | "X"))
+            .map(|part| part.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| VersionError::InvalidVersion(format!("{}: {}", version, e)))?;
+
+        let invalid =
+            || VersionError::InvalidVersion(format!("Invalid calendar version {}", version));
+        let period = match parts[..] {
+            [] => CalVerPeriod::Any,
+            [year] => CalVerPeriod::Year(year),
+            [year, month] if (1..=12).contains(&month) => CalVerPeriod::Month(year, month),
+            [year, month, day] => {
+                CalVerPeriod::Day(CalVer::date_from_parts(year, month, day).ok_or_else(invalid)?)
+            }
+            [year, month, day, build] => CalVerPeriod::Exact(CalVer::new(
+                CalVer::date_from_parts(year, month, day).ok_or_else(invalid)?,
+                build,
+            )),
+            _ => return Err(invalid()),
+        };
+
+        Ok(period)
+    }
+
+    fn is_exact(&self) -> bool {
+        matches!(self, CalVerPeriod::Exact(_))
+    }
+
+    /// First stored version in the period and the first one past it. For an exact version
+    /// both are the version itself
+    fn range(&self) -> Option<(Version, Version)> {
+        match self {
+            CalVerPeriod::Any => None,
+            CalVerPeriod::Year(year) => {
+                Some((Version::new(*year, 0, 0), Version::new(year + 1, 0, 0)))
+            }
+            CalVerPeriod::Month(year, month) => Some((
+                Version::new(*year, month * 100, 0),
+                Version::new(*year, (month + 1) * 100, 0),
+            )),
+            CalVerPeriod::Day(date) => {
+                let start = CalVer::new(*date, 0).to_semver();
+                let mut end = start.clone();
+                end.minor += 1;
+                Some((start, end))
+            }
+            CalVerPeriod::Exact(calver) => Some((calver.to_semver(), calver.to_semver())),
+        }
+    }
+}
+
+/// Parse a card version that may be either semver or a calendar version. Calendar versions
+/// are returned in their stored semver form
+pub fn parse_card_version(version: &str) -> Result<Version, VersionError> {
+    match Version::parse(version) {
+        Ok(version) => Ok(version),
+        Err(e) => CalVer::parse(version)
+            .map(|calver| calver.to_semver())
+            .map_err(|_| VersionError::InvalidVersion(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(range: &str, version: &str) -> bool {
+        CalVer::get_version_to_search(range)
+            .unwrap()
+            .matches(&CalVer::parse(version).unwrap().to_semver())
+    }
+
+    #[test]
+    fn test_calver_parse_and_order() {
+        let version = CalVer::parse("2024.12.07.3").unwrap();
+        assert_eq!(version.to_string(), "2024.12.07.3");
+        assert_eq!(version.to_semver(), Version::new(2024, 1207, 3));
+        assert_eq!(CalVer::from_semver(&version.to_semver()), Some(version));
+
+        // build numbers sort numerically, not as text
+        assert!(CalVer::parse("2024.12.07.10").unwrap() > CalVer::parse("2024.12.07.9").unwrap());
+        assert!(
+            CalVer::parse("2024.12.07.1").unwrap().to_semver()
+                > CalVer::parse("2024.11.30.9").unwrap().to_semver()
+        );
+
+        assert!(CalVer::parse("2024.02.30.1").is_err());
+        assert!(CalVer::parse("1.2.3").is_err());
+        assert_eq!(CalVer::from_semver(&Version::new(1, 2, 3)), None);
+
+        assert_eq!(
+            parse_card_version("2024.12.07.3").unwrap(),
+            Version::new(2024, 1207, 3)
+        );
+        assert_eq!(parse_card_version("1.2.3").unwrap(), Version::new(1, 2, 3));
+    }
+
+    #[test]
+    fn test_calver_next() {
+        let today = NaiveDate::from_ymd_opt(2024, 12, 7).unwrap();
+        assert_eq!(CalVer::next(None, today).to_string(), "2024.12.07.1");
+
+        let latest = CalVer::parse("2024.12.07.4").unwrap();
+        assert_eq!(
+            CalVer::next(Some(&latest), today).to_string(),
+            "2024.12.07.5"
+        );
+
+        let latest = CalVer::parse("2024.12.06.4").unwrap();
+        assert_eq!(
+            CalVer::next(Some(&latest), today).to_string(),
+            "2024.12.07.1"
+        );
+    }
+
+    #[test]
+    fn test_calver_ranges() {
+        assert!(matches("2024.12", "2024.12.31.2"));
+        assert!(!matches("2024.12", "2025.01.01.1"));
+        assert!(matches("2024.12.*", "2024.12.01.1"));
+        assert!(matches("2024", "2024.06.15.1"));
+        assert!(matches("2024.12.07", "2024.12.07.12"));
+        assert!(!matches("2024.12.07", "2024.12.08.1"));
+        assert!(matches(">=2024.11, <2024.12.15", "2024.12.14.3"));
+        assert!(!matches(">=2024.11, <2024.12.15", "2024.12.15.1"));
+        assert!(matches(">2024.12.07", "2024.12.08.1"));
+        assert!(!matches(">2024.12.07", "2024.12.07.9"));
+        assert!(matches("<=2024.12", "2024.12.31.1"));
+        assert!(matches("*", "2024.12.31.1"));
+
+        let bounds = CalVer::get_version_to_search("2024.12.07.3").unwrap();
+        assert_eq!(bounds.parser_type, VersionParser::Exact);
+        let bounds = CalVer::get_version_to_search("2024.12").unwrap();
+        assert_eq!(bounds.parser_type, VersionParser::Star);
+
+        assert!(CalVer::get_version_to_search("^2024.12").is_err());
+        assert!(CalVer::get_version_to_search("2024.13").is_err());
+    }
+}
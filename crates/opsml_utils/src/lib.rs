@@ -1,7 +1,9 @@
+pub mod calver;
 pub mod color;
 pub mod semver;
 pub mod utils;
 
+pub use calver::*;
 pub use color::*;
 pub use semver::*;
 pub use utils::*;
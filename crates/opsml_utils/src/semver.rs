@@ -1,6 +1,7 @@
 use opsml_error::error::VersionError;
 use opsml_types::enums::VersionType;
 use semver::{BuildMetadata, Prerelease, Version};
+use std::cmp::Ordering;
 
 #[derive(Debug, PartialEq)]
 pub struct VersionArgs {
@@ -92,13 +93,79 @@ impl VersionValidator {
     }
 }
 
+/// One end of a version range
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionBound {
+    pub version: Version,
+    pub inclusive: bool,
+}
+
+impl VersionBound {
+    pub(crate) fn new(version: Version, inclusive: bool) -> Self {
+        VersionBound { version, inclusive }
+    }
+}
+
+/// The interval of versions matched by a version expression. A missing bound is unbounded.
+/// Every supported expression is a conjunction of half-open comparisons, so it always
+/// reduces to a single interval
 #[derive(Debug, PartialEq)]
 pub struct VersionBounds {
-    pub lower_bound: Version,
-    pub upper_bound: Version,
-    pub no_upper_bound: bool,
+    pub lower_bound: Option<VersionBound>,
+    pub upper_bound: Option<VersionBound>,
     pub parser_type: VersionParser,
-    pub num_parts: usize,
+}
+
+impl VersionBounds {
+    /// Check a version against the bounds using semver precedence (pre-releases sort
+    /// before their release, build metadata is ignored)
+    pub fn matches(&self, version: &Version) -> bool {
+        let above_lower = self.lower_bound.as_ref().is_none_or(|bound| {
+            match version.cmp_precedence(&bound.version) {
+                Ordering::Greater => true,
+                Ordering::Equal => bound.inclusive,
+                Ordering::Less => false,
+            }
+        });
+
+        let below_upper = self.upper_bound.as_ref().is_none_or(|bound| {
+            match version.cmp_precedence(&bound.version) {
+                Ordering::Less => true,
+                Ordering::Equal => bound.inclusive,
+                Ordering::Greater => false,
+            }
+        });
+
+        above_lower && below_upper
+    }
+
+    pub(crate) fn restrict_lower(&mut self, bound: VersionBound) {
+        let replace = match &self.lower_bound {
+            None => true,
+            Some(current) => match bound.version.cmp_precedence(&current.version) {
+                Ordering::Greater => true,
+                Ordering::Equal => !bound.inclusive,
+                Ordering::Less => false,
+            },
+        };
+        if replace {
+            self.lower_bound = Some(bound);
+        }
+    }
+
+    pub(crate) fn restrict_upper(&mut self, bound: VersionBound) {
+        let replace = match &self.upper_bound {
+            None => true,
+            Some(current) => match bound.version.cmp_precedence(&current.version) {
+                Ordering::Less => true,
+                Ordering::Equal => !bound.inclusive,
+                Ordering::Greater => false,
+            },
+        };
+        if replace {
+            self.upper_bound = Some(bound);
+        }
+    }
 }
 
 #[derive(PartialEq, Debug)]
@@ -107,202 +174,283 @@ pub enum VersionParser {
     Caret,
     Tilde,
     Exact,
+    Range,
 }
 
-impl VersionParser {
-    pub fn new(version: &str) -> Result<VersionParser, VersionError> {
-        // check if version contains
-        if version.contains('*') {
-            Ok(VersionParser::Star)
-        } else if version.contains('^') {
-            Ok(VersionParser::Caret)
-        } else if version.contains('~') {
-            Ok(VersionParser::Tilde)
+/// A possibly partial version such as `1`, `1.2`, `1.x` or `1.2.3-rc.1`
+struct PartialVersion {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Prerelease,
+}
+
+impl PartialVersion {
+    fn parse(version: &str) -> Result<Self, VersionError> {
+        // build metadata does not take part in precedence
+        let version = version.split('+').next().unwrap_or_default().trim();
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, pre),
+            None => (version, ""),
+        };
+
+        let mut parts = Vec::new();
+        let mut wildcard = false;
+        for part in core.split('.').filter(|part| !part.is_empty()) {
+            if matches!(part, "*" | "x" | "X") {
+                wildcard = true;
+                parts.push(None);
+            } else if wildcard {
+                return Err(VersionError::InvalidVersion(format!(
+                    "Version {} has a number after a wildcard",
+                    version
+                )));
+            } else {
+                let part = part
+                    .parse::<u64>()
+                    .map_err(|e| VersionError::InvalidVersion(format!("{}: {}", version, e)))?;
+                parts.push(Some(part));
+            }
+        }
+
+        if parts.len() > 3 {
+            return Err(VersionError::InvalidVersion(format!(
+                "Version {} has more than 3 parts",
+                version
+            )));
+        }
+        parts.resize(3, None);
+
+        let pre =
+            Prerelease::new(pre).map_err(|e| VersionError::InvalidPreRelease(e.to_string()))?;
+        if !pre.is_empty() && parts[2].is_none() {
+            return Err(VersionError::InvalidPreRelease(format!(
+                "Pre-release requires a full version: {}",
+                version
+            )));
+        }
+
+        Ok(PartialVersion {
+            major: parts[0],
+            minor: parts[1],
+            patch: parts[2],
+            pre,
+        })
+    }
+
+    fn is_full(&self) -> bool {
+        self.patch.is_some()
+    }
+
+    fn is_any(&self) -> bool {
+        self.major.is_none()
+    }
+
+    /// Lowest version the partial version stands for
+    fn floor(&self) -> Version {
+        let mut version = Version::new(
+            self.major.unwrap_or(0),
+            self.minor.unwrap_or(0),
+            self.patch.unwrap_or(0),
+        );
+        version.pre = self.pre.clone();
+        version
+    }
+
+    /// Lowest version a lower bound starts from. Without a pre-release tag the bound covers
+    /// the pre-releases of its (major, minor, patch) too
+    fn lower(&self) -> Version {
+        if self.pre.is_empty() {
+            lowest(
+                self.major.unwrap_or(0),
+                self.minor.unwrap_or(0),
+                self.patch.unwrap_or(0),
+            )
         } else {
-            Ok(VersionParser::Exact)
+            self.floor()
         }
     }
 
-    pub fn remove_version_prefix(&self, version: &str) -> String {
-        // break version into parts
-        match self {
-            VersionParser::Star => version.replace("*", ""),
-            VersionParser::Caret => version.replace("^", ""),
-            VersionParser::Tilde => version.replace("~", ""),
-            VersionParser::Exact => version.to_string(),
+    /// First version past a partial version (`1` -> `2.0.0-0`, `1.2` -> `1.3.0-0`)
+    fn ceiling(&self) -> Version {
+        match (self.major, self.minor) {
+            (Some(major), None) => lowest(major + 1, 0, 0),
+            (Some(major), Some(minor)) => lowest(major, minor + 1, 0),
+            _ => lowest(0, 0, 0),
         }
     }
+}
+
+/// Lowest version with the given numbers. `-0` sorts before every other pre-release, so an
+/// exclusive upper bound on it leaves out the pre-releases of that version too
+fn lowest(major: u64, minor: u64, patch: u64) -> Version {
+    let mut version = Version::new(major, minor, patch);
+    version.pre = Prerelease::new("0").unwrap();
+    version
+}
 
+const OPERATORS: [&str; 7] = [">=", "<=", ">", "<", "=", "^", "~"];
+
+impl VersionParser {
+    pub fn new(version: &str) -> Result<VersionParser, VersionError> {
+        let version = version.trim();
+        let (op, rest) = split_operator(version);
+
+        if version.contains(',') || version.contains(" - ") || rest.contains(char::is_whitespace) {
+            return Ok(VersionParser::Range);
+        }
+
+        let partial = PartialVersion::parse(rest)?;
+        let parser = match op {
+            "^" => VersionParser::Caret,
+            "~" => VersionParser::Tilde,
+            "" | "=" if partial.is_full() => VersionParser::Exact,
+            "" if partial.is_any() || rest.contains(['*', 'x', 'X']) => VersionParser::Star,
+            _ => VersionParser::Range,
+        };
+
+        Ok(parser)
+    }
+
+    /// Parse a version expression into the interval of versions it matches.
+    ///
+    /// Supported forms:
+    /// * exact versions: `1.2.3`, `=1.2.3-rc.1`
+    /// * partial versions and wildcards: `1`, `1.2`, `1.*`, `1.x`, `*`
+    /// * comparisons: `>1.2`, `>=1.2.5`, `<2`, `<=2.1.0`
+    /// * caret and tilde: `^1.2.3` (`>=1.2.3, <2.0.0`), `~1.2.3` (`>=1.2.3, <1.3.0`)
+    /// * hyphen ranges: `1.2.3 - 2.3` (`>=1.2.3, <2.4.0`)
+    /// * conjunctions separated by commas or whitespace: `>=1.2, <2.0`
+    ///
+    /// Comparators without a pre-release tag compare (major, minor, patch), so `>=1.2.0`
+    /// matches `1.2.0-rc.1` and `<1.2.0` does not. Comparators with a tag follow semver
+    /// pre-release precedence. Exact versions match a single version
     pub fn get_version_to_search(version: &str) -> Result<VersionBounds, VersionError> {
-        let parser = VersionParser::new(version)?;
-
-        let cleaned_version = parser.remove_version_prefix(version);
-
-        // determine number of "." in the version and split into int parts
-        let version_parts = if !cleaned_version.is_empty() {
-            cleaned_version
-                .split(".")
-                .filter(|v| !v.is_empty())
-                .map(|v| v.parse::<u64>())
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|e| VersionError::InvalidVersion(e.to_string()))?
-        } else {
-            vec![]
+        let mut bounds = VersionBounds {
+            lower_bound: None,
+            upper_bound: None,
+            parser_type: VersionParser::new(version)?,
         };
 
-        let num_parts = version_parts.len();
-
-        match parser {
-            VersionParser::Star => {
-                if num_parts == 0 {
-                    Ok(VersionBounds {
-                        lower_bound: Version::parse("0.0.0")
-                            .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        upper_bound: Version::parse("0.0.0")
-                            .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        no_upper_bound: true,
-                        parser_type: VersionParser::Star,
-                        num_parts,
-                    })
-                } else if num_parts == 1 {
-                    Ok(VersionBounds {
-                        lower_bound: Version::parse(&format!("{}.0.0", version_parts[0]))
-                            .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        upper_bound: Version::parse(&format!("{}.0.0", version_parts[0] + 1))
-                            .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        no_upper_bound: false,
-                        parser_type: VersionParser::Star,
-                        num_parts,
-                    })
-                } else if num_parts == 2 {
-                    Ok(VersionBounds {
-                        lower_bound: Version::parse(&format!(
-                            "{}.{}.0",
-                            version_parts[0], version_parts[1]
-                        ))
-                        .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        upper_bound: Version::parse(&format!(
-                            "{}.{}.0",
-                            version_parts[0],
-                            version_parts[1] + 1
-                        ))
-                        .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        no_upper_bound: false,
-                        parser_type: VersionParser::Star,
-                        num_parts,
-                    })
-                } else if num_parts == 3 {
-                    Ok(VersionBounds {
-                        lower_bound: Version::parse(&format!(
-                            "{}.{}.{}",
-                            version_parts[0], version_parts[1], version_parts[2]
-                        ))
-                        .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        upper_bound: Version::parse(&format!(
-                            "{}.{}.{}",
-                            version_parts[0],
-                            version_parts[1],
-                            version_parts[2] + 1
-                        ))
-                        .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        no_upper_bound: false,
-                        parser_type: VersionParser::Star,
-                        num_parts,
-                    })
-                } else {
-                    Err(VersionError::InvalidVersion(
-                        "Invalid version provided with * syntax".to_string(),
-                    ))
+        for clause in version.split(',').map(str::trim) {
+            if let Some((low, high)) = clause.split_once(" - ") {
+                let low = PartialVersion::parse(low)?;
+                let high = PartialVersion::parse(high)?;
+                if !low.is_any() {
+                    bounds.restrict_lower(VersionBound::new(low.lower(), true));
                 }
-            }
-            VersionParser::Tilde => {
-                if num_parts == 1 {
-                    Ok(VersionBounds {
-                        lower_bound: Version::parse(&format!("{}.0.0", version_parts[0]))
-                            .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        upper_bound: Version::parse(&format!("{}.0.0", version_parts[0] + 1))
-                            .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        no_upper_bound: false,
-                        parser_type: VersionParser::Tilde,
-                        num_parts,
-                    })
-                } else if num_parts == 2 {
-                    Ok(VersionBounds {
-                        lower_bound: Version::parse(&format!(
-                            "{}.{}.0",
-                            version_parts[0], version_parts[1]
-                        ))
-                        .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        upper_bound: Version::parse(&format!(
-                            "{}.{}.0",
-                            version_parts[0],
-                            version_parts[1] + 1
-                        ))
-                        .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        no_upper_bound: false,
-                        parser_type: VersionParser::Tilde,
-                        num_parts,
-                    })
-                } else if num_parts >= 3 {
-                    Ok(VersionBounds {
-                        lower_bound: Version::parse(&format!(
-                            "{}.{}.{}",
-                            version_parts[0], version_parts[1], version_parts[2]
-                        ))
-                        .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        upper_bound: Version::parse(&format!(
-                            "{}.{}.0",
-                            version_parts[0],
-                            version_parts[1] + 1,
-                        ))
-                        .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        no_upper_bound: false,
-                        parser_type: VersionParser::Tilde,
-                        num_parts,
-                    })
-                } else {
-                    Err(VersionError::InvalidVersion(
-                        "Invalid version provided with ~ syntax".to_string(),
-                    ))
+                if high.is_full() {
+                    bounds.restrict_upper(VersionBound::new(high.floor(), true));
+                } else if !high.is_any() {
+                    bounds.restrict_upper(VersionBound::new(high.ceiling(), false));
                 }
+                continue;
             }
-            VersionParser::Caret => {
-                // must bea  full semver version for caret
-
-                if num_parts >= 2 {
-                    Ok(VersionBounds {
-                        lower_bound: Version::parse(&format!(
-                            "{}.{}.{}",
-                            version_parts[0], version_parts[1], version_parts[2]
-                        ))
-                        .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        upper_bound: Version::parse(&format!(
-                            "{}.{}.0",
-                            version_parts[0],
-                            version_parts[1] + 1,
-                        ))
-                        .map_err(|e| VersionError::InvalidVersion(e.to_string()))?,
-                        no_upper_bound: false,
-                        parser_type: VersionParser::Caret,
-                        num_parts,
-                    })
-                } else {
-                    Err(VersionError::InvalidVersion(
-                        "Invalid version provided with ^ syntax".to_string(),
-                    ))
-                }
+
+            for comparator in split_comparators(clause) {
+                apply_comparator(&mut bounds, &comparator)?;
             }
-            VersionParser::Exact => Version::parse(&cleaned_version)
-                .map(|v| VersionBounds {
-                    lower_bound: v.clone(),
-                    upper_bound: v,
-                    no_upper_bound: true,
-                    parser_type: VersionParser::Exact,
-                    num_parts,
-                })
-                .map_err(|e| VersionError::InvalidVersion(e.to_string())),
         }
+
+        Ok(bounds)
+    }
+}
+
+pub(crate) fn split_operator(comparator: &str) -> (&str, &str) {
+    OPERATORS
+        .iter()
+        .find(|op| comparator.starts_with(*op))
+        .map(|op| (*op, comparator[op.len()..].trim()))
+        .unwrap_or(("", comparator))
+}
+
+/// Split a clause on whitespace, keeping an operator attached to the version after it (`>= 1.2`)
+pub(crate) fn split_comparators(clause: &str) -> Vec<String> {
+    let mut comparators: Vec<String> = Vec::new();
+    let mut pending_op = String::new();
+
+    for token in clause.split_whitespace() {
+        if OPERATORS.contains(&token) {
+            pending_op = token.to_string();
+        } else {
+            comparators.push(format!("{}{}", pending_op, token));
+            pending_op.clear();
+        }
+    }
+
+    if comparators.is_empty() {
+        comparators.push(pending_op);
+    }
+    comparators
+}
+
+fn apply_comparator(bounds: &mut VersionBounds, comparator: &str) -> Result<(), VersionError> {
+    let (op, version) = split_operator(comparator);
+    let partial = PartialVersion::parse(version)?;
+
+    // a bare wildcard matches everything whatever the operator
+    if partial.is_any() {
+        return Ok(());
     }
+
+    let floor = partial.floor();
+    match op {
+        "" | "=" => {
+            if partial.is_full() {
+                bounds.restrict_lower(VersionBound::new(floor.clone(), true));
+                bounds.restrict_upper(VersionBound::new(floor, true));
+            } else {
+                bounds.restrict_lower(VersionBound::new(partial.lower(), true));
+                bounds.restrict_upper(VersionBound::new(partial.ceiling(), false));
+            }
+        }
+        ">" => {
+            if !partial.pre.is_empty() {
+                bounds.restrict_lower(VersionBound::new(floor, false));
+            } else if partial.is_full() {
+                let next = lowest(floor.major, floor.minor, floor.patch + 1);
+                bounds.restrict_lower(VersionBound::new(next, true));
+            } else {
+                bounds.restrict_lower(VersionBound::new(partial.ceiling(), true));
+            }
+        }
+        ">=" => bounds.restrict_lower(VersionBound::new(partial.lower(), true)),
+        "<" => bounds.restrict_upper(VersionBound::new(partial.lower(), false)),
+        "<=" => {
+            if partial.is_full() {
+                bounds.restrict_upper(VersionBound::new(floor, true));
+            } else {
+                bounds.restrict_upper(VersionBound::new(partial.ceiling(), false));
+            }
+        }
+        "~" => {
+            let major = floor.major;
+            let upper = match partial.minor {
+                Some(minor) => lowest(major, minor + 1, 0),
+                None => lowest(major + 1, 0, 0),
+            };
+            bounds.restrict_lower(VersionBound::new(partial.lower(), true));
+            bounds.restrict_upper(VersionBound::new(upper, false));
+        }
+        "^" => {
+            // the left-most non-zero number may not change
+            let upper = match (floor.major, partial.minor, partial.patch) {
+                (0, Some(0), Some(patch)) => lowest(0, 0, patch + 1),
+                (0, Some(minor), _) => lowest(0, minor + 1, 0),
+                (major, _, _) => lowest(major + 1, 0, 0),
+            };
+            bounds.restrict_lower(VersionBound::new(partial.lower(), true));
+            bounds.restrict_upper(VersionBound::new(upper, false));
+        }
+        _ => {
+            return Err(VersionError::InvalidVersion(format!(
+                "Unsupported operator in {}",
+                comparator
+            )))
+        }
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -407,39 +555,106 @@ mod tests {
         assert_eq!(VersionParser::new("1.2.3").unwrap(), VersionParser::Exact);
     }
 
-    #[test]
-    fn test_version_parser_remove_version_prefix() {
-        assert_eq!(VersionParser::Star.remove_version_prefix("*"), "");
-        assert_eq!(VersionParser::Star.remove_version_prefix("*1"), "1");
-        assert_eq!(VersionParser::Star.remove_version_prefix("1.*"), "1.0");
-        assert_eq!(VersionParser::Star.remove_version_prefix("1.2.*"), "1.2.0");
-        assert_eq!(
-            VersionParser::Caret.remove_version_prefix("^1.2.3"),
-            "1.2.3"
-        );
-        assert_eq!(
-            VersionParser::Tilde.remove_version_prefix("~1.2.3"),
-            "1.2.3"
-        );
-        assert_eq!(VersionParser::Exact.remove_version_prefix("1.2.3"), "1.2.3");
-    }
-
     #[test]
     fn test_version_parser_get_version_to_search() {
         let bounds = VersionParser::get_version_to_search("*").unwrap();
-        assert_eq!(bounds.lower_bound, Version::parse("0.0.0").unwrap());
-        assert!(bounds.no_upper_bound);
+        assert_eq!(bounds.lower_bound, None);
+        assert_eq!(bounds.upper_bound, None);
 
         let bounds = VersionParser::get_version_to_search("1.*").unwrap();
-        assert_eq!(bounds.lower_bound, Version::parse("1.0.0").unwrap());
-        assert_eq!(bounds.upper_bound, Version::parse("1.1.0").unwrap());
+        assert_eq!(bounds.parser_type, VersionParser::Star);
+        assert_eq!(
+            bounds.lower_bound.unwrap().version,
+            Version::parse("1.0.0-0").unwrap()
+        );
+        assert_eq!(
+            bounds.upper_bound.unwrap().version,
+            Version::parse("2.0.0-0").unwrap()
+        );
 
         let bounds = VersionParser::get_version_to_search("^1.2.3").unwrap();
-        assert_eq!(bounds.lower_bound, Version::parse("1.2.3").unwrap());
-        assert_eq!(bounds.upper_bound, Version::parse("1.3.0").unwrap());
+        assert_eq!(
+            bounds.lower_bound.unwrap().version,
+            Version::parse("1.2.3-0").unwrap()
+        );
+        assert_eq!(
+            bounds.upper_bound.unwrap().version,
+            Version::parse("2.0.0-0").unwrap()
+        );
 
         let bounds = VersionParser::get_version_to_search("~1.2.3").unwrap();
-        assert_eq!(bounds.lower_bound, Version::parse("1.2.3").unwrap());
-        assert_eq!(bounds.upper_bound, Version::parse("1.3.0").unwrap());
+        assert_eq!(
+            bounds.lower_bound.unwrap().version,
+            Version::parse("1.2.3-0").unwrap()
+        );
+        assert_eq!(
+            bounds.upper_bound.unwrap().version,
+            Version::parse("1.3.0-0").unwrap()
+        );
+
+        let bounds = VersionParser::get_version_to_search("1.2.3").unwrap();
+        assert_eq!(bounds.parser_type, VersionParser::Exact);
+        assert_eq!(bounds.lower_bound, bounds.upper_bound);
+    }
+
+    fn matches(range: &str, version: &str) -> bool {
+        VersionParser::get_version_to_search(range)
+            .unwrap()
+            .matches(&Version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn test_version_parser_ranges() {
+        // tuple comparison, not per-component
+        assert!(matches(">=1.2.5", "1.3.0"));
+        assert!(matches(">=1.2.5", "2.0.1"));
+        assert!(!matches(">=1.2.5", "1.2.4"));
+        assert!(matches(">1.2", "1.3.0"));
+        assert!(!matches(">1.2", "1.2.9"));
+        assert!(matches("<=2.1", "2.1.7"));
+        assert!(!matches("<2", "2.0.0-alpha"));
+
+        // conjunctions
+        assert!(matches(">=1.2, <2.0", "1.9.9"));
+        assert!(!matches(">=1.2, <2.0", "2.0.0"));
+        assert!(matches(">= 1.2 < 2", "1.5.0"));
+        assert_eq!(
+            VersionParser::new(">=1.2, <2.0").unwrap(),
+            VersionParser::Range
+        );
+
+        // x wildcards
+        assert!(matches("1.x", "1.9.0"));
+        assert!(!matches("1.2.x", "1.3.0"));
+        assert!(matches("X", "7.0.0"));
+
+        // hyphen ranges
+        assert!(matches("1.2.3 - 2.3.4", "2.3.4"));
+        assert!(!matches("1.2.3 - 2.3.4", "2.3.5"));
+        assert!(matches("1.2.3 - 2.3", "2.3.9"));
+        assert!(!matches("1.2.3 - 2.3", "2.4.0"));
+
+        // caret keeps the left-most non-zero number
+        assert!(matches("^0.2.3", "0.2.9"));
+        assert!(!matches("^0.2.3", "0.3.0"));
+        assert!(!matches("^0.0.3", "0.0.4"));
+
+        // pre-release precedence
+        assert!(matches(">=1.0.0-alpha", "1.0.0-beta"));
+        assert!(matches(">=1.0.0-alpha.2", "1.0.0-alpha.10"));
+        assert!(!matches(">=1.0.0-beta", "1.0.0-alpha"));
+        assert!(matches("<1.0.0-rc.1", "1.0.0-beta"));
+        assert!(!matches("1.0.0", "1.0.0-rc.1"));
+
+        // without a pre-release tag the bound covers the pre-releases of its version
+        assert!(matches(">=1.0.0", "1.0.0-rc.1"));
+        assert!(!matches("<1.0.0", "1.0.0-rc.1"));
+        assert!(!matches(">1.0.0", "1.0.0-rc.1"));
+        assert!(matches("~1.1.1", "1.1.1-delta"));
+        assert!(matches("=1.0.0-rc.1", "1.0.0-rc.1+build.5"));
+
+        assert!(VersionParser::get_version_to_search("1.2.3.4").is_err());
+        assert!(VersionParser::get_version_to_search("1.x.3").is_err());
+        assert!(VersionParser::get_version_to_search("!1.2").is_err());
     }
 }